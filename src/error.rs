@@ -0,0 +1,78 @@
+//==============================================================================
+// Import standard library
+use std::fmt;
+
+//==============================================================================
+// Import modules
+use crate::sa::config::ConfigError;
+
+//==============================================================================
+/// Error of the crate. The variants separate a problem of the instance from a
+/// problem of the configuration, from a failed file operation and from a bug.
+//
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Infeasible(String),  // The instance or the schedule violates a constraint
+    Config(ConfigError), // A configuration or input file is invalid
+    Io(String),          // A file could not be read or written
+    Invariant(String),   // An internal invariant was violated
+}
+
+//==============================================================================
+/// Implementation of Display for Error
+//
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            Error::Infeasible(m) => write!(f, "infeasible: {}", m),
+            Error::Config(e) => write!(f, "invalid configuration: {}", e),
+            Error::Io(m) => write!(f, "i/o error: {}", m),
+            Error::Invariant(m) => write!(f, "internal error: {}", m),
+        };
+    }
+}
+
+//==============================================================================
+/// Implementation of Error for Error
+//
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            Error::Config(e) => Some(e),
+            _ => None,
+        };
+    }
+}
+
+//==============================================================================
+/// Implementation of From<ConfigError> for Error
+//
+impl From<ConfigError> for Error {
+    fn from(e: ConfigError) -> Self {
+        return Error::Config(e);
+    }
+}
+
+//==============================================================================
+/// Implementation of Error
+//
+impl Error {
+    //--------------------------------------------------------------------------
+    /// Separate an infeasible result from the errors that stop a run. An
+    /// infeasible candidate is discarded by the search, the other errors are
+    /// passed on.
+    ///
+    /// # Input
+    /// * res: Result of an evaluation
+    ///
+    /// # Output
+    /// * Option<T>: The value, or `None` if the result is infeasible
+    ///
+    pub fn feasible<T>(res: Result<T, Error>) -> Result<Option<T>, Error> {
+        return match res {
+            Ok(v) => Ok(Some(v)),
+            Err(Error::Infeasible(_)) => Ok(None),
+            Err(e) => Err(e),
+        };
+    }
+}
@@ -1,4 +1,5 @@
 // My modules
+pub mod error; // Errors of the crate
 pub mod lp; // Linear program
 pub mod plotter; // Plot the solution
 pub mod sa; // Simulated annealing algorithm
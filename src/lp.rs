@@ -2,3 +2,4 @@
 // Declare modules
 pub mod constraints;
 pub mod objectives;
+pub mod validate;
@@ -2,6 +2,7 @@
 // Declare modules
 pub mod dynamic;
 pub mod packing;
+pub mod report;
 
 //==============================================================================
 // Import modules
@@ -21,8 +22,13 @@ use crate::sa::data::Data;
 /// # Output
 /// * bool: Constraint successfully applied and is true
 ///
+/// The `violation` function measures how far the decision variables of an
+/// evaluated schedule are from satisfying the constraint. It does not update
+/// the decision variables and returns zero if the constraint holds.
+///
 pub trait Constraint {
     fn run(dat: &mut Data, ch: &mut Charger, i: usize, j: usize) -> bool;
+    fn violation(dat: &Data, i: usize, j: usize) -> f32;
 }
 
 //==============================================================================
@@ -31,27 +37,62 @@ pub trait Constraint {
 pub mod constraints {
     //==============================================================================
     // Import modules
+    use crate::error::Error;
     use crate::lp::constraints::dynamic::dynamic;
+    use crate::lp::constraints::packing::grid_capacity::GridCapacity;
     use crate::lp::constraints::packing::packing;
+    use crate::lp::constraints::Constraint;
     use crate::sa::charger::Charger;
     use crate::sa::data::Data;
 
     //--------------------------------------------------------------------------
-    //
-    pub fn run(run_constr: bool, dat: &mut Data, ch: &mut Charger, i: usize, j: usize) -> bool {
+    /// Run the packing (if requested) and dynamic constraints for visit `i`
+    /// against visit/queue `j`. The grid capacity is a hard limit and is
+    /// checked once for every visit, also when the packing constraints are
+    /// not run.
+    ///
+    /// # Input
+    /// * run_constr: Boolean to run packing constraints
+    /// * dat: Data for the current model
+    /// * ch: Charger object
+    /// * i: index of the visit
+    /// * j: index for the queue
+    ///
+    /// # Output
+    /// * NONE, or an infeasibility error naming the violated constraint
+    ///
+    pub fn run(
+        run_constr: bool,
+        dat: &mut Data,
+        ch: &mut Charger,
+        i: usize,
+        j: usize,
+    ) -> Result<(), Error> {
         // If packing constraints fail
         if run_constr && !packing::run(dat, ch, i, j) {
-            println!("Packing");
-            return false;
+            return Err(Error::Infeasible(format!(
+                "Packing constraints violated by visits {} and {}.",
+                i, j
+            )));
+        }
+
+        // If the chargers draw more power than the grid connection allows
+        if j == 0 && !GridCapacity::run(dat, ch, i, j) {
+            return Err(Error::Infeasible(format!(
+                "Grid capacity exceeded while visit {} charges.",
+                i
+            )));
         }
 
         // If dynamic constraints fail
         if !dynamic::run(dat, ch, i, j) {
-            println!("Dynamic");
-            return false;
+            return Err(Error::Infeasible(format!(
+                "Dynamic constraints violated by visit {}.",
+                i
+            )));
         }
 
         // Success
-        return true;
+        return Ok(());
     }
 }
@@ -17,7 +17,8 @@ pub struct ChargePropagate {}
 impl ChargePropagate {
     //==========================================================================
     /// The `update_lin_charge` function adjusts the charge time so that the
-    /// BEB is not over charged using the linear battery dynamics model.
+    /// BEB is not over charged using the linear battery dynamics model. The
+    /// charge is delivered at the time-varying rate of the charger queue.
     ///
     /// # Input
     /// * dat: Data object
@@ -28,7 +29,7 @@ impl ChargePropagate {
     /// * charge: Liner battery dynamics SOC estimation
     ///
     #[allow(non_snake_case)]
-    fn update_lin_charge(dat: &mut Data, _ch: &mut Charger, i: usize) -> f32 {
+    fn update_lin_charge(dat: &mut Data, ch: &mut Charger, i: usize) -> f32 {
         // Extract parameters
         let Gam = &dat.param.Gam;
         let r = &dat.param.r;
@@ -51,7 +52,7 @@ impl ChargePropagate {
         // and the schedule does not fail
         //
         // Units: Kwh * (hr / Kwh) = hr
-        s[i] = (kappa[Gam[i] as usize] - eta[i]) / charge_rate;
+        s[i] = ch.service_time(v[i], u[i], (kappa[Gam[i] as usize] - eta[i]) / charge_rate);
 
         // Ensure charge time is non-zero
         if s[i] == 0.0 {
@@ -71,12 +72,13 @@ impl ChargePropagate {
         }
 
         // Update the charge
-        return r[v[i]] * s[i];
+        return r[v[i]] * ch.effective_time(v[i], u[i], s[i]);
     }
 
     //==========================================================================
     /// The `linear_model` function determines the amount of charge supplied to
-    /// the BEB for visit `i` using a linear battery dynamics model.
+    /// the BEB for visit `i` using a linear battery dynamics model. The charge
+    /// is delivered at the time-varying rate of the charger queue.
     ///
     /// # Input
     /// * dat: Data object
@@ -96,9 +98,11 @@ impl ChargePropagate {
         let kappa = &dat.param.k;
         let r = &dat.param.r;
         let s = &dat.dec.s;
+        let u = &dat.dec.u;
+        let v = dat.dec.v[i];
 
         // Calculate charge amount
-        let mut charge: f32 = r[dat.dec.v[i]] * s[i];
+        let mut charge: f32 = r[v] * ch.effective_time(v, u[i], s[i]);
 
         // Ensure the charge does not exceed the battery limit
         if dat.dec.eta[i] + charge > kappa[Gam[i] as usize] && dat.dec.v[i] >= dat.param.A {
@@ -110,7 +114,8 @@ impl ChargePropagate {
 
     //==========================================================================
     /// The `nonlinear_model` function determines the amount of charge supplied
-    /// to the BEB for visit `i` using a non-linear battery dynamics.
+    /// to the BEB for visit `i` using a non-linear battery dynamics. A shared
+    /// charger converges slower while it delivers less than its full rate.
     ///
     /// # Input
     /// * dat: Data object
@@ -121,17 +126,17 @@ impl ChargePropagate {
     /// * charge: Non-linear battery dynamics SOC estimation
     ///
     #[allow(non_snake_case)]
-    fn nonlinear_model(dat: &mut Data, _ch: &mut Charger, i: usize) -> f32 {
+    fn nonlinear_model(dat: &mut Data, ch: &mut Charger, i: usize) -> f32 {
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Constraint
 
         // Extract parameters
         let Gam = &dat.param.Gam;
-        let dt = dat.dec.s[i] * 3600.0;
         let eta = &dat.dec.eta;
         let kappa = &dat.param.k;
         let r = &dat.param.conv;
         let v = &dat.dec.v;
+        let dt = ch.effective_time(v[i], dat.dec.u[i], dat.dec.s[i]) * 3600.0;
 
         // Calculate model parameters
         let abar = f32::exp(-r[v[i]] * dt);
@@ -193,12 +198,125 @@ impl Constraint for ChargePropagate {
 
         return true;
     }
+
+    fn violation(dat: &Data, i: usize, _: usize) -> f32 {
+        return ChargePropagate::mismatch(dat, i, ChargePropagate::charge(dat, i));
+    }
 }
 
 //==============================================================================
 /// Implementation of helper functions for `ChargePropogation`
 //
 impl ChargePropagate {
+    //--------------------------------------------------------------------------
+    /// The `charge` function calculates the charge supplied during visit `i`
+    /// from the decision variables, without adjusting the charge time. The
+    /// charger queue delivers its full rate.
+    ///
+    /// # Input
+    /// * dat: Data object
+    /// * i: Visit index
+    ///
+    /// # Output
+    /// * charge: Charge supplied during the visit
+    ///
+    pub fn charge(dat: &Data, i: usize) -> f32 {
+        return ChargePropagate::charge_for(dat, i, dat.dec.s[i]);
+    }
+
+    //--------------------------------------------------------------------------
+    /// The `shared_charge` function calculates the charge supplied during
+    /// visit `i` at the time-varying rate of the charger queue, without
+    /// adjusting the charge time.
+    ///
+    /// # Input
+    /// * dat: Data object
+    /// * ch: Charger object
+    /// * i: Visit index
+    ///
+    /// # Output
+    /// * charge: Charge supplied during the visit
+    ///
+    pub fn shared_charge(dat: &Data, ch: &Charger, i: usize) -> f32 {
+        let t = ch.effective_time(dat.dec.v[i], dat.dec.u[i], dat.dec.s[i]);
+        return ChargePropagate::charge_for(dat, i, t);
+    }
+
+    //--------------------------------------------------------------------------
+    /// The `shared_violation` function measures the charge propagation of
+    /// visit `i` at the time-varying rate of the charger queue.
+    ///
+    /// # Input
+    /// * dat: Data object
+    /// * ch: Charger object
+    /// * i: Visit index
+    ///
+    /// # Output
+    /// * amount: Difference between the SOC of the next visit and the
+    ///   propagated SOC
+    ///
+    pub fn shared_violation(dat: &Data, ch: &Charger, i: usize) -> f32 {
+        return ChargePropagate::mismatch(dat, i, ChargePropagate::shared_charge(dat, ch, i));
+    }
+
+    //--------------------------------------------------------------------------
+    /// The `mismatch` function compares the SOC of the next visit of the BEB
+    /// with the SOC propagated from visit `i`.
+    ///
+    /// # Input
+    /// * dat: Data object
+    /// * i: Visit index
+    /// * charge: Charge supplied during the visit
+    ///
+    /// # Output
+    /// * amount: Difference between the SOC of the next visit and the
+    ///   propagated SOC
+    ///
+    fn mismatch(dat: &Data, i: usize, charge: f32) -> f32 {
+        // Extract parameters
+        let gam = dat.param.gam[i];
+        let l = dat.param.l[i];
+        let eta = &dat.dec.eta;
+
+        // The last visit of a BEB does not propagate its charge
+        if gam < 0 {
+            return 0.0;
+        }
+
+        return (eta[gam as usize] - (eta[i] + charge - l)).abs();
+    }
+
+    //--------------------------------------------------------------------------
+    /// The `charge_for` function calculates the charge supplied during visit
+    /// `i` for a charge time at the full charge rate.
+    ///
+    /// # Input
+    /// * dat: Data object
+    /// * i: Visit index
+    /// * s: Charge time at the full charge rate [hr]
+    ///
+    /// # Output
+    /// * charge: Charge supplied during the visit
+    ///
+    #[allow(non_snake_case)]
+    fn charge_for(dat: &Data, i: usize, s: f32) -> f32 {
+        // Extract parameters
+        let Gam = &dat.param.Gam;
+        let kappa = &dat.param.k;
+        let v = dat.dec.v[i];
+
+        // Linear battery dynamics
+        if dat.param.model == "linear" {
+            return dat.param.r[v] * s;
+        }
+
+        // Non-linear battery dynamics
+        let abar = f32::exp(-dat.param.conv[v] * s * 3600.0);
+        let eta = dat.dec.eta[i];
+
+        return eta * abar - (abar - 1.0) * kappa[Gam[i] as usize] - eta;
+    }
+
     //--------------------------------------------------------------------------
     /// The `update_dec_var` function updates the decision variables associated
     /// with the `ChargePropogation` constraints.
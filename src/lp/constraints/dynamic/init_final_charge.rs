@@ -50,4 +50,25 @@ impl Constraint for InitFinalCharge {
 
         return true;
     }
+
+    fn violation(dat: &Data, i: usize, _: usize) -> f32 {
+        // Extract parameters
+        let kappa = dat.param.k[dat.param.Gam[i] as usize];
+        let alpha = dat.param.alpha[i];
+        let beta = dat.param.beta[i];
+        let eta = dat.dec.eta[i];
+        let mut amount: f32 = 0.0;
+
+        // The initial charge differs from the specified charge
+        if alpha > 0.0 {
+            amount += (eta - alpha * kappa).abs();
+        }
+
+        // The final charge is below the specified threshold
+        if beta > 0.0 {
+            amount += (beta * kappa - eta).max(0.0);
+        }
+
+        return amount;
+    }
 }
@@ -31,27 +31,25 @@ impl Constraint for ScalarToVectorQueue {
 
         // Determine the queue vector `w` for visit `i`
         if w[i][v[i]] != true {
-            println!("Visit {}", i);
-            println!("w[i][v[i]] = {}", w[i][v[i]]);
-            println!("v[i]       = {}", v[i]);
-            println!("scalar_to_vector_queue.rs: w[i][v[i]] != v[i].");
             return false;
         }
 
         // Ensure the visit vector does not have simultaneous assignments
         // https://stackoverflow.com/questions/69847288/is-there-an-easy-way-to-count-booleans-in-rust/69847395?noredirect=1#comment123467398_69847395
         if w[i].clone().into_iter().filter(|a| *a).count() > 1 {
-            println!("Visit {}", i);
-            println!("Charger indices that are true:");
-            for (idx, itr) in w[i].iter().enumerate() {
-                if *itr == false {
-                    println!("- {}", idx);
-                }
-            }
-            println!("scalar_to_vector_queue.rs: sum(w[i]) > 1");
             return false;
         }
 
         return true;
     }
+
+    fn violation(d: &Data, i: usize, _: usize) -> f32 {
+        // Count the entries of `w` that do not match the queue `v`
+        let v = d.dec.v[i];
+        return d.dec.w[i]
+            .iter()
+            .enumerate()
+            .filter(|(q, w)| **w != (*q == v))
+            .count() as f32;
+    }
 }
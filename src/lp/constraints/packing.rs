@@ -1,5 +1,6 @@
 //===============================================================================
 // Declare modules
+pub mod grid_capacity;
 pub mod psi_sigma;
 pub mod service_time;
 pub mod space_time_big_o;
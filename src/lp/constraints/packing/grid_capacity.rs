@@ -0,0 +1,105 @@
+//===============================================================================
+// Import developed modules
+use crate::lp::constraints::Constraint;
+use crate::sa::charger::Charger;
+use crate::sa::data::Data;
+
+//===============================================================================
+/// Structure defining the information to calculate the grid capacity
+//
+pub struct GridCapacity {}
+
+//===============================================================================
+/// Implementation of `Constraint` for `GridCapacity` structure. The summed
+/// charge rate of the chargers may not exceed the power limit of the grid
+/// connection while visit `i` charges. A limit of zero leaves the grid
+/// connection unlimited. The `violation` takes the full charge rate of each
+/// queue, see `shared_violation` for the rates shared by a cabinet.
+///
+/// # Input
+/// * d: Data for the current model
+/// * i: index of the visit
+/// * j: unused
+///
+/// # Output
+/// * bool: Constraint successfully applied and is true
+///
+#[allow(non_snake_case)]
+impl Constraint for GridCapacity {
+    fn run(dat: &mut Data, ch: &mut Charger, i: usize, _j: usize) -> bool {
+        // Extract parameters
+        let limit = dat.param.grid;
+        let q = dat.dec.v[i];
+
+        // Visits on a wait queue do not draw power
+        if limit <= 0.0 || q < ch.charger_count.0 || q >= ch.schedule.len() {
+            return true;
+        }
+
+        return ch.peak_power(&(dat.dec.u[i], dat.dec.d[i])) <= limit;
+    }
+
+    fn violation(dat: &Data, i: usize, _j: usize) -> f32 {
+        // Extract parameters
+        let N = dat.param.N;
+        let A = dat.param.A;
+        let limit = dat.param.grid;
+
+        // Extract decision variables
+        let u = &dat.dec.u;
+        let d = &dat.dec.d;
+        let v = &dat.dec.v;
+
+        // Visits on a wait queue do not draw power
+        if limit <= 0.0 || v[i] < A {
+            return 0.0;
+        }
+
+        // Summed charge rate of the visits charging at time `t`
+        let power = |t: f32| -> f32 {
+            return (0..N)
+                .filter(|k| v[*k] >= A && u[*k] <= t && t < d[*k])
+                .map(|k| dat.param.r[v[k]])
+                .sum();
+        };
+
+        // The power only rises at the attach time of a visit
+        let peak = (0..N)
+            .filter(|k| u[*k] > u[i] && u[*k] < d[i])
+            .map(|k| power(u[k]))
+            .fold(power(u[i]), f32::max);
+
+        return (peak - limit).max(0.0);
+    }
+}
+
+//===============================================================================
+/// Implementation of `GridCapacity` structure
+//
+impl GridCapacity {
+    //--------------------------------------------------------------------------
+    /// The `shared_violation` function measures the power above the grid limit
+    /// while visit `i` charges, at the time-varying rates of the charger
+    /// queues.
+    ///
+    /// # Input
+    /// * dat: Data object
+    /// * ch: Charger object
+    /// * i: Visit index
+    ///
+    /// # Output
+    /// * amount: Peak summed charge rate above the grid limit
+    ///
+    pub fn shared_violation(dat: &Data, ch: &Charger, i: usize) -> f32 {
+        // Extract parameters
+        let limit = dat.param.grid;
+        let q = dat.dec.v[i];
+
+        // Visits on a wait queue do not draw power
+        if limit <= 0.0 || q < ch.charger_count.0 || q >= ch.schedule.len() {
+            return 0.0;
+        }
+
+        return (ch.peak_power(&(dat.dec.u[i], dat.dec.d[i])) - limit).max(0.0);
+    }
+}
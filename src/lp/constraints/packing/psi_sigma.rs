@@ -40,57 +40,38 @@ impl Constraint for PsiSigma {
 
         // Check the spatial ordering
         if !(psi[i][j] as usize + psi[j][i] as usize <= 1) {
-            println!("Schedule: {:?}", ch.schedule[dat.dec.v[i]]);
-            println!("{} + {} > 1", psi[i][j], psi[j][i]);
-            println!(
-                "I: i: {}, Gam: {}, v: {}",
-                i, dat.param.Gam[i], dat.dec.v[i]
-            );
-            println!("J: j: {} Gam: {} v: {}", j, dat.param.Gam[i], dat.dec.v[j]);
-            println!("psi_sigma.rs: PSI > 1");
             return false;
         }
 
         // Check the temporal ordering
         if !(sig[i][j] as usize + sig[j][i] as usize <= 1) {
-            println!("Schedule: {:?}", ch.schedule[dat.dec.v[i]]);
-            println!("{} + {} > 1", sig[i][j], sig[j][i]);
-            println!(
-                "I: i: {}, Gam: {},  u: {}, d: {}",
-                i, dat.param.Gam[i], dat.dec.u[i], dat.dec.d[i]
-            );
-            println!(
-                "J: j: {} Gam: {} u: {}, d: {}",
-                j, dat.param.Gam[i], dat.dec.u[j], dat.dec.d[j]
-            );
-            println!("psi_sigma.rs: SIGMA > 1");
             return false;
         }
 
         // Check the spatiotemporal ordering
         if !(psi[i][j] as usize + psi[j][i] as usize + sig[i][j] as usize + sig[j][i] as usize >= 1)
         {
-            println!("Visit {}", i);
-            println!("Schedule: {:?}", ch.schedule[dat.dec.v[i]]);
-            println!("Free Time: {:?}", ch.free_time[dat.dec.v[i]]);
-            println!(
-                "{} + {} + {} + {} <= 1",
-                psi[i][j], psi[j][i], sig[i][j], sig[j][i]
-            );
-            println!(
-                "I: i: {}, Gam: {}, v: {}, u: {}, d: {}",
-                i, dat.param.Gam[i], dat.dec.v[i], dat.dec.u[i], dat.dec.d[i]
-            );
-            println!(
-                "J: j: {} Gam: {} v: {}, u: {}, d: {}",
-                j, dat.param.Gam[i], dat.dec.v[j], dat.dec.u[j], dat.dec.d[j]
-            );
-            println!("psi_sigma.rs: SIGMA+PSI < 1");
             return false;
         }
 
         return true;
     }
+
+    fn violation(dat: &Data, i: usize, j: usize) -> f32 {
+        // Ignore the cases where i == j
+        if i == j {
+            return 0.0;
+        }
+
+        // Extract decision variables
+        let psi = &dat.dec.psi;
+        let sig = &dat.dec.sigma;
+        let p = psi[i][j] as i32 + psi[j][i] as i32;
+        let s = sig[i][j] as i32 + sig[j][i] as i32;
+
+        // Spatial, temporal and spatiotemporal ordering
+        return ((p - 1).max(0) + (s - 1).max(0) + (1 - p - s).max(0)) as f32;
+    }
 }
 
 //==============================================================================
@@ -34,4 +34,8 @@ impl Constraint for ServiceTime {
 
         return true;
     }
+
+    fn violation(dat: &Data, i: usize, _: usize) -> f32 {
+        return (dat.dec.s[i] - (dat.dec.d[i] - dat.dec.u[i])).abs();
+    }
 }
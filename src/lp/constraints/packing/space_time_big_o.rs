@@ -63,4 +63,28 @@ impl Constraint for SpaceTimeBigO {
 
         return true;
     }
+
+    fn violation(dat: &Data, i: usize, j: usize) -> f32 {
+        // The constraints only relate different visits
+        if i == j {
+            return 0.0;
+        }
+
+        // Extract parameters
+        let Q = dat.param.Q as f32;
+        let T = dat.param.T;
+        let S = dat.param.S as f32;
+
+        // Extract decision variables
+        let psi = f32::from(dat.dec.psi[i][j]);
+        let sig = f32::from(dat.dec.sigma[i][j]);
+        let vi = dat.dec.v[i] as f32;
+        let vj = dat.dec.v[j] as f32;
+
+        // Amount by which each of the big O constraints is not met
+        let space = -(vj - vi - S - (psi - S) * Q);
+        let time = -(dat.dec.u[j] - dat.dec.d[i] - (sig - 1.0) * T);
+
+        return space.max(0.0) + time.max(0.0);
+    }
 }
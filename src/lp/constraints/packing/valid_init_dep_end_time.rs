@@ -41,30 +41,36 @@ impl Constraint for ValidInitDepEndTimes {
 
         // Ensure the arrival time is before the attach time
         if !(a[i] <= u[i]) {
-            println!("Visit {}", i);
-            println!("{} > {}", a[i], u[i]);
-            println!("valid_init_dep_end-time.rs: a[i] > u[i]");
             return false;
         }
 
         // Ensure the detach time is before the departure time
         if !(d[i] <= e[i]) {
-            println!("Visit {}", i);
-            println!("{} > {}", d[i], e[i]);
-            println!("valid_init_dep_end-time.rs: d[i] > e[i]");
             return false;
         }
 
         // Ensure the initial time is early enough such that the service time does not exceed the time horizon
         if !(u[i] <= T - s[i]) {
-            println!("Visit {}", i);
-            println!("{} > {} - {}", u[i], T, s[i]);
-            println!("valid_init_dep_end-time.rs: u[i] > T - s[i]");
             return false;
         }
 
         return true;
     }
+
+    fn violation(dat: &Data, i: usize, _: usize) -> f32 {
+        // Extract parameters
+        let T = dat.param.T;
+        let a = dat.param.a[i];
+        let e = dat.param.e[i];
+
+        // Extract decision variables
+        let d = dat.dec.d[i];
+        let u = dat.dec.u[i];
+        let s = dat.dec.s[i];
+
+        // Attach before arrival, detach after departure and service past the time horizon
+        return (a - u).max(0.0) + (d - e).max(0.0) + (u - (T - s)).max(0.0);
+    }
 }
 
 //==============================================================================
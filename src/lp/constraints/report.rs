@@ -0,0 +1,247 @@
+//==============================================================================
+// Import standard library
+use std::fmt;
+
+//==============================================================================
+// Import developed modules
+use crate::lp::constraints::dynamic::charge_propagation::ChargePropagate;
+use crate::lp::constraints::dynamic::init_final_charge::InitFinalCharge;
+use crate::lp::constraints::packing::grid_capacity::GridCapacity;
+use crate::lp::constraints::packing::psi_sigma::PsiSigma;
+use crate::lp::constraints::packing::service_time::ServiceTime;
+use crate::lp::constraints::packing::space_time_big_o::SpaceTimeBigO;
+use crate::lp::constraints::packing::valid_init_dep_end_time::ValidInitDepEndTimes;
+use crate::lp::constraints::Constraint;
+use crate::sa::charger::Charger;
+use crate::sa::data::Data;
+
+//==============================================================================
+/// Constraints that are checked by the report
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintKind {
+    PsiSigma,
+    SpaceTimeBigO,
+    ServiceTime,
+    ValidInitDepEndTimes,
+    ChargePropagate,
+    InitFinalCharge,
+    GridCapacity,        // The summed charge rate exceeds the grid limit
+    ScalarToVectorQueue, // The queue vector `w` does not match the queue `v`
+    MinCharge,           // The SOC on arrival is below `nu * k`
+    UnscheduledVisit,    // The visit is missing from the charger schedule
+    UnknownAssignment,   // The charger schedule contains an assignment of no visit
+    QueueOverlap,        // Two assignments of a charger queue overlap
+}
+
+//==============================================================================
+/// Violation of a constraint
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub kind: ConstraintKind, // Violated constraint
+    pub i: usize,             // Index of the visit, or of the assignment in queue `q`
+    pub j: Option<usize>,     // Index of the other visit or assignment for pairwise constraints
+    pub q: Option<usize>,     // Charger queue of the charger schedule constraints
+    pub amount: f32,          // Amount by which the constraint is violated
+}
+
+//==============================================================================
+/// Report of the constraints that an evaluated schedule violates
+//
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConstraintReport {
+    pub violations: Vec<Violation>, // Violated constraints in order of the visits
+}
+
+//==============================================================================
+/// Implementation of `ConstraintReport`
+//
+#[allow(non_snake_case)]
+impl ConstraintReport {
+    // Constants
+    pub const TOLERANCE: f32 = 1e-3; // Violations up to the tolerance are rounding
+
+    //--------------------------------------------------------------------------
+    /// Check every constraint of an evaluated schedule. The spatial and
+    /// temporal ordering of the visits are derived from the schedule, the
+    /// remaining decision variables are checked as they are.
+    ///
+    /// # Input
+    /// * dat: Data object of the evaluated schedule
+    /// * ch: Charger object of the schedule
+    ///
+    /// # Output
+    /// * `ConstraintReport`: Violated constraints
+    ///
+    pub fn new(dat: &Data, ch: &Charger) -> ConstraintReport {
+        let N = dat.param.N;
+        let mut report = ConstraintReport::default();
+
+        // Derive the ordering of every pair of visits
+        let mut d = dat.clone();
+        let mut c = ch.clone();
+        for i in 0..N {
+            for j in 0..N {
+                SpaceTimeBigO::run(&mut d, &mut c, i, j);
+            }
+        }
+
+        for i in 0..N {
+            // Constraints of a single visit
+            report.check(
+                ConstraintKind::ServiceTime,
+                i,
+                None,
+                None,
+                ServiceTime::violation(&d, i, 0),
+            );
+            report.check(
+                ConstraintKind::ValidInitDepEndTimes,
+                i,
+                None,
+                None,
+                ValidInitDepEndTimes::violation(&d, i, 0),
+            );
+            report.check(
+                ConstraintKind::ChargePropagate,
+                i,
+                None,
+                None,
+                ChargePropagate::shared_violation(&d, &c, i),
+            );
+            report.check(
+                ConstraintKind::InitFinalCharge,
+                i,
+                None,
+                None,
+                InitFinalCharge::violation(&d, i, 0),
+            );
+            report.check(
+                ConstraintKind::GridCapacity,
+                i,
+                None,
+                None,
+                GridCapacity::shared_violation(&d, &c, i),
+            );
+
+            // Constraints between two visits
+            for j in 0..N {
+                if i == j {
+                    continue;
+                }
+
+                report.check(
+                    ConstraintKind::SpaceTimeBigO,
+                    i,
+                    Some(j),
+                    None,
+                    SpaceTimeBigO::violation(&d, i, j),
+                );
+
+                // The ordering constraints are symmetric
+                if i < j {
+                    report.check(
+                        ConstraintKind::PsiSigma,
+                        i,
+                        Some(j),
+                        None,
+                        PsiSigma::violation(&d, i, j),
+                    );
+                }
+            }
+        }
+
+        return report;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Indicate whether the schedule satisfies every constraint
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * bool: True if no constraint is violated
+    ///
+    pub fn is_feasible(self: &ConstraintReport) -> bool {
+        return self.violations.is_empty();
+    }
+
+    //--------------------------------------------------------------------------
+    /// Return the violations of a constraint
+    ///
+    /// # Input
+    /// * kind: Constraint of interest
+    ///
+    /// # Output
+    /// * violations: Violations of the constraint
+    ///
+    pub fn of(self: &ConstraintReport, kind: ConstraintKind) -> Vec<&Violation> {
+        return self.violations.iter().filter(|v| v.kind == kind).collect();
+    }
+
+    //--------------------------------------------------------------------------
+    /// Record a violation if the amount exceeds the tolerance
+    ///
+    /// # Input
+    /// * kind: Checked constraint
+    /// * i: Index of the visit or assignment
+    /// * j: Index of the other visit or assignment
+    /// * q: Charger queue
+    /// * amount: Amount by which the constraint is violated
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    pub fn check(
+        self: &mut ConstraintReport,
+        kind: ConstraintKind,
+        i: usize,
+        j: Option<usize>,
+        q: Option<usize>,
+        amount: f32,
+    ) {
+        if amount > ConstraintReport::TOLERANCE {
+            self.violations.push(Violation {
+                kind,
+                i,
+                j,
+                q,
+                amount,
+            });
+        }
+    }
+}
+
+//==============================================================================
+/// Implementation of Display for `ConstraintReport`, one line per violation
+//
+impl fmt::Display for ConstraintReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_feasible() {
+            return writeln!(f, "The schedule satisfies every constraint.");
+        }
+
+        writeln!(f, "{} constraint violations:", self.violations.len())?;
+        for v in self.violations.iter() {
+            // Assignments of the charger schedule are located by their queue
+            let item = if v.q.is_some() && v.kind != ConstraintKind::UnscheduledVisit {
+                "assignment"
+            } else {
+                "visit"
+            };
+
+            write!(f, "- {:?}: {} {}", v.kind, item, v.i)?;
+            if let Some(j) = v.j {
+                write!(f, " and {}", j)?;
+            }
+            if let Some(q) = v.q {
+                write!(f, " on queue {}", q)?;
+            }
+            writeln!(f, " by {}", v.amount)?;
+        }
+
+        return Ok(());
+    }
+}
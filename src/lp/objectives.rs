@@ -1,15 +1,305 @@
 //=========================================================================
 // Import modules
+use crate::error::Error;
 use crate::sa::charger::Charger;
-use crate::sa::data::Data;
+use crate::sa::data::{Data, DecisionVar};
 
 //===============================================================================
 // Declare modules
 pub mod std_obj;
+pub mod terms;
 
 //===============================================================================
-/// Trait to define `objective` interfaces
+/// Terms of the objective function by the name of the term. The objective
+/// function value is the weighted sum of the terms.
+//
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Breakdown {
+    pub terms: Vec<(String, f64)>, // Name and cost of each term
+}
+
+//===============================================================================
+/// Weights of the terms of the objective function by the name of the term
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct Weights {
+    pub terms: Vec<(String, f64)>, // Name and weight of each term
+}
+
+//===============================================================================
+/// Terms of an evaluated schedule that the objective function of a tweaked
+/// schedule is updated from
+//
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Evaluation {
+    pub J: f64,              // Objective function value
+    pub terms: Breakdown,    // Terms of the objective function
+    pub cost: Vec<Vec<f64>>, // Cost of each term for each visit, zero for the global terms
+    pub power: Vec<f64>,     // Power consumption at each discrete time step
+    pub window: Vec<f64>,    // Power consumption of the demand window before each time step
+    pub peak: f64,           // Peak of the power averaged over the demand window [KW]
+}
+
+//===============================================================================
+/// Trait to define a term of the objective function. A term either costs every
+/// visit on its own or the schedule as a whole, i.e. the demand charge of the
+/// peak power, and implements `visit` or `global` respectively.
+//
+pub trait Term {
+    //--------------------------------------------------------------------------
+    /// Name of the term. The weight of the term is set under this name in the
+    /// `objective` section of the schedule configuration.
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * name: Name of the term
+    ///
+    fn name(&self) -> &str;
+
+    //--------------------------------------------------------------------------
+    /// Cost of a visit of the schedule
+    ///
+    /// # Input
+    /// * dat: Data object containing the current charge schedule
+    /// * ch: Charger object
+    /// * i: Visit of interest
+    ///
+    /// # Output
+    /// * cost: Cost of the visit, zero for a global term
+    ///
+    fn visit(&self, _dat: &Data, _ch: &Charger, _i: usize) -> f64 {
+        return 0.0;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Cost of the schedule as a whole
+    ///
+    /// # Input
+    /// * dat: Data object containing the current charge schedule
+    /// * e: Evaluation of the schedule with the power consumption and peak
+    ///
+    /// # Output
+    /// * cost: Cost of the schedule, zero for a per-visit term
+    ///
+    fn global(&self, _dat: &Data, _e: &Evaluation) -> f64 {
+        return 0.0;
+    }
+}
+
+//===============================================================================
+/// Implementation of `Breakdown`
+//
+impl Breakdown {
+    //--------------------------------------------------------------------------
+    /// Cost of a term
+    ///
+    /// # Input
+    /// * name: Name of the term
+    ///
+    /// # Output
+    /// * cost: Cost of the term, zero if the objective function has no such
+    ///   term
+    ///
+    pub fn get(self: &Breakdown, name: &str) -> f64 {
+        return self.terms.iter().find(|t| t.0 == name).map_or(0.0, |t| t.1);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Sum the terms
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * J: Objective function value of the terms with unit weights
+    ///
+    pub fn total(self: &Breakdown) -> f64 {
+        return self.terms.iter().map(|t| t.1).sum();
+    }
+
+    //--------------------------------------------------------------------------
+    /// Sum the weighted terms
+    ///
+    /// # Input
+    /// * w: Weight of each term
+    ///
+    /// # Output
+    /// * J: Objective function value of the terms, a term without a weight has
+    ///   a unit weight
+    ///
+    pub fn weighted(self: &Breakdown, w: &Weights) -> f64 {
+        return self.terms.iter().map(|t| w.get(&t.0) * t.1).sum();
+    }
+
+    //--------------------------------------------------------------------------
+    /// Append the terms to the score history. A term that is not in the
+    /// history yet is added to it.
+    ///
+    /// # Input
+    /// * dec: Decision variables that hold the score history
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    pub fn record(self: &Breakdown, dec: &mut DecisionVar) {
+        for (name, x) in self.terms.iter() {
+            match dec.terms.iter_mut().find(|t| t.0 == *name) {
+                Some(t) => t.1.push(*x),
+                None => dec.terms.push((name.clone(), vec![*x])),
+            }
+        }
+    }
+}
+
+//===============================================================================
+/// Implementation of `Weights`
+//
+impl Weights {
+    //--------------------------------------------------------------------------
+    /// Weight of a term
+    ///
+    /// # Input
+    /// * name: Name of the term
+    ///
+    /// # Output
+    /// * w: Weight of the term, one if the term has no weight
+    ///
+    pub fn get(self: &Weights, name: &str) -> f64 {
+        return self.terms.iter().find(|t| t.0 == name).map_or(1.0, |t| t.1);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Set the weight of a term
+    ///
+    /// # Input
+    /// * name: Name of the term
+    /// * w: Weight of the term
+    ///
+    /// # Output
+    /// * Weights: The weights with the weight of the term replaced
+    ///
+    pub fn with(mut self: Weights, name: &str, w: f64) -> Weights {
+        match self.terms.iter_mut().find(|t| t.0 == name) {
+            Some(t) => t.1 = w,
+            None => self.terms.push((name.to_string(), w)),
+        }
+
+        return self;
+    }
+}
+
+//===============================================================================
+/// Implementation of `Evaluation`
+//
+impl Evaluation {
+    //--------------------------------------------------------------------------
+    /// Terms of the objective function of the schedule
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * Breakdown: Terms of the objective function of the schedule
+    ///
+    pub fn breakdown(self: &Evaluation) -> Breakdown {
+        return self.terms.clone();
+    }
+}
+
+//===============================================================================
+/// Implementation of `Default` for `Weights`. Every standard term has a unit
+/// weight.
+//
+impl Default for Weights {
+    fn default() -> Self {
+        return Weights {
+            terms: terms::NAMES.iter().map(|n| (n.to_string(), 1.0)).collect(),
+        };
+    }
+}
+
+//===============================================================================
+/// Trait to define `objective` interfaces. The solvers hold the objective as a
+/// `Box<dyn Objective>`, so an alternative formulation only has to implement
+/// `evaluate`.
 //
 pub trait Objective {
-    fn run(dat: &mut Data, ch: &mut Charger, run_constr: bool) -> (bool, f64);
+    //--------------------------------------------------------------------------
+    /// Evaluate the provided schedule
+    ///
+    /// # Input
+    /// * dat: Data object containing the current charge schedule
+    /// * ch: Charger object
+    /// * run_constr: Flag to indicate whether to run all of the constraints
+    ///
+    /// # Output
+    /// * Evaluation: Evaluation of the schedule, or an error if the schedule is
+    ///   infeasible
+    ///
+    fn evaluate(
+        &self,
+        dat: &mut Data,
+        ch: &mut Charger,
+        run_constr: bool,
+    ) -> Result<Evaluation, Error>;
+
+    //--------------------------------------------------------------------------
+    /// Evaluate a candidate schedule from the evaluation of the schedule it was
+    /// tweaked from. The candidate is evaluated in full unless the objective
+    /// updates the evaluation from the change log of the charger.
+    ///
+    /// # Input
+    /// * dat: Data object of the candidate schedule
+    /// * ch: Charger object of the candidate schedule, including its change log
+    /// * base: Evaluation of the schedule before the tweak
+    ///
+    /// # Output
+    /// * Evaluation: Evaluation of the candidate schedule, or an error if the
+    ///   schedule is infeasible
+    ///
+    fn run_delta(
+        &self,
+        dat: &mut Data,
+        ch: &mut Charger,
+        _base: &Evaluation,
+    ) -> Result<Evaluation, Error> {
+        return self.evaluate(dat, ch, false);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Calculates the objective function for the provided schedule.
+    ///
+    /// # Input
+    /// * dat: Data object containing the current charge schedule
+    /// * ch: Charger object
+    /// * run_constr: Flag to indicate whether to run all of the constraints
+    ///
+    /// # Output
+    /// * J: Objective function cost, or an error if the schedule is infeasible
+    ///
+    fn run(&self, dat: &mut Data, ch: &mut Charger, run_constr: bool) -> Result<f64, Error> {
+        return Ok(self.evaluate(dat, ch, run_constr)?.J);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Evaluate a schedule on its own charge schedule without changing it.
+    ///
+    /// # Input
+    /// * dat: Data object of the schedule
+    /// * ch: Charger object, the charge schedule is rebuilt from `dat`
+    ///
+    /// # Output
+    /// * Breakdown: Terms of the objective function, or an error if the
+    ///   schedule is infeasible
+    ///
+    fn breakdown(&self, dat: &Data, ch: &Charger) -> Result<Breakdown, Error> {
+        let mut d = dat.clone();
+        let mut c = ch.clone();
+        c.milp_to_schedule(&d);
+
+        return Ok(self.evaluate(&mut d, &mut c, false)?.breakdown());
+    }
 }
@@ -1,7 +1,11 @@
 //===============================================================================
 // Import developed modules
+use crate::error::Error;
 use crate::lp::constraints::constraints;
-use crate::lp::objectives::Objective;
+use crate::lp::objectives::terms::{
+    self, AssignmentCost, ConsumptionCost, DegradationCost, DemandCharge, SocPenalty,
+};
+use crate::lp::objectives::{Breakdown, Evaluation, Objective, Term, Weights};
 use crate::sa::charger::Charger;
 use crate::sa::data::Data;
 
@@ -11,9 +15,12 @@ use itertools_num::linspace;
 
 //===============================================================================
 /// Structure defining the data required to calculate the standard objective
-/// function for SA PAP
+/// function for SA PAP. The objective function value is the weighted sum of
+/// its terms.
 //
-pub struct StdObj {}
+pub struct StdObj {
+    pub terms: Vec<(f64, Box<dyn Term>)>, // Weight and term of each term of the objective function
+}
 
 //===============================================================================
 /// Implementation of the `StdObj` object. Contains helper functions for
@@ -22,85 +29,50 @@ pub struct StdObj {}
 #[allow(non_snake_case)]
 impl StdObj {
     //--------------------------------------------------------------------------
-    /// Calculates the assignment cost for the objective function
+    /// Initialize the standard objective function from the weights of the
+    /// standard terms
     ///
     /// # Input
-    /// * dat: Data object containing the current charge schedule
-    /// * i: Visit of interest
+    /// * weights: Weights of the terms of the objective function
     ///
     /// # Output
-    /// * AC: Assignment cost for the provided schedule
-    ///
-    fn AC(dat: &mut Data, i: usize) -> f64 {
-        // Extract input parameters
-        let G = &dat.param.Gam;
-        let ep = &dat.param.ep;
-        let r = &dat.param.r;
-        let nu = dat.param.nu;
-        let k = &dat.param.k;
-
-        // Extract decision variables
-        let v = dat.dec.v[i];
-        let eta = &dat.dec.eta;
-
-        // Calculate the penalty
-        let mut phi: f64 = 0.0;
-
-        // If the charge goes below the threshold
-        let c_dif = eta[i] - (nu * k[G[i] as usize]) as f32;
-        if c_dif < 0.0 {
-            // Calculate the penalty
-            let zp: f32 = 5000.0;
-
-            phi = (zp * f32::powf(c_dif, 2.0)) as f64;
+    /// * StdObj: Standard objective function, or an error if a weight does not
+    ///   belong to a standard term
+    ///
+    pub fn new(weights: &Weights) -> Result<StdObj, Error> {
+        let mut t: Vec<(f64, Box<dyn Term>)> = Vec::new();
+
+        for (name, w) in weights.terms.iter() {
+            match terms::from_name(name) {
+                Some(term) => t.push((*w, term)),
+                None => {
+                    return Err(Error::Invariant(format!(
+                        "std_obj.rs: There is no objective term named '{}'.",
+                        name
+                    )))
+                }
+            }
         }
 
-        // Calculate the assignment cost
-        return (ep[v] * r[v]) as f64 + phi;
+        return Ok(StdObj::with_terms(t));
     }
 
     //--------------------------------------------------------------------------
-    /// Calculates the utility cost for the objective function
+    /// Initialize the objective function from weighted terms
     ///
     /// # Input
-    /// * d: Data object containing the current charge schedule
-    /// * i: Visit of interest
-    /// * q: Charger queue of interest
+    /// * terms: Weight and term of each term of the objective function
     ///
     /// # Output
-    /// * UC: Assignment cost for the provided schedule
-    ///
-    fn UC(dat: &mut Data, i: usize) -> f64 {
-        // Extract decision variables
-        let s = dat.dec.s[i];
-        let v = dat.dec.v[i];
-
-        // Extract input parameters
-        let r = dat.param.r[v];
-
-        // Calculate the consumption cost
-        return (r * s) as f64;
-    }
-
-    //--------------------------------------------------------------------------
-    /// Calculates the demand cost for the usage cost
+    /// * StdObj: Objective function
     ///
-    /// # Input
-    /// * dat: Data structure for candidate schedule
-    /// * ch : Charger availability object for candidate schedule
-    ///
-    /// # Output
-    /// * pmax : Demand cost of the system
-    fn demand_cost(dat: &mut Data, ch: &Charger) -> f64 {
-        // Calculate vector of power consumption
-        let p: Vec<f64> = StdObj::calc_power_vec(dat, ch);
-
-        // Calculate the p15 and return the value
-        return StdObj::calc_p15(&p);
+    pub fn with_terms(terms: Vec<(f64, Box<dyn Term>)>) -> StdObj {
+        return StdObj { terms };
     }
 
     //--------------------------------------------------------------------------
-    /// Calculate the power vector over the time horizon.
+    /// Calculate the power vector over the time horizon. A charger queue that
+    /// shares the power of a cabinet draws its time-varying rate.
     ///
     /// # Input
     /// * dat: Data structure for candidate schedule
@@ -111,7 +83,7 @@ impl StdObj {
     ///
     fn calc_power_vec(dat: &Data, ch: &Charger) -> Vec<f64> {
         // Variables
-        let dt = 1.0 / 60.0; // Step size of one minute
+        let dt: f32 = 1.0 / 60.0; // Step size of one minute
         let H = (dat.param.T / dt) as usize; // Get the time horizon divided by the step size
         let mut p: Vec<f64> = vec![0.0; H]; // Track the power consumption at each discrete point
 
@@ -122,28 +94,11 @@ impl StdObj {
             .enumerate()
             .skip_while(|x| x.0 < ch.charger_count.0)
         {
-            // Get the charge rate
-            let rate: f32 = ch.get_charge_rate(i);
-
             // For every time slice in the schedule for charger for `q`
             for ts in q {
-                // Calculate the number of steps to take
-                let n: usize = ((ts.t.1 - ts.t.0) / dt) as usize;
-
-                // Create a vector of discrete time steps
-                //
-                // t = k*dt
-                // k = t/dt
-                //
-                for mut k in linspace::<f64>(ts.t.0 as f64, ts.t.1 as f64, n).map(|x| x / dt as f64)
-                {
-                    // Ensure the index is in the time horizon
-                    if k as usize >= p.len() {
-                        k = (p.len() - 1) as f64;
-                    }
-
-                    // Update the power usage for discrete time
-                    p[k as usize] += rate as f64;
+                // Update the power usage for discrete time
+                for (k, t) in StdObj::power_steps(ts.t, H) {
+                    p[k] += ch.rate_at(i, t) as f64;
                 }
             }
         }
@@ -152,117 +107,210 @@ impl StdObj {
     }
 
     //--------------------------------------------------------------------------
-    /// Calculate the p15 given the vector of discrete power consumption.
+    /// Discrete time steps at which a charge draws power.
+    ///
+    /// # Input
+    /// * t: Attach and detach time of the charge
+    /// * H: Number of discrete time steps in the time horizon
+    ///
+    /// # Output
+    /// * (k, t): Index of each discrete time step of the charge and the time
+    ///   its charge rate is taken at, half a step before the step so that the
+    ///   step at the detach time has the rate before the detach
+    ///
+    fn power_steps(t: (f32, f32), H: usize) -> impl Iterator<Item = (usize, f32)> {
+        let dt: f32 = 1.0 / 60.0; // Step size of one minute
+
+        // Calculate the number of steps to take
+        let n: usize = ((t.1 - t.0) / dt) as usize;
+
+        // Create a vector of discrete time steps
+        //
+        // t = k*dt
+        // k = t/dt
+        //
+        // Every step is kept in the time horizon
+        return linspace::<f64>(t.0 as f64, t.1 as f64, n).map(move |x| {
+            (
+                ((x / dt as f64) as usize).min(H - 1),
+                (x as f32 - dt / 2.0).max(t.0),
+            )
+        });
+    }
+
+    //--------------------------------------------------------------------------
+    /// Calculate the power consumed over the demand window before each
+    /// discrete time step.
     ///
     /// # Input
     /// * p: Vector of discrete power consumption
+    /// * W: Length of the demand window [steps]
     ///
     /// # Output
-    /// * p15: Peak 15 over the time horizon
+    /// * w: Power consumption of the preceding window, zero for the first
+    ///   window
     ///
-    fn calc_p15(p: &Vec<f64>) -> f64 {
-        // Calculate p15
-        let mut pmax: f64 = 0.0; // Maximum cost
+    fn calc_windows(p: &[f64], W: usize) -> Vec<f64> {
+        let mut w: Vec<f64> = vec![0.0; p.len()];
 
-        // For each visit that is after 15 minutes into the working day
-        for (i, _) in p.iter().enumerate().skip_while(|x| x.0 < 15) {
-            // Extract 15 minutes worth of power consumption and sum it
-            let slice: f64 = p[i - 15..i].into_iter().sum();
+        // For each time step that is a full window into the working day
+        for i in W..p.len() {
+            // Extract a window worth of power consumption and sum it
+            w[i] = p[i - W..i].iter().sum();
+        }
 
-            // If the slice is greater than pmax, update pmax
-            if slice > pmax {
-                pmax = 10000.0 * slice;
-            }
+        return w;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Calculate the peak power as the rolling maximum of the power averaged
+    /// over the demand window.
+    ///
+    /// # Input
+    /// * w: Power consumption of the window before each discrete time step
+    /// * W: Length of the demand window [steps]
+    ///
+    /// # Output
+    /// * peak: Peak power over the time horizon [KW]
+    ///
+    fn calc_peak(w: &[f64], W: usize) -> f64 {
+        let mut pmax: f64 = 0.0;
+
+        // For each full window
+        for slice in w.iter().skip(W) {
+            pmax = pmax.max(*slice);
         }
 
-        return pmax;
+        return pmax / W as f64;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Add the power of a charge to the power vector and update the demand
+    /// windows that contain it. A negative sign removes the charge.
+    ///
+    /// # Input
+    /// * e: Evaluation to update
+    /// * ch: Charger object
+    /// * q: Charger queue of the charge
+    /// * t: Attach and detach time of the charge
+    /// * sign: 1 to add the charge, -1 to remove it
+    /// * W: Length of the demand window [steps]
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    fn shift_power(e: &mut Evaluation, ch: &Charger, q: usize, t: (f32, f32), sign: f64, W: usize) {
+        let H = e.power.len();
+        let (mut lo, mut hi) = (H, 0);
+
+        // Update the power usage for discrete time
+        for (k, x) in StdObj::power_steps(t, H) {
+            e.power[k] += sign * ch.rate_at(q, x) as f64;
+            lo = lo.min(k);
+            hi = hi.max(k);
+        }
+
+        // Update the windows that contain a changed time step
+        for i in (lo + 1).max(W)..(hi + W + 1).min(H) {
+            e.window[i] = e.power[i - W..i].iter().sum();
+        }
     }
 
     //--------------------------------------------------------------------------
-    /// The run all constraints function does an exhaustive run of all the
-    /// constraints. This function exists to ensure for debugging purposes.
+    /// Calculate the charge and cost of a visit. The charge propagation may
+    /// shorten the charge, the previous attach and detach times of a shortened
+    /// charge are recorded.
     ///
     /// # Input
     /// * dat: Data object containing the current charge schedule
+    /// * ch: Charger object
     /// * i: Visit of interest
     /// * run_constr: Flag to indicate whether to run all of the constraints
+    /// * moved: Visits whose charge times changed and their previous times
     ///
     /// # Output
-    /// * (bool, f64): Flag to indicate success and the objective function score
+    /// * cost: Cost of each term for the visit, or an error if a constraint is
+    ///   violated
     ///
-    fn run_all_constr(dat: &mut Data, ch: &mut Charger, run_constr: bool) -> (bool, f64) {
+    fn visit(
+        self: &StdObj,
+        dat: &mut Data,
+        ch: &mut Charger,
+        i: usize,
+        run_constr: bool,
+        moved: &mut Vec<(usize, (f32, f32))>,
+    ) -> Result<Vec<f64>, Error> {
+        let ud = (dat.dec.u[i], dat.dec.d[i]);
+
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-        // Extract input parameters
-        let N = dat.param.N;
-        let mut J: f64 = 0.0;
-        let mut val_sched: bool = false;
+        // Calculate constraints
 
-        for i in 0..N {
-            for j in 0..N {
-                //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-                // Calculate constraints
-                val_sched = constraints::run(run_constr, dat, ch, i, j);
+        // The run all constraints option does an exhaustive run of all the
+        // constraints for debugging purposes
+        if run_constr {
+            for j in 0..dat.param.N {
+                constraints::run(run_constr, dat, ch, i, j)?;
             }
+        // Otherwise only the constraints required for the SA algorithm to
+        // function properly are run
+        } else {
+            constraints::run(run_constr, dat, ch, i, 0)?;
+        }
 
-            //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-            // Calculate the objective function
-            J += StdObj::AC(dat, i) + StdObj::UC(dat, i);
+        if ud != (dat.dec.u[i], dat.dec.d[i]) {
+            moved.push((i, ud));
         }
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-        // Calculate the demand cost
-        J += StdObj::demand_cost(dat, ch);
-
-        return (val_sched, J);
+        // Calculate the objective function
+        return Ok(self.terms.iter().map(|t| t.1.visit(dat, ch, i)).collect());
     }
 
     //--------------------------------------------------------------------------
-    /// The run limited constraint function executes only the required constraints
-    /// for the SA algorithm to function properly.
+    /// Move the charges that the charge propagation shortened so that the
+    /// power vector matches the charger schedule of the evaluated data, then
+    /// sum the visit costs and global cost of each term and weight them.
     ///
     /// # Input
-    /// * dat: Data object containing the current charge schedule
-    /// * i: Visit of interest
-    /// * run_constr: Flag to indicate whether to run all of the constraints
+    /// * e: Evaluation to complete
+    /// * dat: Evaluated data object
+    /// * ch: Charger object
+    /// * moved: Visits whose charge times changed and their previous times
     ///
     /// # Output
-    /// * (bool, f64): Flag to indicate success and the objective function score
+    /// * NONE
     ///
-    fn run_lim_constr(dat: &mut Data, ch: &mut Charger, run_constr: bool) -> (bool, f64) {
-        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-        // Extract input parameters
-        let N = dat.param.N;
-        let mut J: f64 = 0.0;
-        let mut val_sched: bool = false;
-
-        // Ensure charge schedule is valid
-        // for q in ch.schedule.iter().skip(dat.param.A - 1) {
-        //     for i in 1..q.len() {
-        //         if q[i - 1].t.0 <= q[i - 1].t.1 && q[i - 1].t.1 <= q[i].t.0 && q[i].t.0 <= q[i].t.1
-        //         {
-        //             continue;
-        //         }
-
-        //         return (val_sched, J);
-        //     }
-        // }
-
-        // Calculate charges and objective
-        for i in 0..N {
-            //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-            // Calculate constraints
-            val_sched = constraints::run(run_constr, dat, ch, i, 0);
-
-            //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-            // Calculate the objective function
-            J += StdObj::AC(dat, i) + StdObj::UC(dat, i);
+    fn finish(
+        self: &StdObj,
+        e: &mut Evaluation,
+        dat: &Data,
+        ch: &Charger,
+        moved: &[(usize, (f32, f32))],
+    ) {
+        let W = dat.param.demand.window;
+
+        // Update the power of the shortened charges on the charger queues
+        for (i, ud) in moved.iter() {
+            let q = dat.dec.v[*i];
+            if q >= ch.charger_count.0 && q < ch.schedule.len() {
+                StdObj::shift_power(e, ch, q, *ud, -1.0, W);
+                StdObj::shift_power(e, ch, q, (dat.dec.u[*i], dat.dec.d[*i]), 1.0, W);
+            }
         }
 
-        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-        // Calculate the demand cost
-        J += StdObj::demand_cost(dat, ch);
+        // Calculate the peak power
+        e.peak = StdObj::calc_peak(&e.window, W);
 
-        return (val_sched, J);
+        // Sum the terms
+        let mut J: f64 = 0.0;
+        let mut b = Breakdown::default();
+        for (k, (w, t)) in self.terms.iter().enumerate() {
+            let x = e.cost.iter().map(|c| c[k]).sum::<f64>() + t.global(dat, e);
+            J += w * x;
+            b.terms.push((t.name().to_string(), x));
+        }
+        e.terms = b;
+        e.J = J;
     }
 }
 
@@ -272,29 +320,129 @@ impl StdObj {
 #[allow(non_snake_case)]
 impl Objective for StdObj {
     //--------------------------------------------------------------------------
-    /// Calculates the objective function for the provided schedule.
+    /// Evaluate the provided schedule and keep the terms that candidate
+    /// schedules are evaluated from with `run_delta`.
     ///
     /// # Input
-    /// * d: Data object containing the current charge schedule
+    /// * dat: Data object containing the current charge schedule
     /// * ch: Charger object
     /// * run_constr: Flag to indicate whether to run all of the constraints
     ///
     /// # Output
-    /// * J: Objective function cost
+    /// * Evaluation: Evaluation of the schedule, or an error if the schedule is
+    ///   infeasible
     ///
-    fn run(dat: &mut Data, ch: &mut Charger, run_constr: bool) -> (bool, f64) {
-        // Variables
-        let J: f64;
-        let val_sched: bool;
+    fn evaluate(
+        self: &StdObj,
+        dat: &mut Data,
+        ch: &mut Charger,
+        run_constr: bool,
+    ) -> Result<Evaluation, Error> {
+        let mut moved: Vec<(usize, (f32, f32))> = Vec::new();
+        let mut e = Evaluation::default();
+
+        // Calculate charges and visit costs
+        for i in 0..dat.param.N {
+            let c = self.visit(dat, ch, i, run_constr, &mut moved)?;
+            e.cost.push(c);
+        }
 
-        // If all the constraints are to be ran
-        if run_constr {
-            (val_sched, J) = StdObj::run_all_constr(dat, ch, run_constr);
-        // Otherwise only a limited number of the constraints are required
-        } else {
-            (val_sched, J) = StdObj::run_lim_constr(dat, ch, run_constr);
+        // Calculate vector of power consumption
+        e.power = StdObj::calc_power_vec(dat, ch);
+        e.window = StdObj::calc_windows(&e.power, dat.param.demand.window);
+
+        self.finish(&mut e, dat, ch, &moved);
+
+        return Ok(e);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Evaluate a candidate schedule from the evaluation of the schedule it was
+    /// tweaked from. Only the charge intervals and visits in the change log of
+    /// the charger are updated: the power of the changed intervals and the SOC
+    /// of every following visit of the moved BEBs. The objective function value
+    /// is the same as that of `evaluate` without the packing constraints. The
+    /// rate of a queue that shares a cabinet depends on the charges of the
+    /// other queues of the cabinet, with charger cabinets the candidate is
+    /// evaluated in full.
+    ///
+    /// # Input
+    /// * dat: Data object of the candidate schedule
+    /// * ch: Charger object of the candidate schedule, including its change log
+    /// * base: Evaluation of the schedule before the tweak
+    ///
+    /// # Output
+    /// * Evaluation: Evaluation of the candidate schedule, or an error if the
+    ///   schedule is infeasible
+    ///
+    fn run_delta(
+        self: &StdObj,
+        dat: &mut Data,
+        ch: &mut Charger,
+        base: &Evaluation,
+    ) -> Result<Evaluation, Error> {
+        let mut moved: Vec<(usize, (f32, f32))> = Vec::new();
+        let mut e = base.clone();
+        let delta = ch.delta.clone();
+        let W = dat.param.demand.window;
+
+        // The charge of a shared queue depends on every charge of its cabinet
+        if !ch.cabinets.is_empty() {
+            return self.evaluate(dat, ch, false);
         }
 
-        return (val_sched, J);
+        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        // Update the power of the changed charge intervals
+        for (q, t) in delta.removed.iter().filter(|x| x.0 >= ch.charger_count.0) {
+            StdObj::shift_power(&mut e, ch, *q, *t, -1.0, W);
+        }
+        for (q, t) in delta.added.iter().filter(|x| x.0 >= ch.charger_count.0) {
+            StdObj::shift_power(&mut e, ch, *q, *t, 1.0, W);
+        }
+
+        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        // Propagate the charge from the first moved visit of each BEB
+        let mut visits = delta.visits.clone();
+        visits.sort();
+        let mut done: Vec<bool> = vec![false; dat.param.A];
+
+        for i in visits {
+            let b = dat.param.Gam[i] as usize;
+            if done[b] {
+                continue;
+            }
+            done[b] = true;
+
+            // Visit the remaining visits of the BEB in order
+            let mut j = i;
+            loop {
+                e.cost[j] = self.visit(dat, ch, j, false, &mut moved)?;
+
+                if dat.param.gam[j] < 0 {
+                    break;
+                }
+                j = dat.param.gam[j] as usize;
+            }
+        }
+
+        self.finish(&mut e, dat, ch, &moved);
+
+        return Ok(e);
+    }
+}
+
+//===============================================================================
+/// Implementation of `Default` for `StdObj`. Every standard term has a unit
+/// weight.
+//
+impl Default for StdObj {
+    fn default() -> Self {
+        return StdObj::with_terms(vec![
+            (1.0, Box::new(AssignmentCost {})),
+            (1.0, Box::new(ConsumptionCost {})),
+            (1.0, Box::new(SocPenalty {})),
+            (1.0, Box::new(DemandCharge {})),
+            (1.0, Box::new(DegradationCost {})),
+        ]);
     }
 }
@@ -0,0 +1,268 @@
+//===============================================================================
+// Import developed modules
+use crate::lp::constraints::dynamic::charge_propagation::ChargePropagate;
+use crate::lp::objectives::{Evaluation, Term};
+use crate::sa::charger::Charger;
+use crate::sa::data::Data;
+
+//===============================================================================
+// Static data
+pub static NAMES: [&str; 5] = ["AC", "UC", "phi", "demand", "DC"]; // Names of the standard terms
+
+//===============================================================================
+/// Assignment cost of the charger queue of each visit, without the SOC penalty
+//
+pub struct AssignmentCost {}
+
+//===============================================================================
+/// Consumption cost of the energy of each charge
+//
+pub struct ConsumptionCost {}
+
+//===============================================================================
+/// Penalty of the assignment cost for a charge below the minimum SOC
+//
+pub struct SocPenalty {}
+
+//===============================================================================
+/// Demand charge of the peak power of the schedule
+//
+pub struct DemandCharge {}
+
+//===============================================================================
+/// Battery degradation cost of each charge
+//
+pub struct DegradationCost {}
+
+//===============================================================================
+// PUBLIC
+
+//------------------------------------------------------------------------------
+/// Create a standard term from its name
+///
+/// # Input
+/// * name: Name of the term
+///
+/// # Output
+/// * Term: The term, or `None` if there is no standard term of that name
+///
+pub fn from_name(name: &str) -> Option<Box<dyn Term>> {
+    return match name {
+        "AC" => Some(Box::new(AssignmentCost {})),
+        "UC" => Some(Box::new(ConsumptionCost {})),
+        "phi" => Some(Box::new(SocPenalty {})),
+        "demand" => Some(Box::new(DemandCharge {})),
+        "DC" => Some(Box::new(DegradationCost {})),
+        _ => None,
+    };
+}
+
+//===============================================================================
+/// Implementation of `ConsumptionCost`
+//
+impl ConsumptionCost {
+    //--------------------------------------------------------------------------
+    /// Calculates the utility cost of a visit. The energy of the charge is
+    /// priced by the time-of-use tariff over the charge. A queue that shares
+    /// the power of a cabinet delivers less energy than its full rate, the
+    /// delivered energy is priced at the mean price over the charge.
+    ///
+    /// # Input
+    /// * dat: Data object containing the current charge schedule
+    /// * ch: Charger object
+    /// * i: Visit of interest
+    ///
+    /// # Output
+    /// * UC: Utility cost for the provided schedule
+    ///
+    pub fn cost(dat: &Data, ch: &Charger, i: usize) -> f64 {
+        // Extract decision variables
+        let s = dat.dec.s[i];
+        let u = dat.dec.u[i];
+        let v = dat.dec.v[i];
+
+        // Extract input parameters
+        let r = dat.param.r[v];
+
+        // Calculate the consumption cost at the full rate
+        let cost = r * dat.param.tariff.cost(u, s);
+
+        // Scale the cost to the energy delivered by a shared queue
+        let t = ch.effective_time(v, u, s);
+        if t == s {
+            return cost as f64;
+        }
+
+        return (cost * t / s) as f64;
+    }
+}
+
+//===============================================================================
+/// Implementation of `DegradationCost`
+//
+impl DegradationCost {
+    //--------------------------------------------------------------------------
+    /// Calculates the battery degradation cost of a visit. The charge of the
+    /// visit is a cycle that wears the battery by `DOD^exponent / cycles` of
+    /// its price. The SOC is taken to rise linearly during the charge, the
+    /// time the BEB spends at the depot above the high SOC is charged at the
+    /// degradation rate. The charge is delivered at the time-varying rate of
+    /// the charger queue.
+    ///
+    /// # Input
+    /// * dat: Data object containing the current charge schedule
+    /// * ch: Charger object
+    /// * i: Visit of interest
+    ///
+    /// # Output
+    /// * DC: Degradation cost of the visit
+    ///
+    pub fn cost(dat: &Data, ch: &Charger, i: usize) -> f64 {
+        // Extract input parameters
+        let deg = &dat.param.deg;
+        let b = dat.param.Gam[i] as usize;
+        let kappa = dat.param.k[b];
+
+        // Extract decision variables
+        let u = dat.dec.u[i];
+        let s = dat.dec.s[i];
+
+        // SOC on arrival and departure
+        let e0 = dat.dec.eta[i];
+        let e1 = (e0 + ChargePropagate::shared_charge(dat, ch, i)).min(kappa);
+
+        // Cycle wear
+        let price = deg.cost.get(b).copied().unwrap_or(0.0);
+        let dod = ((e1 - e0) / kappa).max(0.0);
+        let cycle = price * dod.powf(deg.exponent) / deg.cycles;
+
+        // Time above the high SOC
+        let high = deg.soc * kappa;
+        let t_high = if e1 <= high {
+            0.0
+        } else if e0 >= high {
+            dat.param.e[i] - dat.param.a[i]
+        } else {
+            dat.param.e[i] - (u + s * (high - e0) / (e1 - e0))
+        };
+
+        return (cycle + deg.rate * t_high.max(0.0)) as f64;
+    }
+}
+
+//===============================================================================
+/// Implementation of `Term` for `AssignmentCost`
+//
+impl Term for AssignmentCost {
+    //--------------------------------------------------------------------------
+    //
+    fn name(&self) -> &str {
+        return "AC";
+    }
+
+    //--------------------------------------------------------------------------
+    /// The assignment cost is the cost of the charger queue of the visit
+    //
+    fn visit(&self, dat: &Data, _ch: &Charger, i: usize) -> f64 {
+        // Extract input parameters
+        let ep = &dat.param.ep;
+        let r = &dat.param.r;
+
+        // Extract decision variables
+        let v = dat.dec.v[i];
+
+        // Calculate the assignment cost
+        return (ep[v] * r[v]) as f64;
+    }
+}
+
+//===============================================================================
+/// Implementation of `Term` for `ConsumptionCost`
+//
+impl Term for ConsumptionCost {
+    //--------------------------------------------------------------------------
+    //
+    fn name(&self) -> &str {
+        return "UC";
+    }
+
+    //--------------------------------------------------------------------------
+    //
+    fn visit(&self, dat: &Data, ch: &Charger, i: usize) -> f64 {
+        return ConsumptionCost::cost(dat, ch, i);
+    }
+}
+
+//===============================================================================
+/// Implementation of `Term` for `SocPenalty`
+//
+#[allow(non_snake_case)]
+impl Term for SocPenalty {
+    //--------------------------------------------------------------------------
+    //
+    fn name(&self) -> &str {
+        return "phi";
+    }
+
+    //--------------------------------------------------------------------------
+    /// The penalty grows with the square of the charge below the minimum SOC
+    //
+    fn visit(&self, dat: &Data, _ch: &Charger, i: usize) -> f64 {
+        // Extract input parameters
+        let G = &dat.param.Gam;
+        let nu = dat.param.nu;
+        let k = &dat.param.k;
+
+        // Extract decision variables
+        let eta = &dat.dec.eta;
+
+        // Calculate the penalty
+        let mut phi: f64 = 0.0;
+
+        // If the charge goes below the threshold
+        let c_dif = eta[i] - nu * k[G[i] as usize];
+        if c_dif < 0.0 {
+            // Calculate the penalty
+            let zp: f32 = 5000.0;
+
+            phi = (zp * f32::powf(c_dif, 2.0)) as f64;
+        }
+
+        return phi;
+    }
+}
+
+//===============================================================================
+/// Implementation of `Term` for `DemandCharge`
+//
+impl Term for DemandCharge {
+    //--------------------------------------------------------------------------
+    //
+    fn name(&self) -> &str {
+        return "demand";
+    }
+
+    //--------------------------------------------------------------------------
+    /// The demand charge is priced by the peak of the schedule
+    //
+    fn global(&self, dat: &Data, e: &Evaluation) -> f64 {
+        return dat.param.demand.cost(e.peak);
+    }
+}
+
+//===============================================================================
+/// Implementation of `Term` for `DegradationCost`
+//
+impl Term for DegradationCost {
+    //--------------------------------------------------------------------------
+    //
+    fn name(&self) -> &str {
+        return "DC";
+    }
+
+    //--------------------------------------------------------------------------
+    //
+    fn visit(&self, dat: &Data, ch: &Charger, i: usize) -> f64 {
+        return DegradationCost::cost(dat, ch, i);
+    }
+}
@@ -0,0 +1,335 @@
+//==============================================================================
+// Import standard library
+use csv::ReaderBuilder;
+
+//==============================================================================
+// Import developed modules
+use crate::error::Error;
+use crate::lp::constraints::dynamic::charge_propagation::ChargePropagate;
+use crate::lp::constraints::dynamic::init_final_charge::InitFinalCharge;
+use crate::lp::constraints::dynamic::scalar_to_vector_queue::ScalarToVectorQueue;
+use crate::lp::constraints::packing::grid_capacity::GridCapacity;
+use crate::lp::constraints::packing::service_time::ServiceTime;
+use crate::lp::constraints::packing::valid_init_dep_end_time::ValidInitDepEndTimes;
+use crate::lp::constraints::report::{ConstraintKind, ConstraintReport};
+use crate::lp::constraints::Constraint;
+use crate::sa::charger::{Assignment, Charger};
+use crate::sa::config::ConfigError;
+use crate::sa::data::Data;
+
+//==============================================================================
+/// Check a charge schedule without running the solver. The SOC of every visit
+/// is propagated from the initial charges and the schedule, the given `eta` is
+/// ignored. The following is checked:
+///
+/// - Every queue of the charger schedule is free of overlapping assignments
+/// - Every visit is in the charger schedule at its queue and times, and every
+///   assignment belongs to a visit
+/// - The attach/detach times lie inside [a, e]
+/// - The SOC on arrival is at least `nu * k` and the final SOC at least `beta * k`
+/// - The summed charge rate stays within the power limit of the grid connection
+/// - The queue `v`, queue vector `w` and times `u`, `d` and `s` are consistent
+///
+/// # Input
+/// * dat: Data object of the schedule
+/// * ch: Charger object of the schedule
+///
+/// # Output
+/// * `ConstraintReport`: Violated constraints
+///
+#[allow(non_snake_case)]
+pub fn validate(dat: &Data, ch: &Charger) -> ConstraintReport {
+    let N = dat.param.N;
+    let mut report = ConstraintReport::default();
+
+    // Propagate the SOC of the schedule
+    let d = propagate_charge(dat, ch);
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Visits
+
+    for i in 0..N {
+        let kappa = d.param.k[d.param.Gam[i] as usize];
+        let q = d.dec.v[i];
+
+        // The visit must be scheduled on a queue that exists
+        if q >= ch.schedule.len() || find(&ch.schedule[q], &d, i).is_none() {
+            report.check(ConstraintKind::UnscheduledVisit, i, None, Some(q), 1.0);
+        }
+
+        // Consistency of the decision variables
+        report.check(
+            ConstraintKind::ScalarToVectorQueue,
+            i,
+            None,
+            None,
+            ScalarToVectorQueue::violation(&d, i, 0),
+        );
+        report.check(
+            ConstraintKind::ServiceTime,
+            i,
+            None,
+            None,
+            ServiceTime::violation(&d, i, 0),
+        );
+
+        // Attach and detach times
+        report.check(
+            ConstraintKind::ValidInitDepEndTimes,
+            i,
+            None,
+            None,
+            ValidInitDepEndTimes::violation(&d, i, 0),
+        );
+
+        // Charge
+        report.check(
+            ConstraintKind::MinCharge,
+            i,
+            None,
+            None,
+            d.param.nu * kappa - d.dec.eta[i],
+        );
+        report.check(
+            ConstraintKind::InitFinalCharge,
+            i,
+            None,
+            None,
+            InitFinalCharge::violation(&d, i, 0),
+        );
+
+        // Grid connection
+        report.check(
+            ConstraintKind::GridCapacity,
+            i,
+            None,
+            None,
+            GridCapacity::shared_violation(&d, ch, i),
+        );
+    }
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Charger queues
+
+    for (q, queue) in ch.schedule.iter().enumerate() {
+        // Every assignment must belong to a visit
+        for (a, asg) in queue.iter().enumerate() {
+            let owned = (0..N).any(|i| d.dec.v[i] == q && matches(asg, &d, i));
+            if !owned {
+                report.check(ConstraintKind::UnknownAssignment, a, None, Some(q), 1.0);
+            }
+        }
+
+        // Check the assignments in order of their attach times
+        let mut order: Vec<usize> = (0..queue.len()).collect();
+        order.sort_by(|x, y| queue[*x].t.0.total_cmp(&queue[*y].t.0));
+        for w in order.windows(2) {
+            let overlap = queue[w[0]].t.1 - queue[w[1]].t.0;
+            report.check(
+                ConstraintKind::QueueOverlap,
+                w[0],
+                Some(w[1]),
+                Some(q),
+                overlap,
+            );
+        }
+    }
+
+    return report;
+}
+
+//------------------------------------------------------------------------------
+/// Load a charge schedule in the format of the `-schedule.csv` file written by
+/// `DataOutput`. Every row is a visit of the route and every bus has the columns
+/// `charger`, `u` and `s`, the cells of the other buses are empty (`nan`). A
+/// visit without a charge waits on the queue of its bus for the whole visit. The
+/// decision variables of `dat` and the schedule of `ch` are replaced.
+///
+/// # Input
+/// * path: Path to the schedule CSV
+/// * dat: Data object of the route
+/// * ch: Charger object of the route
+///
+/// # Output
+/// * NONE, or an error if the file could not be read or does not fit the route
+///
+#[allow(non_snake_case)]
+pub fn load_schedule(path: &str, dat: &mut Data, ch: &mut Charger) -> Result<(), Error> {
+    let A = dat.param.A;
+    let N = dat.param.N;
+    let Q = dat.param.Q;
+    let wait = ch.charger_count.0;
+    let err = |key: &str, message: String| {
+        return Error::Config(ConfigError {
+            file: path.to_string(),
+            key: key.to_string(),
+            message,
+        });
+    };
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Read the file
+
+    let mut rdr = ReaderBuilder::new()
+        .has_headers(true)
+        .from_path(path)
+        .map_err(|e| err("", e.to_string()))?;
+    let header: Vec<String> = rdr
+        .headers()
+        .map_err(|e| err("", e.to_string()))?
+        .iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+    if header.len() != 3 * A {
+        return Err(err(
+            "",
+            format!(
+                "The route has {} buses and needs {} columns, the file has {}.",
+                A,
+                3 * A,
+                header.len()
+            ),
+        ));
+    }
+
+    // Empty cells are `None`
+    let mut rows: Vec<Vec<Option<f32>>> = Vec::new();
+    for r in rdr.records() {
+        let r = r.map_err(|e| err("", e.to_string()))?;
+        let mut row: Vec<Option<f32>> = Vec::new();
+        for (c, x) in r.iter().map(|x| x.trim()).enumerate() {
+            let cell = match x {
+                "nan" | "" => None,
+                _ => match x.parse::<f32>() {
+                    Ok(f) if f.is_finite() => Some(f),
+                    _ => {
+                        return Err(err(
+                            &header[c],
+                            format!("Visit {} has an invalid value '{}'.", rows.len(), x),
+                        ))
+                    }
+                },
+            };
+            row.push(cell);
+        }
+        rows.push(row);
+    }
+    if rows.len() != N {
+        return Err(err(
+            "",
+            format!("The route has {} visits, the file has {}.", N, rows.len()),
+        ));
+    }
+
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Set the decision variables of every visit
+
+    for (i, row) in rows.iter().enumerate() {
+        let b = dat.param.Gam[i] as usize;
+
+        // Only the columns of the bus of the visit may be filled
+        if let Some(c) = (0..3 * A).find(|c| c / 3 != b && row[*c].is_some()) {
+            return Err(err(
+                &header[c],
+                format!("Visit {} belongs to bus {}.", i, b),
+            ));
+        }
+
+        let (v, u, s) = match (row[3 * b], row[3 * b + 1], row[3 * b + 2]) {
+            (None, None, None) => (b, dat.param.a[i], dat.param.e[i] - dat.param.a[i]),
+            (Some(q), Some(u), Some(s))
+                if q >= 0.0 && q.fract() == 0.0 && wait + (q as usize) < Q =>
+            {
+                (wait + q as usize, u, s)
+            }
+            _ => {
+                return Err(err(
+                    &header[3 * b],
+                    format!("Visit {} does not have a valid charger, u and s.", i),
+                ))
+            }
+        };
+
+        dat.dec.v[i] = v;
+        dat.dec.u[i] = u;
+        dat.dec.s[i] = s;
+        dat.dec.d[i] = u + s;
+        dat.dec.w[i].fill(false);
+        dat.dec.w[i][v] = true;
+    }
+
+    // Rebuild the charger schedule from the visits
+    ch.milp_to_schedule(dat);
+
+    return Ok(());
+}
+
+//==============================================================================
+// PRIVATE
+
+//------------------------------------------------------------------------------
+/// Propagate the SOC from the initial charges through the visits of every BEB.
+/// The battery does not charge beyond its capacity.
+///
+/// # Input
+/// * dat: Data object of the schedule
+/// * ch: Charger object of the schedule
+///
+/// # Output
+/// * d: Copy of `dat` with the propagated SOC
+///
+fn propagate_charge(dat: &Data, ch: &Charger) -> Data {
+    let mut d = dat.clone();
+
+    for i in 0..d.param.N {
+        let kappa = d.param.k[d.param.Gam[i] as usize];
+
+        // Initial charge
+        if d.param.alpha[i] > 0.0 {
+            d.dec.eta[i] = d.param.alpha[i] * kappa;
+        }
+
+        // Charge of the next visit
+        let gam = d.param.gam[i];
+        if gam >= 0 {
+            let soc = (d.dec.eta[i] + ChargePropagate::shared_charge(&d, ch, i)).min(kappa);
+            d.dec.eta[gam as usize] = soc - d.param.l[i];
+        }
+    }
+
+    return d;
+}
+
+//------------------------------------------------------------------------------
+/// Find the assignment of visit `i` in a charger queue
+///
+/// # Input
+/// * queue: Assignments of the charger queue
+/// * dat: Data object of the schedule
+/// * i: Index of the visit
+///
+/// # Output
+/// * Index of the assignment, `None` if it is missing
+///
+fn find(queue: &[Assignment], dat: &Data, i: usize) -> Option<usize> {
+    return queue.iter().position(|a| matches(a, dat, i));
+}
+
+//------------------------------------------------------------------------------
+/// Indicate whether an assignment is the charge of visit `i`
+///
+/// # Input
+/// * a: Assignment of a charger queue
+/// * dat: Data object of the schedule
+/// * i: Index of the visit
+///
+/// # Output
+/// * bool: True if the BEB and the attach/detach times match
+///
+fn matches(a: &Assignment, dat: &Data, i: usize) -> bool {
+    let tol = ConstraintReport::TOLERANCE;
+
+    return a.b == dat.param.Gam[i] as usize
+        && (a.t.0 - dat.dec.u[i]).abs() <= tol
+        && (a.t.1 - dat.dec.d[i]).abs() <= tol;
+}
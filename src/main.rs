@@ -3,26 +3,35 @@
 use indicatif::{MultiProgress, ProgressBar};
 use std::env;
 use std::thread;
-use yaml_rust::Yaml;
 
 //------------------------------------------------------------------------------
 // Import developed modules
+use sa_pap::error::Error;
+use sa_pap::lp::validate::{load_schedule, validate};
 use sa_pap::plotter;
+use sa_pap::sa::charger::Charger;
+use sa_pap::sa::config::general::GeneralConfig;
+use sa_pap::sa::config::schedule::ScheduleConfig;
+use sa_pap::sa::config::{ConfigError, SaConfig};
 use sa_pap::sa::generators::gen_new_visits::GenNewVisits;
 use sa_pap::sa::generators::gen_wait_queue::GenWaitQueue;
 use sa_pap::sa::generators::tweak_schedule::TweakSchedule;
 use sa_pap::sa::generators::tweak_schedule_quick::TweakScheduleQuick;
 use sa_pap::sa::generators::Generator;
+use sa_pap::sa::island::Island;
+use sa_pap::sa::mosa::MOSA;
+use sa_pap::sa::observers::json_lines_observer::JsonLinesObserver;
+use sa_pap::sa::observers::progress_bar_observer::ProgressBarObserver;
+use sa_pap::sa::observers::real_time_plot_observer::RealTimePlotObserver;
+use sa_pap::sa::observers::SaObserver;
+use sa_pap::sa::pt::PT;
 use sa_pap::sa::route::route_csv_generator::RouteCSVGenerator;
 use sa_pap::sa::route::route_rand_generator::RouteRandGenerator;
 use sa_pap::sa::route::Route;
-use sa_pap::sa::temp_func::{
-    CoolSchedule::Exponential, CoolSchedule::Geometric, CoolSchedule::Linear, TempFunc,
-};
-use sa_pap::sa::SA;
-use sa_pap::util::bool_util;
+use sa_pap::sa::temp_func::TempFunc;
+use sa_pap::sa::{Results, SA};
 use sa_pap::util::data_output::DataOutput;
-use sa_pap::util::fileio::yaml_loader;
+use sa_pap::util::rand_utils;
 
 //===============================================================================
 // FUNCTIONS
@@ -46,35 +55,65 @@ fn general_path() -> &'static str {
 }
 
 //------------------------------------------------------------------------------
+// Load and check the general and schedule configurations
 //
-fn execute(pb: &mut ProgressBar) {
-    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-    // Initialize
-
-    // Load in general YAML
-    let gen_config: Yaml = yaml_loader::load_yaml(general_path());
+fn load_configs() -> Result<(GeneralConfig, ScheduleConfig), ConfigError> {
+    let gen_config = GeneralConfig::load(general_path())?;
+    let schedule_config = ScheduleConfig::load(schedule_path())?;
 
-    // Load in schedule YAML
-    let schedule_config: Yaml = yaml_loader::load_yaml(schedule_path());
+    return Ok((gen_config, schedule_config));
+}
 
-    // Determine schedule type
-    let schedule_type = gen_config["schedule"].clone().into_string().unwrap();
+//------------------------------------------------------------------------------
+//
+fn observers(
+    gen_config: &GeneralConfig,
+    pb: &ProgressBar,
+    id: usize,
+) -> Result<Vec<Box<dyn SaObserver>>, Error> {
+    let mut obs: Vec<Box<dyn SaObserver>> = vec![Box::new(ProgressBarObserver::new(pb.clone()))];
+
+    // Plot the best solution in real time
+    if gen_config.rtp {
+        obs.push(Box::new(RealTimePlotObserver::new()));
+    }
 
-    // Determine solution generator
-    let sol_gen = gen_config["solution_gen"].clone().into_string().unwrap();
+    // Each thread logs its events to its own file
+    if !gen_config.event_log.is_empty() {
+        let path = format!("{}-{}.jsonl", gen_config.event_log, id);
+        match JsonLinesObserver::new(&path, gen_config.event_log_iterations) {
+            Ok(o) => obs.push(Box::new(o)),
+            Err(e) => {
+                return Err(Error::Io(format!(
+                    "Could not create the event log {}: {}",
+                    path, e
+                )))
+            }
+        }
+    }
 
-    // Determine schedule tweaker
-    let sched_tweaker = gen_config["tweak_method"].clone().into_string().unwrap();
+    return Ok(obs);
+}
 
-    // Decide to load previous run solution
-    let load_from_file: bool =
-        bool_util::i64_to_bool(gen_config["load_from_file"].clone().into_i64().unwrap());
+//------------------------------------------------------------------------------
+//
+fn execute(
+    pb: &mut ProgressBar,
+    id: usize,
+    exec: usize,
+    seed: u64,
+    island: Option<Island>,
+    gen_config: &GeneralConfig,
+    schedule_config: &ScheduleConfig,
+) {
+    //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Initialize
 
-    // Decide whether to plot results or not
-    let plot: bool = bool_util::i64_to_bool(gen_config["plot"].clone().into_i64().unwrap());
+    // Determine schedule type
+    let schedule_type = gen_config.schedule.as_str();
 
-    // Decide whether to display real time plot
-    let rtp: bool = bool_util::i64_to_bool(gen_config["rtp"].clone().into_i64().unwrap());
+    // Determine the solver
+    let solver = gen_config.solver.clone();
 
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Generate schedule
@@ -84,44 +123,36 @@ fn execute(pb: &mut ProgressBar) {
 
     // Run the schedule that was specified
     if schedule_type == "csv" {
-        gsys = Box::new(RouteCSVGenerator::new(
-            schedule_path(),
-            general_path(),
-            csv_path(),
-        ));
-    } else if schedule_type == "rand" {
-        // Create schedule generator
-        gsys = Box::new(RouteRandGenerator::new(load_from_file, schedule_path()));
+        gsys = match RouteCSVGenerator::from_config(gen_config, schedule_config, csv_path()) {
+            Ok(rg) => Box::new(rg),
+            Err(e) => {
+                eprintln!("Run {} failed: {}", id, e);
+                return;
+            }
+        };
     } else {
-        panic!("Unknown route generator specified in `general.yaml`!!!");
+        // Create schedule generator. The route is always generated, a resumed run restores its data from the
+        // checkpoint.
+        gsys = Box::new(RouteRandGenerator::from_config(false, schedule_config));
     }
 
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Create solution temperature function, generator and tweaker
 
-    // Get parameters
-    let temperature_func = schedule_config["temp"]["type"]
-        .clone()
-        .into_string()
-        .unwrap();
-    let init_temp = schedule_config["temp"]["init"].clone().into_f64().unwrap() as f32;
-    let delta = schedule_config["temp"]["delta"].clone().into_f64().unwrap() as f32;
-
-    // Create temperature function
-    let mut tf: Box<TempFunc>;
-    if temperature_func == "Geometric" {
-        tf = Box::new(TempFunc::new(Geometric, init_temp, delta, true));
-    } else if temperature_func == "Exponential" {
-        tf = Box::new(TempFunc::new(Exponential, init_temp, delta, true));
-    } else if temperature_func == "Linear" {
-        tf = Box::new(TempFunc::new(Linear, init_temp, delta, true));
-    } else {
-        panic!("Invalid temperature schedule provided!!!");
-    }
+    // Create temperature function. The initial temperature is a placeholder when it is set to "auto", SA estimates
+    // it from the initial solution.
+    let temp = &schedule_config.temp;
+    let mut tf: Box<TempFunc> = Box::new(TempFunc::fit(
+        temp.schedule.clone(),
+        temp.init,
+        temp.delta,
+        temp.steps,
+        true,
+    ));
 
     // Create solver
     let gsol: Box<dyn Generator>;
-    if sol_gen == "wait" {
+    if gen_config.solution_gen == "wait" {
         gsol = Box::new(GenWaitQueue::new());
     } else {
         gsol = Box::new(GenNewVisits::new());
@@ -129,51 +160,235 @@ fn execute(pb: &mut ProgressBar) {
 
     // Create tweaker
     let gtweak: Box<dyn Generator>;
-    if sched_tweaker == "quick" {
+    if gen_config.tweak_method == "quick" {
         gtweak = Box::new(TweakScheduleQuick::new());
     } else {
         gtweak = Box::new(TweakSchedule::new());
     }
 
     //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-    // Create SA object and run SA
-
-    // Pass schedule generator, temperature function, solution generator, and solution tweaker into the SA module
-    let mut sa: SA = SA::new(
-        general_path(),
-        schedule_path(),
-        gsol,
-        gsys,
-        gtweak,
-        &mut tf,
-        pb,
-    );
-
-    // Run simulated annealing simulation
-    if let Some(res) = sa.run(rtp, load_from_file) {
-        // Export results to CSV
-        DataOutput::output_data(String::from("sa"), res.clone(), None);
-
-        // Plot results
-        plotter::plot::run(plot, &mut res.data.clone());
+    // Create the solver and run it
+
+    let results: Result<Results, Error>;
+    if solver == "mosa" {
+        // Pass schedule generator, solution generator, and solution tweaker into the MOSA module
+        let mut mosa: MOSA =
+            match MOSA::with_config(gen_config, schedule_config, gsol, gsys, gtweak, seed) {
+                Ok(m) => m,
+                Err(e) => {
+                    eprintln!("Run {} failed: {}", id, e);
+                    return;
+                }
+            };
+
+        // Attach the observers of the run
+        match observers(gen_config, pb, id) {
+            Ok(obs) => {
+                for o in obs {
+                    mosa.add_observer(o);
+                }
+            }
+            Err(e) => {
+                eprintln!("Run {} failed: {}", id, e);
+                return;
+            }
+        }
+
+        // Export the Pareto front to CSV
+        match mosa.run() {
+            Ok(res) => {
+                if let Err(e) = DataOutput::output_front(solver, res, None) {
+                    eprintln!("Run {} failed: {}", id, e);
+                }
+            }
+            Err(e) => eprintln!("Run {} failed: {}", id, e),
+        }
+        return;
+    } else if solver == "pt" {
+        // Pass schedule generator, solution generator, and solution tweaker into the PT module
+        let mut pt: PT =
+            match PT::with_config(gen_config, schedule_config, gsol, gsys, gtweak, seed) {
+                Ok(p) => p,
+                Err(e) => {
+                    eprintln!("Run {} failed: {}", id, e);
+                    return;
+                }
+            };
+
+        // Attach the observers of the run
+        match observers(gen_config, pb, id) {
+            Ok(obs) => {
+                for o in obs {
+                    pt.add_observer(o);
+                }
+            }
+            Err(e) => {
+                eprintln!("Run {} failed: {}", id, e);
+                return;
+            }
+        }
+
+        // Run parallel tempering
+        results = pt.run();
+    } else {
+        // Pass schedule generator, temperature function, solution generator, and solution tweaker into the SA
+        // module
+        let mut sa: SA = match SA::with_config(
+            SaConfig::new(gen_config, schedule_config),
+            schedule_config.chargers.clone(),
+            gsol,
+            gsys,
+            gtweak,
+            &mut tf,
+            seed,
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("Run {} failed: {}", id, e);
+                return;
+            }
+        };
+
+        // Attach the observers of the run
+        match observers(gen_config, pb, id) {
+            Ok(obs) => {
+                for o in obs {
+                    sa.add_observer(o);
+                }
+            }
+            Err(e) => {
+                eprintln!("Run {} failed: {}", id, e);
+                return;
+            }
+        }
+
+        // Each execution of each thread keeps its own checkpoint
+        sa.set_checkpoint_path(&format!(
+            "{}-{}-{}.yaml",
+            gen_config.checkpoint_path, id, exec
+        ));
+
+        // Share the best solution with the other threads
+        if let Some(island) = island {
+            sa.set_island(island);
+        }
+
+        // Run simulated annealing simulation
+        results = sa.run(gen_config.load_from_file);
+    }
+
+    match results {
+        // Only a schedule that improved on the initial solution is exported
+        Ok(res) if !res.improved => {
+            eprintln!(
+                "Run {} did not improve on the initial solution ({:?})",
+                id, res.stop
+            );
+        }
+        Ok(res) => {
+            // Export results to CSV
+            if let Err(e) = DataOutput::output_data(solver, res.clone(), None) {
+                eprintln!("Run {} failed: {}", id, e);
+                return;
+            }
+
+            // Plot results
+            plotter::plot::run(gen_config.plot, &mut res.data.clone());
+        }
+        Err(e) => eprintln!("Run {} failed: {}", id, e),
     }
 }
 
+//------------------------------------------------------------------------------
+// Check a schedule CSV written by `DataOutput` against the routes of a route CSV and print the violated constraints.
+// The exit code is 0 if the schedule is feasible, 1 if it is not and 2 if the configuration or the files could not be
+// loaded.
+//
+fn validate_schedule(
+    path: &str,
+    route_path: &str,
+    gen_config: &GeneralConfig,
+    schedule_config: &ScheduleConfig,
+) -> i32 {
+    // Load the route
+    let mut gsys = match RouteCSVGenerator::from_config(gen_config, schedule_config, route_path) {
+        Ok(g) => g,
+        Err(e) => {
+            eprintln!("Could not load the route: {}", e);
+            return 2;
+        }
+    };
+    if let Err(e) = gsys.run(&mut rand_utils::seeded_rng(0)) {
+        eprintln!("Could not load the route: {}", e);
+        return 2;
+    }
+    let mut dat = *gsys.get_data();
+
+    // Create the chargers of the route
+    let mut chargers = schedule_config.chargers.clone();
+    chargers.count.0 = dat.param.A;
+    let mut charger = Charger::from_config(&chargers, &schedule_config.time);
+
+    // Load the schedule
+    if let Err(e) = load_schedule(path, &mut dat, &mut charger) {
+        eprintln!("Could not load the schedule: {}", e);
+        return 2;
+    }
+
+    // Check the schedule
+    let report = validate(&dat, &charger);
+    print!("{}", report);
+
+    return if report.is_feasible() { 0 } else { 1 };
+}
+
 //===============================================================================
 // MAIN
 fn main() {
     //--------------------------------------------------------------------------
     // Initialize
 
-    // Load in general YAML
-    let gen_config: Yaml = yaml_loader::load_yaml(general_path());
-
-    // Get number of cores
-    let cores = gen_config["cores"].clone().into_i64().unwrap() as usize;
-
     // Read input parameters
     let args: Vec<String> = env::args().collect();
 
+    // Check a schedule instead of solving: `sa_pap validate <schedule.csv> [routes.csv]`. An invalid configuration is
+    // bad input, not an infeasible schedule.
+    if args.len() > 1 && args[1] == "validate" {
+        let path = match args.get(2) {
+            Some(p) => p.clone(),
+            None => {
+                eprintln!("Usage: sa_pap validate <schedule.csv> [routes.csv]");
+                std::process::exit(2);
+            }
+        };
+        let route_path = args.get(3).map_or(csv_path(), |p| p.as_str());
+        let (gen_config, schedule_config) = match load_configs() {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("Invalid configuration: {}", e);
+                std::process::exit(2);
+            }
+        };
+        std::process::exit(validate_schedule(
+            &path,
+            route_path,
+            &gen_config,
+            &schedule_config,
+        ));
+    }
+
+    // Load and check the configuration, an invalid file stops the program before any thread is started
+    let (gen_config, schedule_config) = match load_configs() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Invalid configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Get number of cores
+    let cores = gen_config.cores;
+
     // Default loop count to 4 * core count
     let mut loop_cnt: usize = 4 * cores;
 
@@ -183,6 +398,18 @@ fn main() {
         loop_cnt = args[1].parse::<usize>().unwrap();
     }
 
+    // Base seed of the random number generator, a negative value draws a random seed
+    let mut seed: u64 = gen_config.seed.unwrap_or_else(rand_utils::entropy_seed);
+
+    // If the seed was passed as an argument
+    if args.len() > 2 {
+        // Use that value instead
+        seed = args[2].parse::<u64>().unwrap();
+    }
+
+    // Report the seed so that the run can be reproduced
+    println!("Seed: {}", seed);
+
     // Create vector of threads
     let mut thread_handle = Vec::<std::thread::JoinHandle<()>>::new();
 
@@ -192,16 +419,46 @@ fn main() {
     // Create multiple progress bars
     let m = MultiProgress::new();
 
+    // Temperature steps between the threads sharing their best solution, 0 runs independent threads
+    let island_interval = gen_config.island_interval;
+    let crossover = gen_config.island_adopt == "crossover";
+
+    // The i-th execution of every thread belongs to the same group of islands
+    let islands: Vec<Island> = (0..loop_cnt)
+        .map(|_| Island::new(island_interval, crossover))
+        .collect();
+
     // Set the progress bar delay
     let delay = 3;
 
     //--------------------------------------------------------------------------
     // Execute the algorithm N times with M threads
-    for _ in 0..cores {
+    for id in 0..cores {
         let mut pb = m.add(ProgressBar::new(0));
+        let islands = islands.clone();
+        let gen_config = gen_config.clone();
+        let schedule_config = schedule_config.clone();
         let handle = thread::spawn(move || {
-            for _ in 0..loop_cnt {
-                execute(&mut pb);
+            for (i, island) in islands.iter().enumerate() {
+                // Each execution derives its own seed from the base seed
+                let run_seed = rand_utils::derive_seed(seed, (id * loop_cnt + i) as u64);
+
+                // Connect the execution to the other threads if the island model is enabled
+                let island = if island_interval > 0 {
+                    Some(island.clone())
+                } else {
+                    None
+                };
+
+                execute(
+                    &mut pb,
+                    id,
+                    i,
+                    run_seed,
+                    island,
+                    &gen_config,
+                    &schedule_config,
+                );
 
                 // Add delay to next execution
                 thread::sleep(std::time::Duration::from_secs(delay));
@@ -3,36 +3,43 @@
 //==============================================================================
 // Declare submodules
 pub mod charger; // Parameters and decision variables
+pub mod checkpoint; // Save and restore the state of a run
+pub mod config; // Typed settings of a run
 pub mod data; // Parameters and decision variables
 pub mod generators; // Pool of all the SA generators
+pub mod island; // Share the best solution between SA instances
+pub mod mosa; // Multi-objective simulated annealing (Pareto archive)
+pub mod observers; // Observe the progress of a run
+pub mod pt; // Parallel tempering (replica exchange)
 pub mod route; // Pool of all the route generators
+pub mod stop; // Criteria to stop a run early
 pub mod temp_func; // Temperature functions
 
 //==============================================================================
 // Import standard library
-use gnuplot::Figure;
-use indicatif::{ProgressBar, ProgressStyle};
-use rand::{thread_rng, Rng};
-use std::time::{Duration, Instant};
-use yaml_rust::Yaml;
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::time::Instant;
 
 //==============================================================================
 // Import modules
 use self::temp_func::TempFunc;
+use crate::error::Error;
 use crate::lp::objectives::std_obj::StdObj;
-use crate::lp::objectives::Objective;
-use crate::plotter::accumulated_energy_usage_plot::AccumulatedEnergyUsagePlot;
-use crate::plotter::charge_plot::ChargePlot;
-use crate::plotter::charger_usage_plot::ChargerUsagePlot;
-use crate::plotter::power_usage_plot::PowerUsagePlot;
-use crate::plotter::schedule_plot::SchedulePlot;
-use crate::plotter::score_plot::ScorePlot;
-use crate::plotter::Plotter;
+use crate::lp::objectives::{Breakdown, Evaluation, Objective};
 use crate::sa::charger::Charger;
+use crate::sa::checkpoint::Checkpoint;
+use crate::sa::config::general::GeneralConfig;
+use crate::sa::config::schedule::ScheduleConfig;
+use crate::sa::config::{ChargerConfig, SaConfig, Scale, TempConfig};
 use crate::sa::data::Data;
 use crate::sa::generators::Generator;
+use crate::sa::island::Island;
+use crate::sa::observers::{Fallback, SaObserver, TempStep};
+use crate::sa::pt::ReplicaStats;
 use crate::sa::route::Route;
-use crate::util::fileio::yaml_loader;
+use crate::sa::stop::{StopCriteria, StopReason};
+use crate::util::rand_utils;
 
 //==============================================================================
 /// Results from simulated annealing
@@ -40,8 +47,15 @@ use crate::util::fileio::yaml_loader;
 #[derive(Clone)]
 pub struct Results {
     pub score: f64,
+    pub terms: Breakdown,
     pub data: Box<Data>,
     pub charger: Box<Charger>,
+    pub seed: u64,
+    pub scale: f64,
+    pub T0: f32,
+    pub replicas: Vec<ReplicaStats>,
+    pub stop: StopReason,
+    pub improved: bool, // The best solution improved on the initial solution
 }
 
 //==============================================================================
@@ -49,15 +63,18 @@ pub struct Results {
 //
 #[allow(dead_code)]
 pub struct SA<'a> {
-    gsol: Box<dyn Generator>,   // Solution generator
-    gsys: Box<dyn Route>,       // Route generator
-    gtweak: Box<dyn Generator>, // Solution modifier
-    charger: Box<Charger>,      // Charge schedule keeper
-    tf: &'a mut Box<TempFunc>,  // Cooling Schedule
-    schedule_path: &'a str,     // Path to simulation configuration file
-    config_path: &'a str,       // Path to general configuration file
-    sol_found: bool,            // Indicates whether a solution was found
-    pb: &'a ProgressBar,        // Progress Bar for this thread
+    gsol: Box<dyn Generator>,            // Solution generator
+    gsys: Box<dyn Route>,                // Route generator
+    gtweak: Box<dyn Generator>,          // Solution modifier
+    charger: Box<Charger>,               // Charge schedule keeper
+    objective: Box<dyn Objective>,       // Objective function
+    tf: &'a mut Box<TempFunc>,           // Cooling Schedule
+    config: SaConfig,                    // Settings of the run
+    island: Option<Island>,              // Islands to share the best solution with
+    seed: u64,                           // Seed of the random number generator
+    scale: f64,                          // Scale of the Metropolis acceptance criterion
+    rng: StdRng,                         // Random number generator of the run
+    observers: Vec<Box<dyn SaObserver>>, // Observers of the progress of the run
 }
 
 //==============================================================================
@@ -69,75 +86,174 @@ impl<'a> SA<'a> {
     //==========================================================================
 
     //--------------------------------------------------------------------------
-    /// Initialize the SA object
+    /// Initialize the SA object from the configuration files
     ///
     /// # Input
     /// * `config_path` : String of relative path to configuration file
+    /// * `schedule_path` : String of relative path to schedule file
     /// * `gsol`        : Solution generator
     /// * `gsys`        : Route generator
     /// * `gtweak`      : Tweak schedule
     /// * `tf`          : The temperature function to use
+    /// * `seed`        : Seed of the random number generator
     ///
     /// # Output
-    /// * `Some(Results)`: Results of simulation (if there is any)
+    /// * `SA`: Simulated annealing solver, or an error if a configuration file
+    ///   is invalid or the route could not be generated
     ///
     pub fn new(
-        config_path: &'a str,
-        schedule_path: &'a str,
+        config_path: &str,
+        schedule_path: &str,
+        gsol: Box<dyn Generator>,
+        gsys: Box<dyn Route>,
+        gtweak: Box<dyn Generator>,
+        tf: &'a mut Box<TempFunc>,
+        seed: u64,
+    ) -> Result<SA<'a>, Error> {
+        let sched = ScheduleConfig::load(schedule_path)?;
+        let config = GeneralConfig::load(config_path)?;
+
+        return SA::with_config(
+            SaConfig::new(&config, &sched),
+            sched.chargers.clone(),
+            gsol,
+            gsys,
+            gtweak,
+            tf,
+            seed,
+        );
+    }
+
+    //--------------------------------------------------------------------------
+    /// Initialize the SA object from in-memory settings. The route generator
+    /// may be a `RouteData` to solve an in-memory `Data` without reading any
+    /// file. There is a wait charger for every bus of the route, the wait
+    /// charger count of `charger` is ignored.
+    ///
+    /// # Input
+    /// * `config`  : Settings of the run
+    /// * `charger` : Charger counts and speeds
+    /// * `gsol`    : Solution generator
+    /// * `gsys`    : Route generator
+    /// * `gtweak`  : Tweak schedule
+    /// * `tf`      : The temperature function to use
+    /// * `seed`    : Seed of the random number generator
+    ///
+    /// # Output
+    /// * `SA`: Simulated annealing solver, or an error if the route could not
+    ///   be generated
+    ///
+    pub fn with_config(
+        config: SaConfig,
+        mut charger: ChargerConfig,
         gsol: Box<dyn Generator>,
         mut gsys: Box<dyn Route>,
         gtweak: Box<dyn Generator>,
         tf: &'a mut Box<TempFunc>,
-        pb: &'a mut ProgressBar,
-    ) -> SA<'a> {
+        seed: u64,
+    ) -> Result<SA<'a>, Error> {
+        // Create the random number generator. Every random choice of the run is drawn from it.
+        let mut rng = rand_utils::seeded_rng(seed);
+
         // Generate new solution
-        gsys.run();
+        gsys.run(&mut rng)?;
 
         // Extract BEB count
-        let A = Some(gsys.get_data().param.A);
+        charger.count.0 = gsys.get_data().param.A;
 
         // Create SA object
         let sa: SA = SA {
             gsol,
             gsys,
             gtweak,
-            charger: Box::new(Charger::new(schedule_path, true, A, None)),
+            charger: Box::new(Charger::from_config(&charger, &config.time)),
+            objective: Box::new(StdObj::new(&config.weights)?),
             tf,
-            config_path,
-            schedule_path,
-            sol_found: false,
-            pb,
+            config,
+            island: None,
+            seed,
+            rng,
+            scale: TempConfig::DEFAULT_SCALE,
+            observers: Vec::new(),
         };
 
-        return sa;
+        return Ok(sa);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Set the path of the checkpoint file. If no path is set, the path is
+    /// the `checkpoint_path` of the settings of the run.
+    ///
+    /// # Input
+    /// * path: Path of the checkpoint file
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    pub fn set_checkpoint_path(self: &mut SA<'a>, path: &str) {
+        self.config.checkpoint_path = path.to_string();
+    }
+
+    //--------------------------------------------------------------------------
+    /// Set the objective function of the run. By default the run minimizes the
+    /// standard objective function with the weights of the settings.
+    ///
+    /// # Input
+    /// * objective: Objective function to minimize
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    pub fn set_objective(self: &mut SA<'a>, objective: Box<dyn Objective>) {
+        self.objective = objective;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Add an observer of the progress of the run, i.e. a progress bar, real
+    /// time plots or an event log.
+    ///
+    /// # Input
+    /// * observer: Observer to notify of the events of the run
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    pub fn add_observer(self: &mut SA<'a>, observer: Box<dyn SaObserver>) {
+        self.observers.push(observer);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Connect the SA instance to a group of islands. Every island shares its
+    /// best solution with the group and adopts the best solution of the group,
+    /// or a crossover of the two, if its own best solution is worse.
+    ///
+    /// # Input
+    /// * island: Island of the group
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    pub fn set_island(self: &mut SA<'a>, island: Island) {
+        self.island = Some(island);
     }
 
     //--------------------------------------------------------------------------
     /// Initialize and run the SA algorithm
     ///
     /// # Input
-    /// * lff: Load previous results from file
+    /// * lff: Resume the run from the checkpoint file if it exists
     ///
     /// # Output
-    /// * `Results`: Output of SA algorithm
+    /// * `Results`: Output of SA algorithm, or an error if an evaluation or a
+    ///   checkpoint file operation failed. `improved` is false if the best
+    ///   solution did not change.
     ///
-    pub fn run(self: &mut SA<'a>, rtp: bool, _lff: bool) -> Option<Results> {
+    pub fn run(self: &mut SA<'a>, lff: bool) -> Result<Results, Error> {
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Initialize
 
-        // Create real time figures
-        let mut fg_acc = Figure::new();
-        let mut fg_charge = Figure::new();
-        let mut fg_cu = Figure::new();
-        let mut fg_power = Figure::new();
-        let mut fg_score = Figure::new();
-        let mut fg_schedule = Figure::new();
-
-        // Create progress bar and set style
-        self.pb
-            .set_length(self.tf.get_temp_vec().unwrap().len() as u64);
-        self.pb
-            .set_style(ProgressStyle::with_template("{prefix}|{wide_bar} {pos}/{len}").unwrap());
+        // Start the wall clock of the run
+        let run_start = Instant::now();
 
         // Extract solution sets
         let mut sol_current = *self.gsys.get_data();
@@ -146,9 +262,23 @@ impl<'a> SA<'a> {
         let mut sol_scores = *self.gsys.get_data();
 
         // Set local search iteration count
-        let sched: Yaml = yaml_loader::load_yaml(self.schedule_path);
-        let config: Yaml = yaml_loader::load_yaml(self.config_path);
-        let k = sched["time"]["K"].clone().into_i64().unwrap();
+        let k = self.config.time.k;
+
+        // Adaptive local search length. If `search.accept` is set, the local search runs until that many moves are
+        // accepted, with at least `search.min` and at most `search.max` tweaks. Otherwise it runs `time.K` tweaks.
+        let k_accept = self.config.search.accept;
+        let k_min = self.config.search.min;
+        let k_max = self.config.search.max;
+        let search_done = |k_step: usize, accepted: usize| -> bool {
+            if k_accept == 0 {
+                return k_step >= k;
+            }
+            return k_step >= k_max || (k_step >= k_min && accepted >= k_accept);
+        };
+
+        // Set the checkpoint file and the number of temperature steps between checkpoints
+        let ckpt_path: String = self.config.checkpoint_path.clone();
+        let ckpt_interval = self.config.checkpoint_interval;
 
         // Create objective function variables
         let mut J0: f64;
@@ -156,50 +286,230 @@ impl<'a> SA<'a> {
         let mut JB: f64;
         let JORIG: f64;
 
+        // Evaluations of the current and best solutions that the candidates are evaluated from. Both are
+        // calculated in full at the start of the local search, and again when the solutions are replaced
+        // outside of it, so a rejected candidate always returns to an evaluated solution.
+        let mut eval_current: Option<Evaluation> = None;
+        let mut eval_best: Option<Evaluation> = None;
+
+        // Reheat policy. Reheating is disabled unless `reheat.steps` is set.
+        let reheat_steps = self.config.reheat.steps;
+        let reheat_fraction = self.config.reheat.fraction;
+        let reheat_max = self.config.reheat.max;
+        let reheat_improve = self.config.reheat.improve;
+        let reheat_perturb = self.config.reheat.perturb;
+
+        // Number of reheats and temperature steps without improving the best solution
+        let mut reheats: usize;
+        let mut stall: usize;
+
+        // Criteria to stop the run before the temperature schedule is completed
+        let stop_criteria: StopCriteria = self.config.stop.clone();
+        let mut stop: Option<StopReason> = None;
+
+        // Number of objective evaluations and temperature steps since the best solution improved
+        let mut evaluations: usize;
+        let mut since_best: usize;
+
+        // Index of the first temperature step to execute
+        let step_start: usize;
+
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Execute SA
 
         // Decide whether to run all the constraints or not
-        let run_all_constr = config["run_all_constr"].clone().into_bool().unwrap();
+        let run_all_constr = self.config.run_all_constr;
 
-        // Generate new solution
-        self.gsol.run(&mut self.gsys, &mut self.charger);
+        // Decide whether to estimate the initial temperature
+        let auto_temp = self.config.temp.auto;
+
+        // Load the checkpoint if the run is to be resumed
+        let ckpt: Option<Checkpoint> = if lff {
+            Checkpoint::load(&ckpt_path)?
+        } else {
+            None
+        };
+
+        // If a checkpoint was loaded, resume from it
+        if let Some(c) = ckpt {
+            // Continue with the seed of the interrupted run, a random seed would be drawn anew
+            self.seed = c.seed;
 
-        // Extract new data set and initialize new solution as best solution
-        sol_new = *self.gsys.get_data();
-        sol_best = *self.gsys.get_data();
+            // Restore the solution sets
+            sol_best = c.sol_best;
+            sol_current = c.sol_current;
+
+            // Restore the system and charger to the current solution
+            self.gsys.set_data(Box::new(sol_current.clone()));
+            self.charger.set_schedule(c.schedule)?;
 
-        // Calculate objective function
-        (self.sol_found, J0) = StdObj::run(&mut sol_new, &mut self.charger, run_all_constr);
+            // Restore the objective function values
+            J0 = c.J0;
+            J1 = c.J1;
+            JB = c.JB;
+            JORIG = c.JORIG;
+            self.scale = c.scale;
 
-        // Initialize the current and best solution to the initially generated solution
-        JB = J0;
-        JORIG = J0;
-        self.update_current_values(&mut sol_current, &mut sol_new);
+            // Restore the temperatures, they may have been estimated, adapted or reheated during the run
+            self.tf.set_temp_vec(c.temps, c.origin);
 
-        // Initialize solution scores
-        sol_scores.dec.Jb.push(JB);
-        sol_scores.dec.Jc.push(JB);
-        sol_scores.dec.Jn.push(JB);
+            // Restore solution scores
+            sol_scores.dec.Jb = c.Jb;
+            sol_scores.dec.Jc = c.Jc;
+            sol_scores.dec.Jn = c.Jn;
+            sol_scores.dec.terms = c.terms;
+            sol_scores.dec.reheat = c.reheat;
+            sol_scores.dec.K = c.K;
+
+            // Restore the reheat state
+            reheats = *sol_scores.dec.reheat.last().unwrap();
+            stall = c.stall;
+
+            // Restore the stop state
+            evaluations = c.evaluations;
+            since_best = c.since_best;
+
+            // Continue after the last completed temperature step
+            step_start = c.step + 1;
+        // Otherwise start a new run
+        } else {
+            // Generate new solution
+            self.gsol
+                .run(&mut self.gsys, &mut self.charger, &mut self.rng)?;
+
+            // Extract new data set and initialize new solution as best solution
+            sol_new = *self.gsys.get_data();
+            sol_best = *self.gsys.get_data();
+
+            // Calculate objective function
+            J0 = self
+                .objective
+                .run(&mut sol_new, &mut self.charger, run_all_constr)?;
+
+            // Initialize the current and best solution to the initially generated solution
+            JB = J0;
+            JORIG = J0;
+            self.update_current_values(&mut sol_current, &mut sol_new);
+
+            // Set the scale of the Metropolis acceptance criterion
+            self.scale = self.acceptance_scale(&sol_current, J0, run_all_constr)?;
+
+            // Estimate the initial temperature
+            if auto_temp {
+                let t0 = self.initial_temp(&sol_current, J0, run_all_constr)?;
+                **self.tf = self.tf.with_init_temp(t0);
+            }
+
+            // Initialize solution scores
+            sol_scores.dec.Jb.push(JB);
+            sol_scores.dec.Jc.push(JB);
+            sol_scores.dec.Jn.push(JB);
+            self.objective
+                .breakdown(&sol_best, &self.charger)?
+                .record(&mut sol_scores.dec);
+            sol_scores.dec.reheat.push(0);
+            sol_scores.dec.K.push(0);
+
+            reheats = 0;
+            stall = 0;
+            evaluations = 0;
+            since_best = 0;
+            step_start = 0;
+        }
+
+        // Notify the observers of the start of the run
+        let T0 = self.tf.get_temp(Some(0)).unwrap();
+        let steps = self.tf.get_temp_vec().unwrap().len();
+        for o in self.observers.iter_mut() {
+            o.on_start(step_start, steps, T0);
+        }
 
         // While the temperature function is cooling down
-        for t in self.tf.get_temp_vec().unwrap() {
+        let mut step: usize = step_start;
+        while let Ok(t) = self.tf.get_temp(Some(step as isize)) {
             // Get starting time
             let start = Instant::now();
 
+            // Every temperature step draws from a generator derived from the run seed and the step, so a run
+            // resumed from a checkpoint continues exactly as the uninterrupted run
+            self.rng = rand_utils::seeded_rng(rand_utils::derive_seed(self.seed, step as u64));
+
+            // Count the candidate solutions and the accepted candidates
+            let mut candidates: usize = 0;
+            let mut accepted: usize = 0;
+
+            // Best objective function value before the local search
+            let jb_start = JB;
+
             // Iterate though local search
-            for _ in 0..k {
+            let mut k_step: usize = 0;
+            while !search_done(k_step, accepted) {
+                k_step += 1;
+
+                // Evaluate the best solution when it has no evaluation
+                if eval_best.is_none() {
+                    let mut sol = sol_best.clone();
+                    self.charger.milp_to_schedule(&sol);
+                    eval_best = Some(
+                        self.objective
+                            .evaluate(&mut sol, &mut self.charger, false)?,
+                    );
+                    self.charger.milp_to_schedule(&sol_current);
+                }
+
+                // Evaluate the current solution when it has no evaluation, the charge schedule is rebuilt from it
+                if eval_current.is_none() {
+                    self.charger.milp_to_schedule(&sol_current);
+                    eval_current = Some(self.objective.evaluate(
+                        &mut sol_current,
+                        &mut self.charger,
+                        false,
+                    )?);
+                    self.gsys.set_data(Box::new(sol_current.clone()));
+                    self.charger.milp_to_schedule(&sol_current);
+                }
+
+                // Log the changes of the tweak
+                self.charger.clear_delta();
+
                 // If the schedule is successfully modified
-                if self.gtweak.run(&mut self.gsys, &mut self.charger) {
+                if self
+                    .gtweak
+                    .run(&mut self.gsys, &mut self.charger, &mut self.rng)?
+                {
                     // Extract new data set
                     sol_new = *self.gsys.get_data();
 
-                    // Calculate objective function
-                    (self.sol_found, J1) =
-                        StdObj::run(&mut sol_new, &mut self.charger, run_all_constr);
+                    // Calculate objective function. Without the packing constraints only the changes of the tweak
+                    // are evaluated.
+                    evaluations += 1;
+                    let e = if run_all_constr {
+                        self.objective
+                            .evaluate(&mut sol_new, &mut self.charger, run_all_constr)
+                    } else {
+                        self.objective.run_delta(
+                            &mut sol_new,
+                            &mut self.charger,
+                            eval_current.as_ref().unwrap(),
+                        )
+                    };
+
+                    // Discard an infeasible candidate and return to the current solution
+                    let eval_new = match Error::feasible(e)? {
+                        Some(e) => e,
+                        None => {
+                            self.gsys.set_data(Box::new(sol_current.clone()));
+                            self.charger.milp_to_schedule(&sol_current);
+                            continue;
+                        }
+                    };
+
+                    J1 = eval_new.J;
 
                     // Update data sets
-                    self.update_data_sets(
+                    candidates += 1;
+                    let jb_prev = JB;
+                    let acc = self.update_data_sets(
                         &mut sol_best,
                         &mut sol_current,
                         &mut sol_new,
@@ -208,74 +518,201 @@ impl<'a> SA<'a> {
                         &mut JB,
                         t,
                     );
+                    accepted += acc as usize;
+
+                    // A rejected candidate returns to the best solution
+                    if acc {
+                        eval_current = Some(eval_new);
+                    } else {
+                        eval_current = eval_best.clone();
+                    }
+                    if JB < jb_prev {
+                        eval_best = eval_current.clone();
+                    }
+
+                    // Notify the observers of the candidate
+                    for o in self.observers.iter_mut() {
+                        o.on_iteration(step, k_step - 1, t, J1);
+                        if acc {
+                            o.on_accept(step, J1);
+                        } else {
+                            o.on_reject(step, J1);
+                        }
+                        if JB < jb_prev {
+                            o.on_new_best(step, JB, &sol_best);
+                        }
+                    }
+
+                    // Stop the local search if the run is to be stopped
+                    stop = stop_criteria.check(run_start.elapsed(), evaluations, JB, since_best);
+                    if stop.is_some() {
+                        break;
+                    }
                 }
             }
 
+            // Adapt the cooling schedule to the acceptance ratio of this temperature step
+            self.tf
+                .update(step, accepted as f32 / candidates.max(1) as f32);
+
+            // Share the best solution with the other islands
+            if self
+                .island
+                .as_ref()
+                .is_some_and(|i| i.is_migration_step(step))
+            {
+                self.migrate(
+                    &mut sol_best,
+                    &mut sol_current,
+                    &mut J0,
+                    &mut JB,
+                    run_all_constr,
+                )?;
+                eval_current = None;
+                eval_best = None;
+            }
+
             // Append the latest scores
             sol_scores.dec.Jb.push(JB);
             sol_scores.dec.Jc.push(J0);
             sol_scores.dec.Jn.push(J1);
+            self.objective
+                .breakdown(&sol_best, &self.charger)?
+                .record(&mut sol_scores.dec);
+
+            // Count the temperature steps without improving the best solution
+            if jb_start - JB > reheat_improve * jb_start.abs() {
+                stall = 0;
+            } else {
+                stall += 1;
+            }
 
-            // Plot schedule in real time
-            if rtp {
-                let schedule = &sol_best.clone();
-
-                PowerUsagePlot::real_time(!rtp, &mut Box::new(schedule.clone()), &mut fg_power);
-                ChargerUsagePlot::real_time(!rtp, &mut Box::new(schedule.clone()), &mut fg_cu);
-                ChargePlot::real_time(rtp, &mut Box::new(schedule.clone()), &mut fg_charge);
-                AccumulatedEnergyUsagePlot::real_time(
-                    !rtp,
-                    &mut Box::new(schedule.clone()),
-                    &mut fg_acc,
-                );
-                ScorePlot::real_time(rtp, &mut Box::new(sol_scores.clone()), &mut fg_score);
-                SchedulePlot::real_time(rtp, &mut Box::new(schedule.clone()), &mut fg_schedule);
+            // If the search stagnated, reheat and restart from the best solution
+            if reheat_steps > 0
+                && stall >= reheat_steps
+                && reheats < reheat_max
+                && self.tf.reheat(step, reheat_fraction * T0)
+            {
+                self.restart(
+                    &mut sol_best,
+                    &mut sol_current,
+                    &mut J0,
+                    JB,
+                    reheat_perturb,
+                    run_all_constr,
+                )?;
+                eval_current = None;
+                eval_best = None;
+                reheats += 1;
+                stall = 0;
             }
-            // Set the prefix depending on whether a solution has been found or not
-            self.update_prefix(start.elapsed());
+            sol_scores.dec.reheat.push(reheats);
+            sol_scores.dec.K.push(k_step);
 
-            // Update the status bar
-            self.pb.inc(1);
+            // Count the temperature steps since the best solution improved
+            if jb_start - JB > 0.0 {
+                since_best = 0;
+            } else {
+                since_best += 1;
+            }
+
+            // Check whether the run is to be stopped
+            if stop.is_none() {
+                stop = stop_criteria.check(run_start.elapsed(), evaluations, JB, since_best);
+            }
+
+            // Notify the observers of the temperature step. The cooling schedule may have changed length.
+            let s = TempStep {
+                step,
+                steps: self.tf.get_temp_vec().unwrap().len(),
+                t,
+                elapsed: start.elapsed(),
+                candidates,
+                accepted,
+                jb: JB,
+                jc: J0,
+                jn: J1,
+                sol_best: &sol_best,
+                scores: &sol_scores,
+            };
+            for o in self.observers.iter_mut() {
+                o.on_temp_step(&s);
+            }
+
+            // Periodically save the state of the run
+            if ckpt_interval > 0 && (step + 1).is_multiple_of(ckpt_interval) {
+                let c = Checkpoint {
+                    step,
+                    seed: self.seed,
+                    J0,
+                    J1,
+                    JB,
+                    JORIG,
+                    scale: self.scale,
+                    temps: self.tf.get_temp_vec().unwrap(),
+                    origin: self.tf.get_origin(),
+                    stall,
+                    evaluations,
+                    since_best,
+                    sol_best: sol_best.clone(),
+                    sol_current: sol_current.clone(),
+                    schedule: self.charger.schedule.clone(),
+                    Jb: sol_scores.dec.Jb.clone(),
+                    Jc: sol_scores.dec.Jc.clone(),
+                    Jn: sol_scores.dec.Jn.clone(),
+                    terms: sol_scores.dec.terms.clone(),
+                    reheat: sol_scores.dec.reheat.clone(),
+                    K: sol_scores.dec.K.clone(),
+                };
+
+                c.save(&ckpt_path)?;
+            }
+
+            // Move to the next temperature step
+            step += 1;
+
+            // Stop before the temperature schedule is completed
+            if stop.is_some() {
+                break;
+            }
         }
 
-        // Check if the data has been changed
-        let result: Option<Results>;
-        if JB != JORIG {
-            // Update J's for best
-            sol_best.dec.Jb = sol_scores.dec.Jb.clone();
-            sol_best.dec.Jc = sol_scores.dec.Jc.clone();
-            sol_best.dec.Jn = sol_scores.dec.Jn.clone();
-
-            // Create result object
-            result = Some(Results {
-                score: JB,
-                data: Box::new(sol_best.clone()),
-                charger: self.charger.clone(),
-            });
-        } else {
-            result = None;
+        // The run is complete, a new run should not resume from it
+        if ckpt_interval > 0 {
+            Checkpoint::remove(&ckpt_path)?;
         }
 
-        return result;
+        // Update J's for best
+        sol_best.dec.Jb = sol_scores.dec.Jb.clone();
+        sol_best.dec.Jc = sol_scores.dec.Jc.clone();
+        sol_best.dec.Jn = sol_scores.dec.Jn.clone();
+        sol_best.dec.terms = sol_scores.dec.terms.clone();
+        sol_best.dec.reheat = sol_scores.dec.reheat.clone();
+        sol_best.dec.K = sol_scores.dec.K.clone();
+
+        // The charger follows the current solution, schedule the best one
+        let mut charger = self.charger.clone();
+        charger.milp_to_schedule(&sol_best);
+
+        // Create result object, the stop reason is reported even if the data has not been changed
+        return Ok(Results {
+            score: JB,
+            terms: self.objective.breakdown(&sol_best, &charger)?,
+            data: Box::new(sol_best.clone()),
+            charger,
+            seed: self.seed,
+            scale: self.scale,
+            T0,
+            replicas: Vec::new(),
+            stop: stop.unwrap_or(StopReason::Completed),
+            improved: JB != JORIG,
+        });
     }
 
     //==========================================================================
     // PRIVATE
     //==========================================================================
 
-    //--------------------------------------------------------------------------
-    /// Update the visual indicator next to the progress bar.
-    ///
-    /// # Input
-    /// * te: Time elapsed for SA
-    ///
-    /// # Output
-    /// * None
-    ///
-    fn update_prefix(self: &SA<'a>, te: Duration) {
-        self.pb.set_prefix(format!("{:.3}s", te.as_secs_f64()));
-    }
-
     //--------------------------------------------------------------------------
     /// Update current data sets. Three data sets are provided: best, current,
     /// and new. The logic goes as follows:
@@ -295,7 +732,7 @@ impl<'a> SA<'a> {
     /// * t : Temperature
     ///
     /// # Output
-    /// * NONE
+    /// * accepted: True if the new data set was accepted
     ///
     fn update_data_sets(
         self: &mut SA<'a>,
@@ -306,18 +743,13 @@ impl<'a> SA<'a> {
         j1: &mut f64,
         jb: &mut f64,
         t: f32,
-    ) {
+    ) -> bool {
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Compare current data with new data
+        let accepted: bool;
 
         // Compare the objective functions
         if self.cmp_obj_fnc(*j0, *j1, t) {
-            // Ensure the configuration is valid
-            if !self.sol_found {
-                println!("Erroneous Configuration Detected! Ignoring configuration...");
-                return;
-            }
-
             // Update the current solution with the new data set
             self.update_current_values(sol_current, sol_new);
 
@@ -329,6 +761,7 @@ impl<'a> SA<'a> {
 
             // Update J0
             *j0 = j1.clone();
+            accepted = true;
         // Otherwise try the best data again
         } else {
             // Update the current solution with the new data set
@@ -342,6 +775,7 @@ impl<'a> SA<'a> {
 
             // Update J0
             *j0 = jb.clone();
+            accepted = false;
         }
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -355,10 +789,146 @@ impl<'a> SA<'a> {
             // Update the best to match the current data set
             self.update_current_values(sol_best, sol_current);
         }
+
+        return accepted;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Restart the search from the best solution after a reheat. If `perturb`
+    /// is greater than zero, `perturb` tweaks are applied to a copy of the best
+    /// solution and the search restarts from the copy, unless the copy is not a
+    /// valid configuration.
+    ///
+    /// # Input
+    /// * sol_best: The best known solution data set
+    /// * sol_current: The current solution data set
+    /// * j0: Current objective function
+    /// * jb: Best objective function
+    /// * perturb: Number of tweaks applied to the best solution
+    /// * run_all_constr: Run all the constraints in the objective function
+    ///
+    /// # Output
+    /// * NONE, or an error if a tweak or the evaluation failed
+    ///
+    fn restart(
+        self: &mut SA<'a>,
+        sol_best: &mut Data,
+        sol_current: &mut Data,
+        j0: &mut f64,
+        jb: f64,
+        perturb: usize,
+        run_all_constr: bool,
+    ) -> Result<(), Error> {
+        // Move to the best solution
+        self.update_current_values(sol_current, sol_best);
+        self.gsys.set_data(Box::new(sol_current.clone()));
+        self.charger.milp_to_schedule(sol_current);
+        *j0 = jb;
+
+        if perturb == 0 {
+            return Ok(());
+        }
+
+        // Perturb a copy of the best solution
+        for _ in 0..perturb {
+            self.gtweak
+                .run(&mut self.gsys, &mut self.charger, &mut self.rng)?;
+        }
+
+        // Evaluate the perturbed copy
+        let mut sol_new = *self.gsys.get_data();
+        let j = self
+            .objective
+            .run(&mut sol_new, &mut self.charger, run_all_constr);
+
+        // Restart from the copy if it is feasible, otherwise keep the best solution
+        if let Some(j) = Error::feasible(j)? {
+            self.update_current_values(sol_current, &mut sol_new);
+            *j0 = j;
+        }
+        self.gsys.set_data(Box::new(sol_current.clone()));
+        self.charger.milp_to_schedule(sol_current);
+
+        return Ok(());
+    }
+
+    //--------------------------------------------------------------------------
+    /// Share the best solution with the other islands. If the best solution of
+    /// the islands is better, it becomes the best solution of this island and
+    /// the search continues from it, or from a crossover of it and the previous
+    /// best solution of this island. A crossover with overlapping visits or an
+    /// invalid configuration is discarded in favor of the best solution of the
+    /// islands.
+    ///
+    /// # Input
+    /// * sol_best: The best known solution data set
+    /// * sol_current: The current solution data set
+    /// * j0: Current objective function
+    /// * jb: Best objective function
+    /// * run_all_constr: Run all the constraints in the objective function
+    ///
+    /// # Output
+    /// * NONE, or an error if the evaluation of the crossover failed
+    ///
+    fn migrate(
+        self: &mut SA<'a>,
+        sol_best: &mut Data,
+        sol_current: &mut Data,
+        j0: &mut f64,
+        jb: &mut f64,
+        run_all_constr: bool,
+    ) -> Result<(), Error> {
+        let island = self.island.clone().unwrap();
+
+        // Share the best solution and its charge schedule
+        let mut charger = (*self.charger).clone();
+        charger.milp_to_schedule(sol_best);
+        let m = match island.migrate(*jb, sol_best, &charger) {
+            Some(m) => m,
+            None => return Ok(()),
+        };
+
+        // The best solution of the islands is better than the best solution of this island
+        let mut sol_new = (*m.data).clone();
+        let mut j_new = m.score;
+        charger = *m.charger;
+
+        // Try a crossover of the two best solutions
+        if island.use_crossover() {
+            let mut child = Island::crossover(sol_best, &sol_new, &mut self.rng);
+            let mut ch = (*self.charger).clone();
+            ch.milp_to_schedule(&child);
+
+            if Island::is_valid(&ch) {
+                let j = self.objective.run(&mut child, &mut ch, run_all_constr);
+                if let Some(j) = Error::feasible(j)? {
+                    sol_new = child;
+                    j_new = j;
+                    charger = ch;
+                }
+            }
+        }
+
+        // Update the best solution
+        *sol_best = *m.data;
+        *jb = m.score;
+
+        // Continue from the new solution
+        self.update_current_values(sol_current, &mut sol_new);
+        self.gsys.set_data(Box::new(sol_current.clone()));
+        self.charger.set_schedule(charger.schedule)?;
+        *j0 = j_new;
+        if *jb - *j0 > 0.0 {
+            *jb = *j0;
+            self.update_current_values(sol_best, sol_current);
+        }
+
+        return Ok(());
     }
 
     //--------------------------------------------------------------------------
-    /// Compare objective functions and return the kept result.
+    /// Compare objective functions and return the kept result. The change in
+    /// the objective function is normalized by the acceptance scale.
     ///
     /// # Input
     /// * j0: Previous objective function
@@ -378,13 +948,13 @@ impl<'a> SA<'a> {
         // Otherwise, the new data, `j_1`, has a larger objective function
         } else {
             // Calculate the coefficient
-            let coef: f64 = delta_e / (9000000.0 * t as f64);
+            let coef: f64 = delta_e / (self.scale * t as f64);
 
             // Calculate `e^coef`
             let e: f64 = coef.exp();
 
             // Generate a number between 0 and 1
-            let prob = thread_rng().gen_range(0.0..=1.0);
+            let prob = self.rng.gen_range(0.0..=1.0);
 
             // Return whether to keep the new data.
             // - if e <= prob: keep new data
@@ -394,27 +964,266 @@ impl<'a> SA<'a> {
     }
 
     //--------------------------------------------------------------------------
-    /// Update old data with new
+    /// Determine the scale of the Metropolis acceptance criterion from
+    /// `temp.scale` of the settings of the run. The scale is either a fixed
+    /// value or "auto". In "auto" mode the scale is the mean uphill objective
+    /// delta of a short random walk from the initial solution, so the
+    /// temperature is relative to a typical uphill move of the instance.
+    /// Without an uphill move the default scale is used and reported to the
+    /// observers.
     ///
     /// # Input
-    /// * sol_current: Current solution data set
-    /// * sol_new: New solution data set
+    /// * sol: Initial solution
+    /// * j0: Objective function value of the initial solution
+    /// * run_all_constr: Run all the constraints in the objective function
     ///
     /// # Output
-    /// * NONE
+    /// * scale: Scale of the Metropolis acceptance criterion
     ///
-    fn update_current_values(self: &mut SA<'a>, sol_current: &mut Data, sol_new: &mut Data) {
-        *sol_current = sol_new.clone();
+    fn acceptance_scale(
+        self: &mut SA<'a>,
+        sol: &Data,
+        j0: f64,
+        run_all_constr: bool,
+    ) -> Result<f64, Error> {
+        // The scale is set to a fixed value
+        if let Scale::Fixed(s) = self.config.temp.scale {
+            return Ok(s);
+        }
+
+        // Walk from the initial solution and keep the uphill moves
+        let n = self.config.temp.warmup;
+        let uphill: Vec<f64> = self
+            .random_walk(sol, j0, n, run_all_constr)?
+            .into_iter()
+            .filter(|d| *d > 0.0)
+            .collect();
+
+        // If no uphill move was seen, fall back to the default and report it
+        if uphill.is_empty() {
+            let f = Fallback::Scale(TempConfig::DEFAULT_SCALE);
+            for o in self.observers.iter_mut() {
+                o.on_fallback(&f);
+            }
+            return Ok(TempConfig::DEFAULT_SCALE);
+        }
+
+        return Ok(uphill.iter().sum::<f64>() / uphill.len() as f64);
     }
 
     //--------------------------------------------------------------------------
-    /// Real Time Plots
+    /// Estimate the initial temperature. `temp.samples` tweaks are applied to
+    /// the initial solution and the temperature is chosen such that the
+    /// fraction of accepted tweaks matches `temp.accept`, i.e. solve
+    ///
+    /// $\chi_0 = \frac{m_1 + \sum_{i} e^{-\frac{\delta_i}{s T_0}}}{m_1 + m_2}$
+    ///
+    /// where $m_1$ is the number of downhill tweaks, $\delta_i$ are the $m_2$
+    /// uphill deltas and $s$ is the acceptance scale. The temperature is
+    /// searched in $[10^{-12}, 10^{12}]$. If the target is not reached in the
+    /// range, the closest end of the range is used. Without uphill tweaks the
+    /// ratio does not depend on the temperature, an uphill delta of $s$ is
+    /// then accepted with probability $\chi_0$. Both fallbacks are reported to
+    /// the observers.
     ///
     /// # Input
-    /// * NONE
+    /// * sol: Initial solution
+    /// * j0: Objective function value of the initial solution
+    /// * run_all_constr: Run all the constraints in the objective function
+    ///
+    /// # Output
+    /// * t0: Initial temperature
+    ///
+    fn initial_temp(
+        self: &mut SA<'a>,
+        sol: &Data,
+        j0: f64,
+        run_all_constr: bool,
+    ) -> Result<f32, Error> {
+        // Target acceptance ratio and number of samples
+        let chi0 = self.config.temp.accept;
+        let n = self.config.temp.samples;
+
+        // Sample tweaks of the initial solution
+        let deltas = self.sample_tweaks(sol, j0, n, run_all_constr)?;
+        let uphill: Vec<f64> = deltas
+            .iter()
+            .filter(|d| **d > 0.0)
+            .map(|d| *d / self.scale)
+            .collect();
+        let m1 = (deltas.len() - uphill.len()) as f64;
+        let m = deltas.len() as f64;
+
+        // The acceptance ratio does not depend on the temperature if there are no uphill tweaks, accept an uphill
+        // delta of the size of the scale with the target probability instead
+        if uphill.is_empty() {
+            let t0 = (-1.0 / chi0.ln()) as f32;
+            for o in self.observers.iter_mut() {
+                o.on_fallback(&Fallback::InitTemp(t0));
+            }
+            return Ok(t0);
+        }
+
+        // Acceptance ratio at temperature `t`
+        let chi = |t: f64| (m1 + uphill.iter().map(|d| (-d / t).exp()).sum::<f64>()) / m;
+
+        // Use the closest end of the range if it does not bracket the target
+        let mut lo: f64 = -12.0;
+        let mut hi: f64 = 12.0;
+        let edge = if chi(10.0_f64.powf(hi)) < chi0 {
+            Some(hi)
+        } else if chi(10.0_f64.powf(lo)) >= chi0 {
+            Some(lo)
+        } else {
+            None
+        };
+        if let Some(x) = edge {
+            let t0 = 10.0_f64.powf(x) as f32;
+            for o in self.observers.iter_mut() {
+                o.on_fallback(&Fallback::InitTemp(t0));
+            }
+            return Ok(t0);
+        }
+
+        // Bisect the acceptance ratio on a log scale. The ratio increases with the temperature.
+        for _ in 0..100 {
+            let mid = 0.5 * (lo + hi);
+            if chi(10.0_f64.powf(mid)) < chi0 {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+
+        return Ok(10.0_f64.powf(hi) as f32);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Apply `n` independent tweaks to the solution and record the change in
+    /// the objective function of each. The system and charger are restored to
+    /// `sol` after every tweak.
+    ///
+    /// # Input
+    /// * sol: Solution to tweak
+    /// * j0: Objective function value of `sol`
+    /// * n: Number of tweaks to attempt
+    /// * run_all_constr: Run all the constraints in the objective function
+    ///
+    /// # Output
+    /// * deltas: Objective function change of each successful, feasible tweak
+    ///
+    fn sample_tweaks(
+        self: &mut SA<'a>,
+        sol: &Data,
+        j0: f64,
+        n: usize,
+        run_all_constr: bool,
+    ) -> Result<Vec<f64>, Error> {
+        // Keep the charger to restore it after each tweak
+        let charger = self.charger.clone();
+
+        let mut deltas: Vec<f64> = Vec::new();
+
+        for _ in 0..n {
+            // If the schedule is successfully modified
+            if self
+                .gtweak
+                .run(&mut self.gsys, &mut self.charger, &mut self.rng)?
+            {
+                // Record the change in the objective function
+                let mut sol_new = *self.gsys.get_data();
+                let j = self
+                    .objective
+                    .run(&mut sol_new, &mut self.charger, run_all_constr);
+                if let Some(j) = Error::feasible(j)? {
+                    deltas.push(j - j0);
+                }
+
+                // Restore the starting solution
+                *self.charger = (*charger).clone();
+                self.gsys.set_data(Box::new(sol.clone()));
+            }
+        }
+
+        return Ok(deltas);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Apply `n` tweaks to the solution, accepting every successful tweak, and
+    /// record the change in the objective function of each move. The system and
+    /// charger are restored to `sol` afterwards.
+    ///
+    /// # Input
+    /// * sol: Solution to start from
+    /// * j0: Objective function value of `sol`
+    /// * n: Number of tweaks to attempt
+    /// * run_all_constr: Run all the constraints in the objective function
+    ///
+    /// # Output
+    /// * deltas: Objective function change of each successful, feasible tweak
+    ///
+    fn random_walk(
+        self: &mut SA<'a>,
+        sol: &Data,
+        j0: f64,
+        n: usize,
+        run_all_constr: bool,
+    ) -> Result<Vec<f64>, Error> {
+        // Keep the charger to restore it after the walk
+        let charger = self.charger.clone();
+
+        let mut deltas: Vec<f64> = Vec::new();
+        let mut j_prev: f64 = j0;
+        let mut sol_prev: Data = sol.clone();
+
+        for _ in 0..n {
+            // If the schedule is successfully modified
+            if self
+                .gtweak
+                .run(&mut self.gsys, &mut self.charger, &mut self.rng)?
+            {
+                // Evaluate the new solution, step back if it is infeasible
+                let mut sol_new = *self.gsys.get_data();
+                let j = self
+                    .objective
+                    .run(&mut sol_new, &mut self.charger, run_all_constr);
+                let j = match Error::feasible(j)? {
+                    Some(j) => j,
+                    None => {
+                        self.gsys.set_data(Box::new(sol_prev.clone()));
+                        self.charger.milp_to_schedule(&sol_prev);
+                        continue;
+                    }
+                };
+
+                // Move to the new solution
+                sol_prev = sol_new.clone();
+                self.gsys.set_data(Box::new(sol_new));
+
+                // Record the change in the objective function
+                deltas.push(j - j_prev);
+                j_prev = j;
+            }
+        }
+
+        // Restore the starting solution
+        self.charger = charger;
+        self.gsys.set_data(Box::new(sol.clone()));
+
+        return Ok(deltas);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Update old data with new
+    ///
+    /// # Input
+    /// * sol_current: Current solution data set
+    /// * sol_new: New solution data set
     ///
     /// # Output
     /// * NONE
     ///
-    fn _rtp(self: &mut SA<'a>) {}
+    fn update_current_values(self: &mut SA<'a>, sol_current: &mut Data, sol_new: &mut Data) {
+        *sol_current = sol_new.clone();
+    }
 }
@@ -1,13 +1,15 @@
 //===============================================================================
 // External Crates
+use rand::rngs::StdRng;
 use rand::Rng;
-use yaml_rust::Yaml;
 
 //===============================================================================
 // Import modules
+use crate::error::Error;
+use crate::sa::config::schedule::ScheduleConfig;
+use crate::sa::config::{ChargerConfig, TimeConfig};
 use crate::sa::data::Data;
 use crate::sa::generators::primitives;
-use crate::util::fileio::yaml_loader;
 
 //===============================================================================
 /// Structure to consolidate the bus assignment information
@@ -18,6 +20,28 @@ pub struct Assignment {
     pub b: usize,
 }
 
+//===============================================================================
+/// Visits and charge intervals that changed since the change log of the
+/// charger was last cleared
+///
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Delta {
+    pub visits: Vec<usize>,                // Visits moved by a primitive
+    pub added: Vec<(usize, (f32, f32))>,   // Queue and time slice of the assigned charges
+    pub removed: Vec<(usize, (f32, f32))>, // Queue and time slice of the removed charges
+}
+
+//===============================================================================
+/// Structure of a charger cabinet. The charger queues of a cabinet share its
+/// power. If the plugged-in BEBs ask for more than the cabinet supplies, the
+/// power is split in proportion to the charge rates of their queues.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cabinet {
+    pub queues: Vec<usize>, // Charger queues of the cabinet
+    pub power: f32,         // Power shared by the queues [KW]
+}
+
 //===============================================================================
 /// Structure to track charger information
 ///
@@ -28,9 +52,27 @@ pub struct Charger {
     pub free_time: Vec<Vec<(f32, f32)>>, // Lists of free times
     pub charger_count: (usize, usize, usize), // Charger counts (wait, slow, fast)
     pub charger_speed: (f32, f32, f32), // Charger speeds (wait, slow, fast)
+    pub cabinets: Vec<Cabinet>,         // Cabinets of charger queues that share their power
+    pub delta: Delta,                   // Changes since the last call to `clear_delta`
 
     // Private
-    config: Yaml,
+    bod: f32, // Beginning of day [hr]
+    eod: f32, // End of day       [hr]
+}
+
+//===============================================================================
+/// Implementation of PartialEq for Charger. The change log is not compared.
+//
+impl PartialEq for Charger {
+    fn eq(&self, other: &Charger) -> bool {
+        return self.schedule == other.schedule
+            && self.free_time == other.free_time
+            && self.charger_count == other.charger_count
+            && self.charger_speed == other.charger_speed
+            && self.cabinets == other.cabinets
+            && self.bod == other.bod
+            && self.eod == other.eod;
+    }
 }
 
 //===============================================================================
@@ -52,65 +94,69 @@ impl Charger {
     /// * q_force: The total number of chargers to force
     ///
     /// # Output
-    /// * Return a charger object
+    /// * Return a charger object, or an error if the configuration is invalid
     ///
     pub fn new(
         config_path: &str,
         load_c_from_yaml: bool,
         a_force: Option<usize>,
         q_force: Option<usize>,
-    ) -> Charger {
+    ) -> Result<Charger, Error> {
+        let sched = ScheduleConfig::load(config_path)?;
+
+        // Load the chargers from YAML if specified, otherwise use `q_force` fast chargers
+        let config: ChargerConfig = if load_c_from_yaml {
+            ChargerConfig {
+                count: (
+                    a_force.unwrap_or(sched.chargers.count.0),
+                    sched.chargers.count.1,
+                    sched.chargers.count.2,
+                ),
+                ..sched.chargers
+            }
+        } else {
+            ChargerConfig {
+                count: (0, 0, q_force.unwrap_or(1)),
+                ..Default::default()
+            }
+        };
+
+        return Ok(Charger::from_config(&config, &sched.time));
+    }
+
+    //---------------------------------------------------------------------------
+    /// Constructor that returns a Charger object from in-memory settings
+    ///
+    /// # Input
+    /// * config: Charger counts, speeds and cabinets
+    /// * time: Time settings, the chargers are free from BOD to EOD
+    ///
+    /// # Output
+    /// * Return a charger object
+    ///
+    pub fn from_config(config: &ChargerConfig, time: &TimeConfig) -> Charger {
         // Create a charger
         let mut c: Charger = Charger {
             schedule: Vec::new(),
             free_time: Vec::new(),
-            charger_count: (0, 0, 1),
-            charger_speed: (0.0, 30.0, 910.0),
-            config: yaml_loader::load_yaml(config_path),
+            charger_count: config.count,
+            charger_speed: config.speed,
+            cabinets: Vec::new(),
+            delta: Delta::default(),
+            bod: time.bod,
+            eod: time.eod,
         };
 
-        // Extract the number of queues
-        let mut q: usize = q_force.unwrap_or(1 as usize);
-
-        // Set the charger count
-        c.charger_count = (0, 0, q);
-
-        // Load chargers file if specified
-        let q_wait: usize;
-        if load_c_from_yaml {
-            // Extract the number of queues from YAML
-            if let Some(a) = a_force {
-                q_wait = a;
-            } else {
-                q_wait = c.config.clone()["buses"]["num_bus"].as_i64().unwrap() as usize;
-            }
-
-            let q_slow: usize = c.config.clone()["chargers"]["slow"]["num"]
-                .as_i64()
-                .unwrap() as usize;
-
-            let q_fast: usize = c.config.clone()["chargers"]["fast"]["num"]
-                .as_i64()
-                .unwrap() as usize;
-
-            // Update charger count
-            q = q_wait + q_slow + q_fast;
-
-            // Set the charger count
-            c.charger_count = (q_wait, q_slow, q_fast);
-
-            // Set charger speeds
-            let slow_c = c.config.clone()["chargers"]["slow"]["rate"]
-                .as_f64()
-                .unwrap() as f32;
-            let fast_c = c.config.clone()["chargers"]["fast"]["rate"]
-                .as_f64()
-                .unwrap() as f32;
-            c.charger_speed = (0.0, slow_c, fast_c);
-        }
-
         // Create the number of queues specified
-        c.add_chargers(q);
+        c.add_chargers(config.count.0 + config.count.1 + config.count.2);
+
+        // The chargers of a cabinet are counted from the first slow charger
+        for cab in config.cabinets.iter() {
+            c.cabinets.push(Cabinet {
+                queues: cab.chargers.iter().map(|k| k + config.count.0).collect(),
+                power: cab.power,
+            });
+        }
 
         return c;
     }
@@ -124,9 +170,13 @@ impl Charger {
     /// * id: Identification number of the bus
     ///
     /// # Output
-    /// * assigned: True if the bus was successfully assigned, false otherwise
+    /// * assigned: True if the bus was successfully assigned, false otherwise. An invalid queue index is an
+    ///   error.
     ///
-    pub fn assign(self: &mut Charger, q: usize, c: (f32, f32), id: usize) -> bool {
+    pub fn assign(self: &mut Charger, q: usize, c: (f32, f32), id: usize) -> Result<bool, Error> {
+        // Ensure the queue exists
+        self.check_queue(q)?;
+
         // Create Assignment
         let a: Assignment = Assignment { b: id, t: c };
 
@@ -140,7 +190,10 @@ impl Charger {
         // Update the free time for the qth charger
         self.update_free_time(q);
 
-        return true;
+        // Record the change
+        self.delta.added.push((q, c));
+
+        return Ok(true);
     }
 
     //--------------------------------------------------------------------------
@@ -151,9 +204,12 @@ impl Charger {
     /// * c: Candidate time frame tuple
     ///
     /// # Output
-    /// * rem: True if the time slice was removed, false otherwise
+    /// * rem: True if the time slice was removed, false otherwise. An invalid queue index is an error.
     ///
-    pub fn remove(self: &mut Charger, q: usize, c: (f32, f32)) -> bool {
+    pub fn remove(self: &mut Charger, q: usize, c: (f32, f32)) -> Result<bool, Error> {
+        // Ensure the queue exists
+        self.check_queue(q)?;
+
         // Default to indicate that the time slice item was not removed
         let rem: bool;
 
@@ -166,10 +222,15 @@ impl Charger {
         // State that the item is being removed
         rem = l_bef > self.schedule[q].len();
 
+        // Record every removed copy of the time slice
+        for _ in self.schedule[q].len()..l_bef {
+            self.delta.removed.push((q, c));
+        }
+
         // Update the free time for the qth charger
         self.update_free_time(q);
 
-        return rem;
+        return Ok(rem);
     }
 
     //--------------------------------------------------------------------------
@@ -281,6 +342,7 @@ impl Charger {
     /// # Input
     /// * ae: Arrival/departure times
     /// * ts: Available time slice
+    /// * rng: Random number generator
     ///
     /// # Output
     /// * (fits, ud) : The tuple indicating that the arrival/departure time fits and the charge start/stop charge times
@@ -289,6 +351,7 @@ impl Charger {
         self: &mut Charger,
         ae: &(f32, f32),
         ts: &(f32, f32),
+        rng: &mut StdRng,
     ) -> (bool, (f32, f32)) {
         let lower = ts.0;
         let upper = ts.1;
@@ -312,20 +375,20 @@ impl Charger {
 
         // The arrival/departure times are fully within the free time
         if lower <= a && a <= e && e <= upper {
-            (u, fits_u) = self.get_rand_range(None, Some(d), (a, e));
-            (d, fits_d) = self.get_rand_range(Some(u), None, (u, e));
+            (u, fits_u) = self.get_rand_range(None, Some(d), (a, e), rng);
+            (d, fits_d) = self.get_rand_range(Some(u), None, (u, e), rng);
             // The departure time is fully within the free time and the arrival time is less than the lower bound
         } else if a <= lower && lower <= e && e <= upper {
-            (u, fits_u) = self.get_rand_range(None, Some(d), (lower, e));
-            (d, fits_d) = self.get_rand_range(Some(u), None, (u, e));
+            (u, fits_u) = self.get_rand_range(None, Some(d), (lower, e), rng);
+            (d, fits_d) = self.get_rand_range(Some(u), None, (u, e), rng);
             // The arrival time is fully within the free time and the departure time is greater than the lower bound
         } else if lower <= a && a <= upper && upper <= e {
-            (u, fits_u) = self.get_rand_range(None, Some(d), (a, upper));
-            (d, fits_d) = self.get_rand_range(Some(u), None, (u, upper));
+            (u, fits_u) = self.get_rand_range(None, Some(d), (a, upper), rng);
+            (d, fits_d) = self.get_rand_range(Some(u), None, (u, upper), rng);
             // The arrival/departure times are less than and greater than the lower and upper bound, respectively
         } else if a <= lower && lower <= upper && upper <= e {
-            (u, fits_u) = self.get_rand_range(None, Some(d), (lower, upper));
-            (d, fits_d) = self.get_rand_range(Some(u), None, (u, upper));
+            (u, fits_u) = self.get_rand_range(None, Some(d), (lower, upper), rng);
+            (d, fits_d) = self.get_rand_range(Some(u), None, (u, upper), rng);
         }
 
         // Keep the window above a certain threshold. This value should be bigger than `primitives::EPSILON`
@@ -340,6 +403,35 @@ impl Charger {
         return (fits, (u, d));
     }
 
+    //--------------------------------------------------------------------------
+    /// Record that a primitive moved visit `i`. The charge intervals are
+    /// recorded by `assign` and `remove`.
+    ///
+    /// # Input
+    /// * i: Index of the visit
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    pub fn touch(self: &mut Charger, i: usize) {
+        if !self.delta.visits.contains(&i) {
+            self.delta.visits.push(i);
+        }
+    }
+
+    //--------------------------------------------------------------------------
+    /// Empty the change log of the charger
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    pub fn clear_delta(self: &mut Charger) {
+        self.delta = Delta::default();
+    }
+
     //--------------------------------------------------------------------------
     /// The `add_chargers' function adds charger queues.
     ///
@@ -351,8 +443,8 @@ impl Charger {
     ///
     pub fn add_chargers(self: &mut Charger, q: usize) {
         // Extract the BOD and EOD
-        let bod = self.config.clone()["time"]["BOD"].as_f64().unwrap() as f32;
-        let eod = self.config.clone()["time"]["EOD"].as_f64().unwrap() as f32;
+        let bod = self.bod;
+        let eod = self.eod;
 
         // Create the appropriate number of schedules and free time lists
         for _ in 0..q {
@@ -362,7 +454,8 @@ impl Charger {
     }
 
     //--------------------------------------------------------------------------
-    /// Given MILP data `dat`, update the charge availability matrix.
+    /// Given MILP data `dat`, update the charge availability matrix. The change
+    /// log is cleared.
     ///
     /// # Input
     /// * dat: MILP data object
@@ -396,6 +489,43 @@ impl Charger {
         for q in 0..dat.param.Q {
             self.update_free_time(q);
         }
+
+        // The change log is relative to the new schedule
+        self.clear_delta();
+    }
+
+    //--------------------------------------------------------------------------
+    /// Replace the charge schedule and regenerate the free times of every
+    /// queue. The change log is cleared.
+    ///
+    /// # Input
+    /// * schedule: Lists of scheduled charge times for each queue
+    ///
+    /// # Output
+    /// * NONE, or an error if the number of queues does not match
+    ///
+    pub fn set_schedule(self: &mut Charger, schedule: Vec<Vec<Assignment>>) -> Result<(), Error> {
+        // Ensure the number of queues match
+        if schedule.len() != self.schedule.len() {
+            return Err(Error::Invariant(format!(
+                "charger.rs: Schedule of {} queues does not match the {} chargers.",
+                schedule.len(),
+                self.schedule.len()
+            )));
+        }
+
+        // Update the schedule
+        self.schedule = schedule;
+
+        // Regenerate availability matrix
+        for q in 0..self.schedule.len() {
+            self.update_free_time(q);
+        }
+
+        // The change log is relative to the new schedule
+        self.clear_delta();
+
+        return Ok(());
     }
 
     //--------------------------------------------------------------------------
@@ -429,10 +559,258 @@ impl Charger {
         return rate;
     }
 
+    //--------------------------------------------------------------------------
+    /// Sum the charge rates of the assignments on the charger queues that are
+    /// charging at time `t`. The queues of a cabinet draw at most the power of
+    /// the cabinet.
+    ///
+    /// # Input
+    /// * t: Time of interest [hr]
+    ///
+    /// # Output
+    /// * p: Summed charge rate [KW]
+    ///
+    pub fn power_at(self: &Charger, t: f32) -> f32 {
+        return (self.charger_count.0..self.schedule.len())
+            .map(|q| self.active(q, t) as f32 * self.rate_at(q, t))
+            .sum();
+    }
+
+    //--------------------------------------------------------------------------
+    /// Peak of the summed charge rates over a time slice. The summed power only
+    /// rises at the attach time of an assignment, so the peak is found at the
+    /// start of the slice or at an attach time inside it.
+    ///
+    /// # Input
+    /// * c: Time slice of interest
+    ///
+    /// # Output
+    /// * p: Peak summed charge rate [KW]
+    ///
+    pub fn peak_power(self: &Charger, c: &(f32, f32)) -> f32 {
+        let mut peak = self.power_at(c.0);
+        for q in self.charger_count.0..self.schedule.len() {
+            for a in self.schedule[q]
+                .iter()
+                .filter(|a| a.t.0 > c.0 && a.t.0 < c.1)
+            {
+                peak = peak.max(self.power_at(a.t.0));
+            }
+        }
+
+        return peak;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Indicate whether a charge on queue `q` over time slice `c` keeps the
+    /// summed charge rate within the power limit of the grid connection.
+    ///
+    /// # Input
+    /// * q: Charger queue index
+    /// * c: Candidate time slice
+    /// * limit: Power limit of the grid connection, 0 is unlimited [KW]
+    ///
+    /// # Output
+    /// * bool: True if the charge fits within the limit
+    ///
+    pub fn fits_grid(self: &Charger, q: usize, c: &(f32, f32), limit: f32) -> bool {
+        if limit <= 0.0 || q < self.charger_count.0 {
+            return true;
+        }
+
+        return self.peak_power(c) + self.get_charge_rate(q) <= limit;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Find the cabinet of charger queue `q`
+    ///
+    /// # Input
+    /// * q: Charger queue index
+    ///
+    /// # Output
+    /// * cabinet: Cabinet of the queue, `None` if the queue has its own power
+    ///
+    pub fn cabinet(self: &Charger, q: usize) -> Option<&Cabinet> {
+        return self.cabinets.iter().find(|c| c.queues.contains(&q));
+    }
+
+    //--------------------------------------------------------------------------
+    /// Charge rate delivered by charger queue `q` to a BEB plugged in at time
+    /// `t`. The rate of a queue that shares a cabinet drops while the other
+    /// queues of the cabinet ask for more power than the cabinet supplies.
+    ///
+    /// # Input
+    /// * q: Charger queue index
+    /// * t: Time of interest [hr]
+    ///
+    /// # Output
+    /// * rate: Delivered charge rate [KW]
+    ///
+    pub fn rate_at(self: &Charger, q: usize, t: f32) -> f32 {
+        let rate = self.get_charge_rate(q);
+
+        // The queue has its own power
+        let cab = match self.cabinet(q) {
+            Some(c) => c,
+            None => return rate,
+        };
+
+        // Power asked for by the BEB and the BEBs charging on the other queues
+        let demand: f32 = rate
+            + cab
+                .queues
+                .iter()
+                .filter(|k| **k != q)
+                .map(|k| self.active(*k, t) as f32 * self.get_charge_rate(*k))
+                .sum::<f32>();
+
+        if demand <= cab.power {
+            return rate;
+        }
+
+        return rate * cab.power / demand;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Time at the full charge rate of queue `q` that delivers the same charge
+    /// as charging for `s` hours from `u`. It is `s` for a queue that has its
+    /// own power.
+    ///
+    /// # Input
+    /// * q: Charger queue index
+    /// * u: Attach time [hr]
+    /// * s: Service time [hr]
+    ///
+    /// # Output
+    /// * t: Charge time at the full charge rate [hr]
+    ///
+    pub fn effective_time(self: &Charger, q: usize, u: f32, s: f32) -> f32 {
+        // The queue has its own power
+        let cab = match self.cabinet(q) {
+            Some(c) => c,
+            None => return s,
+        };
+
+        // The delivered rate only changes when a BEB of the cabinet attaches or detaches
+        let rate = self.get_charge_rate(q);
+        let mut times = self.changes(cab, q, u, u + s);
+        times.push(u + s);
+
+        let mut t: f32 = 0.0;
+        let mut t0 = u;
+        for t1 in times {
+            t += (t1 - t0) * self.rate_at(q, t0) / rate;
+            t0 = t1;
+        }
+
+        return t;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Service time from `u` that delivers the same charge as charging for `t`
+    /// hours at the full charge rate of queue `q`. It is the inverse of
+    /// `effective_time`.
+    ///
+    /// # Input
+    /// * q: Charger queue index
+    /// * u: Attach time [hr]
+    /// * t: Charge time at the full charge rate [hr]
+    ///
+    /// # Output
+    /// * s: Service time [hr]
+    ///
+    pub fn service_time(self: &Charger, q: usize, u: f32, t: f32) -> f32 {
+        // The queue has its own power
+        let cab = match self.cabinet(q) {
+            Some(c) => c,
+            None => return t,
+        };
+
+        // Charge at the rate of each interval until the charge time is delivered
+        let rate = self.get_charge_rate(q);
+        let mut left = t;
+        let mut t0 = u;
+        for t1 in self.changes(cab, q, u, f32::INFINITY) {
+            let share = self.rate_at(q, t0) / rate;
+            if (t1 - t0) * share >= left {
+                break;
+            }
+            left -= (t1 - t0) * share;
+            t0 = t1;
+        }
+
+        return t0 + left * rate / self.rate_at(q, t0) - u;
+    }
+
     /////////////////////////////////////////////////////////////////////////////
     // PRIVATE
     /////////////////////////////////////////////////////////////////////////////
 
+    //--------------------------------------------------------------------------
+    /// Count the assignments of charger queue `q` that are charging at time `t`
+    ///
+    /// # Input
+    /// * q: Charger queue index
+    /// * t: Time of interest [hr]
+    ///
+    /// # Output
+    /// * n: Number of active assignments
+    ///
+    fn active(self: &Charger, q: usize, t: f32) -> usize {
+        return self.schedule[q]
+            .iter()
+            .filter(|a| a.t.0 <= t && t < a.t.1)
+            .count();
+    }
+
+    //--------------------------------------------------------------------------
+    /// Attach and detach times of the other queues of a cabinet strictly
+    /// between `t0` and `t1`, in increasing order
+    ///
+    /// # Input
+    /// * cab: Cabinet of queue `q`
+    /// * q: Charger queue index
+    /// * t0: Start of the interval [hr]
+    /// * t1: End of the interval [hr]
+    ///
+    /// # Output
+    /// * times: Times at which the delivered rate of `q` may change
+    ///
+    fn changes(self: &Charger, cab: &Cabinet, q: usize, t0: f32, t1: f32) -> Vec<f32> {
+        let mut times: Vec<f32> = cab
+            .queues
+            .iter()
+            .filter(|k| **k != q)
+            .flat_map(|k| self.schedule[*k].iter().flat_map(|a| [a.t.0, a.t.1]))
+            .filter(|t| *t > t0 && *t < t1)
+            .collect();
+        times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        times.dedup();
+
+        return times;
+    }
+
+    //--------------------------------------------------------------------------
+    /// The `check_queue' function checks that the charger queue exists.
+    ///
+    /// # Input
+    /// * q: Index of the charger queue
+    ///
+    /// # Output
+    /// * NONE, or an error if the queue does not exist
+    ///
+    fn check_queue(self: &Charger, q: usize) -> Result<(), Error> {
+        if q >= self.schedule.len() {
+            return Err(Error::Invariant(format!(
+                "charger.rs: Charger queue {} does not exist ({} queues).",
+                q,
+                self.schedule.len()
+            )));
+        }
+
+        return Ok(());
+    }
+
     //--------------------------------------------------------------------------
     /// The `update_free_time' function updates the times that charger q is available.
     ///
@@ -444,8 +822,8 @@ impl Charger {
     ///
     fn update_free_time(self: &mut Charger, q: usize) {
         // Extract the BOD and EOD
-        let bod = self.config.clone()["time"]["BOD"].as_f64().unwrap() as f32;
-        let eod = self.config.clone()["time"]["EOD"].as_f64().unwrap() as f32;
+        let bod = self.bod;
+        let eod = self.eod;
         let mut s_prev: std::option::Option<&Assignment> = None;
 
         // Create a new free time vector
@@ -494,8 +872,8 @@ impl Charger {
     /// * bool: true if the values are within the time horizon, false otherwise
     ///
     fn _check_in_bounds(self: &mut Charger, c: &(f32, f32)) -> bool {
-        let bod = self.config.clone()["time"]["BOD"].as_f64().unwrap() as f32;
-        let eod = self.config.clone()["time"]["EOD"].as_f64().unwrap() as f32;
+        let bod = self.bod;
+        let eod = self.eod;
 
         // Check the ordering
         //
@@ -519,6 +897,7 @@ impl Charger {
     /// * u: Current attach time
     /// * d: Current detach times
     /// * lu: Lower/upper bound
+    /// * rng: Random number generator
     ///
     /// # Output
     /// * v: Random value.
@@ -528,13 +907,11 @@ impl Charger {
         u: Option<f32>,
         d: Option<f32>,
         lu: (f32, f32),
+        rng: &mut StdRng,
     ) -> (f32, bool) {
         // Create charge start/stop buffers
         let mut v: f32;
 
-        // Create random object
-        let mut rng = rand::thread_rng();
-
         // Check if the window is large enough
         if lu.1 - lu.0 < primitives::EPSILON {
             // If it is not, return false
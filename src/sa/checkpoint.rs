@@ -0,0 +1,648 @@
+#![allow(non_snake_case)]
+
+//===============================================================================
+// Import standard library
+use std::fs;
+use std::path::Path;
+use yaml_rust::yaml::Hash;
+use yaml_rust::{Yaml, YamlEmitter, YamlLoader};
+
+//===============================================================================
+// Import modules
+use crate::error::Error;
+use crate::sa::charger::Assignment;
+use crate::sa::config::ConfigError;
+use crate::sa::data::{Data, DecisionVar, Degradation, Demand, Parameter, Tariff};
+
+//===============================================================================
+/// Snapshot of a simulated annealing run.
+///
+/// The checkpoint contains everything `SA::run` needs to continue the annealing
+/// process from the temperature step after `step`. The state of the random
+/// number generator is not stored, every temperature step draws from a
+/// generator derived from the stored seed of the run and the step.
+//
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Checkpoint {
+    pub step: usize,                    // Index of the last completed temperature step
+    pub seed: u64,                      // Seed of the random number generator of the run
+    pub J0: f64,                        // Objective score of the current solution
+    pub J1: f64,                        // Objective score of the last candidate
+    pub JB: f64,                        // Objective score of the best solution
+    pub JORIG: f64,                     // Objective score of the initial solution
+    pub scale: f64,                     // Scale of the Metropolis acceptance criterion
+    pub temps: Vec<f32>,                // Temperatures of the run
+    pub origin: usize,                  // Temperature step of the last reheat
+    pub stall: usize,                   // Temperature steps without improving the best solution
+    pub evaluations: usize,             // Number of objective evaluations
+    pub since_best: usize,              // Temperature steps since the best solution improved
+    pub sol_best: Data,                 // Best known solution
+    pub sol_current: Data,              // Current solution
+    pub schedule: Vec<Vec<Assignment>>, // Charger schedule of the current solution
+    pub Jb: Vec<f64>,                   // Best objective function score history
+    pub Jc: Vec<f64>,                   // Current objective function score history
+    pub Jn: Vec<f64>,                   // New objective function score history
+    pub terms: Vec<(String, Vec<f64>)>, // Objective term histories of the best solution by name
+    pub reheat: Vec<usize>,             // Reheat count history
+    pub K: Vec<usize>,                  // Local search length history
+}
+
+//===============================================================================
+/// Implementation of `Checkpoint`
+//
+impl Checkpoint {
+    //===========================================================================
+    // PUBLIC
+
+    //---------------------------------------------------------------------------
+    /// Write the checkpoint to disk. The file is written to a temporary file
+    /// first and then renamed so that a crash while writing does not corrupt a
+    /// previously saved checkpoint.
+    ///
+    /// # Input
+    /// * path: Path of the checkpoint file
+    ///
+    /// # Output
+    /// * Result of the file operations
+    ///
+    pub fn save(self: &Checkpoint, path: &str) -> Result<(), Error> {
+        let io = |e: std::io::Error| Error::Io(format!("{}: {}", path, e));
+
+        // Create the checkpoint directory if required
+        if let Some(dir) = Path::new(path).parent() {
+            fs::create_dir_all(dir).map_err(io)?;
+        }
+
+        // Convert the checkpoint to YAML
+        let mut text = String::new();
+        YamlEmitter::new(&mut text)
+            .dump(&self.to_yaml())
+            .map_err(|e| Error::Io(format!("{}: {:?}", path, e)))?;
+
+        // Write to a temporary file and move it into place
+        let tmp = path.to_owned() + ".tmp";
+        fs::write(&tmp, text).map_err(io)?;
+        fs::rename(&tmp, path).map_err(io)?;
+
+        return Ok(());
+    }
+
+    //---------------------------------------------------------------------------
+    /// Load a checkpoint from disk.
+    ///
+    /// # Input
+    /// * path: Path of the checkpoint file
+    ///
+    /// # Output
+    /// * `Some(Checkpoint)` if the file exists, `None` otherwise, or an error
+    ///   if the file could not be read or is not a checkpoint
+    ///
+    pub fn load(path: &str) -> Result<Option<Checkpoint>, Error> {
+        // Read the file if it exists
+        let text = match fs::read_to_string(path) {
+            Ok(t) => t,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(Error::Io(format!("{}: {}", path, e))),
+        };
+
+        // Parse YAML, a malformed checkpoint names the file
+        let invalid = |message: String| {
+            Error::Config(ConfigError {
+                file: path.to_string(),
+                key: String::new(),
+                message,
+            })
+        };
+        let yaml = YamlLoader::load_from_str(&text).map_err(|e| invalid(e.to_string()))?;
+        let doc = match yaml.first() {
+            Some(d) => d,
+            None => return Err(invalid(String::from("The file is empty"))),
+        };
+
+        return match Checkpoint::from_yaml(doc) {
+            Ok(c) => Ok(Some(c)),
+            Err(e) => Err(Error::Config(ConfigError {
+                file: path.to_string(),
+                ..e
+            })),
+        };
+    }
+
+    //---------------------------------------------------------------------------
+    /// Delete a checkpoint from disk if it exists.
+    ///
+    /// # Input
+    /// * path: Path of the checkpoint file
+    ///
+    /// # Output
+    /// * Result of the file operation
+    ///
+    pub fn remove(path: &str) -> Result<(), Error> {
+        return match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(Error::Io(format!("{}: {}", path, e)))
+            }
+            _ => Ok(()),
+        };
+    }
+
+    //---------------------------------------------------------------------------
+    /// Convert a `Data` object into YAML.
+    ///
+    /// # Input
+    /// * dat: Data object
+    ///
+    /// # Output
+    /// * yaml: YAML representation of `dat`
+    ///
+    pub fn data_to_yaml(dat: &Data) -> Yaml {
+        let p = &dat.param;
+        let d = &dat.dec;
+
+        // Parameters
+        let mut param = Hash::new();
+        param.insert(key("A"), uint(p.A));
+        param.insert(key("D"), f32_vec(&p.D));
+        param.insert(
+            key("Gam"),
+            Yaml::Array(p.Gam.iter().map(|x| uint(*x as usize)).collect()),
+        );
+        param.insert(key("K"), uint(p.K as usize));
+        param.insert(key("N"), uint(p.N));
+        param.insert(key("Q"), uint(p.Q));
+        param.insert(key("S"), uint(p.S));
+        param.insert(key("T"), real(p.T));
+        param.insert(key("a"), f32_vec(&p.a));
+        param.insert(key("alpha"), f32_vec(&p.alpha));
+        param.insert(key("beta"), f32_vec(&p.beta));
+        let mut deg = Hash::new();
+        deg.insert(key("cost"), f32_vec(&p.deg.cost));
+        deg.insert(key("cycles"), real(p.deg.cycles));
+        deg.insert(key("exponent"), real(p.deg.exponent));
+        deg.insert(key("soc"), real(p.deg.soc));
+        deg.insert(key("rate"), real(p.deg.rate));
+        param.insert(key("deg"), Yaml::Hash(deg));
+        let mut demand = Hash::new();
+        demand.insert(key("window"), uint(p.demand.window));
+        demand.insert(key("price"), real(p.demand.price));
+        demand.insert(key("peak"), real(p.demand.peak));
+        param.insert(key("demand"), Yaml::Hash(demand));
+        param.insert(key("dt"), real(p.dt));
+        param.insert(key("e"), f32_vec(&p.e));
+        param.insert(key("ep"), f32_vec(&p.ep));
+        param.insert(key("fast"), uint(p.fast));
+        param.insert(
+            key("gam"),
+            Yaml::Array(p.gam.iter().map(|x| Yaml::Integer(*x as i64)).collect()),
+        );
+        param.insert(key("grid"), real(p.grid));
+        param.insert(key("k"), f32_vec(&p.k));
+        param.insert(key("l"), f32_vec(&p.l));
+        param.insert(key("model"), Yaml::String(p.model.clone()));
+        param.insert(key("nu"), real(p.nu));
+        param.insert(key("r"), f32_vec(&p.r));
+        param.insert(key("conv"), f32_vec(&p.conv));
+        param.insert(key("slow"), uint(p.slow));
+        let mut tariff = Hash::new();
+        tariff.insert(key("base"), real(p.tariff.base));
+        tariff.insert(
+            key("bands"),
+            Yaml::Array(
+                p.tariff
+                    .bands
+                    .iter()
+                    .map(|b| f32_vec(&[b.0, b.1, b.2]))
+                    .collect(),
+            ),
+        );
+        param.insert(key("tariff"), Yaml::Hash(tariff));
+        param.insert(key("tk"), f32_vec(&p.tk));
+        param.insert(key("ts"), real(p.ts));
+        param.insert(key("zeta"), f32_vec(&p.zeta));
+
+        // Decision variables
+        let mut dec = Hash::new();
+        dec.insert(key("Jb"), f64_vec(&d.Jb));
+        dec.insert(key("Jc"), f64_vec(&d.Jc));
+        dec.insert(key("Jn"), f64_vec(&d.Jn));
+        dec.insert(key("terms"), terms(&d.terms));
+        dec.insert(
+            key("reheat"),
+            Yaml::Array(d.reheat.iter().map(|x| uint(*x)).collect()),
+        );
+        dec.insert(
+            key("K"),
+            Yaml::Array(d.K.iter().map(|x| uint(*x)).collect()),
+        );
+        dec.insert(key("d"), f32_vec(&d.d));
+        dec.insert(
+            key("g"),
+            Yaml::Array(d.g.iter().map(Vec::as_slice).map(f32_vec).collect()),
+        );
+        dec.insert(key("psi"), bool_mat(&d.psi));
+        dec.insert(key("eta"), f32_vec(&d.eta));
+        dec.insert(key("s"), f32_vec(&d.s));
+        dec.insert(key("sigma"), bool_mat(&d.sigma));
+        dec.insert(key("u"), f32_vec(&d.u));
+        dec.insert(
+            key("v"),
+            Yaml::Array(d.v.iter().map(|x| uint(*x)).collect()),
+        );
+        dec.insert(key("w"), bool_mat(&d.w));
+
+        let mut y = Hash::new();
+        y.insert(key("param"), Yaml::Hash(param));
+        y.insert(key("dec"), Yaml::Hash(dec));
+
+        return Yaml::Hash(y);
+    }
+
+    //---------------------------------------------------------------------------
+    /// Convert YAML into a `Data` object.
+    ///
+    /// # Input
+    /// * y: YAML representation of a data object
+    ///
+    /// # Output
+    /// * dat: Data object, or an error naming the first missing or malformed
+    ///   key
+    ///
+    pub fn data_from_yaml(y: &Yaml) -> Result<Data, ConfigError> {
+        let param = within(y, "param", |p| {
+            return Ok(Parameter {
+                A: at(p, "A", to_usize)?,
+                D: at(p, "D", to_f32_vec)?,
+                Gam: at(p, "Gam", |x| to_vec(x, |g| Ok(to_usize(g)? as u16)))?,
+                K: at(p, "K", to_usize)? as u16,
+                N: at(p, "N", to_usize)?,
+                Q: at(p, "Q", to_usize)?,
+                S: at(p, "S", to_usize)?,
+                T: at(p, "T", to_f32)?,
+                a: at(p, "a", to_f32_vec)?,
+                alpha: at(p, "alpha", to_f32_vec)?,
+                beta: at(p, "beta", to_f32_vec)?,
+                deg: within(p, "deg", |g| {
+                    return Ok(Degradation {
+                        cost: at(g, "cost", to_f32_vec)?,
+                        cycles: at(g, "cycles", to_f32)?,
+                        exponent: at(g, "exponent", to_f32)?,
+                        soc: at(g, "soc", to_f32)?,
+                        rate: at(g, "rate", to_f32)?,
+                    });
+                })?,
+                demand: within(p, "demand", |m| {
+                    return Ok(Demand {
+                        window: at(m, "window", to_usize)?,
+                        price: at(m, "price", to_f32)?,
+                        peak: at(m, "peak", to_f32)?,
+                    });
+                })?,
+                dt: at(p, "dt", to_f32)?,
+                e: at(p, "e", to_f32_vec)?,
+                ep: at(p, "ep", to_f32_vec)?,
+                fast: at(p, "fast", to_usize)?,
+                gam: at(p, "gam", |x| to_vec(x, |g| Ok(to_int(g)? as i16)))?,
+                grid: at(p, "grid", to_f32)?,
+                k: at(p, "k", to_f32_vec)?,
+                l: at(p, "l", to_f32_vec)?,
+                model: at(p, "model", to_str)?,
+                nu: at(p, "nu", to_f32)?,
+                r: at(p, "r", to_f32_vec)?,
+                conv: at(p, "conv", to_f32_vec)?,
+                slow: at(p, "slow", to_usize)?,
+                tariff: within(p, "tariff", |t| {
+                    return Ok(Tariff {
+                        base: at(t, "base", to_f32)?,
+                        bands: at(t, "bands", |x| {
+                            to_vec(x, |b| Ok((to_f32(&b[0])?, to_f32(&b[1])?, to_f32(&b[2])?)))
+                        })?,
+                    });
+                })?,
+                tk: at(p, "tk", to_f32_vec)?,
+                ts: at(p, "ts", to_f32)?,
+                zeta: at(p, "zeta", to_f32_vec)?,
+            });
+        })?;
+
+        let dec = within(y, "dec", |d| {
+            return Ok(DecisionVar {
+                Jb: at(d, "Jb", to_f64_vec)?,
+                Jc: at(d, "Jc", to_f64_vec)?,
+                Jn: at(d, "Jn", to_f64_vec)?,
+                terms: at(d, "terms", to_terms)?,
+                reheat: at(d, "reheat", to_usize_vec)?,
+                K: at(d, "K", to_usize_vec)?,
+                d: at(d, "d", to_f32_vec)?,
+                g: at(d, "g", |x| to_vec(x, to_f32_vec))?,
+                psi: at(d, "psi", to_bool_mat)?,
+                eta: at(d, "eta", to_f32_vec)?,
+                s: at(d, "s", to_f32_vec)?,
+                sigma: at(d, "sigma", to_bool_mat)?,
+                u: at(d, "u", to_f32_vec)?,
+                v: at(d, "v", to_usize_vec)?,
+                w: at(d, "w", to_bool_mat)?,
+            });
+        })?;
+
+        return Ok(Data { param, dec });
+    }
+
+    //---------------------------------------------------------------------------
+    /// Convert a charger schedule into YAML. Each queue is a list of
+    /// `[bus, attach, detach]` entries.
+    ///
+    /// # Input
+    /// * schedule: Charger schedule
+    ///
+    /// # Output
+    /// * yaml: YAML representation of `schedule`
+    ///
+    pub fn schedule_to_yaml(schedule: &[Vec<Assignment>]) -> Yaml {
+        return Yaml::Array(
+            schedule
+                .iter()
+                .map(|q| {
+                    Yaml::Array(
+                        q.iter()
+                            .map(|a| Yaml::Array(vec![uint(a.b), real(a.t.0), real(a.t.1)]))
+                            .collect(),
+                    )
+                })
+                .collect(),
+        );
+    }
+
+    //---------------------------------------------------------------------------
+    /// Convert YAML into a charger schedule.
+    ///
+    /// # Input
+    /// * y: YAML representation of a charger schedule
+    ///
+    /// # Output
+    /// * schedule: Charger schedule, or a description of the first malformed
+    ///   value
+    ///
+    pub fn schedule_from_yaml(y: &Yaml) -> Result<Vec<Vec<Assignment>>, String> {
+        return to_vec(y, |q| {
+            to_vec(q, |a| {
+                Ok(Assignment {
+                    b: to_usize(&a[0])?,
+                    t: (to_f32(&a[1])?, to_f32(&a[2])?),
+                })
+            })
+        });
+    }
+
+    //===========================================================================
+    // PRIVATE
+
+    //---------------------------------------------------------------------------
+    /// Convert the checkpoint into YAML.
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * yaml: YAML representation of the checkpoint
+    ///
+    fn to_yaml(self: &Checkpoint) -> Yaml {
+        let mut y = Hash::new();
+        y.insert(key("step"), uint(self.step));
+        // The seed keeps its bits in the signed integer of YAML
+        y.insert(key("seed"), Yaml::Integer(self.seed as i64));
+        y.insert(key("J0"), real64(self.J0));
+        y.insert(key("J1"), real64(self.J1));
+        y.insert(key("JB"), real64(self.JB));
+        y.insert(key("JORIG"), real64(self.JORIG));
+        y.insert(key("scale"), real64(self.scale));
+        y.insert(key("temps"), f32_vec(&self.temps));
+        y.insert(key("origin"), uint(self.origin));
+        y.insert(key("stall"), uint(self.stall));
+        y.insert(key("evaluations"), uint(self.evaluations));
+        y.insert(key("since_best"), uint(self.since_best));
+        y.insert(key("Jb"), f64_vec(&self.Jb));
+        y.insert(key("Jc"), f64_vec(&self.Jc));
+        y.insert(key("Jn"), f64_vec(&self.Jn));
+        y.insert(key("terms"), terms(&self.terms));
+        y.insert(
+            key("reheat"),
+            Yaml::Array(self.reheat.iter().map(|x| uint(*x)).collect()),
+        );
+        y.insert(
+            key("K"),
+            Yaml::Array(self.K.iter().map(|x| uint(*x)).collect()),
+        );
+        y.insert(
+            key("schedule"),
+            Checkpoint::schedule_to_yaml(&self.schedule),
+        );
+        y.insert(key("sol_best"), Checkpoint::data_to_yaml(&self.sol_best));
+        y.insert(
+            key("sol_current"),
+            Checkpoint::data_to_yaml(&self.sol_current),
+        );
+
+        return Yaml::Hash(y);
+    }
+
+    //---------------------------------------------------------------------------
+    /// Create a checkpoint from YAML. Every key must be present, a resumed run
+    /// needs at least one temperature and the reheat count of the initial
+    /// solution.
+    ///
+    /// # Input
+    /// * y: YAML representation of the checkpoint
+    ///
+    /// # Output
+    /// * Checkpoint, or an error naming the first missing or malformed key
+    ///
+    fn from_yaml(y: &Yaml) -> Result<Checkpoint, ConfigError> {
+        let c = Checkpoint {
+            step: at(y, "step", to_usize)?,
+            seed: at(y, "seed", to_int)? as u64,
+            J0: at(y, "J0", to_f64)?,
+            J1: at(y, "J1", to_f64)?,
+            JB: at(y, "JB", to_f64)?,
+            JORIG: at(y, "JORIG", to_f64)?,
+            scale: at(y, "scale", to_f64)?,
+            temps: at(y, "temps", to_f32_vec)?,
+            origin: at(y, "origin", to_usize)?,
+            stall: at(y, "stall", to_usize)?,
+            evaluations: at(y, "evaluations", to_usize)?,
+            since_best: at(y, "since_best", to_usize)?,
+            Jb: at(y, "Jb", to_f64_vec)?,
+            Jc: at(y, "Jc", to_f64_vec)?,
+            Jn: at(y, "Jn", to_f64_vec)?,
+            terms: at(y, "terms", to_terms)?,
+            reheat: at(y, "reheat", to_usize_vec)?,
+            K: at(y, "K", to_usize_vec)?,
+            schedule: at(y, "schedule", Checkpoint::schedule_from_yaml)?,
+            sol_best: within(y, "sol_best", Checkpoint::data_from_yaml)?,
+            sol_current: within(y, "sol_current", Checkpoint::data_from_yaml)?,
+        };
+
+        // The lists that the run resumes from may not be empty
+        for (k, empty) in [
+            ("temps", c.temps.is_empty()),
+            ("reheat", c.reheat.is_empty()),
+        ] {
+            if empty {
+                return Err(invalid(k, String::from("The list is empty")));
+            }
+        }
+
+        return Ok(c);
+    }
+}
+
+//===============================================================================
+// YAML HELPERS
+//
+// Floating point values are written with their shortest round-trip
+// representation and parsed back from the text so that a loaded checkpoint is
+// bit-for-bit identical to the saved one.
+
+fn key(k: &str) -> Yaml {
+    return Yaml::String(k.to_string());
+}
+
+fn uint(x: usize) -> Yaml {
+    return Yaml::Integer(x as i64);
+}
+
+fn real(x: f32) -> Yaml {
+    return Yaml::Real(format!("{:?}", x));
+}
+
+fn real64(x: f64) -> Yaml {
+    return Yaml::Real(format!("{:?}", x));
+}
+
+fn f32_vec(v: &[f32]) -> Yaml {
+    return Yaml::Array(v.iter().map(|x| real(*x)).collect());
+}
+
+fn f64_vec(v: &[f64]) -> Yaml {
+    return Yaml::Array(v.iter().map(|x| real64(*x)).collect());
+}
+
+fn terms(t: &[(String, Vec<f64>)]) -> Yaml {
+    let mut h = Hash::new();
+    for (name, v) in t.iter() {
+        h.insert(key(name), f64_vec(v));
+    }
+
+    return Yaml::Hash(h);
+}
+
+fn bool_mat(m: &[Vec<bool>]) -> Yaml {
+    return Yaml::Array(
+        m.iter()
+            .map(|r| Yaml::String(r.iter().map(|b| if *b { '1' } else { '0' }).collect()))
+            .collect(),
+    );
+}
+
+fn scalar_str(y: &Yaml) -> Result<String, String> {
+    return match y {
+        Yaml::Real(s) | Yaml::String(s) => Ok(s.clone()),
+        Yaml::Integer(i) => Ok(i.to_string()),
+        _ => Err(format!("Expected a number, found {:?}", y)),
+    };
+}
+
+fn to_f32(y: &Yaml) -> Result<f32, String> {
+    let s = scalar_str(y)?;
+    return s
+        .parse::<f32>()
+        .map_err(|_| format!("Expected a number, found {:?}", s));
+}
+
+fn to_f64(y: &Yaml) -> Result<f64, String> {
+    let s = scalar_str(y)?;
+    return s
+        .parse::<f64>()
+        .map_err(|_| format!("Expected a number, found {:?}", s));
+}
+
+fn to_int(y: &Yaml) -> Result<i64, String> {
+    return y
+        .as_i64()
+        .ok_or_else(|| format!("Expected an integer, found {:?}", y));
+}
+
+fn to_usize(y: &Yaml) -> Result<usize, String> {
+    return usize::try_from(to_int(y)?)
+        .map_err(|_| format!("Expected a non-negative integer, found {:?}", y));
+}
+
+fn to_str(y: &Yaml) -> Result<String, String> {
+    return match y.as_str() {
+        Some(s) => Ok(s.to_string()),
+        None => Err(format!("Expected a string, found {:?}", y)),
+    };
+}
+
+fn to_vec<T>(y: &Yaml, f: impl Fn(&Yaml) -> Result<T, String>) -> Result<Vec<T>, String> {
+    return match y.as_vec() {
+        Some(v) => v.iter().map(f).collect(),
+        None => Err(format!("Expected a list, found {:?}", y)),
+    };
+}
+
+fn to_f64_vec(y: &Yaml) -> Result<Vec<f64>, String> {
+    return to_vec(y, to_f64);
+}
+
+fn to_usize_vec(y: &Yaml) -> Result<Vec<usize>, String> {
+    return to_vec(y, to_usize);
+}
+
+fn to_terms(y: &Yaml) -> Result<Vec<(String, Vec<f64>)>, String> {
+    let h = match y.as_hash() {
+        Some(h) => h,
+        None => return Err(format!("Expected a mapping of term names, found {:?}", y)),
+    };
+
+    return h
+        .iter()
+        .map(|(k, v)| Ok((to_str(k)?, to_vec(v, to_f64)?)))
+        .collect();
+}
+
+fn to_f32_vec(y: &Yaml) -> Result<Vec<f32>, String> {
+    return to_vec(y, to_f32);
+}
+
+fn to_bool_mat(y: &Yaml) -> Result<Vec<Vec<bool>>, String> {
+    return to_vec(y, |r| Ok(to_str(r)?.chars().map(|c| c == '1').collect()));
+}
+
+// A missing or malformed key of a checkpoint. The file is named by `load`.
+fn invalid(k: &str, message: String) -> ConfigError {
+    return ConfigError {
+        file: String::new(),
+        key: k.to_string(),
+        message,
+    };
+}
+
+// Convert the value of key `k` of `y`
+fn at<T>(y: &Yaml, k: &str, f: impl Fn(&Yaml) -> Result<T, String>) -> Result<T, ConfigError> {
+    return match &y[k] {
+        Yaml::BadValue => Err(invalid(k, String::from("The key is missing"))),
+        v => f(v).map_err(|message| invalid(k, message)),
+    };
+}
+
+// Convert the section `k` of `y`, the keys of the section are prefixed by `k`
+fn within<T>(
+    y: &Yaml,
+    k: &str,
+    f: impl Fn(&Yaml) -> Result<T, ConfigError>,
+) -> Result<T, ConfigError> {
+    return match &y[k] {
+        Yaml::BadValue => Err(invalid(k, String::from("The key is missing"))),
+        v => f(v).map_err(|e| invalid(&format!("{}.{}", k, e.key), e.message)),
+    };
+}
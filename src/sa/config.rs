@@ -0,0 +1,940 @@
+//==============================================================================
+// Declare submodules
+pub mod general; // Settings of `general.yaml`
+pub mod schedule; // Settings of `schedule.yaml`
+
+//==============================================================================
+// Import standard library
+use std::fmt;
+use yaml_rust::{Yaml, YamlLoader};
+
+//==============================================================================
+// Import modules
+use crate::lp::objectives::Weights;
+use crate::sa::config::general::GeneralConfig;
+use crate::sa::config::schedule::ScheduleConfig;
+use crate::sa::stop::StopCriteria;
+use crate::sa::temp_func::CoolSchedule;
+
+//==============================================================================
+/// Error of an invalid configuration. The error names the file and the key,
+/// i.e. `schedule.yaml: buses.min_charge: must not exceed buses.max_charge`.
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    pub file: String,    // Path of the configuration file
+    pub key: String,     // Dotted path of the key, empty if the whole file is invalid
+    pub message: String, // Description of the problem
+}
+
+//==============================================================================
+/// Implementation of Display for ConfigError
+//
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.key.is_empty() {
+            return write!(f, "{}: {}", self.file, self.message);
+        }
+
+        return write!(f, "{}: {}: {}", self.file, self.key, self.message);
+    }
+}
+
+//==============================================================================
+/// Implementation of Error for ConfigError
+//
+impl std::error::Error for ConfigError {}
+
+//==============================================================================
+/// Key of a configuration file. The section keeps track of the file and the
+/// dotted path of the key so that every error names both.
+//
+#[derive(Debug, Clone)]
+pub struct Section<'a> {
+    file: &'a str,  // Path of the configuration file
+    key: String,    // Dotted path of the key
+    yaml: &'a Yaml, // Value of the key
+}
+
+//==============================================================================
+/// Implementation of Section
+//
+impl<'a> Section<'a> {
+    //--------------------------------------------------------------------------
+    /// Create the root section of a configuration file
+    ///
+    /// # Input
+    /// * `file` : Path of the configuration file
+    /// * `yaml` : Contents of the configuration file
+    ///
+    /// # Output
+    /// * `Section`
+    ///
+    pub fn new(file: &'a str, yaml: &'a Yaml) -> Section<'a> {
+        return Section {
+            file,
+            key: String::new(),
+            yaml,
+        };
+    }
+
+    //--------------------------------------------------------------------------
+    /// Load a configuration file
+    ///
+    /// # Input
+    /// * `file` : Path of the configuration file
+    ///
+    /// # Output
+    /// * `Yaml`: Contents of the file, or the error of reading or parsing it
+    ///
+    pub fn load(file: &str) -> Result<Yaml, ConfigError> {
+        let error = |message: String| ConfigError {
+            file: file.to_string(),
+            key: String::new(),
+            message,
+        };
+
+        let text = std::fs::read_to_string(file).map_err(|e| error(e.to_string()))?;
+        let docs = YamlLoader::load_from_str(&text).map_err(|e| error(e.to_string()))?;
+
+        return Ok(docs.into_iter().next().unwrap_or(Yaml::Null));
+    }
+
+    //--------------------------------------------------------------------------
+    /// Return the section of a child key
+    ///
+    /// # Input
+    /// * `key` : Name of the child key
+    ///
+    /// # Output
+    /// * `Section`
+    ///
+    pub fn get(self: &Section<'a>, key: &str) -> Section<'a> {
+        let yaml: &'a Yaml = self.yaml;
+        let path = if self.key.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", self.key, key)
+        };
+
+        return Section {
+            file: self.file,
+            key: path,
+            yaml: &yaml[key],
+        };
+    }
+
+    //--------------------------------------------------------------------------
+    /// Return the section of an element of a list
+    ///
+    /// # Input
+    /// * `i` : Index of the element
+    ///
+    /// # Output
+    /// * `Section`
+    ///
+    pub fn at(self: &Section<'a>, i: usize) -> Section<'a> {
+        let yaml: &'a Yaml = self.yaml;
+
+        return Section {
+            file: self.file,
+            key: format!("{}[{}]", self.key, i),
+            yaml: &yaml[i],
+        };
+    }
+
+    //--------------------------------------------------------------------------
+    /// Return the value of the key
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * `Yaml`: Value of the key
+    ///
+    pub fn yaml(self: &Section<'a>) -> &'a Yaml {
+        return self.yaml;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Check whether the key is set
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * `bool`: True if the key has a value
+    ///
+    pub fn is_set(self: &Section<'a>) -> bool {
+        return !self.yaml.is_badvalue() && !self.yaml.is_null();
+    }
+
+    //--------------------------------------------------------------------------
+    /// Create an error for the key
+    ///
+    /// # Input
+    /// * `message` : Description of the problem
+    ///
+    /// # Output
+    /// * `ConfigError`
+    ///
+    pub fn error(self: &Section<'a>, message: &str) -> ConfigError {
+        return ConfigError {
+            file: self.file.to_string(),
+            key: self.key.clone(),
+            message: message.to_string(),
+        };
+    }
+
+    //--------------------------------------------------------------------------
+    /// Return an error for the key unless `ok` holds
+    ///
+    /// # Input
+    /// * `ok`      : Condition the value must satisfy
+    /// * `message` : Description of the violated condition
+    ///
+    /// # Output
+    /// * `Result`
+    ///
+    pub fn check(self: &Section<'a>, ok: bool, message: &str) -> Result<(), ConfigError> {
+        if ok {
+            return Ok(());
+        }
+
+        return Err(self.error(message));
+    }
+
+    //--------------------------------------------------------------------------
+    /// Return the value of a required key
+    ///
+    /// # Input
+    /// * `value` : Value of the key if it is set
+    ///
+    /// # Output
+    /// * `T`: Value, or an error if the key is missing
+    ///
+    pub fn require<T>(self: &Section<'a>, value: Option<T>) -> Result<T, ConfigError> {
+        return value.ok_or_else(|| self.error("is missing"));
+    }
+
+    //--------------------------------------------------------------------------
+    /// Read a number. Integers and reals are accepted.
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * `Option<f64>`: The number if the key is set
+    ///
+    pub fn number(self: &Section<'a>) -> Result<Option<f64>, ConfigError> {
+        if !self.is_set() {
+            return Ok(None);
+        }
+
+        return match self.yaml.as_f64().or(self.yaml.as_i64().map(|x| x as f64)) {
+            Some(x) => Ok(Some(x)),
+            None => Err(self.error(&format!("expected a number, got {:?}", self.yaml))),
+        };
+    }
+
+    //--------------------------------------------------------------------------
+    /// Read a number in the range `[lo, hi]`
+    ///
+    /// # Input
+    /// * `default` : Value if the key is not set, `None` if the key is required
+    /// * `lo`      : Lower bound
+    /// * `hi`      : Upper bound
+    ///
+    /// # Output
+    /// * `f64`: The number
+    ///
+    pub fn number_in(
+        self: &Section<'a>,
+        default: Option<f64>,
+        lo: f64,
+        hi: f64,
+    ) -> Result<f64, ConfigError> {
+        let x = self.require(self.number()?.or(default))?;
+        self.check(
+            lo <= x && x <= hi,
+            &format!("must be in [{}, {}], got {}", lo, hi, x),
+        )?;
+
+        return Ok(x);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Read a positive number
+    ///
+    /// # Input
+    /// * `default` : Value if the key is not set, `None` if the key is required
+    ///
+    /// # Output
+    /// * `f64`: The number
+    ///
+    pub fn positive(self: &Section<'a>, default: Option<f64>) -> Result<f64, ConfigError> {
+        let x = self.require(self.number()?.or(default))?;
+        self.check(x > 0.0, &format!("must be positive, got {}", x))?;
+
+        return Ok(x);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Read an integer
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * `Option<i64>`: The integer if the key is set
+    ///
+    pub fn integer(self: &Section<'a>) -> Result<Option<i64>, ConfigError> {
+        if !self.is_set() {
+            return Ok(None);
+        }
+
+        return match self.yaml.as_i64() {
+            Some(x) => Ok(Some(x)),
+            None => Err(self.error(&format!("expected an integer, got {:?}", self.yaml))),
+        };
+    }
+
+    //--------------------------------------------------------------------------
+    /// Read a non-negative integer
+    ///
+    /// # Input
+    /// * `default` : Value if the key is not set, `None` if the key is required
+    ///
+    /// # Output
+    /// * `usize`: The integer
+    ///
+    pub fn count(self: &Section<'a>, default: Option<usize>) -> Result<usize, ConfigError> {
+        return match self.integer()? {
+            Some(x) if x < 0 => Err(self.error(&format!("must not be negative, got {}", x))),
+            Some(x) => Ok(x as usize),
+            None => self.require(default),
+        };
+    }
+
+    //--------------------------------------------------------------------------
+    /// Read a flag. Booleans and the integers 0 and 1 are accepted.
+    ///
+    /// # Input
+    /// * `default` : Value if the key is not set
+    ///
+    /// # Output
+    /// * `bool`: The flag
+    ///
+    pub fn flag(self: &Section<'a>, default: bool) -> Result<bool, ConfigError> {
+        if !self.is_set() {
+            return Ok(default);
+        }
+
+        return match (self.yaml.as_bool(), self.yaml.as_i64()) {
+            (Some(b), _) => Ok(b),
+            (_, Some(0)) => Ok(false),
+            (_, Some(1)) => Ok(true),
+            _ => Err(self.error(&format!(
+                "expected true, false, 0 or 1, got {:?}",
+                self.yaml
+            ))),
+        };
+    }
+
+    //--------------------------------------------------------------------------
+    /// Read a string
+    ///
+    /// # Input
+    /// * `default` : Value if the key is not set
+    ///
+    /// # Output
+    /// * `String`: The string
+    ///
+    pub fn text(self: &Section<'a>, default: &str) -> Result<String, ConfigError> {
+        if !self.is_set() {
+            return Ok(default.to_string());
+        }
+
+        return match self.yaml.as_str() {
+            Some(s) => Ok(s.to_string()),
+            None => Err(self.error(&format!("expected a string, got {:?}", self.yaml))),
+        };
+    }
+
+    //--------------------------------------------------------------------------
+    /// Read one of a list of strings
+    ///
+    /// # Input
+    /// * `options` : Accepted strings
+    /// * `default` : Value if the key is not set
+    ///
+    /// # Output
+    /// * `String`: The string
+    ///
+    pub fn choice(
+        self: &Section<'a>,
+        options: &[&str],
+        default: &str,
+    ) -> Result<String, ConfigError> {
+        let s = self.text(default)?;
+        self.check(
+            options.contains(&s.as_str()),
+            &format!("must be one of {:?}, got {:?}", options, s),
+        )?;
+
+        return Ok(s);
+    }
+}
+
+//==============================================================================
+/// Time settings of the `time` section of the schedule configuration
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeConfig {
+    pub bod: f32, // Beginning of day [hr]
+    pub eod: f32, // End of day       [hr]
+    pub k: usize, // Tweaks per temperature step
+}
+
+//==============================================================================
+/// Charger settings of the `chargers` section of the schedule configuration
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChargerConfig {
+    pub count: (usize, usize, usize), // Charger counts (wait, slow, fast)
+    pub speed: (f32, f32, f32),       // Charger speeds (wait, slow, fast) [Kw]
+    pub conv: (f32, f32, f32), // Convergence rates of the nonlinear battery model (wait, slow, fast)
+    pub cabinets: Vec<CabinetConfig>, // Chargers that share the power of a cabinet
+}
+
+//==============================================================================
+/// Charger cabinet of the `chargers.cabinets` list of the schedule
+/// configuration. The chargers are counted from the first slow charger, the
+/// fast chargers follow the slow chargers.
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct CabinetConfig {
+    pub chargers: Vec<usize>, // Chargers of the cabinet
+    pub power: f32,           // Power shared by the chargers of the cabinet [Kw]
+}
+
+//==============================================================================
+/// Scale of the Metropolis acceptance criterion
+//
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scale {
+    Fixed(f64), // Fixed scale
+    Auto,       // Mean uphill delta of a random walk from the initial solution
+}
+
+//==============================================================================
+/// Temperature settings of the `temp` section of the schedule configuration.
+/// SA cools along the `TempFunc` passed to it, `schedule`, `init`, `delta` and
+/// `steps` describe the `TempFunc` to create.
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct TempConfig {
+    pub schedule: CoolSchedule, // Cooling schedule
+    pub init: f32,              // Initial temperature, a placeholder if `auto` is set
+    pub delta: f32,             // Temperature step size
+    pub steps: usize,           // Number of temperatures of the Logarithmic and LundyMees schedules
+    pub auto: bool,             // Estimate the initial temperature from the initial solution
+    pub accept: f64,            // Target initial acceptance ratio of the estimated temperature
+    pub samples: usize,         // Tweaks sampled to estimate the initial temperature
+    pub scale: Scale,           // Scale of the Metropolis acceptance criterion
+    pub warmup: usize,          // Random walk length of the "auto" scale
+}
+
+//==============================================================================
+/// Local search settings of the `search` section of the schedule
+/// configuration. If `accept` is 0, every temperature step runs `time.K`
+/// tweaks.
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchConfig {
+    pub accept: usize, // Accepted moves per temperature step
+    pub min: usize,    // Minimum tweaks per temperature step
+    pub max: usize,    // Maximum tweaks per temperature step
+}
+
+//==============================================================================
+/// Reheat settings of the `reheat` section of the schedule configuration. If
+/// `steps` is 0, reheating is disabled.
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReheatConfig {
+    pub steps: usize,   // Temperature steps without improvement before reheating
+    pub fraction: f32,  // Fraction of the initial temperature to reheat to
+    pub max: usize,     // Maximum number of reheats
+    pub improve: f64,   // Minimum relative decrease of the best score that counts as an improvement
+    pub perturb: usize, // Tweaks applied to the best solution on restart
+}
+
+//==============================================================================
+/// Settings of a simulated annealing run
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct SaConfig {
+    pub time: TimeConfig,           // Time settings
+    pub temp: TempConfig,           // Temperature settings
+    pub search: SearchConfig,       // Local search settings
+    pub reheat: ReheatConfig,       // Reheat settings
+    pub stop: StopCriteria,         // Criteria to stop the run early
+    pub run_all_constr: bool,       // Run all the constraints in the objective function
+    pub checkpoint_path: String,    // Path of the checkpoint file
+    pub checkpoint_interval: usize, // Temperature steps between checkpoints, 0 disables checkpoints
+    pub weights: Weights,           // Weights of the terms of the objective function
+}
+
+//==============================================================================
+/// Implementation of TimeConfig
+//
+impl TimeConfig {
+    // Constants
+    const BOD: f64 = 0.0; // Beginning of day when `time.BOD` is not set
+    const EOD: f64 = 24.0; // End of day when `time.EOD` is not set
+    const K: usize = 500; // Tweaks per temperature step when `time.K` is not set
+
+    //--------------------------------------------------------------------------
+    /// Read the `time` section of the schedule configuration
+    ///
+    /// # Input
+    /// * `time` : `time` section
+    ///
+    /// # Output
+    /// * `TimeConfig`
+    ///
+    pub fn from_section(time: &Section) -> Result<TimeConfig, ConfigError> {
+        let bod = time
+            .get("BOD")
+            .number_in(Some(TimeConfig::BOD), 0.0, f64::MAX)?;
+        let eod = time
+            .get("EOD")
+            .number_in(Some(TimeConfig::EOD), 0.0, f64::MAX)?;
+        time.get("EOD")
+            .check(bod < eod, &format!("must be after time.BOD, got {}", eod))?;
+
+        let k = time.get("K").count(Some(TimeConfig::K))?;
+        time.get("K").check(k > 0, "must be positive")?;
+
+        return Ok(TimeConfig {
+            bod: bod as f32,
+            eod: eod as f32,
+            k,
+        });
+    }
+}
+
+//==============================================================================
+/// Implementation of Default for TimeConfig
+//
+impl Default for TimeConfig {
+    fn default() -> Self {
+        return TimeConfig {
+            bod: TimeConfig::BOD as f32,
+            eod: TimeConfig::EOD as f32,
+            k: TimeConfig::K,
+        };
+    }
+}
+
+//==============================================================================
+/// Implementation of ChargerConfig
+//
+impl ChargerConfig {
+    //--------------------------------------------------------------------------
+    /// Read the `chargers` section of the schedule configuration. There is a
+    /// wait charger for every bus. A charger belongs to at most one cabinet.
+    ///
+    /// # Input
+    /// * `chargers` : `chargers` section
+    /// * `num_bus`  : Number of buses
+    ///
+    /// # Output
+    /// * `ChargerConfig`
+    ///
+    pub fn from_section(chargers: &Section, num_bus: usize) -> Result<ChargerConfig, ConfigError> {
+        let slow = chargers.get("slow");
+        let fast = chargers.get("fast");
+        let count = (
+            num_bus,
+            slow.get("num").count(None)?,
+            fast.get("num").count(None)?,
+        );
+
+        return Ok(ChargerConfig {
+            count,
+            speed: (
+                0.0,
+                slow.get("rate").positive(None)? as f32,
+                fast.get("rate").positive(None)? as f32,
+            ),
+            conv: (
+                0.0,
+                slow.get("conv").number_in(Some(0.0), 0.0, f64::MAX)? as f32,
+                fast.get("conv").number_in(Some(0.0), 0.0, f64::MAX)? as f32,
+            ),
+            cabinets: ChargerConfig::cabinets(&chargers.get("cabinets"), count.1 + count.2)?,
+        });
+    }
+
+    //--------------------------------------------------------------------------
+    /// Read the `chargers.cabinets` list of the schedule configuration
+    ///
+    /// # Input
+    /// * `list`  : `cabinets` list
+    /// * `total` : Number of slow and fast chargers
+    ///
+    /// # Output
+    /// * `Vec<CabinetConfig>`: Charger cabinets, empty if the list is not set
+    ///
+    fn cabinets(list: &Section, total: usize) -> Result<Vec<CabinetConfig>, ConfigError> {
+        let mut cabinets: Vec<CabinetConfig> = Vec::new();
+        let mut used: Vec<bool> = vec![false; total];
+        let n = if list.is_set() {
+            list.require(list.yaml().as_vec())?.len()
+        } else {
+            0
+        };
+
+        for i in 0..n {
+            let c = list.at(i);
+            let ids = c.get("chargers");
+            let m = ids.require(ids.yaml().as_vec())?.len();
+            ids.check(m > 0, "must list at least one charger")?;
+
+            let mut members: Vec<usize> = Vec::new();
+            for j in 0..m {
+                let id = ids.at(j);
+                let k = id.count(None)?;
+                id.check(
+                    k < total,
+                    &format!("must be below the number of chargers ({} >= {})", k, total),
+                )?;
+                id.check(!used[k], &format!("charger {} is already in a cabinet", k))?;
+                used[k] = true;
+                members.push(k);
+            }
+
+            cabinets.push(CabinetConfig {
+                chargers: members,
+                power: c.get("power").positive(None)? as f32,
+            });
+        }
+
+        return Ok(cabinets);
+    }
+}
+
+//==============================================================================
+/// Implementation of Default for ChargerConfig. The default is a single fast
+/// charger.
+//
+impl Default for ChargerConfig {
+    fn default() -> Self {
+        return ChargerConfig {
+            count: (0, 0, 1),
+            speed: (0.0, 30.0, 910.0),
+            conv: (0.0, 0.0, 0.0),
+            cabinets: Vec::new(),
+        };
+    }
+}
+
+//==============================================================================
+/// Implementation of TempConfig
+//
+impl TempConfig {
+    // Constants
+    pub const DEFAULT_SCALE: f64 = 9000000.0; // Metropolis scale when `temp.scale` is not set
+    const INIT_TEMP: f64 = 90000.0; // Initial temperature when `temp.init` is not set
+    const DELTA: f64 = 0.997; // Temperature step size when `temp.delta` is not set
+    const STEPS: usize = 4000; // Length of the Logarithmic and LundyMees schedules when `temp.steps` is not set
+    const WARMUP_STEPS: usize = 100; // Random walk length when `temp.warmup` is not set
+    const TARGET_ACCEPT: f64 = 0.8; // Initial acceptance ratio when `temp.accept` is not set
+    const TEMP_SAMPLES: usize = 100; // Number of tweaks when `temp.samples` is not set
+    const SCHEDULES: [&'static str; 7] = [
+        "Linear",
+        "Geometric",
+        "Exponential",
+        "Logarithmic",
+        "LundyMees",
+        "Adaptive",
+        "Piecewise",
+    ]; // Names of the cooling schedules
+
+    //--------------------------------------------------------------------------
+    /// Read the `temp` section of the schedule configuration. The initial
+    /// temperature and the scale are either a number or "auto". The step size
+    /// must be in (0, 1) for the Geometric and Adaptive schedules. The
+    /// Logarithmic and LundyMees schedules ignore the step size, theirs is fit
+    /// so that they cool to the minimum temperature in `steps` steps.
+    ///
+    /// # Input
+    /// * `temp` : `temp` section
+    ///
+    /// # Output
+    /// * `TempConfig`
+    ///
+    pub fn from_section(temp: &Section) -> Result<TempConfig, ConfigError> {
+        // Check the parameters of the schedule before creating it
+        let kind = temp
+            .get("type")
+            .choice(&TempConfig::SCHEDULES, "Geometric")?;
+        let mut target = 0.4;
+        if kind == "Adaptive" {
+            let t = temp.get("target");
+            target = t.number_in(Some(target), 0.0, 1.0)?;
+            t.check(
+                target > 0.0 && target < 1.0,
+                &format!("must be in (0, 1), got {}", target),
+            )?;
+        }
+        let mut points = Vec::new();
+        if kind == "Piecewise" {
+            points = TempConfig::read_points(&temp.get("points"))?;
+        }
+        let schedule = CoolSchedule::new(&kind, target as f32, points)
+            .map_err(|e| temp.get("type").error(&e.to_string()))?;
+
+        // The range of the step size depends on the schedule
+        let d = temp.get("delta");
+        let delta = d.require(d.number()?.or(Some(TempConfig::DELTA)))?;
+        match schedule {
+            CoolSchedule::Geometric | CoolSchedule::Adaptive(_) => d.check(
+                delta > 0.0 && delta < 1.0,
+                &format!("must be in (0, 1) for the {} schedule, got {}", kind, delta),
+            )?,
+            CoolSchedule::Exponential => d.check(delta != 0.0, "must not be 0")?,
+            CoolSchedule::Logarithmic | CoolSchedule::LundyMees | CoolSchedule::Piecewise(_) => (),
+            _ => d.check(
+                delta > 0.0,
+                &format!("must be positive for the {} schedule, got {}", kind, delta),
+            )?,
+        }
+
+        // The fit schedules need at least one step after the initial temperature
+        let n = temp.get("steps");
+        let steps = n.count(Some(TempConfig::STEPS))?;
+        n.check(steps > 1, &format!("must be larger than 1, got {}", steps))?;
+
+        // The initial temperature is a number or "auto"
+        let init = temp.get("init");
+        let auto = init.yaml().as_str() == Some("auto");
+        let t0 = if auto {
+            1.0
+        } else {
+            init.positive(Some(TempConfig::INIT_TEMP))?
+        };
+
+        // The scale of the Metropolis acceptance criterion is a number or "auto"
+        let s = temp.get("scale");
+        let scale = if s.yaml().as_str() == Some("auto") {
+            Scale::Auto
+        } else {
+            Scale::Fixed(s.positive(Some(TempConfig::DEFAULT_SCALE))?)
+        };
+
+        // The target acceptance ratio must be reachable
+        let a = temp.get("accept");
+        let accept = a.number_in(Some(TempConfig::TARGET_ACCEPT), 0.0, 1.0)?;
+        a.check(
+            accept > 0.0 && accept < 1.0,
+            &format!("must be in (0, 1), got {}", accept),
+        )?;
+
+        let samples = temp.get("samples").count(Some(TempConfig::TEMP_SAMPLES))?;
+        temp.get("samples").check(samples > 0, "must be positive")?;
+
+        return Ok(TempConfig {
+            schedule,
+            init: t0 as f32,
+            delta: delta as f32,
+            steps,
+            auto,
+            accept,
+            samples,
+            scale,
+            warmup: temp.get("warmup").count(Some(TempConfig::WARMUP_STEPS))?,
+        });
+    }
+
+    //--------------------------------------------------------------------------
+    /// Read the `[step, temperature]` points of the Piecewise schedule. The
+    /// steps must increase and the temperatures must be positive.
+    ///
+    /// # Input
+    /// * `points` : `temp.points` key
+    ///
+    /// # Output
+    /// * `(step, temperature)` points
+    ///
+    fn read_points(points: &Section) -> Result<Vec<(usize, f32)>, ConfigError> {
+        let p = points.require(points.yaml().as_vec())?;
+        points.check(!p.is_empty(), "must not be empty")?;
+
+        let mut prev: Option<i64> = None;
+        let mut out = Vec::with_capacity(p.len());
+        for (i, point) in p.iter().enumerate() {
+            let step = point[0].as_i64();
+            let t = point[1].as_f64().or(point[1].as_i64().map(|t| t as f64));
+
+            points.check(
+                point.as_vec().map(|v| v.len()) == Some(2) && step.is_some_and(|s| s >= 0),
+                &format!("point {} must be [step, temperature]", i),
+            )?;
+            points.check(
+                t.is_some_and(|t| t > 0.0),
+                &format!("point {} must have a positive temperature", i),
+            )?;
+            points.check(
+                prev < step,
+                &format!(
+                    "point {} must have a larger step than the point before it",
+                    i
+                ),
+            )?;
+
+            prev = step;
+            out.push((step.unwrap() as usize, t.unwrap() as f32));
+        }
+
+        return Ok(out);
+    }
+}
+
+//==============================================================================
+/// Implementation of Default for TempConfig
+//
+impl Default for TempConfig {
+    fn default() -> Self {
+        return TempConfig::from_section(&Section::new("", &Yaml::Null)).unwrap();
+    }
+}
+
+//==============================================================================
+/// Implementation of SearchConfig
+//
+impl SearchConfig {
+    //--------------------------------------------------------------------------
+    /// Read the `search` section of the schedule configuration. The bounds
+    /// default to `time.K`.
+    ///
+    /// # Input
+    /// * `search` : `search` section
+    /// * `k`      : Tweaks per temperature step
+    ///
+    /// # Output
+    /// * `SearchConfig`
+    ///
+    pub fn from_section(search: &Section, k: usize) -> Result<SearchConfig, ConfigError> {
+        let accept = search.get("accept").count(Some(0))?;
+        let min = search.get("min").count(Some(k))?;
+        let max = search.get("max").count(Some(k))?;
+
+        // The bounds only apply to the adaptive local search length
+        if accept > 0 {
+            search.get("min").check(
+                min <= max,
+                &format!("must not exceed search.max ({} > {})", min, max),
+            )?;
+            search.get("max").check(max > 0, "must be positive")?;
+        }
+
+        return Ok(SearchConfig { accept, min, max });
+    }
+}
+
+//==============================================================================
+/// Implementation of ReheatConfig
+//
+impl ReheatConfig {
+    // Constants
+    const REHEAT_FRACTION: f64 = 0.5; // Fraction of the initial temperature when `reheat.fraction` is not set
+    const REHEAT_MAX: usize = 10; // Maximum number of reheats when `reheat.max` is not set
+
+    //--------------------------------------------------------------------------
+    /// Read the `reheat` section of the schedule configuration
+    ///
+    /// # Input
+    /// * `reheat` : `reheat` section
+    ///
+    /// # Output
+    /// * `ReheatConfig`
+    ///
+    pub fn from_section(reheat: &Section) -> Result<ReheatConfig, ConfigError> {
+        let fraction = reheat.get("fraction");
+        let f = fraction.number_in(Some(ReheatConfig::REHEAT_FRACTION), 0.0, 1.0)?;
+        fraction.check(f > 0.0, &format!("must be in (0, 1], got {}", f))?;
+
+        return Ok(ReheatConfig {
+            steps: reheat.get("steps").count(Some(0))?,
+            fraction: f as f32,
+            max: reheat.get("max").count(Some(ReheatConfig::REHEAT_MAX))?,
+            improve: reheat.get("improve").number_in(Some(0.0), 0.0, f64::MAX)?,
+            perturb: reheat.get("perturb").count(Some(0))?,
+        });
+    }
+}
+
+//==============================================================================
+/// Implementation of Default for ReheatConfig. Reheating is disabled.
+//
+impl Default for ReheatConfig {
+    fn default() -> Self {
+        return ReheatConfig::from_section(&Section::new("", &Yaml::Null)).unwrap();
+    }
+}
+
+//==============================================================================
+/// Implementation of SaConfig
+//
+impl SaConfig {
+    //--------------------------------------------------------------------------
+    /// Collect the settings of a run from the general and schedule
+    /// configuration
+    ///
+    /// # Input
+    /// * `general`  : General configuration
+    /// * `schedule` : Schedule configuration
+    ///
+    /// # Output
+    /// * `SaConfig`
+    ///
+    pub fn new(general: &GeneralConfig, schedule: &ScheduleConfig) -> SaConfig {
+        return SaConfig {
+            time: schedule.time.clone(),
+            temp: schedule.temp.clone(),
+            search: schedule.search.clone(),
+            reheat: schedule.reheat.clone(),
+            stop: schedule.stop.clone(),
+            run_all_constr: general.run_all_constr,
+            checkpoint_path: general.checkpoint_path.clone() + ".yaml",
+            checkpoint_interval: general.checkpoint_interval,
+            weights: schedule.weights.clone(),
+        };
+    }
+}
+
+//==============================================================================
+/// Implementation of Default for SaConfig. Checkpoints are disabled, so a
+/// default run does not touch the filesystem.
+//
+impl Default for SaConfig {
+    fn default() -> Self {
+        let time = TimeConfig::default();
+        let search = SearchConfig::from_section(&Section::new("", &Yaml::Null), time.k).unwrap();
+
+        return SaConfig {
+            time,
+            temp: TempConfig::default(),
+            search,
+            reheat: ReheatConfig::default(),
+            stop: StopCriteria::default(),
+            run_all_constr: false,
+            checkpoint_path: String::from("data/checkpoint.yaml"),
+            checkpoint_interval: 0,
+            weights: Weights::default(),
+        };
+    }
+}
@@ -0,0 +1,120 @@
+//==============================================================================
+// Import standard library
+use yaml_rust::Yaml;
+
+//==============================================================================
+// Import modules
+use crate::sa::config::{ConfigError, Section};
+
+//==============================================================================
+/// Settings of the general configuration, `general.yaml`
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneralConfig {
+    pub cores: usize,               // Number of threads
+    pub plot: bool,                 // Plot the results
+    pub rtp: bool,                  // Plot the best solution in real time
+    pub bat_model: String,          // Battery dynamic model, "linear" or "nonlinear"
+    pub schedule: String,           // Route generator, "rand" or "csv"
+    pub solution_gen: String,       // Solution generator, "wait" or "rand"
+    pub tweak_method: String,       // Solution tweaker, "quick" or "exhaustive"
+    pub solver: String,             // Solver, "sa", "pt" or "mosa"
+    pub island_interval: usize, // Temperature steps between the islands sharing their best solution
+    pub island_adopt: String,   // How an island adopts the best solution, "best" or "crossover"
+    pub seed: Option<u64>,      // Seed of the random number generator, `None` draws a random seed
+    pub run_all_constr: bool,   // Run all the constraints in the objective function
+    pub load_from_file: bool,   // Resume the previous run from its checkpoint
+    pub checkpoint_interval: usize, // Temperature steps between checkpoints, 0 disables checkpoints
+    pub checkpoint_path: String, // Base path of the checkpoint files
+    pub event_log: String,      // Base path of the event log, empty disables the log
+    pub event_log_iterations: bool, // Log every candidate solution
+    pub run_prev: bool,         // Load the previous input parameters
+    pub verbose: bool,          // Verbose output
+}
+
+//==============================================================================
+/// Implementation of GeneralConfig
+//
+impl GeneralConfig {
+    //--------------------------------------------------------------------------
+    /// Load and check the general configuration
+    ///
+    /// # Input
+    /// * `path` : Path of `general.yaml`
+    ///
+    /// # Output
+    /// * `GeneralConfig`, or the first invalid key
+    ///
+    pub fn load(path: &str) -> Result<GeneralConfig, ConfigError> {
+        return GeneralConfig::from_yaml(&Section::load(path)?, path);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Check the general configuration. Missing keys take their default.
+    ///
+    /// # Input
+    /// * `yaml` : Contents of the general configuration
+    /// * `file` : Name of the configuration file used in errors
+    ///
+    /// # Output
+    /// * `GeneralConfig`, or the first invalid key
+    ///
+    pub fn from_yaml(yaml: &Yaml, file: &str) -> Result<GeneralConfig, ConfigError> {
+        let root = Section::new(file, yaml);
+
+        let cores = root.get("cores").count(Some(1))?;
+        root.get("cores").check(cores > 0, "must be positive")?;
+
+        // A negative seed draws a random seed
+        let seed = root
+            .get("seed")
+            .integer()?
+            .filter(|s| *s >= 0)
+            .map(|s| s as u64);
+
+        // The islands must solve the same instance
+        let schedule = root.get("schedule").choice(&["rand", "csv"], "csv")?;
+        let island_interval = root.get("island_interval").count(Some(0))?;
+        root.get("island_interval").check(
+            island_interval == 0 || schedule == "csv",
+            "requires the `csv` schedule",
+        )?;
+
+        return Ok(GeneralConfig {
+            cores,
+            plot: root.get("plot").flag(false)?,
+            rtp: root.get("rtp").flag(false)?,
+            bat_model: root
+                .get("bat_model")
+                .choice(&["linear", "nonlinear"], "linear")?,
+            schedule,
+            solution_gen: root.get("solution_gen").choice(&["wait", "rand"], "rand")?,
+            tweak_method: root
+                .get("tweak_method")
+                .choice(&["quick", "exhaustive"], "exhaustive")?,
+            solver: root.get("solver").choice(&["sa", "pt", "mosa"], "sa")?,
+            island_interval,
+            island_adopt: root
+                .get("island_adopt")
+                .choice(&["best", "crossover"], "best")?,
+            seed,
+            run_all_constr: root.get("run_all_constr").flag(false)?,
+            load_from_file: root.get("load_from_file").flag(false)?,
+            checkpoint_interval: root.get("checkpoint_interval").count(Some(0))?,
+            checkpoint_path: root.get("checkpoint_path").text("data/checkpoint")?,
+            event_log: root.get("event_log").text("")?,
+            event_log_iterations: root.get("event_log_iterations").flag(false)?,
+            run_prev: root.get("run_prev").flag(false)?,
+            verbose: root.get("verbose").flag(false)?,
+        });
+    }
+}
+
+//==============================================================================
+/// Implementation of Default for GeneralConfig
+//
+impl Default for GeneralConfig {
+    fn default() -> Self {
+        return GeneralConfig::from_yaml(&Yaml::Null, "").unwrap();
+    }
+}
@@ -0,0 +1,641 @@
+//==============================================================================
+// Import standard library
+use yaml_rust::Yaml;
+
+//==============================================================================
+// Import modules
+use crate::lp::objectives::{terms, Weights};
+use crate::sa::config::{
+    ChargerConfig, ConfigError, ReheatConfig, SearchConfig, Section, TempConfig, TimeConfig,
+};
+use crate::sa::data::{Degradation, Demand, Tariff};
+use crate::sa::stop::StopCriteria;
+
+//==============================================================================
+/// Bus settings of the `buses` section of the schedule configuration
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct BusConfig {
+    pub num_bus: usize,    // Number of buses
+    pub num_visit: usize,  // Number of visits of the random route generator
+    pub bat_capacity: f32, // Battery capacity [KWh]
+    pub max_charge: f32,   // Maximum state of charge [0-1]
+    pub min_charge: f32,   // Minimum state of charge [0-1]
+    pub dis_rate: f32,     // Discharge rate [Kw]
+    pub min_rest: f32,     // Minimum rest between routes [hr]
+    pub max_rest: f32,     // Maximum rest between routes [hr]
+}
+
+//==============================================================================
+/// Initial charge settings of the `initial_charge` section of the schedule
+/// configuration
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct InitialChargeConfig {
+    pub min: f32, // Minimum initial state of charge [0-1]
+    pub max: f32, // Maximum initial state of charge [0-1]
+}
+
+//==============================================================================
+/// Parallel tempering settings of the `pt` section of the schedule
+/// configuration
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct PtConfig {
+    pub replicas: usize, // Number of replicas
+    pub t_min: f32,      // Temperature of the coldest replica
+    pub t_max: f32,      // Temperature of the hottest replica
+    pub rounds: usize,   // Number of local search rounds
+    pub interval: usize, // Rounds between exchanges of neighboring replicas
+}
+
+//==============================================================================
+/// Multi-objective annealing settings of the `mosa` section of the schedule
+/// configuration
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct MosaConfig {
+    pub archive: usize, // Number of solutions kept on the Pareto front (hard limit)
+    pub soft: usize,    // Size the archive may grow to before it is thinned (soft limit)
+    pub t_max: f32,     // Initial temperature
+    pub t_min: f32,     // Final temperature
+    pub alpha: f32,     // Cooling factor of each temperature step
+}
+
+//==============================================================================
+/// Time band of the `tariff.bands` list of the schedule configuration
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct TariffBand {
+    pub start: f32,       // Start of the band [hr of the day]
+    pub end: f32,         // End of the band [hr of the day]
+    pub price: f32,       // Price of energy in the band [$/KWh]
+    pub days: Vec<usize>, // Weekdays of the band, 0 is Monday
+}
+
+//==============================================================================
+/// Time-of-use tariff of the `tariff` section of the schedule configuration
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct TariffConfig {
+    pub day: usize,    // Weekday of the beginning of the time horizon, 0 is Monday
+    pub off_peak: f32, // Price of energy outside of the bands [$/KWh]
+    pub bands: Vec<TariffBand>, // Time bands
+}
+
+//==============================================================================
+/// Settings of the schedule configuration, `schedule.yaml`
+//
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleConfig {
+    pub ignore: Vec<usize>,   // Routes of the CSV schedule to ignore
+    pub time: TimeConfig,     // Time settings
+    pub temp: TempConfig,     // Temperature settings
+    pub search: SearchConfig, // Local search settings
+    pub reheat: ReheatConfig, // Reheat settings
+    pub stop: StopCriteria,   // Criteria to stop a run early
+    pub pt: PtConfig,         // Parallel tempering settings
+    pub mosa: MosaConfig,     // Multi-objective annealing settings
+    pub buses: BusConfig,     // Bus settings
+    pub initial_charge: InitialChargeConfig, // Initial charge settings
+    pub final_charge: f32,    // Final state of charge [0-1]
+    pub chargers: ChargerConfig, // Charger settings
+    pub tariff: TariffConfig, // Time-of-use tariff
+    pub demand: Demand,       // Demand charge
+    pub degradation: Degradation, // Battery degradation cost
+    pub grid: f32,            // Power limit of the grid connection, 0 is unlimited [KW]
+    pub weights: Weights,     // Weights of the terms of the objective function
+}
+
+//==============================================================================
+/// Implementation of BusConfig
+//
+impl BusConfig {
+    //--------------------------------------------------------------------------
+    /// Read the `buses` section of the schedule configuration. The states of
+    /// charge must be in [0, 1] and the minimum may not exceed the maximum.
+    ///
+    /// # Input
+    /// * `buses` : `buses` section
+    ///
+    /// # Output
+    /// * `BusConfig`
+    ///
+    pub fn from_section(buses: &Section) -> Result<BusConfig, ConfigError> {
+        let max_charge = buses.get("max_charge").number_in(Some(1.0), 0.0, 1.0)?;
+        let min_charge = buses.get("min_charge").number_in(None, 0.0, 1.0)?;
+        buses.get("min_charge").check(
+            min_charge <= max_charge,
+            &format!(
+                "must not exceed buses.max_charge ({} > {})",
+                min_charge, max_charge
+            ),
+        )?;
+
+        let min_rest = buses.get("min_rest").number_in(None, 0.0, f64::MAX)?;
+        let max_rest = buses.get("max_rest").number_in(None, 0.0, f64::MAX)?;
+        buses.get("min_rest").check(
+            min_rest <= max_rest,
+            &format!(
+                "must not exceed buses.max_rest ({} > {})",
+                min_rest, max_rest
+            ),
+        )?;
+
+        return Ok(BusConfig {
+            num_bus: buses.get("num_bus").count(None)?,
+            num_visit: buses.get("num_visit").count(None)?,
+            bat_capacity: buses.get("bat_capacity").positive(None)? as f32,
+            max_charge: max_charge as f32,
+            min_charge: min_charge as f32,
+            dis_rate: buses.get("dis_rate").number_in(None, 0.0, f64::MAX)? as f32,
+            min_rest: min_rest as f32,
+            max_rest: max_rest as f32,
+        });
+    }
+}
+
+//==============================================================================
+/// Implementation of PtConfig
+//
+impl PtConfig {
+    // Constants
+    const REPLICAS: usize = 8; // Number of replicas when `pt.replicas` is not set
+    const T_MIN: f64 = 1.0; // Temperature of the coldest replica when `pt.t_min` is not set
+    const ROUNDS: usize = 1000; // Number of rounds when `pt.rounds` is not set
+    const INTERVAL: usize = 1; // Rounds between exchanges when `pt.interval` is not set
+
+    //--------------------------------------------------------------------------
+    /// Read the `pt` section of the schedule configuration. The temperature of
+    /// the hottest replica defaults to the initial temperature.
+    ///
+    /// # Input
+    /// * `pt`   : `pt` section
+    /// * `temp` : Temperature settings
+    ///
+    /// # Output
+    /// * `PtConfig`
+    ///
+    pub fn from_section(pt: &Section, temp: &TempConfig) -> Result<PtConfig, ConfigError> {
+        let replicas = pt.get("replicas").count(Some(PtConfig::REPLICAS))?;
+        pt.get("replicas").check(replicas > 0, "must be positive")?;
+        let rounds = pt.get("rounds").count(Some(PtConfig::ROUNDS))?;
+        let interval = pt.get("interval").count(Some(PtConfig::INTERVAL))?;
+        pt.get("interval").check(interval > 0, "must be positive")?;
+
+        // The ladder runs from the coldest to the hottest replica
+        let t_min = pt.get("t_min").positive(Some(PtConfig::T_MIN))?;
+        let t_init = if temp.auto { t_min } else { temp.init as f64 };
+        let t_max = pt.get("t_max").positive(Some(t_init.max(t_min)))?;
+        pt.get("t_max").check(
+            t_min <= t_max,
+            &format!("must not be below pt.t_min ({} < {})", t_max, t_min),
+        )?;
+
+        return Ok(PtConfig {
+            replicas,
+            t_min: t_min as f32,
+            t_max: t_max as f32,
+            rounds,
+            interval,
+        });
+    }
+}
+
+//==============================================================================
+/// Implementation of MosaConfig
+//
+impl MosaConfig {
+    // Constants
+    const ARCHIVE: usize = 20; // Size of the Pareto front when `mosa.archive` is not set
+    const T_MAX: f64 = 100.0; // Initial temperature when `mosa.t_max` is not set
+    const T_MIN: f64 = 0.01; // Final temperature when `mosa.t_min` is not set
+    const ALPHA: f64 = 0.9; // Cooling factor when `mosa.alpha` is not set
+
+    //--------------------------------------------------------------------------
+    /// Read the `mosa` section of the schedule configuration. The soft limit
+    /// defaults to twice the archive size and may not be below it. The
+    /// temperatures apply to the amount of domination, which is in [0, 1].
+    ///
+    /// # Input
+    /// * `mosa` : `mosa` section
+    ///
+    /// # Output
+    /// * `MosaConfig`
+    ///
+    pub fn from_section(mosa: &Section) -> Result<MosaConfig, ConfigError> {
+        let archive = mosa.get("archive").count(Some(MosaConfig::ARCHIVE))?;
+        mosa.get("archive").check(archive > 0, "must be positive")?;
+        let soft = mosa.get("soft").count(Some(2 * archive))?;
+        mosa.get("soft").check(
+            soft >= archive,
+            &format!("must not be below mosa.archive ({} < {})", soft, archive),
+        )?;
+
+        // The temperature falls from `t_max` to `t_min`
+        let t_max = mosa.get("t_max").positive(Some(MosaConfig::T_MAX))?;
+        let t_min = mosa
+            .get("t_min")
+            .positive(Some(MosaConfig::T_MIN.min(t_max)))?;
+        mosa.get("t_min").check(
+            t_min <= t_max,
+            &format!("must not exceed mosa.t_max ({} > {})", t_min, t_max),
+        )?;
+        let alpha = mosa.get("alpha").positive(Some(MosaConfig::ALPHA))?;
+        mosa.get("alpha").check(alpha < 1.0, "must be below 1")?;
+
+        return Ok(MosaConfig {
+            archive,
+            soft,
+            t_max: t_max as f32,
+            t_min: t_min as f32,
+            alpha: alpha as f32,
+        });
+    }
+}
+
+//==============================================================================
+/// Implementation of TariffConfig
+//
+impl TariffConfig {
+    // Constants
+    const DAYS: [&'static str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+    const OFF_PEAK: f64 = 1.0; // Price outside of the bands when `tariff.off_peak` is not set
+
+    //--------------------------------------------------------------------------
+    /// Read the `tariff` section of the schedule configuration. The bands are
+    /// given in hours of the day and bands of the same weekday may not
+    /// overlap. A band without `days` applies to every day.
+    ///
+    /// # Input
+    /// * `tariff` : `tariff` section
+    ///
+    /// # Output
+    /// * `TariffConfig`
+    ///
+    pub fn from_section(tariff: &Section) -> Result<TariffConfig, ConfigError> {
+        let day = TariffConfig::day(&tariff.get("day"), "mon")?;
+        let off_peak =
+            tariff
+                .get("off_peak")
+                .number_in(Some(TariffConfig::OFF_PEAK), 0.0, f64::MAX)? as f32;
+
+        let list = tariff.get("bands");
+        let mut bands: Vec<TariffBand> = Vec::new();
+        let n = if list.is_set() {
+            list.require(list.yaml().as_vec())?.len()
+        } else {
+            0
+        };
+
+        for i in 0..n {
+            let b = list.at(i);
+            let start = b.get("start").number_in(None, 0.0, 24.0)?;
+            let end = b.get("end").number_in(None, 0.0, 24.0)?;
+            b.get("end").check(
+                start < end,
+                &format!("must be after the start of the band ({} <= {})", end, start),
+            )?;
+
+            let days = b.get("days");
+            let days: Vec<usize> = if days.is_set() {
+                let m = days.require(days.yaml().as_vec())?.len();
+                (0..m)
+                    .map(|j| TariffConfig::day(&days.at(j), ""))
+                    .collect::<Result<Vec<usize>, ConfigError>>()?
+            } else {
+                (0..7).collect()
+            };
+
+            let band = TariffBand {
+                start: start as f32,
+                end: end as f32,
+                price: b.get("price").number_in(None, 0.0, f64::MAX)? as f32,
+                days,
+            };
+
+            // Bands of the same weekday may not overlap
+            for (j, other) in bands.iter().enumerate() {
+                let shared = band.days.iter().any(|d| other.days.contains(d));
+                b.check(
+                    !shared || band.end <= other.start || other.end <= band.start,
+                    &format!("must not overlap band {}", j),
+                )?;
+            }
+
+            bands.push(band);
+        }
+
+        return Ok(TariffConfig {
+            day,
+            off_peak,
+            bands,
+        });
+    }
+
+    //--------------------------------------------------------------------------
+    /// Resolve the tariff over the time horizon. Each band is repeated on every
+    /// day of the horizon that falls on one of its weekdays.
+    ///
+    /// # Input
+    /// * `bod` : Beginning of the time horizon [hr]
+    /// * `eod` : End of the time horizon [hr]
+    ///
+    /// # Output
+    /// * `Tariff`: Bands in the time of the horizon
+    ///
+    pub fn tariff(self: &TariffConfig, bod: f32, eod: f32) -> Tariff {
+        let mut bands: Vec<(f32, f32, f32)> = Vec::new();
+
+        // For every day of the time horizon
+        let first = (bod / 24.0).floor() as i64;
+        let last = (eod / 24.0).ceil() as i64;
+        for day in first..last {
+            let weekday = (self.day as i64 + day).rem_euclid(7) as usize;
+            let t0 = 24.0 * day as f32;
+
+            for b in self.bands.iter().filter(|b| b.days.contains(&weekday)) {
+                let start = (t0 + b.start).max(bod);
+                let end = (t0 + b.end).min(eod);
+                if start < end {
+                    bands.push((start, end, b.price));
+                }
+            }
+        }
+        bands.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+        return Tariff {
+            base: self.off_peak,
+            bands,
+        };
+    }
+
+    //--------------------------------------------------------------------------
+    /// Read a weekday
+    ///
+    /// # Input
+    /// * `day`     : Key of the weekday
+    /// * `default` : Weekday if the key is not set
+    ///
+    /// # Output
+    /// * `usize`: Index of the weekday, 0 is Monday
+    ///
+    fn day(day: &Section, default: &str) -> Result<usize, ConfigError> {
+        let d = day.choice(&TariffConfig::DAYS, default)?;
+
+        return Ok(TariffConfig::DAYS.iter().position(|x| *x == d).unwrap());
+    }
+}
+
+//==============================================================================
+/// Implementation of Default for TariffConfig
+//
+impl Default for TariffConfig {
+    fn default() -> Self {
+        return TariffConfig::from_section(&Section::new("", &Yaml::Null)).unwrap();
+    }
+}
+
+//==============================================================================
+/// Implementation of ScheduleConfig
+//
+impl ScheduleConfig {
+    //--------------------------------------------------------------------------
+    /// Load and check the schedule configuration
+    ///
+    /// # Input
+    /// * `path` : Path of `schedule.yaml`
+    ///
+    /// # Output
+    /// * `ScheduleConfig`, or the first invalid key
+    ///
+    pub fn load(path: &str) -> Result<ScheduleConfig, ConfigError> {
+        return ScheduleConfig::from_yaml(&Section::load(path)?, path);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Check the schedule configuration. The `buses`, `initial_charge`,
+    /// `final_charge` and `chargers` sections are required, the other
+    /// sections take their defaults. The final charge may not exceed the
+    /// maximum initial charge.
+    ///
+    /// # Input
+    /// * `yaml` : Contents of the schedule configuration
+    /// * `file` : Name of the configuration file used in errors
+    ///
+    /// # Output
+    /// * `ScheduleConfig`, or the first invalid key
+    ///
+    pub fn from_yaml(yaml: &Yaml, file: &str) -> Result<ScheduleConfig, ConfigError> {
+        let root = Section::new(file, yaml);
+
+        // Run settings
+        let time = TimeConfig::from_section(&root.get("time"))?;
+        let temp = TempConfig::from_section(&root.get("temp"))?;
+        let search = SearchConfig::from_section(&root.get("search"), time.k)?;
+        let reheat = ReheatConfig::from_section(&root.get("reheat"))?;
+        let stop = ScheduleConfig::stop(&root.get("stop"))?;
+        let pt = PtConfig::from_section(&root.get("pt"), &temp)?;
+        let mosa = MosaConfig::from_section(&root.get("mosa"))?;
+
+        // Bus settings
+        let buses = BusConfig::from_section(&root.get("buses"))?;
+        let ic = root.get("initial_charge");
+        let initial_charge = InitialChargeConfig {
+            min: ic.get("min").number_in(None, 0.0, 1.0)? as f32,
+            max: ic.get("max").number_in(None, 0.0, 1.0)? as f32,
+        };
+        ic.get("min").check(
+            initial_charge.min <= initial_charge.max,
+            &format!(
+                "must not exceed initial_charge.max ({} > {})",
+                initial_charge.min, initial_charge.max
+            ),
+        )?;
+        let final_charge = root.get("final_charge").number_in(None, 0.0, 1.0)? as f32;
+        root.get("final_charge").check(
+            final_charge <= initial_charge.max,
+            &format!(
+                "must not exceed initial_charge.max ({} > {})",
+                final_charge, initial_charge.max
+            ),
+        )?;
+
+        return Ok(ScheduleConfig {
+            ignore: ScheduleConfig::ignore(&root.get("ignore"))?,
+            time,
+            temp,
+            search,
+            reheat,
+            stop,
+            pt,
+            mosa,
+            chargers: ChargerConfig::from_section(&root.get("chargers"), buses.num_bus)?,
+            degradation: ScheduleConfig::degradation(&root.get("degradation"), buses.num_bus)?,
+            buses,
+            initial_charge,
+            final_charge,
+            tariff: TariffConfig::from_section(&root.get("tariff"))?,
+            demand: ScheduleConfig::demand(&root.get("demand"))?,
+            grid: root
+                .get("grid")
+                .get("limit")
+                .number_in(Some(0.0), 0.0, f64::MAX)? as f32,
+            weights: ScheduleConfig::weights(&root.get("objective"))?,
+        });
+    }
+
+    //--------------------------------------------------------------------------
+    /// Read the list of routes to ignore
+    ///
+    /// # Input
+    /// * `ignore` : `ignore` key
+    ///
+    /// # Output
+    /// * `Vec<usize>`: Indices of the routes to ignore
+    ///
+    fn ignore(ignore: &Section) -> Result<Vec<usize>, ConfigError> {
+        if !ignore.is_set() {
+            return Ok(Vec::new());
+        }
+
+        let routes = ignore.require(ignore.yaml().as_vec())?;
+        return routes
+            .iter()
+            .map(|r| match r.as_i64() {
+                Some(i) if i >= 0 => Ok(i as usize),
+                _ => Err(ignore.error(&format!("expected route indices, got {:?}", r))),
+            })
+            .collect();
+    }
+
+    //--------------------------------------------------------------------------
+    /// Read the `demand` section. The window is given in minutes and the
+    /// billed peak in KW.
+    ///
+    /// # Input
+    /// * `demand` : `demand` section
+    ///
+    /// # Output
+    /// * `Demand`
+    ///
+    fn demand(demand: &Section) -> Result<Demand, ConfigError> {
+        let d = Demand::default();
+        let window = demand.get("window").count(Some(d.window))?;
+        demand.get("window").check(window > 0, "must be positive")?;
+
+        return Ok(Demand {
+            window,
+            price: demand
+                .get("price")
+                .number_in(Some(d.price as f64), 0.0, f64::MAX)? as f32,
+            peak: demand
+                .get("peak")
+                .number_in(Some(d.peak as f64), 0.0, f64::MAX)? as f32,
+        });
+    }
+
+    //--------------------------------------------------------------------------
+    /// Read the `degradation` section. The battery price is a single price for
+    /// every bus or a list with one price per bus.
+    ///
+    /// # Input
+    /// * `deg`     : `degradation` section
+    /// * `num_bus` : Number of buses
+    ///
+    /// # Output
+    /// * `Degradation`
+    ///
+    fn degradation(deg: &Section, num_bus: usize) -> Result<Degradation, ConfigError> {
+        let d = Degradation::default();
+
+        // Battery prices
+        let cost = deg.get("cost");
+        let prices: Vec<f32> = if !cost.is_set() {
+            Vec::new()
+        } else if let Some(list) = cost.yaml().as_vec() {
+            cost.check(
+                list.len() == num_bus,
+                &format!(
+                    "must have one price per bus ({} != {})",
+                    list.len(),
+                    num_bus
+                ),
+            )?;
+            (0..list.len())
+                .map(|b| Ok(cost.at(b).number_in(None, 0.0, f64::MAX)? as f32))
+                .collect::<Result<Vec<f32>, ConfigError>>()?
+        } else {
+            vec![cost.number_in(None, 0.0, f64::MAX)? as f32]
+        };
+
+        return Ok(Degradation {
+            cost: prices,
+            cycles: deg.get("cycles").positive(Some(d.cycles as f64))? as f32,
+            exponent: deg.get("exponent").positive(Some(d.exponent as f64))? as f32,
+            soc: deg.get("soc").number_in(Some(d.soc as f64), 0.0, 1.0)? as f32,
+            rate: deg
+                .get("rate")
+                .number_in(Some(d.rate as f64), 0.0, f64::MAX)? as f32,
+        });
+    }
+
+    //--------------------------------------------------------------------------
+    /// Read the `objective` section. Every key is the name of a term of the
+    /// objective function. A standard term that is not set has a unit weight,
+    /// a weight of zero drops the term from the objective function value.
+    ///
+    /// # Input
+    /// * `objective` : `objective` section
+    ///
+    /// # Output
+    /// * `Weights`
+    ///
+    fn weights(objective: &Section) -> Result<Weights, ConfigError> {
+        let mut w = Weights::default();
+
+        // The section may be left out
+        let keys: Vec<String> = match objective.yaml().as_hash() {
+            Some(h) => h
+                .keys()
+                .map(|k| k.as_str().unwrap_or_default().to_string())
+                .collect(),
+            None => {
+                objective.check(
+                    !objective.is_set(),
+                    "must be a mapping of term names to weights",
+                )?;
+                Vec::new()
+            }
+        };
+
+        for name in keys.iter() {
+            let term = objective.get(name);
+            term.check(
+                terms::from_name(name).is_some(),
+                "is not a term of the objective function",
+            )?;
+            w = w.with(name, term.number_in(None, 0.0, f64::MAX)?);
+        }
+
+        return Ok(w);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Check the `stop` section before reading the stop criteria
+    ///
+    /// # Input
+    /// * `stop` : `stop` section
+    ///
+    /// # Output
+    /// * `StopCriteria`
+    ///
+    fn stop(stop: &Section) -> Result<StopCriteria, ConfigError> {
+        stop.get("time").number_in(Some(0.0), 0.0, f64::MAX)?;
+        stop.get("evaluations").count(Some(0))?;
+        stop.get("target").number()?;
+        stop.get("stall").count(Some(0))?;
+
+        return Ok(StopCriteria::from_yaml(stop.yaml()));
+    }
+}
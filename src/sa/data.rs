@@ -24,32 +24,76 @@ pub struct Data {
 ///
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct Parameter {
-    pub A: usize,        // Number of buses
-    pub D: Vec<f32>,     // Discharge of route i
-    pub Gam: Vec<u16>,   // Array of visit IDs
-    pub K: u16,          // Discrete number of steps in T
-    pub N: usize,        // Number of total visits
-    pub Q: usize,        // Number of chargers
-    pub S: usize,        // Length of a single bus
-    pub T: f32,          // Time horizon                                   [hr]
-    pub a: Vec<f32>,     // Arrival time of bus visit i                    [hr]
-    pub alpha: Vec<f32>, // Initial charge percentage for bus a            [%]
-    pub beta: Vec<f32>,  // Final charge percentage for bus a at T         [%]
-    pub dt: f32,         // Discrete time step                             [hr]
-    pub e: Vec<f32>,     // Exit time for bus visit i                      [hr]
-    pub ep: Vec<f32>,    // (epsilon) Cost of using charger q per unit time
-    pub fast: usize,     // Number of fast chargers
-    pub gam: Vec<i16>,   // Array of values indicating the next index for bus i
-    pub k: Vec<f32>,     // (kappa) Battery capacity for bus i             [MJ]
-    pub l: Vec<f32>,     // (lambda) Discharge of bus visit over route i
-    pub model: String,   // Linear/non-linear battery dynamics model
-    pub nu: f32,         // Minimum charge allowed on departure of visit i [%]
-    pub r: Vec<f32>,     // Charge rate for charger q                      [KWh]
-    pub conv: Vec<f32>,  // Convergence rate for charger q
-    pub slow: usize,     // Number of slow chargers
-    pub tk: Vec<f32>,    // Array of discrete times                        [hr]
-    pub ts: f32,         // Amount of offset applied                       [hr]
-    pub zeta: Vec<f32>,  // Discharge rate of bus b
+    pub A: usize,         // Number of buses
+    pub D: Vec<f32>,      // Discharge of route i
+    pub Gam: Vec<u16>,    // Array of visit IDs
+    pub K: u16,           // Discrete number of steps in T
+    pub N: usize,         // Number of total visits
+    pub Q: usize,         // Number of chargers
+    pub S: usize,         // Length of a single bus
+    pub T: f32,           // Time horizon                                   [hr]
+    pub a: Vec<f32>,      // Arrival time of bus visit i                    [hr]
+    pub alpha: Vec<f32>,  // Initial charge percentage for bus a            [%]
+    pub beta: Vec<f32>,   // Final charge percentage for bus a at T         [%]
+    pub deg: Degradation, // Battery degradation cost
+    pub demand: Demand,   // Demand charge of the peak power
+    pub dt: f32,          // Discrete time step                             [hr]
+    pub e: Vec<f32>,      // Exit time for bus visit i                      [hr]
+    pub ep: Vec<f32>,     // (epsilon) Cost of using charger q per unit time
+    pub fast: usize,      // Number of fast chargers
+    pub gam: Vec<i16>,    // Array of values indicating the next index for bus i
+    pub grid: f32,        // Power limit of the grid connection, 0 is unlimited [KW]
+    pub k: Vec<f32>,      // (kappa) Battery capacity for bus i             [MJ]
+    pub l: Vec<f32>,      // (lambda) Discharge of bus visit over route i
+    pub model: String,    // Linear/non-linear battery dynamics model
+    pub nu: f32,          // Minimum charge allowed on departure of visit i [%]
+    pub r: Vec<f32>,      // Charge rate for charger q                      [KWh]
+    pub conv: Vec<f32>,   // Convergence rate for charger q
+    pub slow: usize,      // Number of slow chargers
+    pub tariff: Tariff,   // Time-of-use price of energy
+    pub tk: Vec<f32>,     // Array of discrete times                        [hr]
+    pub ts: f32,          // Amount of offset applied                       [hr]
+    pub zeta: Vec<f32>,   // Discharge rate of bus b
+}
+
+//===============================================================================
+/// Structure for the time-of-use tariff
+///
+/// Defines the price of energy over the time horizon. The price of a band replaces the base price
+/// between the start and end time of the band.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tariff {
+    pub base: f32, // Price of energy outside of the bands          [$/KWh]
+    pub bands: Vec<(f32, f32, f32)>, // Start time, end time and price of each band   [hr, hr, $/KWh]
+}
+
+//===============================================================================
+/// Structure for the demand charge
+///
+/// Defines the charge of the peak power. The power is averaged over a rolling window and only the
+/// increase of the peak over the peak that was already billed (ratchet) is charged.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Demand {
+    pub window: usize, // Length of the rolling window                       [min]
+    pub price: f32,    // Price of the peak power                            [$/KW]
+    pub peak: f32,     // Peak power billed before the time horizon          [KW]
+}
+
+//===============================================================================
+/// Structure for the battery degradation cost
+///
+/// Defines the wear of the batteries. Each charge is a cycle whose depth of discharge wears the
+/// battery, and the time a BEB waits at the depot above a high SOC is charged at a fixed rate.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Degradation {
+    pub cost: Vec<f32>, // Price of the battery of bus b                   [$]
+    pub cycles: f32,    // Cycle life at a depth of discharge of 100%
+    pub exponent: f32,  // Exponent of the depth of discharge of a cycle
+    pub soc: f32,       // SOC above which the battery wears faster      [0-1]
+    pub rate: f32,      // Cost of the time spent above `soc`             [$/hr]
 }
 
 //===============================================================================
@@ -59,16 +103,131 @@ pub struct Parameter {
 ///
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct DecisionVar {
-    pub Jb: Vec<f64>,          //  Best objective function score
-    pub Jc: Vec<f64>,          //  Current objective function score
-    pub Jn: Vec<f64>,          //  New objective function score
-    pub d: Vec<f32>,           //  Detach time for visit i                [hr]
-    pub g: Vec<Vec<f32>>,      //  Detach time for visit i                [hr]
-    pub psi: Vec<Vec<bool>>,   //  Determines if i is "fully left" of j
-    pub eta: Vec<f32>,         //  Initial charge for bus visit i         [MJ]
-    pub s: Vec<f32>,           //  Time to charge for bus visit i         [hr]
-    pub sigma: Vec<Vec<bool>>, //  Determines if i is "fully below" j
-    pub u: Vec<f32>,           //  Initial charge time for visit i        [hr]
-    pub v: Vec<usize>,         //  Assigned queue for visit i
-    pub w: Vec<Vec<bool>>,     //  Matrix representation of bus charger assignments
+    pub Jb: Vec<f64>,                   //  Best objective function score
+    pub Jc: Vec<f64>,                   //  Current objective function score
+    pub Jn: Vec<f64>,                   //  New objective function score
+    pub terms: Vec<(String, Vec<f64>)>, //  Terms of the objective function of the best solution by name
+    pub reheat: Vec<usize>,             //  Number of reheats of the run
+    pub K: Vec<usize>,                  //  Local search length of each temperature step
+    pub d: Vec<f32>,                    //  Detach time for visit i                [hr]
+    pub g: Vec<Vec<f32>>,               //  Detach time for visit i                [hr]
+    pub psi: Vec<Vec<bool>>,            //  Determines if i is "fully left" of j
+    pub eta: Vec<f32>,                  //  Initial charge for bus visit i         [MJ]
+    pub s: Vec<f32>,                    //  Time to charge for bus visit i         [hr]
+    pub sigma: Vec<Vec<bool>>,          //  Determines if i is "fully below" j
+    pub u: Vec<f32>,                    //  Initial charge time for visit i        [hr]
+    pub v: Vec<usize>,                  //  Assigned queue for visit i
+    pub w: Vec<Vec<bool>>,              //  Matrix representation of bus charger assignments
+}
+
+//===============================================================================
+/// Implementation of `DecisionVar`
+//
+impl DecisionVar {
+    //---------------------------------------------------------------------------
+    /// History of a term of the objective function
+    ///
+    /// # Input
+    /// * name: Name of the term
+    ///
+    /// # Output
+    /// * history: Value of the term at each temperature step, empty if the
+    ///   objective function has no such term
+    ///
+    pub fn term(self: &DecisionVar, name: &str) -> &[f64] {
+        return self
+            .terms
+            .iter()
+            .find(|t| t.0 == name)
+            .map_or(&[], |t| t.1.as_slice());
+    }
+}
+
+//===============================================================================
+/// Implementation of `Tariff`
+//
+impl Tariff {
+    //---------------------------------------------------------------------------
+    /// Integrate the price of energy over a charge.
+    ///
+    /// # Input
+    /// * u: Start time of the charge [hr]
+    /// * s: Duration of the charge [hr]
+    ///
+    /// # Output
+    /// * c: Integral of the price over `[u, u + s]` [$ hr/KWh]
+    ///
+    pub fn cost(self: &Tariff, u: f32, s: f32) -> f32 {
+        // The base price over the whole charge
+        let mut c = self.base * s;
+
+        // The difference to the base price over the overlap with each band
+        for (start, end, price) in self.bands.iter() {
+            let h = end.min(u + s) - start.max(u);
+            if h > 0.0 {
+                c += (price - self.base) * h;
+            }
+        }
+
+        return c;
+    }
+}
+
+//===============================================================================
+/// Implementation of `Default` for `Tariff`. The flat tariff prices energy at 1.
+//
+impl Default for Tariff {
+    fn default() -> Self {
+        return Tariff {
+            base: 1.0,
+            bands: Vec::new(),
+        };
+    }
+}
+
+//===============================================================================
+/// Implementation of `Demand`
+//
+impl Demand {
+    //---------------------------------------------------------------------------
+    /// Calculate the demand charge of a peak power.
+    ///
+    /// # Input
+    /// * peak: Peak power over the time horizon [KW]
+    ///
+    /// # Output
+    /// * c: Demand charge of the increase over the billed peak [$]
+    ///
+    pub fn cost(self: &Demand, peak: f64) -> f64 {
+        return self.price as f64 * (peak - self.peak as f64).max(0.0);
+    }
+}
+
+//===============================================================================
+/// Implementation of `Default` for `Demand`. The default window is 15 minutes
+/// without a billed peak.
+//
+impl Default for Demand {
+    fn default() -> Self {
+        return Demand {
+            window: 15,
+            price: 10000.0,
+            peak: 0.0,
+        };
+    }
+}
+
+//===============================================================================
+/// Implementation of `Default` for `Degradation`. The batteries do not wear.
+//
+impl Default for Degradation {
+    fn default() -> Self {
+        return Degradation {
+            cost: Vec::new(),
+            cycles: 3000.0,
+            exponent: 1.0,
+            soc: 1.0,
+            rate: 0.0,
+        };
+    }
 }
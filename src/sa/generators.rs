@@ -1,7 +1,9 @@
 //===============================================================================
 // Import modules
+use crate::error::Error;
 use crate::sa::charger::Charger;
 use crate::sa::route::Route;
+use rand::rngs::StdRng;
 
 //===============================================================================
 // Declare modules
@@ -15,5 +17,10 @@ pub mod tweak_schedule_quick; // Alter a charge schedule quickly uncertainly
 /// Trait to define `Generator` interfaces
 //
 pub trait Generator {
-    fn run(&mut self, s: &mut Box<dyn Route>, c: &mut Charger) -> bool;
+    fn run(
+        &mut self,
+        s: &mut Box<dyn Route>,
+        c: &mut Charger,
+        rng: &mut StdRng,
+    ) -> Result<bool, Error>;
 }
@@ -1,9 +1,11 @@
 //===============================================================================
 // Import modules
+use crate::error::Error;
 use crate::sa::charger::Charger;
 use crate::sa::generators::primitives::new_visit::*;
 use crate::sa::generators::Generator;
 use crate::sa::route::Route;
+use rand::rngs::StdRng;
 
 //===============================================================================
 /// Structure defining the information to create a charge schedule
@@ -40,12 +42,17 @@ impl Generator for GenNewVisits {
     /// # Input
     /// * r: Route object
     /// * c: Charger object
-    /// * d: Data object
+    /// * rng: Random number generator
     ///
     /// # Output
-    /// * Updated charger object
+    /// * Updated charger object, or an error if an assignment was invalid
     ///
-    fn run(self: &mut GenNewVisits, r: &mut Box<dyn Route>, c: &mut Charger) -> bool {
+    fn run(
+        self: &mut GenNewVisits,
+        r: &mut Box<dyn Route>,
+        c: &mut Charger,
+        rng: &mut StdRng,
+    ) -> Result<bool, Error> {
         // Get information about the route
         let mut route = r.get_route_events().clone();
         let mut data = r.get_data();
@@ -56,7 +63,16 @@ impl Generator for GenNewVisits {
             let ae = &(i.arrival_time, i.departure_time);
 
             // Check if the bus can be assigned, assign the bus wait queue
-            if new_visit::run(&mut data, i.visit, c, i.id as usize, i.id as usize, ae, ae) {
+            if new_visit::run(
+                &mut data,
+                i.visit,
+                c,
+                i.id as usize,
+                i.id as usize,
+                ae,
+                ae,
+                rng,
+            )? {
                 // Update route event
                 i.attach_time = ae.0;
                 i.detach_time = ae.1;
@@ -68,6 +84,6 @@ impl Generator for GenNewVisits {
         r.set_route_events(Box::new(&mut route));
         r.set_data(data);
 
-        return true;
+        return Ok(true);
     }
 }
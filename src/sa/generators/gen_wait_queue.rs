@@ -1,8 +1,10 @@
 //===============================================================================
 // Import modules
+use crate::error::Error;
 use crate::sa::charger::Charger;
 use crate::sa::generators::Generator;
 use crate::sa::route::Route;
+use rand::rngs::StdRng;
 
 //===============================================================================
 /// Structure defining the information to create a charge schedule
@@ -39,12 +41,17 @@ impl Generator for GenWaitQueue {
     /// # Input
     /// * r: Route object
     /// * c: Charger object
-    /// * d: Data object
+    /// * _rng: Random number generator (unused)
     ///
     /// # Output
-    /// * Updated charger object
+    /// * Updated charger object, or an error if an assignment was invalid
     ///
-    fn run(self: &mut GenWaitQueue, r: &mut Box<dyn Route>, c: &mut Charger) -> bool {
+    fn run(
+        self: &mut GenWaitQueue,
+        r: &mut Box<dyn Route>,
+        c: &mut Charger,
+        _rng: &mut StdRng,
+    ) -> Result<bool, Error> {
         // Get information about the route
         let mut route = r.get_route_events().clone();
 
@@ -60,7 +67,7 @@ impl Generator for GenWaitQueue {
             let ad = (i.arrival_time, i.departure_time);
 
             // Check if the bus can be assigned, assign the bus wait queue
-            if c.assign(b.clone(), ad.clone(), b.clone()) {
+            if c.assign(b.clone(), ad.clone(), b.clone())? {
                 // Update route event
                 i.attach_time = ad.0.clone();
                 i.detach_time = ad.1.clone();
@@ -79,6 +86,6 @@ impl Generator for GenWaitQueue {
         r.set_route_events(Box::new(&mut route));
         r.set_data(data);
 
-        return true;
+        return Ok(true);
     }
 }
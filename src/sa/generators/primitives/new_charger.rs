@@ -5,9 +5,10 @@ pub mod new_charger {
 
     // Import standard lib
     use rand::distributions::{Distribution, WeightedIndex};
-    use rand::prelude::*;
+    use rand::rngs::StdRng;
 
     // Import modules
+    use crate::error::Error;
     use crate::sa::charger::Charger;
     use crate::sa::data::Data;
     use crate::sa::generators::primitives::purge::*;
@@ -24,9 +25,10 @@ pub mod new_charger {
     /// * q: Charger queue index
     /// * b: Bus id
     /// * ud: Start/stop charging times
+    /// * rng: Random number generator
     ///
     /// # Output
-    /// * bool: Assignment failure/success
+    /// * bool: Assignment failure/success, or an error if the visit could not be restored
     ///
     pub fn run(
         d: &mut Data,
@@ -35,10 +37,11 @@ pub mod new_charger {
         q: usize,
         b: usize,
         ud: &(f32, f32),
-    ) -> bool {
+        rng: &mut StdRng,
+    ) -> Result<bool, Error> {
         // Remove the visit, return false if unsuccessful
-        if !purge::run(d, i, ch, q, ud) {
-            return false;
+        if !purge::run(d, i, ch, q, ud)? {
+            return Ok(false);
         }
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -52,8 +55,7 @@ pub mod new_charger {
         let w = [3, 1];
         let dist = WeightedIndex::new(&w).unwrap();
         let selection_vals = [0, 1];
-        let mut rng = thread_rng();
-        let charge_type: usize = selection_vals[dist.sample(&mut rng)];
+        let charge_type: usize = selection_vals[dist.sample(rng)];
 
         // If the charger selected is a slow charger
         if charge_type == 0 {
@@ -75,11 +77,15 @@ pub mod new_charger {
             // Check if the arrival/departure fits in the time slice
             // Note that this line is what differentiates this function from `new_visit` by applying the same
             // start/stop charge time as before, just on a new charger.
-            let (fits, _) = ch.find_free_time(ud, &ts);
+            let (fits, _) = ch.find_free_time(ud, &ts, rng);
 
-            // If the selected time slice arrival/departure fits in the time slice, assign the start/stop charge
-            // times
-            if ts != (0.0, 0.0) && fits && ch.assign(q_new, *ud, b) {
+            // If the selected time slice arrival/departure fits in the time slice and the grid connection, assign
+            // the start/stop charge times
+            if ts != (0.0, 0.0)
+                && fits
+                && ch.fits_grid(q_new, ud, d.param.grid)
+                && ch.assign(q_new, *ud, b)?
+            {
                 // Update route data
                 // Update queue
                 d.dec.v[i] = q_new;
@@ -88,16 +94,19 @@ pub mod new_charger {
                 d.dec.w[i].fill(false);
                 d.dec.w[i][q_new] = true;
 
-                return true;
+                return Ok(true);
             }
         }
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Place the original visit back in the queue availability matrix
-        if !ch.assign(q, *ud, b) {
-            panic!("Lost a visit!");
+        if !ch.assign(q, *ud, b)? {
+            return Err(Error::Invariant(format!(
+                "new_charger.rs: Lost visit {} of bus {} while restoring it.",
+                i, b
+            )));
         };
 
-        return false;
+        return Ok(false);
     }
 }
@@ -5,8 +5,10 @@ pub mod new_charger_quick {
 
     // Import standard lib
     use crate::util::rand_utils;
+    use rand::rngs::StdRng;
 
     // Import modules
+    use crate::error::Error;
     use crate::sa::charger::Charger;
     use crate::sa::data::Data;
     use crate::sa::generators::primitives::purge::*;
@@ -23,9 +25,10 @@ pub mod new_charger_quick {
     /// * q: Charger queue index
     /// * b: Bus id
     /// * ud: Start/stop charging times
+    /// * rng: Random number generator
     ///
     /// # Output
-    /// * bool: Assignment failure/success
+    /// * bool: Assignment failure/success, or an error if the visit could not be restored
     ///
     pub fn run(
         d: &mut Data,
@@ -34,18 +37,19 @@ pub mod new_charger_quick {
         q: usize,
         b: usize,
         ud: &(f32, f32),
-    ) -> bool {
+        rng: &mut StdRng,
+    ) -> Result<bool, Error> {
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Remove the visit, return false if unsuccessful
-        if !purge::run(d, i, ch, q, ud) {
-            return false;
+        if !purge::run(d, i, ch, q, ud)? {
+            return Ok(false);
         }
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Random selection
 
         // Select a random charger queue
-        let q_new = rand_utils::rand_range(ch.charger_count.0, ch.schedule.len() - 1);
+        let q_new = rand_utils::rand_range(ch.charger_count.0, ch.schedule.len() - 1, rng);
 
         // Find a ts that fits this time slot
         let ts = ch.get_ts(&q_new, ud);
@@ -56,11 +60,11 @@ pub mod new_charger_quick {
         // Check if the arrival/departure fits in the time slice
         // Note that this line is what differentiates this function from `new_visit` by applying the same
         // start/stop charge time as before, just on a new charger.
-        let (fits, _) = ch.find_free_time(ud, &ts);
+        let (fits, _) = ch.find_free_time(ud, &ts, rng);
 
-        // If the selected time slice arrival/departure fits in the time slice, assign the start/stop charge
-        // times
-        if fits && ch.assign(q_new, *ud, b) {
+        // If the selected time slice arrival/departure fits in the time slice and the grid connection, assign
+        // the start/stop charge times
+        if fits && ch.fits_grid(q_new, ud, d.param.grid) && ch.assign(q_new, *ud, b)? {
             // Update queue
             d.dec.v[i] = q_new;
 
@@ -68,15 +72,18 @@ pub mod new_charger_quick {
             d.dec.w[i].fill(false);
             d.dec.w[i][q_new] = true;
 
-            return true;
+            return Ok(true);
         }
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Place the original visit back in the queue availability matrix
-        if !ch.assign(q, *ud, b) {
-            panic!("Lost a visit!");
+        if !ch.assign(q, *ud, b)? {
+            return Err(Error::Invariant(format!(
+                "new_charger_quick.rs: Lost visit {} of bus {} while restoring it.",
+                i, b
+            )));
         };
 
-        return false;
+        return Ok(false);
     }
 }
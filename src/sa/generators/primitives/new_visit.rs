@@ -4,9 +4,10 @@
 pub mod new_visit {
     // Import standard lib
     use rand::distributions::{Distribution, WeightedIndex};
-    use rand::prelude::*;
+    use rand::rngs::StdRng;
 
     // Import modules
+    use crate::error::Error;
     use crate::sa::charger::Charger;
     use crate::sa::data::Data;
     use crate::sa::generators::primitives;
@@ -24,9 +25,11 @@ pub mod new_visit {
     /// * ch: Charger object
     /// * b: Bus id
     /// * ae: Arrival/exit times
+    /// * ud: Start/stop charging times
+    /// * rng: Random number generator
     ///
     /// # Output
-    /// * bool: Assignment failure/success
+    /// * bool: Assignment failure/success, or an error if the visit could not be restored
     ///
     pub fn run(
         dat: &mut Data,
@@ -36,7 +39,8 @@ pub mod new_visit {
         b: usize,
         ae: &(f32, f32),
         ud: &(f32, f32),
-    ) -> bool {
+        rng: &mut StdRng,
+    ) -> Result<bool, Error> {
         // Extract the number of chargers
         let q_cnt: usize = ch.schedule.len();
 
@@ -45,8 +49,7 @@ pub mod new_visit {
         let w = [3, 1];
         let dist = WeightedIndex::new(&w).unwrap();
         let selection_vals = [0, 1];
-        let mut rng = thread_rng();
-        let charge_type: usize = selection_vals[dist.sample(&mut rng)];
+        let charge_type: usize = selection_vals[dist.sample(rng)];
 
         // If the charger selected is a slow charger
         if charge_type == 0 {
@@ -64,7 +67,7 @@ pub mod new_visit {
         for q_new in queues.into_iter() {
             // Create a list of time slices and shuffle them
             let mut time_slice = ch.free_time[q_new].clone();
-            time_slice = rand_utils::shuffle_vec(&time_slice);
+            time_slice = rand_utils::shuffle_vec(&time_slice, rng);
 
             // Filter out very small windows
             time_slice = time_slice
@@ -75,11 +78,11 @@ pub mod new_visit {
             // Iterate through the shuffled time slices
             for ts in time_slice.iter() {
                 // Check if the arrival/departure fits in the time slice
-                let (fits, ud) = ch.find_free_time(ae, ts);
+                let (fits, ud) = ch.find_free_time(ae, ts, rng);
 
-                // If the selected time slice arrival/departure fits in the time slice, assign the start/stop charge
-                // times
-                if fits && ch.assign(q_new, ud, b) {
+                // If the selected time slice arrival/departure fits in the time slice and the grid connection,
+                // assign the start/stop charge times
+                if fits && ch.fits_grid(q_new, &ud, dat.param.grid) && ch.assign(q_new, ud, b)? {
                     // Update queue
                     dat.dec.v[i] = q_new;
 
@@ -91,9 +94,10 @@ pub mod new_visit {
                     dat.dec.u[i] = ud.0;
                     dat.dec.d[i] = ud.1;
                     dat.dec.s[i] = ud.1 - ud.0;
+                    ch.touch(i);
 
                     // Indicate success
-                    return true;
+                    return Ok(true);
                 }
             }
         }
@@ -101,9 +105,12 @@ pub mod new_visit {
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Place the original visit back in the queue availability matrix
 
-        if !ch.assign(q, *ud, b) {
-            panic!("Lost a visit!");
+        if !ch.assign(q, *ud, b)? {
+            return Err(Error::Invariant(format!(
+                "new_visit.rs: Lost visit {} of bus {} while restoring it.",
+                i, b
+            )));
         };
-        return false;
+        return Ok(false);
     }
 }
@@ -3,10 +3,12 @@
 //
 pub mod new_visit_quick {
     // Import modules
+    use crate::error::Error;
     use crate::sa::charger::Charger;
     use crate::sa::data::Data;
     use crate::sa::generators::primitives::purge::*;
     use crate::util::rand_utils;
+    use rand::rngs::StdRng;
 
     //--------------------------------------------------------------------------
     /// The run function executes the `new_visit` module. Given the set of
@@ -22,9 +24,10 @@ pub mod new_visit_quick {
     /// * b: Bus id
     /// * ae: Arrive/Exit times of the bus
     /// * ud: Start/stop charging times
+    /// * rng: Random number generator
     ///
     /// # Output
-    /// * bool: Assignment failure/success
+    /// * bool: Assignment failure/success, or an error if the visit could not be restored
     ///
     pub fn run(
         d: &mut Data,
@@ -34,31 +37,32 @@ pub mod new_visit_quick {
         b: usize,
         ae: &(f32, f32),
         ud: &(f32, f32),
-    ) -> bool {
+        rng: &mut StdRng,
+    ) -> Result<bool, Error> {
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Remove the visit, return false if unsuccessful
-        if !purge::run(d, i, ch, q, ud) {
-            return false;
+        if !purge::run(d, i, ch, q, ud)? {
+            return Ok(false);
         }
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Random selection
 
         // Select a random charger queue
-        let q_new = rand_utils::rand_range(ch.charger_count.0, ch.schedule.len() - 1);
+        let q_new = rand_utils::rand_range(ch.charger_count.0, ch.schedule.len() - 1, rng);
 
         // Select random time slice availability
-        let ts_idx = rand_utils::rand_range(0, ch.free_time[q_new].len() - 1);
+        let ts_idx = rand_utils::rand_range(0, ch.free_time[q_new].len() - 1, rng);
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Attempt to assign the visit
 
         // Check if the arrival/departure fits in the time slice
-        let (fits, ud_new) = ch.find_free_time(ae, &ch.free_time[q_new][ts_idx].clone());
+        let (fits, ud_new) = ch.find_free_time(ae, &ch.free_time[q_new][ts_idx].clone(), rng);
 
-        // If the selected time slice arrival/departure fits in the time slice, assign the start/stop charge
-        // times
-        if fits && ch.assign(q_new, ud_new, b) {
+        // If the selected time slice arrival/departure fits in the time slice and the grid connection, assign
+        // the start/stop charge times
+        if fits && ch.fits_grid(q_new, &ud_new, d.param.grid) && ch.assign(q_new, ud_new, b)? {
             // Update queue
             d.dec.v[i] = q_new;
 
@@ -71,16 +75,19 @@ pub mod new_visit_quick {
             d.dec.d[i] = ud_new.1;
             d.dec.s[i] = ud_new.1 - ud_new.0;
 
-            return true;
+            return Ok(true);
         }
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Place the original visit back in the queue availability matrix
 
-        if !ch.assign(q, *ud, b) {
-            panic!("Lost a visit!");
+        if !ch.assign(q, *ud, b)? {
+            return Err(Error::Invariant(format!(
+                "new_visit_quick.rs: Lost visit {} of bus {} while restoring it.",
+                i, b
+            )));
         };
 
-        return false;
+        return Ok(false);
     }
 }
@@ -3,7 +3,11 @@
 //
 pub mod new_window {
 
+    // Import standard lib
+    use rand::rngs::StdRng;
+
     // Import modules
+    use crate::error::Error;
     use crate::sa::charger::Charger;
     use crate::sa::data::Data;
     use crate::sa::generators::primitives::new_visit::*;
@@ -21,6 +25,7 @@ pub mod new_window {
     /// * b: Bus id
     /// * ae: Arrival/exit times
     /// * ud: Start/stop charging times
+    /// * rng: Random number generator
     ///
     /// # Output
     /// * bool: Assignment failure/success
@@ -33,17 +38,18 @@ pub mod new_window {
         b: usize,
         ae: &(f32, f32),
         ud: &(f32, f32),
-    ) -> bool {
+        rng: &mut StdRng,
+    ) -> Result<bool, Error> {
         // Remove the visit, return false if unsuccessful
-        if !purge::run(dat, i, ch, q, ud) {
-            return false;
+        if !purge::run(dat, i, ch, q, ud)? {
+            return Ok(false);
         }
 
         // Add the same bus back in as a new visit, return false if unsuccessful
-        if !new_visit::run(dat, i, ch, q, b, ae, ud) {
-            return false;
+        if !new_visit::run(dat, i, ch, q, b, ae, ud, rng)? {
+            return Ok(false);
         }
 
-        return true;
+        return Ok(true);
     }
 }
@@ -4,6 +4,7 @@
 pub mod purge {
 
     // Import modules
+    use crate::error::Error;
     use crate::sa::charger::Charger;
     use crate::sa::data::Data;
 
@@ -21,11 +22,19 @@ pub mod purge {
     /// # Output
     /// * bool: Assignment failure/success
     ///
-    pub fn run(_: &mut Data, _: usize, ch: &mut Charger, q: usize, ud: &(f32, f32)) -> bool {
-        if ch.remove(q, *ud) {
-            return true;
+    pub fn run(
+        _: &mut Data,
+        i: usize,
+        ch: &mut Charger,
+        q: usize,
+        ud: &(f32, f32),
+    ) -> Result<bool, Error> {
+        if ch.remove(q, *ud)? {
+            // The visit is moved by the primitive that purged it
+            ch.touch(i);
+            return Ok(true);
         }
 
-        return false;
+        return Ok(false);
     }
 }
@@ -5,8 +5,10 @@ pub mod slide_visit {
 
     // Standard lib modules
     use crate::util::rand_utils;
+    use rand::rngs::StdRng;
 
     // Import modules
+    use crate::error::Error;
     use crate::sa::charger::Charger;
     use crate::sa::data::Data;
     use crate::sa::generators::primitives::{self, purge::*};
@@ -22,9 +24,10 @@ pub mod slide_visit {
     /// * q: Queue index
     /// * ae: Arrive/departure times for the BEB
     /// * ud: Start/stop charge times
+    /// * rng: Random number generator
     ///
     /// # Output
-    /// * bool: Assignment failure/success
+    /// * bool: Assignment failure/success, or an error if the visit could not be restored
     ///
     pub fn run(
         d: &mut Data,
@@ -34,15 +37,16 @@ pub mod slide_visit {
         q: usize,
         ae: &(f32, f32),
         ud: &(f32, f32),
-    ) -> bool {
+        rng: &mut StdRng,
+    ) -> Result<bool, Error> {
         // Remove the visit, return false if unsuccessful
-        if !purge::run(d, i, ch, q, ud) {
-            return false;
+        if !purge::run(d, i, ch, q, ud)? {
+            return Ok(false);
         }
 
         // Create a list of time slices and shuffle them
         let mut time_slice = ch.free_time[q].clone();
-        time_slice = rand_utils::shuffle_vec(&time_slice);
+        time_slice = rand_utils::shuffle_vec(&time_slice, rng);
 
         // Filter out very small windows
         time_slice = time_slice
@@ -53,27 +57,30 @@ pub mod slide_visit {
         // Iterate through the shuffled time slices
         for ts in time_slice.iter() {
             // Check if the arrival/departure fits in the time slice
-            let (fits, ud_new) = ch.find_free_time(ae, ts);
+            let (fits, ud_new) = ch.find_free_time(ae, ts, rng);
 
-            // If the selected time slice arrival/departure fits in the time slice, assign the start/stop charge
-            // times
-            if fits && ch.assign(q, ud_new, b) {
+            // If the selected time slice arrival/departure fits in the time slice and the grid connection, assign
+            // the start/stop charge times
+            if fits && ch.fits_grid(q, &ud_new, d.param.grid) && ch.assign(q, ud_new, b)? {
                 // Update route data
                 d.dec.u[i] = ud_new.0; // Update attach time
                 d.dec.d[i] = ud_new.1; // Update detach time
                 d.dec.s[i] = ud_new.1 - ud_new.0;
 
-                return true;
+                return Ok(true);
             }
         }
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Place the original visit back in the queue availability matrix
 
-        if !ch.assign(q, *ud, b) {
-            panic!("Lost a visit!");
+        if !ch.assign(q, *ud, b)? {
+            return Err(Error::Invariant(format!(
+                "slide_visit.rs: Lost visit {} of bus {} while restoring it.",
+                i, b
+            )));
         };
 
-        return false;
+        return Ok(false);
     }
 }
@@ -2,7 +2,11 @@
 /// The `slide_visit` primitive is used to assign a bus to an available charger.
 //
 pub mod slide_visit_quick {
+    // Import standard lib
+    use rand::rngs::StdRng;
+
     // Import modules
+    use crate::error::Error;
     use crate::sa::charger::Charger;
     use crate::sa::data::Data;
     use crate::sa::generators::primitives::purge::*;
@@ -18,9 +22,10 @@ pub mod slide_visit_quick {
     /// * q: Queue index
     /// * ae: Arrive/departure times for the BEB
     /// * ud: Start/stop charge times
+    /// * rng: Random number generator
     ///
     /// # Output
-    /// * bool: Assignment failure/success
+    /// * bool: Assignment failure/success, or an error if the visit could not be restored
     ///
     pub fn run(
         d: &mut Data,
@@ -30,11 +35,12 @@ pub mod slide_visit_quick {
         q: usize,
         ae: &(f32, f32),
         ud: &(f32, f32),
-    ) -> bool {
+        rng: &mut StdRng,
+    ) -> Result<bool, Error> {
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Remove the visit, return false if unsuccessful
-        if !purge::run(d, i, ch, q, ud) {
-            return false;
+        if !purge::run(d, i, ch, q, ud)? {
+            return Ok(false);
         }
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -44,26 +50,29 @@ pub mod slide_visit_quick {
         let ts = ch.get_ts(&q, ae);
 
         // Check if the arrival/departure fits in the time slice
-        let (fits, ud_new) = ch.find_free_time(ae, &ts);
+        let (fits, ud_new) = ch.find_free_time(ae, &ts, rng);
 
-        // If the selected time slice arrival/departure fits in the time slice, assign the start/stop charge
-        // times
-        if fits && ch.assign(q, ud_new, b) {
+        // If the selected time slice arrival/departure fits in the time slice and the grid connection, assign
+        // the start/stop charge times
+        if fits && ch.fits_grid(q, &ud_new, d.param.grid) && ch.assign(q, ud_new, b)? {
             // Update initial/final charge times
             d.dec.u[i] = ud_new.0;
             d.dec.d[i] = ud_new.1;
             d.dec.s[i] = ud_new.1 - ud_new.0;
 
-            return true;
+            return Ok(true);
         }
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Place the original visit back in the queue availability matrix
 
-        if !ch.assign(q, *ud, b) {
-            panic!("Lost a visit!");
+        if !ch.assign(q, *ud, b)? {
+            return Err(Error::Invariant(format!(
+                "slide_visit_quick.rs: Lost visit {} of bus {} while restoring it.",
+                i, b
+            )));
         };
 
-        return false;
+        return Ok(false);
     }
 }
@@ -4,6 +4,7 @@
 pub mod wait {
 
     // Import modules
+    use crate::error::Error;
     use crate::sa::charger::Charger;
     use crate::sa::data::Data;
     use crate::sa::generators::primitives::purge::*;
@@ -22,7 +23,7 @@ pub mod wait {
     /// * ud: Start/stop charging times
     ///
     /// # Output
-    /// * bool: Assignment failure/success
+    /// * bool: Assignment failure/success, or an error if the visit could not be restored
     ///
     pub fn run(
         d: &mut Data,
@@ -32,14 +33,14 @@ pub mod wait {
         b: usize,
         ae: &(f32, f32),
         ud: &(f32, f32),
-    ) -> bool {
+    ) -> Result<bool, Error> {
         // Remove the visit, return false if unsuccessful
-        if !purge::run(d, i, ch, q, ud) {
-            return false;
+        if !purge::run(d, i, ch, q, ud)? {
+            return Ok(false);
         }
 
         // Return true/false if assignment succeeded/failed
-        if ch.assign(b, *ae, b) {
+        if ch.assign(b, *ae, b)? {
             // Update queue to wait queue
             d.dec.v[i] = b;
             d.dec.w[i].fill(false);
@@ -50,16 +51,19 @@ pub mod wait {
             d.dec.d[i] = ae.1;
             d.dec.s[i] = ae.1 - ae.0;
 
-            return true;
+            return Ok(true);
         }
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Place the original visit back in the queue availability matrix
 
-        if !ch.assign(q, *ud, b) {
-            panic!("Lost a visit!");
+        if !ch.assign(q, *ud, b)? {
+            return Err(Error::Invariant(format!(
+                "wait.rs: Lost visit {} of bus {} while restoring it.",
+                i, b
+            )));
         };
 
-        return false;
+        return Ok(false);
     }
 }
@@ -2,12 +2,13 @@
 // Import standard library modules
 // use crate::util::rand_utils;
 use rand::distributions::{Distribution, Standard, WeightedIndex};
-use rand::prelude::*;
+use rand::rngs::StdRng;
 use rand::Rng;
 use strum::{EnumIter, IntoEnumIterator};
 
 //===============================================================================
 // Import developed modules
+use crate::error::Error;
 use crate::sa::charger::Charger;
 use crate::sa::generators::primitives::new_charger::*;
 use crate::sa::generators::primitives::new_window::*;
@@ -69,7 +70,12 @@ impl TweakSchedule {
 /// Implementation of `Generator` for `TweakSchedule`
 //
 impl Generator for TweakSchedule {
-    fn run(self: &mut TweakSchedule, r: &mut Box<dyn Route>, c: &mut Charger) -> bool {
+    fn run(
+        self: &mut TweakSchedule,
+        r: &mut Box<dyn Route>,
+        c: &mut Charger,
+        rng: &mut StdRng,
+    ) -> Result<bool, Error> {
         // Get the data
         let mut rd = r.get_data();
         let A = rd.param.A;
@@ -114,18 +120,25 @@ impl Generator for TweakSchedule {
             }
         }
 
-        // println!("{:?}", idx_weight);
-
-        let idx_dist = WeightedIndex::new(&idx_weight).unwrap();
-
-        // Create random generate
-        let mut rng = thread_rng();
+        // Every visit after the initial visits has a zero weight when there are none
+        let idx_dist = match WeightedIndex::new(&idx_weight) {
+            Ok(d) => d,
+            Err(_) => {
+                return Err(Error::Infeasible(
+                    "There are no visits after the initial visits to tweak.".to_string(),
+                ))
+            }
+        };
 
         // Get random visit
-        let ri = idx_dist.sample(&mut rng);
+        let ri = idx_dist.sample(rng);
 
+        // The initial visits are never tweaked
         if ri < A {
-            println!("BUTTS");
+            return Err(Error::Invariant(format!(
+                "Selected initial visit {} to tweak ({} initial visits).",
+                ri, A
+            )));
         }
 
         let q = rd.dec.v[ri];
@@ -134,14 +147,14 @@ impl Generator for TweakSchedule {
         let ae = &(rd.param.a[ri], rd.param.e[ri]);
 
         // Loop through the primitives
-        let p = primitives[prim_dist.sample(&mut rng)].clone();
+        let p = primitives[prim_dist.sample(rng)].clone();
 
         // Try running the primitive and store the result
         success = match p {
-            Primitives::NewCharger => new_charger::run(&mut rd, ri, c, q, id, ud),
-            Primitives::NewWindow => new_window::run(&mut rd, ri, c, q, id, ae, ud),
-            Primitives::Wait => wait::run(&mut rd, ri, c, q, id, ae, ud),
-            Primitives::SlideVisit => slide_visit::run(&mut rd, ri, c, id, q, ae, ud),
+            Primitives::NewCharger => new_charger::run(&mut rd, ri, c, q, id, ud, rng)?,
+            Primitives::NewWindow => new_window::run(&mut rd, ri, c, q, id, ae, ud, rng)?,
+            Primitives::Wait => wait::run(&mut rd, ri, c, q, id, ae, ud)?,
+            Primitives::SlideVisit => slide_visit::run(&mut rd, ri, c, id, q, ae, ud, rng)?,
         };
 
         // If successful, update the MILP data and break out of loop
@@ -149,7 +162,7 @@ impl Generator for TweakSchedule {
             r.set_data(rd.clone());
         }
 
-        return success;
+        return Ok(success);
     }
 }
 
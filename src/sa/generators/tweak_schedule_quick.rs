@@ -2,12 +2,13 @@
 // Import standard library modules
 // use crate::util::rand_utils;
 use rand::distributions::{Distribution, Standard, WeightedIndex};
-use rand::prelude::*;
+use rand::rngs::StdRng;
 use rand::Rng;
 use strum::{EnumIter, IntoEnumIterator};
 
 //===============================================================================
 // Import developed modules
+use crate::error::Error;
 use crate::sa::charger::Charger;
 use crate::sa::generators::primitives::new_charger_quick::*;
 use crate::sa::generators::primitives::new_visit_quick::*;
@@ -69,7 +70,12 @@ impl TweakScheduleQuick {
 /// Implementation of `Generator` for `TweakScheduleQuick`
 //
 impl Generator for TweakScheduleQuick {
-    fn run(self: &mut TweakScheduleQuick, r: &mut Box<dyn Route>, c: &mut Charger) -> bool {
+    fn run(
+        self: &mut TweakScheduleQuick,
+        r: &mut Box<dyn Route>,
+        c: &mut Charger,
+        rng: &mut StdRng,
+    ) -> Result<bool, Error> {
         // Get the data
         let mut rd = r.get_data();
         let A = rd.param.A;
@@ -114,26 +120,40 @@ impl Generator for TweakScheduleQuick {
             }
         }
 
-        let idx_dist = WeightedIndex::new(&idx_weight).unwrap();
-
-        let mut rng = thread_rng();
+        // Every visit after the initial visits has a zero weight when there are none
+        let idx_dist = match WeightedIndex::new(&idx_weight) {
+            Ok(d) => d,
+            Err(_) => {
+                return Err(Error::Infeasible(
+                    "There are no visits after the initial visits to tweak.".to_string(),
+                ))
+            }
+        };
 
         // Get random visit
-        let ri = idx_dist.sample(&mut rng);
+        let ri = idx_dist.sample(rng);
+
+        // The initial visits are never tweaked
+        if ri < A {
+            return Err(Error::Invariant(format!(
+                "Selected initial visit {} to tweak ({} initial visits).",
+                ri, A
+            )));
+        }
         let q = rd.dec.v[ri];
         let id = rd.param.Gam[ri] as usize;
         let ud = &(rd.dec.u[ri], rd.dec.d[ri]);
         let ae = &(rd.param.a[ri], rd.param.e[ri]);
 
         // Select a primitive
-        let p = primitives[prim_dist.sample(&mut rng)].clone();
+        let p = primitives[prim_dist.sample(rng)].clone();
 
         // Try running the primitive and store the result
         success = match p {
-            Primitives::NewCharger => new_charger_quick::run(&mut rd, ri, c, q, id, ud),
-            Primitives::NewWindow => new_visit_quick::run(&mut rd, ri, c, q, id, ae, ud),
-            Primitives::Wait => wait::run(&mut rd, ri, c, q, id, ae, ud),
-            Primitives::SlideVisit => slide_visit_quick::run(&mut rd, ri, c, id, q, ae, ud),
+            Primitives::NewCharger => new_charger_quick::run(&mut rd, ri, c, q, id, ud, rng)?,
+            Primitives::NewWindow => new_visit_quick::run(&mut rd, ri, c, q, id, ae, ud, rng)?,
+            Primitives::Wait => wait::run(&mut rd, ri, c, q, id, ae, ud)?,
+            Primitives::SlideVisit => slide_visit_quick::run(&mut rd, ri, c, id, q, ae, ud, rng)?,
         };
 
         // If successful, update the MILP data and break out of loop
@@ -141,7 +161,7 @@ impl Generator for TweakScheduleQuick {
             r.set_data(rd.clone());
         }
 
-        return success;
+        return Ok(success);
     }
 }
 
@@ -0,0 +1,188 @@
+//==============================================================================
+// Import standard library
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::sync::{Arc, Mutex};
+
+//==============================================================================
+// Import modules
+use crate::sa::charger::Charger;
+use crate::sa::data::Data;
+
+//==============================================================================
+/// Best solution shared between the islands
+//
+#[derive(Clone)]
+pub struct Migrant {
+    pub score: f64,            // Objective score of the solution
+    pub data: Box<Data>,       // Solution
+    pub charger: Box<Charger>, // Charge schedule of the solution
+}
+
+//==============================================================================
+/// Island of the island model. The islands run independent SA instances on the
+/// same instance and periodically share their best solution. Clones of an
+/// island are connected to the same islands.
+//
+#[derive(Clone)]
+pub struct Island {
+    best: Arc<Mutex<Option<Migrant>>>, // Best solution of all the islands
+    interval: usize,                   // Temperature steps between migrations
+    crossover: bool,                   // Adopt a crossover instead of the global best
+}
+
+//==============================================================================
+/// Implementation of Island
+//
+impl Island {
+    //==========================================================================
+    // PUBLIC
+    //==========================================================================
+
+    //--------------------------------------------------------------------------
+    /// Initialize a group of islands. Clone the island to connect more islands.
+    ///
+    /// # Input
+    /// * `interval`  : Temperature steps between migrations
+    /// * `crossover` : Adopt a crossover of the global best and the island best
+    ///                 instead of the global best
+    ///
+    /// # Output
+    /// * `Island`: Island connected to no other island
+    ///
+    pub fn new(interval: usize, crossover: bool) -> Island {
+        return Island {
+            best: Arc::new(Mutex::new(None)),
+            interval,
+            crossover,
+        };
+    }
+
+    //--------------------------------------------------------------------------
+    /// Decide whether the islands migrate after temperature step `step`.
+    ///
+    /// # Input
+    /// * `step` : Temperature step
+    ///
+    /// # Output
+    /// * `bool` : True if the island should migrate
+    ///
+    pub fn is_migration_step(self: &Island, step: usize) -> bool {
+        return self.interval > 0 && (step + 1).is_multiple_of(self.interval);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Decide whether the island adopts a crossover of the global best.
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * `bool` : True if the island adopts a crossover
+    ///
+    pub fn use_crossover(self: &Island) -> bool {
+        return self.crossover;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Share the best solution of the island. If the island best is better
+    /// than the global best, it replaces the global best. If the global best
+    /// is better than the island best, it is returned to be adopted.
+    ///
+    /// # Input
+    /// * `score`   : Objective score of the island best
+    /// * `data`    : Island best solution
+    /// * `charger` : Charge schedule of the island best
+    ///
+    /// # Output
+    /// * `Some(Migrant)` if the global best is better than the island best
+    ///
+    pub fn migrate(self: &Island, score: f64, data: &Data, charger: &Charger) -> Option<Migrant> {
+        let mut best = self.best.lock().unwrap();
+
+        match &*best {
+            // The global best is better, adopt it
+            Some(m) if m.score < score => {
+                return Some(m.clone());
+            }
+            // The global best is as good, keep the island best
+            Some(m) if m.score == score => {
+                return None;
+            }
+            // The island best is better, share it
+            _ => {
+                *best = Some(Migrant {
+                    score,
+                    data: Box::new(data.clone()),
+                    charger: Box::new(charger.clone()),
+                });
+                return None;
+            }
+        }
+    }
+
+    //--------------------------------------------------------------------------
+    /// Get the best solution of all the islands.
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * `Some(Migrant)` if an island has shared its best solution
+    ///
+    pub fn best(self: &Island) -> Option<Migrant> {
+        return self.best.lock().unwrap().clone();
+    }
+
+    //--------------------------------------------------------------------------
+    /// Cross two solutions over. Each bus keeps the charge schedule of either
+    /// parent with equal probability. The child may schedule overlapping
+    /// visits on a charger, see `Island::is_valid`.
+    ///
+    /// # Input
+    /// * `a`   : First parent
+    /// * `b`   : Second parent
+    /// * `rng` : Random number generator
+    ///
+    /// # Output
+    /// * `Data`: Child solution
+    ///
+    pub fn crossover(a: &Data, b: &Data, rng: &mut StdRng) -> Data {
+        let mut child = a.clone();
+
+        // Select the buses that take the schedule of the second parent
+        let take: Vec<bool> = (0..a.param.A).map(|_| rng.gen_bool(0.5)).collect();
+
+        // Copy the visits of the selected buses
+        for i in 0..a.param.N {
+            if take[a.param.Gam[i] as usize] {
+                child.dec.u[i] = b.dec.u[i];
+                child.dec.d[i] = b.dec.d[i];
+                child.dec.s[i] = b.dec.s[i];
+                child.dec.v[i] = b.dec.v[i];
+                child.dec.w[i] = b.dec.w[i].clone();
+                for q in 0..child.dec.g.len() {
+                    child.dec.g[q][i] = b.dec.g[q][i];
+                }
+            }
+        }
+
+        return child;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Check that no queue of the charge schedule has overlapping visits.
+    ///
+    /// # Input
+    /// * `charger` : Charger with the schedule to check
+    ///
+    /// # Output
+    /// * `bool` : True if the visits of every queue are disjoint
+    ///
+    pub fn is_valid(charger: &Charger) -> bool {
+        return charger
+            .schedule
+            .iter()
+            .all(|q| q.windows(2).all(|w| w[0].t.1 <= w[1].t.0));
+    }
+}
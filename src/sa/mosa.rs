@@ -0,0 +1,705 @@
+#![allow(non_snake_case)]
+
+//==============================================================================
+// Import standard library
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::time::Instant;
+
+//==============================================================================
+// Import modules
+use crate::error::Error;
+use crate::lp::objectives::std_obj::StdObj;
+use crate::lp::objectives::{Evaluation, Objective};
+use crate::sa::charger::Charger;
+use crate::sa::config::general::GeneralConfig;
+use crate::sa::config::schedule::ScheduleConfig;
+use crate::sa::config::ChargerConfig;
+use crate::sa::data::Data;
+use crate::sa::generators::Generator;
+use crate::sa::observers::{SaObserver, TempStep};
+use crate::sa::route::Route;
+use crate::util::rand_utils;
+
+//==============================================================================
+/// Objectives of the Pareto front. The energy cost and the peak power are
+/// minimized, the SOC margin is maximized.
+//
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Criteria {
+    pub cost: f64,   // Consumption cost of the energy drawn by the chargers [$]
+    pub peak: f64,   // Peak power averaged over the demand window [KW]
+    pub margin: f64, // Smallest SOC on arrival above the minimum charge of the fleet [0-1]
+}
+
+//==============================================================================
+/// Solution on the Pareto front
+//
+#[derive(Clone)]
+pub struct ParetoSolution {
+    pub sol: Data,          // Schedule of the solution
+    pub charger: Charger,   // Charge schedule of the solution
+    pub criteria: Criteria, // Objectives of the solution
+    pub j: f64,             // Weighted objective function score of the solution
+}
+
+//==============================================================================
+/// Archive of the non-dominated solutions found by the search
+//
+#[derive(Clone, Default)]
+pub struct Archive {
+    pub solutions: Vec<ParetoSolution>, // Mutually non-dominated solutions
+}
+
+//==============================================================================
+/// Results from multi-objective simulated annealing
+//
+#[derive(Clone)]
+pub struct ParetoResults {
+    pub archive: Archive, // Pareto front of the run
+    pub seed: u64,        // Seed of the random number generator
+    pub T0: f32,          // Initial temperature
+}
+
+//==============================================================================
+/// Structure for archived multi-objective simulated annealing (AMOSA)
+//
+pub struct MOSA {
+    gsol: Box<dyn Generator>,            // Solution generator
+    gsys: Box<dyn Route>,                // Route generator
+    gtweak: Box<dyn Generator>,          // Solution modifier
+    charger: Box<Charger>,               // Charge schedule keeper
+    objective: Box<dyn Objective>,       // Objective function that evaluates the schedules
+    sched: ScheduleConfig,               // Schedule configuration
+    run_all_constr: bool,                // Run all the constraints in the objective function
+    seed: u64,                           // Seed of the random number generator
+    rng: StdRng,                         // Random number generator of the run
+    observers: Vec<Box<dyn SaObserver>>, // Observers of the progress of the run
+}
+
+//==============================================================================
+/// Implementation of Criteria
+//
+impl Criteria {
+    //--------------------------------------------------------------------------
+    /// Read the objectives of an evaluated schedule
+    ///
+    /// # Input
+    /// * e: Evaluation of the schedule
+    /// * dat: Evaluated data object
+    ///
+    /// # Output
+    /// * Criteria: Objectives of the schedule
+    ///
+    pub fn new(e: &Evaluation, dat: &Data) -> Criteria {
+        let margin = (0..dat.param.N)
+            .map(|i| {
+                let kappa = dat.param.k[dat.param.Gam[i] as usize];
+                (dat.dec.eta[i] / kappa - dat.param.nu) as f64
+            })
+            .fold(f64::INFINITY, f64::min);
+
+        return Criteria {
+            cost: e.terms.get("UC"),
+            peak: e.peak,
+            margin,
+        };
+    }
+
+    //--------------------------------------------------------------------------
+    /// Objectives as values to minimize
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * f: Energy cost, peak power and negated SOC margin
+    ///
+    pub fn values(self: &Criteria) -> [f64; 3] {
+        return [self.cost, self.peak, -self.margin];
+    }
+
+    //--------------------------------------------------------------------------
+    /// Indicate whether the objectives dominate another set of objectives,
+    /// i.e. they are no worse in every objective and better in at least one.
+    ///
+    /// # Input
+    /// * other: Objectives to compare to
+    ///
+    /// # Output
+    /// * bool: True if `self` dominates `other`
+    ///
+    pub fn dominates(self: &Criteria, other: &Criteria) -> bool {
+        let a = self.values();
+        let b = other.values();
+
+        return (0..3).all(|i| a[i] <= b[i]) && (0..3).any(|i| a[i] < b[i]);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Amount of domination between two sets of objectives, the product of
+    /// their normalized differences in the objectives they differ in.
+    ///
+    /// # Input
+    /// * other: Objectives to compare to
+    /// * range: Range of each objective over the archive
+    ///
+    /// # Output
+    /// * amount: Amount of domination in [0, 1]
+    ///
+    fn amount(self: &Criteria, other: &Criteria, range: &[f64; 3]) -> f64 {
+        let a = self.values();
+        let b = other.values();
+
+        return (0..3)
+            .filter(|i| a[*i] != b[*i] && range[*i] > 0.0)
+            .map(|i| ((a[i] - b[i]).abs() / range[i]).min(1.0))
+            .product();
+    }
+}
+
+//==============================================================================
+/// Implementation of Archive
+//
+impl Archive {
+    //--------------------------------------------------------------------------
+    /// Indices of the archived solutions that dominate a set of objectives
+    ///
+    /// # Input
+    /// * c: Objectives of interest
+    ///
+    /// # Output
+    /// * idx: Indices of the dominating solutions
+    ///
+    pub fn dominating(self: &Archive, c: &Criteria) -> Vec<usize> {
+        return (0..self.solutions.len())
+            .filter(|i| self.solutions[*i].criteria.dominates(c))
+            .collect();
+    }
+
+    //--------------------------------------------------------------------------
+    /// Add a solution to the archive and remove the solutions it dominates. A
+    /// solution that is dominated by, or has the same objectives as, an
+    /// archived solution is not added.
+    ///
+    /// # Input
+    /// * s: Solution to add
+    ///
+    /// # Output
+    /// * bool: True if the solution was added
+    ///
+    pub fn add(self: &mut Archive, s: ParetoSolution) -> bool {
+        let c = s.criteria;
+        if self
+            .solutions
+            .iter()
+            .any(|a| a.criteria.dominates(&c) || a.criteria == c)
+        {
+            return false;
+        }
+
+        self.solutions.retain(|a| !c.dominates(&a.criteria));
+        self.solutions.push(s);
+
+        return true;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Thin the archive to `n` solutions. The most crowded solution, the one
+    /// closest to another solution in the normalized objective space, is
+    /// removed until `n` solutions remain. This keeps the spread of the front
+    /// like the single linkage clustering of AMOSA.
+    ///
+    /// # Input
+    /// * n: Number of solutions to keep
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    pub fn reduce(self: &mut Archive, n: usize) {
+        while self.solutions.len() > n {
+            let range = self.range(&[]);
+            let f: Vec<[f64; 3]> = self
+                .solutions
+                .iter()
+                .map(|s| {
+                    let v = s.criteria.values();
+                    [0, 1, 2].map(|i| if range[i] > 0.0 { v[i] / range[i] } else { 0.0 })
+                })
+                .collect();
+
+            // Distance of every solution to its nearest neighbor
+            let nearest = |i: usize| -> f64 {
+                return (0..f.len())
+                    .filter(|j| *j != i)
+                    .map(|j| (0..3).map(|o| (f[i][o] - f[j][o]).powi(2)).sum::<f64>())
+                    .fold(f64::INFINITY, f64::min);
+            };
+            let crowded = (0..f.len())
+                .min_by(|x, y| nearest(*x).total_cmp(&nearest(*y)))
+                .unwrap();
+
+            self.solutions.remove(crowded);
+        }
+    }
+
+    //--------------------------------------------------------------------------
+    /// Range of each objective over the archive and additional objectives
+    ///
+    /// # Input
+    /// * extra: Objectives to include besides the archive
+    ///
+    /// # Output
+    /// * range: Difference of the largest and smallest value of each objective
+    ///
+    fn range(self: &Archive, extra: &[&Criteria]) -> [f64; 3] {
+        let mut lo = [f64::INFINITY; 3];
+        let mut hi = [f64::NEG_INFINITY; 3];
+        let all = self
+            .solutions
+            .iter()
+            .map(|s| &s.criteria)
+            .chain(extra.iter().copied());
+        for c in all {
+            let v = c.values();
+            for i in 0..3 {
+                lo[i] = lo[i].min(v[i]);
+                hi[i] = hi[i].max(v[i]);
+            }
+        }
+
+        return [0, 1, 2].map(|i| (hi[i] - lo[i]).max(0.0));
+    }
+}
+
+//==============================================================================
+/// Implementation of MOSA
+//
+impl MOSA {
+    //==========================================================================
+    // PUBLIC
+    //==========================================================================
+
+    //--------------------------------------------------------------------------
+    /// Initialize the MOSA object
+    ///
+    /// # Input
+    /// * `config_path`   : String of relative path to configuration file
+    /// * `schedule_path` : String of relative path to schedule file
+    /// * `gsol`          : Solution generator
+    /// * `gsys`          : Route generator
+    /// * `gtweak`        : Tweak schedule
+    /// * `seed`          : Seed of the random number generator
+    ///
+    /// # Output
+    /// * `MOSA`: Multi-objective simulated annealing solver, or an error if a configuration file is
+    ///   invalid or the route could not be generated
+    ///
+    pub fn new(
+        config_path: &str,
+        schedule_path: &str,
+        gsol: Box<dyn Generator>,
+        gsys: Box<dyn Route>,
+        gtweak: Box<dyn Generator>,
+        seed: u64,
+    ) -> Result<MOSA, Error> {
+        // Load the configuration
+        let sched = ScheduleConfig::load(schedule_path)?;
+        let config = GeneralConfig::load(config_path)?;
+
+        return MOSA::with_config(&config, &sched, gsol, gsys, gtweak, seed);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Initialize the MOSA object from loaded configurations
+    ///
+    /// # Input
+    /// * `config` : General configuration
+    /// * `sched`  : Schedule configuration
+    /// * `gsol`   : Solution generator
+    /// * `gsys`   : Route generator
+    /// * `gtweak` : Tweak schedule
+    /// * `seed`   : Seed of the random number generator
+    ///
+    /// # Output
+    /// * `MOSA`: Multi-objective simulated annealing solver, or an error if the route could not be generated
+    ///
+    pub fn with_config(
+        config: &GeneralConfig,
+        sched: &ScheduleConfig,
+        gsol: Box<dyn Generator>,
+        mut gsys: Box<dyn Route>,
+        gtweak: Box<dyn Generator>,
+        seed: u64,
+    ) -> Result<MOSA, Error> {
+        // Create the random number generator. Every random choice of the run is drawn from it.
+        let mut rng = rand_utils::seeded_rng(seed);
+
+        // Generate new solution
+        gsys.run(&mut rng)?;
+
+        // There is a wait charger for every bus
+        let chargers = ChargerConfig {
+            count: (
+                gsys.get_data().param.A,
+                sched.chargers.count.1,
+                sched.chargers.count.2,
+            ),
+            ..sched.chargers.clone()
+        };
+
+        // Create MOSA object
+        let mosa: MOSA = MOSA {
+            gsol,
+            gsys,
+            gtweak,
+            charger: Box::new(Charger::from_config(&chargers, &sched.time)),
+            objective: Box::new(StdObj::new(&sched.weights)?),
+            sched: sched.clone(),
+            run_all_constr: config.run_all_constr,
+            seed,
+            rng,
+            observers: Vec::new(),
+        };
+
+        return Ok(mosa);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Add an observer of the progress of the run. The scores reported to the
+    /// observers are energy costs, the best score is the cheapest solution of
+    /// the archive.
+    ///
+    /// # Input
+    /// * observer: Observer to notify of the events of the run
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    pub fn add_observer(self: &mut MOSA, observer: Box<dyn SaObserver>) {
+        self.observers.push(observer);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Run archived multi-objective simulated annealing. The temperature falls
+    /// geometrically from `mosa.t_max` to `mosa.t_min` and every temperature
+    /// step runs `time.K` tweaks of the current solution. A candidate is kept
+    /// according to the amount by which it is dominated, with probability
+    ///
+    /// $\frac{1}{1 + e^{\Delta_{dom} / T}}$
+    ///
+    /// The archive is thinned to `mosa.archive` solutions whenever it grows
+    /// beyond `mosa.soft` solutions and at the end of the run.
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * `ParetoResults`: Pareto front of the run, or an error if an
+    ///   evaluation failed
+    ///
+    pub fn run(self: &mut MOSA) -> Result<ParetoResults, Error> {
+        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        // Initialize
+
+        // Annealing parameters
+        let k = self.sched.time.k;
+        let run_all_constr = self.run_all_constr;
+        let mosa = self.sched.mosa.clone();
+        let steps = MOSA::steps(mosa.t_max, mosa.t_min, mosa.alpha);
+
+        // Generate the initial solution
+        self.gsol
+            .run(&mut self.gsys, &mut self.charger, &mut self.rng)?;
+        let mut sol = *self.gsys.get_data();
+        let mut charger = (*self.charger).clone();
+        let e = self
+            .objective
+            .evaluate(&mut sol, &mut charger, run_all_constr)?;
+        let mut current = ParetoSolution {
+            criteria: Criteria::new(&e, &sol),
+            j: e.J,
+            sol,
+            charger,
+        };
+
+        // The initial solution is the first solution of the archive
+        let mut archive = Archive::default();
+        archive.add(current.clone());
+
+        // Initialize solution scores
+        let mut sol_scores = current.sol.clone();
+        let mut jn = current.criteria.cost;
+        sol_scores.dec.Jb = vec![jn];
+        sol_scores.dec.Jc = vec![jn];
+        sol_scores.dec.Jn = vec![jn];
+
+        // Notify the observers of the start of the run
+        for o in self.observers.iter_mut() {
+            o.on_start(0, steps, mosa.t_max);
+        }
+
+        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        // Execute MOSA
+        let mut t = mosa.t_max;
+        for step in 0..steps {
+            // Get starting time
+            let start = Instant::now();
+            let mut candidates: usize = 0;
+            let mut accepted: usize = 0;
+
+            for i in 0..k {
+                // Tweak a copy of the current solution
+                let new = match self.tweak(&current, run_all_constr)? {
+                    Some(n) => n,
+                    None => continue,
+                };
+                candidates += 1;
+                jn = new.criteria.cost;
+
+                // Keep the candidate according to the archive
+                let keep = self.accept(&mut current, new, &mut archive, t);
+                if keep {
+                    accepted += 1;
+                }
+
+                // Thin the archive when it reaches the soft limit
+                if archive.solutions.len() > mosa.soft {
+                    archive.reduce(mosa.archive);
+                }
+
+                // Notify the observers of the candidate
+                for o in self.observers.iter_mut() {
+                    o.on_iteration(step, i, t, jn);
+                    if keep {
+                        o.on_accept(step, jn);
+                    } else {
+                        o.on_reject(step, jn);
+                    }
+                }
+            }
+
+            // Append the latest scores
+            let best = MOSA::cheapest(&archive);
+            sol_scores.dec.Jb.push(best.criteria.cost);
+            sol_scores.dec.Jc.push(current.criteria.cost);
+            sol_scores.dec.Jn.push(jn);
+
+            // Notify the observers of the temperature step
+            let s = TempStep {
+                step,
+                steps,
+                t,
+                elapsed: start.elapsed(),
+                candidates,
+                accepted,
+                jb: best.criteria.cost,
+                jc: current.criteria.cost,
+                jn,
+                sol_best: &best.sol,
+                scores: &sol_scores,
+            };
+            for o in self.observers.iter_mut() {
+                o.on_temp_step(&s);
+            }
+
+            // Cool down
+            t *= mosa.alpha;
+        }
+
+        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        // Report results
+
+        // Keep at most `mosa.archive` solutions, ordered by their energy cost
+        archive.reduce(mosa.archive);
+        archive
+            .solutions
+            .sort_by(|a, b| a.criteria.cost.total_cmp(&b.criteria.cost));
+
+        return Ok(ParetoResults {
+            archive,
+            seed: self.seed,
+            T0: mosa.t_max,
+        });
+    }
+
+    //==========================================================================
+    // PRIVATE
+    //==========================================================================
+
+    //--------------------------------------------------------------------------
+    /// Number of temperature steps from `t_max` down to `t_min`
+    ///
+    /// # Input
+    /// * t_max: Initial temperature
+    /// * t_min: Final temperature
+    /// * alpha: Cooling factor
+    ///
+    /// # Output
+    /// * steps: Number of temperature steps
+    ///
+    fn steps(t_max: f32, t_min: f32, alpha: f32) -> usize {
+        return ((t_min / t_max).ln() / alpha.ln()).floor() as usize + 1;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Solution of the archive with the smallest energy cost
+    ///
+    /// # Input
+    /// * archive: Archive of the run
+    ///
+    /// # Output
+    /// * ParetoSolution: Cheapest solution
+    ///
+    fn cheapest(archive: &Archive) -> &ParetoSolution {
+        return archive
+            .solutions
+            .iter()
+            .min_by(|a, b| a.criteria.cost.total_cmp(&b.criteria.cost))
+            .unwrap();
+    }
+
+    //--------------------------------------------------------------------------
+    /// Tweak a copy of a solution and evaluate it
+    ///
+    /// # Input
+    /// * current: Solution to tweak
+    /// * run_all_constr: Run all the constraints in the objective function
+    ///
+    /// # Output
+    /// * `Some(new)` if the schedule was modified into a feasible candidate, or
+    ///   an error if the tweak or the evaluation failed
+    ///
+    fn tweak(
+        self: &mut MOSA,
+        current: &ParetoSolution,
+        run_all_constr: bool,
+    ) -> Result<Option<ParetoSolution>, Error> {
+        // Tweak a copy of the solution
+        self.gsys.set_data(Box::new(current.sol.clone()));
+        let mut charger = current.charger.clone();
+        if !self
+            .gtweak
+            .run(&mut self.gsys, &mut charger, &mut self.rng)?
+        {
+            return Ok(None);
+        }
+
+        // Evaluate the candidate, an infeasible candidate is discarded
+        let mut sol = *self.gsys.get_data();
+        let e = match Error::feasible(self.objective.evaluate(
+            &mut sol,
+            &mut charger,
+            run_all_constr,
+        ))? {
+            Some(e) => e,
+            None => return Ok(None),
+        };
+        charger.milp_to_schedule(&sol);
+
+        return Ok(Some(ParetoSolution {
+            criteria: Criteria::new(&e, &sol),
+            j: e.J,
+            sol,
+            charger,
+        }));
+    }
+
+    //--------------------------------------------------------------------------
+    /// Decide the next current solution from the candidate and the archive
+    /// following the acceptance cases of AMOSA.
+    ///
+    /// - The current solution dominates the candidate: the candidate is kept
+    ///   by the average amount it is dominated by the current solution and the
+    ///   archive.
+    /// - Neither dominates the other: a candidate dominated by the archive is
+    ///   kept by the average amount it is dominated by, otherwise it is kept
+    ///   and added to the archive.
+    /// - The candidate dominates the current solution: if the archive
+    ///   dominates the candidate, the archived solution with the least amount
+    ///   of domination may replace it. Otherwise it is kept and added to the
+    ///   archive.
+    ///
+    /// # Input
+    /// * current: Current solution
+    /// * new: Candidate solution
+    /// * archive: Archive of the run
+    /// * t: Temperature
+    ///
+    /// # Output
+    /// * bool: True if the current solution changed
+    ///
+    fn accept(
+        self: &mut MOSA,
+        current: &mut ParetoSolution,
+        new: ParetoSolution,
+        archive: &mut Archive,
+        t: f32,
+    ) -> bool {
+        let c = current.criteria;
+        let n = new.criteria;
+        let range = archive.range(&[&c, &n]);
+        let dom = archive.dominating(&n);
+
+        // Probability of keeping a candidate with the given amount of domination
+        let prob = |amount: f64| -> f64 { 1.0 / (1.0 + (amount / t as f64).exp()) };
+
+        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        // The current solution dominates the candidate
+        if c.dominates(&n) {
+            let amount: f64 = dom
+                .iter()
+                .map(|i| archive.solutions[*i].criteria.amount(&n, &range))
+                .sum::<f64>()
+                + c.amount(&n, &range);
+            let avg = amount / (dom.len() + 1) as f64;
+
+            if self.rng.gen_range(0.0..=1.0) <= prob(avg) {
+                *current = new;
+                return true;
+            }
+            return false;
+        }
+
+        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        // The candidate dominates the current solution, but not the archive
+        if n.dominates(&c) && !dom.is_empty() {
+            let (i, amount) = dom
+                .iter()
+                .map(|i| (*i, archive.solutions[*i].criteria.amount(&n, &range)))
+                .min_by(|x, y| x.1.total_cmp(&y.1))
+                .unwrap();
+
+            // Move to the archived solution closest to the candidate
+            if self.rng.gen_range(0.0..=1.0) <= 1.0 / (1.0 + (-amount).exp()) {
+                *current = archive.solutions[i].clone();
+            } else {
+                *current = new;
+            }
+            return true;
+        }
+
+        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        // Neither dominates the other and the archive dominates the candidate
+        if !dom.is_empty() {
+            let amount: f64 = dom
+                .iter()
+                .map(|i| archive.solutions[*i].criteria.amount(&n, &range))
+                .sum();
+            let avg = amount / dom.len() as f64;
+
+            if self.rng.gen_range(0.0..=1.0) <= prob(avg) {
+                *current = new;
+                return true;
+            }
+            return false;
+        }
+
+        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        // The candidate is not dominated by the archive
+        archive.add(new.clone());
+        *current = new;
+
+        return true;
+    }
+}
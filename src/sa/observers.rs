@@ -0,0 +1,133 @@
+//===============================================================================
+// Import standard library
+use std::fmt;
+use std::time::Duration;
+
+//===============================================================================
+// Import modules
+use crate::sa::data::Data;
+
+//===============================================================================
+// Declare modules
+pub mod json_lines_observer; // Log the events to a JSON lines file
+pub mod progress_bar_observer; // Display the progress in a progress bar
+pub mod real_time_plot_observer; // Plot the best solution after every temperature step
+
+//===============================================================================
+/// Summary of a completed temperature step
+//
+pub struct TempStep<'a> {
+    pub step: usize,        // Index of the temperature step
+    pub steps: usize,       // Number of temperature steps of the schedule
+    pub t: f32,             // Temperature of the step
+    pub elapsed: Duration,  // Wall clock time of the step
+    pub candidates: usize,  // Number of candidate solutions of the step
+    pub accepted: usize,    // Number of accepted candidate solutions of the step
+    pub jb: f64,            // Best objective function score
+    pub jc: f64,            // Current objective function score
+    pub jn: f64,            // Latest candidate objective function score
+    pub sol_best: &'a Data, // Best solution
+    pub scores: &'a Data,   // Objective function score history
+}
+
+//===============================================================================
+/// Setting of a run that could not be estimated and the value that is used
+/// instead
+//
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fallback {
+    Scale(f64),    // No uphill move during the warmup, the default Metropolis scale is used
+    InitTemp(f32), // The target acceptance ratio could not be matched, the closest initial temperature is used
+}
+
+//===============================================================================
+/// Implementation of Display for Fallback
+//
+impl fmt::Display for Fallback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            Fallback::Scale(s) => write!(
+                f,
+                "No uphill moves during the warmup, using the default Metropolis scale {}",
+                s
+            ),
+            Fallback::InitTemp(t0) => write!(
+                f,
+                "The target acceptance ratio could not be matched, using the initial temperature {}",
+                t0
+            ),
+        };
+    }
+}
+
+//===============================================================================
+/// Trait to observe the progress of a solver. Every hook does nothing by
+/// default.
+//
+pub trait SaObserver {
+    //---------------------------------------------------------------------------
+    /// Called once before the first temperature step.
+    ///
+    /// # Input
+    /// * `step`  : Index of the first temperature step, non-zero if resumed
+    /// * `steps` : Number of temperature steps of the schedule
+    /// * `t0`    : Initial temperature
+    ///
+    fn on_start(&mut self, _step: usize, _steps: usize, _t0: f32) {}
+
+    //---------------------------------------------------------------------------
+    /// Called when a setting of the run could not be estimated, before the
+    /// first temperature step.
+    ///
+    /// # Input
+    /// * `f` : Setting and the value that is used instead
+    ///
+    fn on_fallback(&mut self, _f: &Fallback) {}
+
+    //---------------------------------------------------------------------------
+    /// Called for every evaluated candidate solution.
+    ///
+    /// # Input
+    /// * `step`      : Index of the temperature step
+    /// * `iteration` : Index of the tweak within the temperature step
+    /// * `t`         : Temperature
+    /// * `j`         : Objective function score of the candidate
+    ///
+    fn on_iteration(&mut self, _step: usize, _iteration: usize, _t: f32, _j: f64) {}
+
+    //---------------------------------------------------------------------------
+    /// Called when a candidate solution is accepted.
+    ///
+    /// # Input
+    /// * `step` : Index of the temperature step
+    /// * `j`    : Objective function score of the candidate
+    ///
+    fn on_accept(&mut self, _step: usize, _j: f64) {}
+
+    //---------------------------------------------------------------------------
+    /// Called when a candidate solution is rejected.
+    ///
+    /// # Input
+    /// * `step` : Index of the temperature step
+    /// * `j`    : Objective function score of the candidate
+    ///
+    fn on_reject(&mut self, _step: usize, _j: f64) {}
+
+    //---------------------------------------------------------------------------
+    /// Called when the best solution improves.
+    ///
+    /// # Input
+    /// * `step` : Index of the temperature step
+    /// * `jb`   : Objective function score of the new best solution
+    /// * `sol`  : New best solution
+    ///
+    fn on_new_best(&mut self, _step: usize, _jb: f64, _sol: &Data) {}
+
+    //---------------------------------------------------------------------------
+    /// Called after every temperature step.
+    ///
+    /// # Input
+    /// * `s` : Summary of the temperature step
+    ///
+    fn on_temp_step(&mut self, _s: &TempStep) {}
+}
@@ -0,0 +1,210 @@
+//===============================================================================
+// Import standard library
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+//===============================================================================
+// Import modules
+use crate::sa::data::Data;
+use crate::sa::observers::{Fallback, SaObserver, TempStep};
+
+//===============================================================================
+/// Log the events of a run to a JSON lines file. Every event is written as a
+/// JSON object on its own line with an `event` key naming the hook, i.e.
+///
+/// `{"event":"accept","step":3,"j":1234.5}`
+///
+/// Scores that are not finite are written as `null`. If the file cannot be
+/// written, the error is reported once on stderr and logging stops; the run
+/// itself continues.
+//
+pub struct JsonLinesObserver {
+    path: String,         // Path of the log file
+    out: BufWriter<File>, // Buffered log file
+    iterations: bool,     // Log the iteration, accept and reject events
+    failed: bool,         // A write failed and logging stopped
+}
+
+//===============================================================================
+/// Implementation of JsonLinesObserver
+//
+impl JsonLinesObserver {
+    //---------------------------------------------------------------------------
+    /// Initialize the observer. The log file is created or truncated.
+    ///
+    /// # Input
+    /// * `path`       : Path of the log file
+    /// * `iterations` : Log the iteration, accept and reject events. These are
+    ///                  logged for every candidate solution.
+    ///
+    /// # Output
+    /// * `JsonLinesObserver` or the error of creating the file
+    ///
+    pub fn new(path: &str, iterations: bool) -> std::io::Result<JsonLinesObserver> {
+        let file = File::create(path)?;
+
+        return Ok(JsonLinesObserver {
+            path: path.to_string(),
+            out: BufWriter::new(file),
+            iterations,
+            failed: false,
+        });
+    }
+
+    //---------------------------------------------------------------------------
+    /// Write an event to the log file.
+    ///
+    /// # Input
+    /// * `line` : JSON object of the event
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    fn write(self: &mut JsonLinesObserver, line: String) {
+        if self.failed {
+            return;
+        }
+
+        if let Err(e) = writeln!(self.out, "{}", line) {
+            self.fail(e);
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    /// Report a failed write and stop logging.
+    ///
+    /// # Input
+    /// * `e` : Error of the write
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    fn fail(self: &mut JsonLinesObserver, e: std::io::Error) {
+        eprintln!("Could not write to the file {}: {}", self.path, e);
+        self.failed = true;
+    }
+
+    //---------------------------------------------------------------------------
+    /// Format a number as a JSON value.
+    ///
+    /// # Input
+    /// * `x` : Number
+    ///
+    /// # Output
+    /// * `String`: The number, or `null` if it is not finite
+    ///
+    fn num(x: f64) -> String {
+        if x.is_finite() {
+            return x.to_string();
+        }
+
+        return String::from("null");
+    }
+}
+
+//===============================================================================
+/// Implementation of SaObserver for JsonLinesObserver
+//
+impl SaObserver for JsonLinesObserver {
+    //---------------------------------------------------------------------------
+    //
+    fn on_start(&mut self, step: usize, steps: usize, t0: f32) {
+        self.write(format!(
+            "{{\"event\":\"start\",\"step\":{},\"steps\":{},\"t0\":{}}}",
+            step,
+            steps,
+            JsonLinesObserver::num(t0 as f64)
+        ));
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    fn on_fallback(&mut self, f: &Fallback) {
+        let line = match f {
+            Fallback::Scale(s) => format!(
+                "{{\"event\":\"fallback\",\"scale\":{}}}",
+                JsonLinesObserver::num(*s)
+            ),
+            Fallback::InitTemp(t0) => format!(
+                "{{\"event\":\"fallback\",\"t0\":{}}}",
+                JsonLinesObserver::num(*t0 as f64)
+            ),
+        };
+        self.write(line);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    fn on_iteration(&mut self, step: usize, iteration: usize, t: f32, j: f64) {
+        if self.iterations {
+            self.write(format!(
+                "{{\"event\":\"iteration\",\"step\":{},\"iteration\":{},\"t\":{},\"j\":{}}}",
+                step,
+                iteration,
+                JsonLinesObserver::num(t as f64),
+                JsonLinesObserver::num(j)
+            ));
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    fn on_accept(&mut self, step: usize, j: f64) {
+        if self.iterations {
+            self.write(format!(
+                "{{\"event\":\"accept\",\"step\":{},\"j\":{}}}",
+                step,
+                JsonLinesObserver::num(j)
+            ));
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    fn on_reject(&mut self, step: usize, j: f64) {
+        if self.iterations {
+            self.write(format!(
+                "{{\"event\":\"reject\",\"step\":{},\"j\":{}}}",
+                step,
+                JsonLinesObserver::num(j)
+            ));
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    fn on_new_best(&mut self, step: usize, jb: f64, _sol: &Data) {
+        self.write(format!(
+            "{{\"event\":\"new_best\",\"step\":{},\"jb\":{}}}",
+            step,
+            JsonLinesObserver::num(jb)
+        ));
+    }
+
+    //---------------------------------------------------------------------------
+    // The log is flushed after every temperature step
+    //
+    fn on_temp_step(&mut self, s: &TempStep) {
+        self.write(format!(
+            "{{\"event\":\"temp_step\",\"step\":{},\"steps\":{},\"t\":{},\"elapsed\":{},\"candidates\":{},\
+             \"accepted\":{},\"jb\":{},\"jc\":{},\"jn\":{}}}",
+            s.step,
+            s.steps,
+            JsonLinesObserver::num(s.t as f64),
+            s.elapsed.as_secs_f64(),
+            s.candidates,
+            s.accepted,
+            JsonLinesObserver::num(s.jb),
+            JsonLinesObserver::num(s.jc),
+            JsonLinesObserver::num(s.jn)
+        ));
+
+        if self.failed {
+            return;
+        }
+
+        if let Err(e) = self.out.flush() {
+            self.fail(e);
+        }
+    }
+}
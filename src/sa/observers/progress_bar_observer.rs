@@ -0,0 +1,66 @@
+//===============================================================================
+// Import standard library
+use indicatif::{ProgressBar, ProgressStyle};
+
+//===============================================================================
+// Import modules
+use crate::sa::observers::{Fallback, SaObserver, TempStep};
+
+//===============================================================================
+/// Display the progress of a run in a progress bar. The bar counts the
+/// temperature steps and its prefix shows the time of the last step.
+//
+pub struct ProgressBarObserver {
+    pb: ProgressBar, // Progress bar of the run
+}
+
+//===============================================================================
+/// Implementation of ProgressBarObserver
+//
+impl ProgressBarObserver {
+    //---------------------------------------------------------------------------
+    /// Initialize the observer
+    ///
+    /// # Input
+    /// * `pb` : Progress bar to display the progress in
+    ///
+    /// # Output
+    /// * `ProgressBarObserver`
+    ///
+    pub fn new(pb: ProgressBar) -> ProgressBarObserver {
+        return ProgressBarObserver { pb };
+    }
+}
+
+//===============================================================================
+/// Implementation of SaObserver for ProgressBarObserver
+//
+impl SaObserver for ProgressBarObserver {
+    //---------------------------------------------------------------------------
+    /// Set up the progress bar and report the initial temperature
+    ///
+    fn on_start(&mut self, step: usize, steps: usize, t0: f32) {
+        self.pb
+            .set_style(ProgressStyle::with_template("{prefix}|{wide_bar} {pos}/{len}").unwrap());
+        self.pb.println(format!("Initial temperature: {}", t0));
+        self.pb.set_length(steps as u64);
+        self.pb.set_position(step as u64);
+    }
+
+    //---------------------------------------------------------------------------
+    /// Report the setting that could not be estimated
+    ///
+    fn on_fallback(&mut self, f: &Fallback) {
+        self.pb.println(f.to_string());
+    }
+
+    //---------------------------------------------------------------------------
+    /// Update the progress bar. The schedule may change length during the run.
+    ///
+    fn on_temp_step(&mut self, s: &TempStep) {
+        self.pb.set_length(s.steps as u64);
+        self.pb
+            .set_prefix(format!("{:.3}s", s.elapsed.as_secs_f64()));
+        self.pb.inc(1);
+    }
+}
@@ -0,0 +1,84 @@
+//===============================================================================
+// Import standard library
+use gnuplot::Figure;
+
+//===============================================================================
+// Import modules
+use crate::plotter::accumulated_energy_usage_plot::AccumulatedEnergyUsagePlot;
+use crate::plotter::charge_plot::ChargePlot;
+use crate::plotter::charger_usage_plot::ChargerUsagePlot;
+use crate::plotter::power_usage_plot::PowerUsagePlot;
+use crate::plotter::schedule_plot::SchedulePlot;
+use crate::plotter::score_plot::ScorePlot;
+use crate::plotter::Plotter;
+use crate::sa::observers::{SaObserver, TempStep};
+
+//===============================================================================
+/// Plot the best solution and the score history in real time
+//
+pub struct RealTimePlotObserver {
+    fg_acc: Figure,      // Accumulated energy usage figure
+    fg_charge: Figure,   // Charge figure
+    fg_cu: Figure,       // Charger usage figure
+    fg_power: Figure,    // Power usage figure
+    fg_score: Figure,    // Score figure
+    fg_schedule: Figure, // Schedule figure
+}
+
+//===============================================================================
+/// Implementation of RealTimePlotObserver
+//
+impl RealTimePlotObserver {
+    //---------------------------------------------------------------------------
+    /// Initialize the observer
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * `RealTimePlotObserver`
+    ///
+    pub fn new() -> RealTimePlotObserver {
+        return RealTimePlotObserver {
+            fg_acc: Figure::new(),
+            fg_charge: Figure::new(),
+            fg_cu: Figure::new(),
+            fg_power: Figure::new(),
+            fg_score: Figure::new(),
+            fg_schedule: Figure::new(),
+        };
+    }
+}
+
+//===============================================================================
+/// Implementation of Default for RealTimePlotObserver
+//
+impl Default for RealTimePlotObserver {
+    fn default() -> Self {
+        return RealTimePlotObserver::new();
+    }
+}
+
+//===============================================================================
+/// Implementation of SaObserver for RealTimePlotObserver
+//
+impl SaObserver for RealTimePlotObserver {
+    //---------------------------------------------------------------------------
+    /// Plot the schedule after every temperature step. The power, charger usage
+    /// and accumulated energy plots are disabled.
+    ///
+    fn on_temp_step(&mut self, s: &TempStep) {
+        let schedule = s.sol_best;
+
+        PowerUsagePlot::real_time(false, &mut Box::new(schedule.clone()), &mut self.fg_power);
+        ChargerUsagePlot::real_time(false, &mut Box::new(schedule.clone()), &mut self.fg_cu);
+        ChargePlot::real_time(true, &mut Box::new(schedule.clone()), &mut self.fg_charge);
+        AccumulatedEnergyUsagePlot::real_time(
+            false,
+            &mut Box::new(schedule.clone()),
+            &mut self.fg_acc,
+        );
+        ScorePlot::real_time(true, &mut Box::new(s.scores.clone()), &mut self.fg_score);
+        SchedulePlot::real_time(true, &mut Box::new(schedule.clone()), &mut self.fg_schedule);
+    }
+}
@@ -0,0 +1,576 @@
+#![allow(non_snake_case)]
+
+//==============================================================================
+// Import standard library
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::time::Instant;
+
+//==============================================================================
+// Import modules
+use crate::error::Error;
+use crate::lp::objectives::std_obj::StdObj;
+use crate::lp::objectives::{Breakdown, Objective};
+use crate::sa::charger::Charger;
+use crate::sa::config::general::GeneralConfig;
+use crate::sa::config::schedule::ScheduleConfig;
+use crate::sa::config::{ChargerConfig, Scale, TempConfig};
+use crate::sa::data::Data;
+use crate::sa::generators::Generator;
+use crate::sa::observers::{Fallback, SaObserver, TempStep};
+use crate::sa::route::Route;
+use crate::sa::stop::StopReason;
+use crate::sa::Results;
+use crate::util::rand_utils;
+
+//==============================================================================
+/// Acceptance and exchange statistics of a replica. The swap statistics count
+/// the exchanges with the next hotter replica.
+//
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ReplicaStats {
+    pub temp: f32,            // Temperature of the replica
+    pub candidates: usize,    // Number of candidate solutions
+    pub accepted: usize,      // Number of accepted candidate solutions
+    pub swap_attempts: usize, // Number of attempted exchanges with the next hotter replica
+    pub swaps: usize,         // Number of accepted exchanges with the next hotter replica
+}
+
+//==============================================================================
+/// State of a replica
+//
+#[derive(Clone)]
+struct Replica {
+    sol: Data,        // Current solution of the replica
+    charger: Charger, // Charge schedule of the current solution
+    j: f64,           // Objective score of the current solution
+}
+
+//==============================================================================
+/// Structure for parallel tempering (replica exchange)
+//
+pub struct PT {
+    gsol: Box<dyn Generator>,            // Solution generator
+    gsys: Box<dyn Route>,                // Route generator
+    gtweak: Box<dyn Generator>,          // Solution modifier
+    charger: Box<Charger>,               // Charge schedule keeper
+    objective: Box<dyn Objective>,       // Objective function
+    sched: ScheduleConfig,               // Schedule configuration
+    run_all_constr: bool,                // Run all the constraints in the objective function
+    seed: u64,                           // Seed of the random number generator
+    scale: f64,                          // Scale of the Metropolis acceptance criterion
+    rng: StdRng,                         // Random number generator of the run
+    observers: Vec<Box<dyn SaObserver>>, // Observers of the progress of the run
+}
+
+//==============================================================================
+/// Implementation of PT
+//
+impl PT {
+    //==========================================================================
+    // PUBLIC
+    //==========================================================================
+
+    //--------------------------------------------------------------------------
+    /// Initialize the PT object
+    ///
+    /// # Input
+    /// * `config_path`   : String of relative path to configuration file
+    /// * `schedule_path` : String of relative path to schedule file
+    /// * `gsol`          : Solution generator
+    /// * `gsys`          : Route generator
+    /// * `gtweak`        : Tweak schedule
+    /// * `seed`          : Seed of the random number generator
+    ///
+    /// # Output
+    /// * `PT`: Parallel tempering solver, or an error if a configuration file is
+    ///   invalid or the route could not be generated
+    ///
+    pub fn new(
+        config_path: &str,
+        schedule_path: &str,
+        gsol: Box<dyn Generator>,
+        gsys: Box<dyn Route>,
+        gtweak: Box<dyn Generator>,
+        seed: u64,
+    ) -> Result<PT, Error> {
+        // Load the configuration
+        let sched = ScheduleConfig::load(schedule_path)?;
+        let config = GeneralConfig::load(config_path)?;
+
+        return PT::with_config(&config, &sched, gsol, gsys, gtweak, seed);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Initialize the PT object from loaded configurations
+    ///
+    /// # Input
+    /// * `config` : General configuration
+    /// * `sched`  : Schedule configuration
+    /// * `gsol`   : Solution generator
+    /// * `gsys`   : Route generator
+    /// * `gtweak` : Tweak schedule
+    /// * `seed`   : Seed of the random number generator
+    ///
+    /// # Output
+    /// * `PT`: Parallel tempering solver, or an error if the route could not be generated
+    ///
+    pub fn with_config(
+        config: &GeneralConfig,
+        sched: &ScheduleConfig,
+        gsol: Box<dyn Generator>,
+        mut gsys: Box<dyn Route>,
+        gtweak: Box<dyn Generator>,
+        seed: u64,
+    ) -> Result<PT, Error> {
+        // Create the random number generator. Every random choice of the run is drawn from it.
+        let mut rng = rand_utils::seeded_rng(seed);
+
+        // Generate new solution
+        gsys.run(&mut rng)?;
+
+        // There is a wait charger for every bus
+        let chargers = ChargerConfig {
+            count: (
+                gsys.get_data().param.A,
+                sched.chargers.count.1,
+                sched.chargers.count.2,
+            ),
+            ..sched.chargers.clone()
+        };
+
+        // Create PT object
+        let pt: PT = PT {
+            gsol,
+            gsys,
+            gtweak,
+            charger: Box::new(Charger::from_config(&chargers, &sched.time)),
+            objective: Box::new(StdObj::new(&sched.weights)?),
+            sched: sched.clone(),
+            run_all_constr: config.run_all_constr,
+            seed,
+            rng,
+            scale: TempConfig::DEFAULT_SCALE,
+            observers: Vec::new(),
+        };
+
+        return Ok(pt);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Set the objective function of the run. By default the run minimizes the
+    /// standard objective function with the weights of the schedule
+    /// configuration.
+    ///
+    /// # Input
+    /// * objective: Objective function to minimize
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    pub fn set_objective(self: &mut PT, objective: Box<dyn Objective>) {
+        self.objective = objective;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Add an observer of the progress of the run. A round is reported as a
+    /// temperature step at the temperature of the coldest replica.
+    ///
+    /// # Input
+    /// * observer: Observer to notify of the events of the run
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    pub fn add_observer(self: &mut PT, observer: Box<dyn SaObserver>) {
+        self.observers.push(observer);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Run parallel tempering. Every replica runs `time.K` tweaks of the
+    /// local search at its temperature each round. Every `pt.interval` rounds
+    /// neighboring replicas exchange their solutions with probability
+    ///
+    /// $\min\left(1, e^{\left(\frac{1}{T_i} - \frac{1}{T_{i+1}}\right)\frac{J_i - J_{i+1}}{s}}\right)$
+    ///
+    /// where $s$ is the acceptance scale. The even and odd pairs of neighbors
+    /// take turns.
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * `Results`: Output of the PT algorithm, or an error if an evaluation
+    ///   failed. `improved` is false if the best solution did not
+    ///   change.
+    ///
+    pub fn run(self: &mut PT) -> Result<Results, Error> {
+        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        // Initialize
+
+        // Replica exchange parameters
+        let k = self.sched.time.k;
+        let run_all_constr = self.run_all_constr;
+        let n = self.sched.pt.replicas;
+        let rounds = self.sched.pt.rounds;
+        let interval = self.sched.pt.interval;
+        let temps = PT::ladder(self.sched.pt.t_min, self.sched.pt.t_max, n);
+
+        // Generate the initial solution, every replica starts from it
+        self.gsol
+            .run(&mut self.gsys, &mut self.charger, &mut self.rng)?;
+        let mut sol = *self.gsys.get_data();
+        let j = self
+            .objective
+            .run(&mut sol, &mut self.charger, run_all_constr)?;
+        let mut replicas: Vec<Replica> = vec![
+            Replica {
+                sol: sol.clone(),
+                charger: (*self.charger).clone(),
+                j,
+            };
+            n
+        ];
+
+        // Set the scale of the Metropolis acceptance criterion
+        self.scale = self.acceptance_scale(&replicas[0], run_all_constr)?;
+
+        let mut stats: Vec<ReplicaStats> = temps
+            .iter()
+            .map(|t| ReplicaStats {
+                temp: *t,
+                ..Default::default()
+            })
+            .collect();
+
+        // Initialize the best solution to the initial solution
+        let mut best: Replica = replicas[0].clone();
+        let JORIG: f64 = j;
+
+        // Initialize solution scores. The current score is the score of the coldest replica.
+        let mut sol_scores = sol.clone();
+        sol_scores.dec.Jb = vec![j];
+        sol_scores.dec.Jc = vec![j];
+        sol_scores.dec.Jn = vec![j];
+        let mut terms: Breakdown = self.objective.breakdown(&sol, &self.charger)?;
+        terms.record(&mut sol_scores.dec);
+
+        // Notify the observers of the start of the run
+        for o in self.observers.iter_mut() {
+            o.on_start(0, rounds, *temps.last().unwrap());
+        }
+
+        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        // Execute PT
+        let mut exchanges: usize = 0;
+        let mut j1: f64 = j;
+        for round in 0..rounds {
+            // Get starting time
+            let start = Instant::now();
+
+            // Count the candidate solutions and the accepted candidates of the round
+            let candidates: usize = stats.iter().map(|s| s.candidates).sum();
+            let accepted: usize = stats.iter().map(|s| s.accepted).sum();
+
+            // Run the local search of every replica
+            for (r, rep) in replicas.iter_mut().enumerate() {
+                for i in 0..k {
+                    if let Some(j) = self.tweak(rep, &mut stats[r], round, i, run_all_constr)? {
+                        // Keep the latest candidate score of the coldest replica
+                        if r == 0 {
+                            j1 = j;
+                        }
+
+                        // Update the best solution
+                        if best.j - rep.j > 0.0 {
+                            best = rep.clone();
+                            terms = self.objective.breakdown(&best.sol, &best.charger)?;
+                            for o in self.observers.iter_mut() {
+                                o.on_new_best(round, best.j, &best.sol);
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Exchange the solutions of neighboring replicas
+            if (round + 1).is_multiple_of(interval) {
+                self.exchange(&mut replicas, &mut stats, exchanges % 2);
+                exchanges += 1;
+            }
+
+            // Append the latest scores
+            sol_scores.dec.Jb.push(best.j);
+            sol_scores.dec.Jc.push(replicas[0].j);
+            sol_scores.dec.Jn.push(j1);
+            terms.record(&mut sol_scores.dec);
+
+            // Notify the observers of the round
+            let s = TempStep {
+                step: round,
+                steps: rounds,
+                t: temps[0],
+                elapsed: start.elapsed(),
+                candidates: stats.iter().map(|s| s.candidates).sum::<usize>() - candidates,
+                accepted: stats.iter().map(|s| s.accepted).sum::<usize>() - accepted,
+                jb: best.j,
+                jc: replicas[0].j,
+                jn: j1,
+                sol_best: &best.sol,
+                scores: &sol_scores,
+            };
+            for o in self.observers.iter_mut() {
+                o.on_temp_step(&s);
+            }
+        }
+
+        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        // Report results
+
+        // Attach the score history to the best solution. Replicas are not reheated and run `time.K` tweaks every
+        // round.
+        let mut sol_best = best.sol;
+        sol_best.dec.reheat = vec![0; sol_scores.dec.Jb.len()];
+        sol_best.dec.K = vec![k; sol_scores.dec.Jb.len()];
+        sol_best.dec.K[0] = 0;
+        sol_best.dec.Jb = sol_scores.dec.Jb;
+        sol_best.dec.Jc = sol_scores.dec.Jc;
+        sol_best.dec.Jn = sol_scores.dec.Jn;
+        sol_best.dec.terms = sol_scores.dec.terms;
+
+        return Ok(Results {
+            score: best.j,
+            terms,
+            data: Box::new(sol_best),
+            charger: Box::new(best.charger),
+            seed: self.seed,
+            scale: self.scale,
+            T0: *temps.last().unwrap(),
+            replicas: stats,
+            stop: StopReason::Completed,
+            improved: best.j != JORIG,
+        });
+    }
+
+    //==========================================================================
+    // PRIVATE
+    //==========================================================================
+
+    //--------------------------------------------------------------------------
+    /// Create a geometric ladder of `n` temperatures from `t_min` to `t_max`.
+    ///
+    /// # Input
+    /// * t_min: Temperature of the coldest replica
+    /// * t_max: Temperature of the hottest replica
+    /// * n: Number of replicas
+    ///
+    /// # Output
+    /// * temps: Temperatures of the replicas from coldest to hottest
+    ///
+    fn ladder(t_min: f32, t_max: f32, n: usize) -> Vec<f32> {
+        if n == 1 {
+            return vec![t_min];
+        }
+
+        let ratio = (t_max / t_min).powf(1.0 / (n - 1) as f32);
+        let mut temps: Vec<f32> = (0..n).map(|i| t_min * ratio.powi(i as i32)).collect();
+
+        // Avoid rounding at the hot end of the ladder
+        temps[n - 1] = t_max;
+
+        return temps;
+    }
+
+    //--------------------------------------------------------------------------
+    /// Determine the scale of the Metropolis acceptance criterion from
+    /// `temp.scale` of the schedule configuration. In "auto" mode the scale is
+    /// the mean uphill objective delta of a random walk of `temp.warmup` tweaks
+    /// from the initial solution, as in `SA`. Without an uphill move the
+    /// default scale is used and reported to the observers.
+    ///
+    /// # Input
+    /// * start: Replica of the initial solution
+    /// * run_all_constr: Run all the constraints in the objective function
+    ///
+    /// # Output
+    /// * scale: Scale of the Metropolis acceptance criterion
+    ///
+    fn acceptance_scale(
+        self: &mut PT,
+        start: &Replica,
+        run_all_constr: bool,
+    ) -> Result<f64, Error> {
+        // The scale is set to a fixed value
+        if let Scale::Fixed(s) = self.sched.temp.scale {
+            return Ok(s);
+        }
+
+        // Walk from the initial solution and keep the uphill moves, infeasible steps are skipped
+        let mut walk: Replica = start.clone();
+        let mut uphill: Vec<f64> = Vec::new();
+        for _ in 0..self.sched.temp.warmup {
+            self.gsys.set_data(Box::new(walk.sol.clone()));
+            let mut charger = walk.charger.clone();
+            if !self
+                .gtweak
+                .run(&mut self.gsys, &mut charger, &mut self.rng)?
+            {
+                continue;
+            }
+
+            let mut sol_new = *self.gsys.get_data();
+            let j = match Error::feasible(self.objective.run(
+                &mut sol_new,
+                &mut charger,
+                run_all_constr,
+            ))? {
+                Some(j) => j,
+                None => continue,
+            };
+
+            if j > walk.j {
+                uphill.push(j - walk.j);
+            }
+            charger.milp_to_schedule(&sol_new);
+            walk = Replica {
+                sol: sol_new,
+                charger,
+                j,
+            };
+        }
+
+        // If no uphill move was seen, fall back to the default and report it
+        if uphill.is_empty() {
+            let f = Fallback::Scale(TempConfig::DEFAULT_SCALE);
+            for o in self.observers.iter_mut() {
+                o.on_fallback(&f);
+            }
+            return Ok(TempConfig::DEFAULT_SCALE);
+        }
+
+        return Ok(uphill.iter().sum::<f64>() / uphill.len() as f64);
+    }
+
+    //--------------------------------------------------------------------------
+    /// Tweak the solution of a replica and keep the candidate according to the
+    /// Metropolis criterion at the temperature of the replica.
+    ///
+    /// # Input
+    /// * rep: Replica to tweak
+    /// * stats: Statistics of the replica
+    /// * round: Index of the round
+    /// * iteration: Index of the tweak within the round
+    /// * run_all_constr: Run all the constraints in the objective function
+    ///
+    /// # Output
+    /// * `Some(j)` with the candidate score if the schedule was modified into a
+    ///   feasible candidate, or an error if the tweak or the evaluation failed
+    ///
+    fn tweak(
+        self: &mut PT,
+        rep: &mut Replica,
+        stats: &mut ReplicaStats,
+        round: usize,
+        iteration: usize,
+        run_all_constr: bool,
+    ) -> Result<Option<f64>, Error> {
+        // Tweak a copy of the replica
+        self.gsys.set_data(Box::new(rep.sol.clone()));
+        let mut charger = rep.charger.clone();
+        if !self
+            .gtweak
+            .run(&mut self.gsys, &mut charger, &mut self.rng)?
+        {
+            return Ok(None);
+        }
+
+        // Calculate objective function, an infeasible candidate is discarded
+        let mut sol_new = *self.gsys.get_data();
+        let j = match Error::feasible(self.objective.run(
+            &mut sol_new,
+            &mut charger,
+            run_all_constr,
+        ))? {
+            Some(j) => j,
+            None => return Ok(None),
+        };
+        stats.candidates += 1;
+
+        // Keep candidates that pass the acceptance criterion
+        let accepted = self.cmp_obj_fnc(rep.j, j, stats.temp);
+        if accepted {
+            charger.milp_to_schedule(&sol_new);
+            rep.sol = sol_new;
+            rep.charger = charger;
+            rep.j = j;
+            stats.accepted += 1;
+        }
+
+        // Notify the observers of the candidate
+        for o in self.observers.iter_mut() {
+            o.on_iteration(round, iteration, stats.temp, j);
+            if accepted {
+                o.on_accept(round, j);
+            } else {
+                o.on_reject(round, j);
+            }
+        }
+
+        return Ok(Some(j));
+    }
+
+    //--------------------------------------------------------------------------
+    /// Attempt to exchange the solutions of the neighboring replicas
+    /// `(i, i+1)` for `i = parity, parity + 2, ...`.
+    ///
+    /// # Input
+    /// * replicas: Replicas from coldest to hottest
+    /// * stats: Statistics of the replicas
+    /// * parity: 0 to exchange the even pairs, 1 for the odd pairs
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    fn exchange(
+        self: &mut PT,
+        replicas: &mut [Replica],
+        stats: &mut [ReplicaStats],
+        parity: usize,
+    ) {
+        for i in (parity..replicas.len().saturating_sub(1)).step_by(2) {
+            let beta = 1.0 / stats[i].temp as f64 - 1.0 / stats[i + 1].temp as f64;
+            let coef = beta * (replicas[i].j - replicas[i + 1].j) / self.scale;
+            stats[i].swap_attempts += 1;
+
+            // Swap the solutions, the temperatures stay in place
+            if coef >= 0.0 || self.rng.gen_range(0.0..=1.0) <= coef.exp() {
+                replicas.swap(i, i + 1);
+                stats[i].swaps += 1;
+            }
+        }
+    }
+
+    //--------------------------------------------------------------------------
+    /// Compare objective functions and return the kept result.
+    ///
+    /// # Input
+    /// * j0: Previous objective function
+    /// * j1: New Objective function
+    /// * t : Temperature
+    ///
+    /// # Output
+    /// * true if the data has been changed to `j_1`, false otherwise
+    ///
+    fn cmp_obj_fnc(self: &mut PT, j0: f64, j1: f64, t: f32) -> bool {
+        let delta_e: f64 = j0 - j1;
+
+        // The new data has a smaller objective function value than the old
+        if delta_e > 0.0 {
+            return true;
+        }
+
+        // Otherwise keep the new data with probability `e^coef`
+        let coef: f64 = delta_e / (self.scale * t as f64);
+        return self.rng.gen_range(0.0..=1.0) <= coef.exp();
+    }
+}
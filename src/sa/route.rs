@@ -2,6 +2,7 @@
 
 // Public
 pub mod route_csv_generator;
+pub mod route_data; // Route of an in-memory data object
 pub mod route_event;
 pub mod route_rand_generator; // Keep public for testing
 
@@ -9,9 +10,11 @@ pub mod route_rand_generator; // Keep public for testing
 mod bus;
 
 // Public imports
+use rand::rngs::StdRng;
 use std::boxed::Box;
 
 // Developed imports
+use crate::error::Error;
 use crate::sa::data::Data;
 use crate::sa::route::route_event::RouteEvent; // Keep public for testing
 
@@ -19,7 +22,7 @@ use crate::sa::route::route_event::RouteEvent; // Keep public for testing
 /// Trait to define `Route` interfaces
 //
 pub trait Route {
-    fn run(&mut self);
+    fn run(&mut self, rng: &mut StdRng) -> Result<(), Error>;
 
     // Getters
     fn get_route_events(&mut self) -> Box<&mut Vec<RouteEvent>>;
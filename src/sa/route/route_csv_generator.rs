@@ -7,18 +7,21 @@ pub mod parse_routes;
 //===============================================================================
 // Standard library
 use csv;
+use rand::rngs::StdRng;
 use std::boxed::Box;
 use std::collections::HashMap;
-use yaml_rust::Yaml;
 
 //===============================================================================
 // Import modules
+use crate::error::Error;
+use crate::sa::config::general::GeneralConfig;
+use crate::sa::config::schedule::ScheduleConfig;
+use crate::sa::config::ConfigError;
 use crate::sa::data::Data;
 use crate::sa::route::bus::Bus;
 use crate::sa::route::route_event::RouteEvent;
 use crate::sa::route::Route;
 use crate::util::array_util::arry_util::{first, last};
-use crate::util::fileio::yaml_loader;
 
 //===============================================================================
 // Import modules
@@ -33,8 +36,9 @@ pub struct RouteCSVGenerator {
     pub route: Vec<RouteEvent>,
 
     // PRIVATE
-    g_config: Yaml,
-    s_config: Yaml,
+    g_config: GeneralConfig,
+    s_config: ScheduleConfig,
+    csv_path: String,
     csv_h: csv::Reader<std::fs::File>,
 }
 
@@ -53,21 +57,59 @@ impl RouteCSVGenerator {
     /// * `csv_path`      : Path to CSV file
     ///
     /// # Output
-    /// * `RouteCSVGenerator`
+    /// * `RouteCSVGenerator`, or an error if a configuration file is invalid or
+    ///   the CSV file could not be opened
+    ///
+    pub fn new(
+        schedule_path: &str,
+        general_path: &str,
+        csv_path: &str,
+    ) -> Result<RouteCSVGenerator, Error> {
+        let s_config = ScheduleConfig::load(schedule_path)?;
+        let g_config = GeneralConfig::load(general_path)?;
+
+        return RouteCSVGenerator::from_config(&g_config, &s_config, csv_path);
+    }
+
+    //---------------------------------------------------------------------------
+    /// Constructor that returns a CSV schedule generator from loaded
+    /// configurations
     ///
-    pub fn new(schedule_path: &str, general_path: &str, csv_path: &str) -> RouteCSVGenerator {
+    /// # Input
+    /// * `g_config` : General configuration
+    /// * `s_config` : Schedule configuration
+    /// * `csv_path` : Path to CSV file
+    ///
+    /// # Output
+    /// * `RouteCSVGenerator`, or an error if the CSV file could not be opened
+    ///
+    pub fn from_config(
+        g_config: &GeneralConfig,
+        s_config: &ScheduleConfig,
+        csv_path: &str,
+    ) -> Result<RouteCSVGenerator, Error> {
+        // Open the CSV file
+        let csv_h = parse_routes::read_csv(csv_path).map_err(|e| {
+            Error::Config(ConfigError {
+                file: csv_path.to_string(),
+                key: String::new(),
+                message: e,
+            })
+        })?;
+
         // Create new RouteGenerator
         let rg = RouteCSVGenerator {
             csv_schedule: (Vec::new(), Vec::new()),
             data: Default::default(),
             route: Vec::new(),
-            g_config: yaml_loader::load_yaml(general_path),
-            s_config: yaml_loader::load_yaml(schedule_path),
-            csv_h: parse_routes::read_csv(csv_path),
+            g_config: g_config.clone(),
+            s_config: s_config.clone(),
+            csv_path: csv_path.to_string(),
+            csv_h,
         };
 
         // Return Route Generator
-        return rg;
+        return Ok(rg);
     }
 
     //---------------------------------------------------------------------------
@@ -138,14 +180,14 @@ impl RouteCSVGenerator {
     fn buffer_input_parameters(self: &mut RouteCSVGenerator, visits: &HashMap<u16, Vec<Vec<f32>>>) {
         // Misc Variables
         let csv: &(Vec<u16>, Vec<Vec<f32>>) = &self.csv_schedule;
-        let bod: f32 = self.s_config["time"]["BOD"].as_f64().unwrap() as f32;
-        let eod: f32 = self.s_config["time"]["EOD"].as_f64().unwrap() as f32;
+        let bod: f32 = self.s_config.time.bod;
+        let eod: f32 = self.s_config.time.eod;
 
         // Constants
         self.data.param.A = csv.0.len();
         self.data.param.N = self.count_visits(visits);
         self.data.param.T = eod - bod;
-        self.data.param.K = self.s_config["time"]["K"].as_i64().unwrap() as u16;
+        self.data.param.K = self.s_config.time.k as u16;
         self.data.param.S = 1;
         self.data.param.ts = 0.001;
 
@@ -171,8 +213,7 @@ impl RouteCSVGenerator {
         self.data.param.dt = T / K as f32;
 
         // Battery capacity
-        self.data.param.k =
-            [self.s_config["buses"]["bat_capacity"].as_f64().unwrap() as f32].repeat(N);
+        self.data.param.k = [self.s_config.buses.bat_capacity].repeat(N);
 
         // Create assignment cost
         let Q = self.data.param.Q;
@@ -180,11 +221,17 @@ impl RouteCSVGenerator {
         let mut charge_queue: Vec<f32> = (0..(Q - A)).map(|x| 1000.0 * (x as f32 + 1.0)).collect();
         self.data.param.ep.append(&mut charge_queue);
 
-        self.data.param.nu = self.s_config["buses"]["min_charge"].as_f64().unwrap() as f32;
-        self.data.param.D = [self.s_config["buses"]["dis_rate"].as_f64().unwrap() as f32].repeat(A);
+        self.data.param.nu = self.s_config.buses.min_charge;
+        self.data.param.tariff = self.s_config.tariff.tariff(bod, eod);
+        self.data.param.demand = self.s_config.demand.clone();
+        self.data.param.grid = self.s_config.grid;
+        self.data.param.deg = self.s_config.degradation.clone();
+        if self.data.param.deg.cost.len() == 1 {
+            self.data.param.deg.cost = self.data.param.deg.cost.repeat(A);
+        }
+        self.data.param.D = [self.s_config.buses.dis_rate].repeat(A);
 
-        self.data.param.zeta =
-            [self.s_config["buses"]["dis_rate"].as_f64().unwrap() as f32].repeat(A);
+        self.data.param.zeta = [self.s_config.buses.dis_rate].repeat(A);
     }
 
     //---------------------------------------------------------------------------
@@ -200,22 +247,18 @@ impl RouteCSVGenerator {
     ///
     fn create_charge_rate_vector(self: &mut RouteCSVGenerator) -> Vec<f32> {
         // Set the model type
-        self.data.param.model = self.g_config["bat_model"].as_str().unwrap().to_string();
+        self.data.param.model = self.g_config.bat_model.clone();
 
         // Create parts of charge rate vector
         let wait_c: Vec<f32> = vec![0.0; self.data.param.A];
-        let slow_c = [self.s_config["chargers"]["slow"]["rate"].as_f64().unwrap() as f32]
-            .repeat(self.s_config["chargers"]["slow"]["num"].as_i64().unwrap() as usize);
-        let fast_c = [self.s_config["chargers"]["fast"]["rate"].as_f64().unwrap() as f32]
-            .repeat(self.s_config["chargers"]["fast"]["num"].as_i64().unwrap() as usize);
+        let slow_c = [self.s_config.chargers.speed.1].repeat(self.s_config.chargers.count.1);
+        let fast_c = [self.s_config.chargers.speed.2].repeat(self.s_config.chargers.count.2);
 
         // Otherwise the system us utilizing the non-linear model
         if self.data.param.model == "nonlinear" {
             // Get the convergence rates
-            let slow_conv = [self.s_config["chargers"]["slow"]["conv"].as_f64().unwrap() as f32]
-                .repeat(self.s_config["chargers"]["slow"]["num"].as_i64().unwrap() as usize);
-            let fast_conv = [self.s_config["chargers"]["fast"]["conv"].as_f64().unwrap() as f32]
-                .repeat(self.s_config["chargers"]["fast"]["num"].as_i64().unwrap() as usize);
+            let slow_conv = [self.s_config.chargers.conv.1].repeat(self.s_config.chargers.count.1);
+            let fast_conv = [self.s_config.chargers.conv.2].repeat(self.s_config.chargers.count.2);
 
             self.data.param.conv = vec![wait_c.clone(), slow_conv, fast_conv].concat();
         }
@@ -293,8 +336,8 @@ impl RouteCSVGenerator {
     ///
     fn convert_route_to_visit(self: &RouteCSVGenerator) -> HashMap<u16, Vec<Vec<f32>>> {
         // Variables
-        let bod: f32 = self.s_config["time"]["BOD"].as_f64().unwrap() as f32;
-        let eod: f32 = self.s_config["time"]["EOD"].as_f64().unwrap() as f32;
+        let bod: f32 = self.s_config.time.bod;
+        let eod: f32 = self.s_config.time.eod;
         let mut route_visit: HashMap<u16, Vec<Vec<f32>>> = HashMap::new();
 
         // Generate set of visit/departures
@@ -358,7 +401,7 @@ impl RouteCSVGenerator {
     ///
     fn calc_discharge(self: &RouteCSVGenerator) -> HashMap<usize, Vec<f32>> {
         let mut discharge: HashMap<usize, Vec<f32>> = HashMap::new();
-        let eod: f32 = self.s_config["time"]["EOD"].as_f64().unwrap() as f32;
+        let eod: f32 = self.s_config.time.eod;
         let routes = &self.csv_schedule;
 
         // For each set of routes for bus b
@@ -404,14 +447,16 @@ impl RouteCSVGenerator {
         // Allocate route buffer space
         let mut route: Vec<RouteEvent> = Vec::new();
 
-        // Loop through each visit/discharge
-        for it in visit.into_iter() {
-            // Extract visit and discharge
-            let vis = it;
+        // Sort the bus IDs. The hash map iteration order is random and visits with equal arrival times would
+        // otherwise be ordered differently between runs.
+        let mut ids: Vec<&u16> = visit.keys().collect();
+        ids.sort();
 
+        // Loop through each visit/discharge
+        for id in ids.into_iter() {
             // Extract the bus ID and visit
-            let b: usize = *vis.0 as usize;
-            let vis: &Vec<Vec<f32>> = vis.1;
+            let b: usize = *id as usize;
+            let vis: &Vec<Vec<f32>> = &visit[id];
 
             // Loop through each start/stop pair
             for it in vis.into_iter().zip(&discharge[&b]) {
@@ -465,15 +510,15 @@ impl RouteCSVGenerator {
     /// * Bus: Information about bus b
     ///
     fn gen_bus(self: &RouteCSVGenerator) -> Bus {
-        let bat_capacity = self.s_config["buses"]["bat_capacity"].as_f64().unwrap() as f32;
-        let alpha = self.s_config["initial_charge"]["max"].as_f64().unwrap() as f32;
-        let beta = self.s_config["final_charge"].as_f64().unwrap() as f32;
+        let bat_capacity = self.s_config.buses.bat_capacity;
+        let alpha = self.s_config.initial_charge.max;
+        let beta = self.s_config.final_charge;
 
         return Bus {
             bat_capacity,
             initial_charge: alpha * bat_capacity,
             final_charge: beta * bat_capacity,
-            discharge_rate: self.s_config["buses"]["dis_rate"].as_f64().unwrap() as f32,
+            discharge_rate: self.s_config.buses.dis_rate,
         };
     }
 
@@ -579,10 +624,7 @@ impl RouteCSVGenerator {
     ///
     fn determine_initial_charges(self: &mut RouteCSVGenerator) {
         // Local variables
-        let init_charge = self.s_config["initial_charge"]["max"]
-            .clone()
-            .into_f64()
-            .unwrap() as f32;
+        let init_charge = self.s_config.initial_charge.max;
         let Gam = &self.data.param.Gam;
 
         // Loop through each BEB
@@ -611,7 +653,7 @@ impl RouteCSVGenerator {
     ///
     fn determine_final_charges(self: &mut RouteCSVGenerator) {
         // Local variables
-        let final_charge = self.s_config["final_charge"].clone().into_f64().unwrap() as f32;
+        let final_charge = self.s_config.final_charge;
         let gam = &self.data.param.gam;
         let beta = &mut self.data.param.beta;
 
@@ -693,14 +735,30 @@ impl Route for RouteCSVGenerator {
     /// Generate or load route
     ///
     /// # Input
-    /// * NONE
+    /// * _rng: Random number generator (unused, the routes are read from the CSV)
     ///
     /// # Output
-    /// * `route_schedule`: The routes that the buses must adhere to
+    /// * `route_schedule`: The routes that the buses must adhere to, or an error if the CSV is malformed
+    ///   or every route is ignored
     ///
-    fn run(self: &mut RouteCSVGenerator) {
+    fn run(self: &mut RouteCSVGenerator, _rng: &mut StdRng) -> Result<(), Error> {
         // Parse CSV
-        self.csv_schedule = parse_routes::parse_csv(&mut self.csv_h, &self.s_config);
+        self.csv_schedule = parse_routes::parse_csv(&mut self.csv_h, &self.s_config.ignore)
+            .map_err(|e| {
+                Error::Config(ConfigError {
+                    file: self.csv_path.clone(),
+                    key: String::new(),
+                    message: e,
+                })
+            })?;
+
+        // A schedule needs at least one route
+        if self.csv_schedule.0.is_empty() {
+            return Err(Error::Infeasible(format!(
+                "{}: No routes remain after ignoring {:?}.",
+                self.csv_path, self.s_config.ignore
+            )));
+        }
 
         // Convert routes to visits
         let visits = self.convert_route_to_visit();
@@ -719,6 +777,8 @@ impl Route for RouteCSVGenerator {
 
         // Generate schedule parameters
         self.generate_schedule_params();
+
+        return Ok(());
     }
 
     //---------------------------------------------------------------------------
@@ -808,6 +868,7 @@ mod priv_test_route_gen {
     //==========================================================================
     // Import modules
     use super::{Route, RouteCSVGenerator, RouteEvent};
+    use crate::util::rand_utils;
 
     //--------------------------------------------------------------------------
     //
@@ -816,7 +877,8 @@ mod priv_test_route_gen {
             "./src/config/schedule-test.yaml",
             "./src/config/general.yaml",
             "./src/config/routes.csv",
-        );
+        )
+        .unwrap();
     }
 
     //--------------------------------------------------------------------------
@@ -827,7 +889,7 @@ mod priv_test_route_gen {
         let mut rg: RouteCSVGenerator = create_object();
 
         // Run the generator
-        rg.run();
+        rg.run(&mut rand_utils::seeded_rng(0)).unwrap();
 
         // Get the route visits
         let route = rg.convert_route_to_visit();
@@ -887,7 +949,7 @@ mod priv_test_route_gen {
         let mut rg: RouteCSVGenerator = create_object();
 
         // Run the generator
-        rg.run();
+        rg.run(&mut rand_utils::seeded_rng(0)).unwrap();
 
         // Get the route visits
         let _route = rg.convert_route_to_visit();
@@ -930,7 +992,7 @@ mod priv_test_route_gen {
         let mut rg: RouteCSVGenerator = create_object();
 
         // Run the generator
-        rg.run();
+        rg.run(&mut rand_utils::seeded_rng(0)).unwrap();
 
         // Get the route visits
         let visit = rg.convert_route_to_visit();
@@ -970,7 +1032,7 @@ mod priv_test_route_gen {
         let mut rg: RouteCSVGenerator = create_object();
 
         // Run the generator
-        rg.run();
+        rg.run(&mut rand_utils::seeded_rng(0)).unwrap();
 
         // Get the visit identifiers
         let Gam = rg.data.param.Gam.clone();
@@ -993,7 +1055,7 @@ mod priv_test_route_gen {
         let mut rg: RouteCSVGenerator = create_object();
 
         // Run the generator
-        rg.run();
+        rg.run(&mut rand_utils::seeded_rng(0)).unwrap();
 
         // Get the initial visit and the next visit indices
         let Gam = rg.data.param.Gam.clone();
@@ -1022,7 +1084,7 @@ mod priv_test_route_gen {
         let mut rg: RouteCSVGenerator = create_object();
 
         // Run the generator
-        rg.run();
+        rg.run(&mut rand_utils::seeded_rng(0)).unwrap();
 
         // Get the charge percentage and the battery capacity
         let alpha = rg.data.param.alpha.clone();
@@ -1062,7 +1124,7 @@ mod priv_test_route_gen {
         let mut rg: RouteCSVGenerator = create_object();
 
         // Run the generator
-        rg.run();
+        rg.run(&mut rand_utils::seeded_rng(0)).unwrap();
 
         // Get the charge percentage and the battery capacity
         let beta = rg.data.param.beta.clone();
@@ -1102,7 +1164,7 @@ mod priv_test_route_gen {
         let mut rg: RouteCSVGenerator = create_object();
 
         // Run the generator
-        rg.run();
+        rg.run(&mut rand_utils::seeded_rng(0)).unwrap();
 
         // Loop through each visit
         for i in 0..rg.data.param.a.len() {
@@ -1122,7 +1184,7 @@ mod priv_test_route_gen {
         let mut rg: RouteCSVGenerator = create_object();
 
         // Run the generator
-        rg.run();
+        rg.run(&mut rand_utils::seeded_rng(0)).unwrap();
 
         // Loop through each visit
         for i in 0..rg.data.param.a.len() {
@@ -1142,7 +1204,7 @@ mod priv_test_route_gen {
         let mut rg: RouteCSVGenerator = create_object();
 
         // Run the generator
-        rg.run();
+        rg.run(&mut rand_utils::seeded_rng(0)).unwrap();
 
         // Loop through each visit
         for i in 0..rg.data.param.a.len() {
@@ -1162,7 +1224,7 @@ mod priv_test_route_gen {
         let mut rg: RouteCSVGenerator = create_object();
 
         // Run the generator
-        rg.run();
+        rg.run(&mut rand_utils::seeded_rng(0)).unwrap();
 
         // Loop through each visit
         for i in 0..rg.data.param.N {
@@ -1,7 +1,6 @@
 //=========================================================================
 // Import Crates
 use csv::ReaderBuilder;
-use yaml_rust::Yaml;
 
 //===============================================================================
 // External Crates
@@ -15,18 +14,15 @@ use yaml_rust::Yaml;
 /// * `csv_path`: Path to CSV file
 ///
 /// # Output
-/// * `csv::Reader`
+/// * `csv::Reader`, or a description of the error if the file could not be opened
 ///
-pub fn read_csv(csv_path: &str) -> csv::Reader<std::fs::File> {
-    match ReaderBuilder::new()
+pub fn read_csv(csv_path: &str) -> Result<csv::Reader<std::fs::File>, String> {
+    return ReaderBuilder::new()
         .flexible(true)
         .has_headers(true)
         .delimiter(b',')
         .from_path(csv_path)
-    {
-        Ok(reader) => return reader,
-        Err(e) => panic!("{:?}", e),
-    };
+        .map_err(|e| e.to_string());
 }
 
 //---------------------------------------------------------------------------
@@ -34,26 +30,22 @@ pub fn read_csv(csv_path: &str) -> csv::Reader<std::fs::File> {
 ///
 /// # Input
 /// * `csv_h`: File handler for the CSV file
+/// * `ignore`: IDs of the routes to ignore
 ///
 /// # Output
-/// * `routes`: Tuple of that contains the vector of bus IDs and vector of routes
+/// * `routes`: Tuple of that contains the vector of bus IDs and vector of routes, or a description of
+///   the first malformed row
 ///
 pub fn parse_csv(
     csv_h: &mut csv::Reader<std::fs::File>,
-    config: &Yaml,
-) -> (Vec<u16>, Vec<Vec<f32>>) {
+    ignore: &[usize],
+) -> Result<(Vec<u16>, Vec<Vec<f32>>), String> {
     // Seconds to hour
     let hr2sec: f32 = 3600.0;
 
     // Stores the route data
     let mut routes: (Vec<u16>, Vec<Vec<f32>>) = (Vec::new(), Vec::from(Vec::new()));
     let mut b: u16 = 0;
-    let ignore: Vec<usize> = config["ignore"]
-        .as_vec()
-        .unwrap()
-        .iter()
-        .map(|x| x.as_i64().unwrap() as usize)
-        .collect();
 
     // Loop through each row in the CSV file
     for result in csv_h.records() {
@@ -63,11 +55,19 @@ pub fn parse_csv(
         // Unpack the row if possible
         let r = match result {
             Ok(r) => r,
-            Err(e) => panic!("{:?}", e),
+            Err(e) => return Err(e.to_string()),
         };
 
         // Check if the ID is in the ignore list
-        let id: u16 = r[0].parse::<u16>().unwrap();
+        let id: u16 = match r.get(0).map(|s| s.trim().parse::<u16>()) {
+            Some(Ok(id)) => id,
+            _ => {
+                return Err(format!(
+                    "Row {} does not start with a route ID.",
+                    routes.0.len() + 1
+                ))
+            }
+        };
 
         // If the id is in the ignore list, don't include the route data
         if ignore.contains(&(id as usize)) {
@@ -81,7 +81,10 @@ pub fn parse_csv(
         // Append the routes, skip the first element (queue index)
         for s in r.iter().skip(1) {
             // Convert the jth variable to float and convert to hours
-            let f: f32 = s.trim().parse::<f32>().unwrap() / hr2sec;
+            let f: f32 = match s.trim().parse::<f32>() {
+                Ok(f) => f / hr2sec,
+                Err(_) => return Err(format!("Route {} has an invalid time '{}'.", id, s)),
+            };
 
             // Append the float to the ith route vector
             route_i.push(f);
@@ -91,7 +94,7 @@ pub fn parse_csv(
         routes.1.push(route_i);
     }
 
-    return routes;
+    return Ok(routes);
 }
 
 //===========================================================================
@@ -0,0 +1,236 @@
+#![allow(non_snake_case)]
+
+//===============================================================================
+// Standard library
+use rand::rngs::StdRng;
+use std::boxed::Box;
+
+//===============================================================================
+// Import modules
+use crate::error::Error;
+use crate::sa::data::{Data, DecisionVar};
+use crate::sa::route::bus::Bus;
+use crate::sa::route::route_event::RouteEvent;
+use crate::sa::route::Route;
+
+//===============================================================================
+/// Route of an in-memory `Data` object. The parameters of the data define the
+/// visits, nothing is read from the filesystem. Running the route clears the
+/// decision variables, so the solver starts from an unassigned schedule.
+//
+pub struct RouteData {
+    // PUBLIC
+    pub data: Data,
+    pub route: Vec<RouteEvent>,
+}
+
+//===============================================================================
+/// Implementation of RouteData
+//
+impl RouteData {
+    //===========================================================================
+    // PUBLIC
+
+    //---------------------------------------------------------------------------
+    /// Constructor that returns a route of the given data
+    ///
+    /// # Input
+    /// * `data` : Data object, only the parameters are used
+    ///
+    /// # Output
+    /// * `RouteData`
+    ///
+    pub fn new(data: Data) -> RouteData {
+        return RouteData {
+            data,
+            route: Vec::new(),
+        };
+    }
+
+    //===========================================================================
+    // PRIVATE
+
+    //---------------------------------------------------------------------------
+    /// Allocate the decision variables and set the initial charge of the first
+    /// visit of every bus.
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    fn buffer_decision_variables(self: &mut RouteData) {
+        // Variables
+        let Q = self.data.param.Q;
+        let N = self.data.param.N;
+
+        // Generate decision variable buffers
+        self.data.dec = DecisionVar {
+            u: vec![0.0; N],
+            v: vec![0; N],
+            d: vec![0.0; N],
+            s: vec![0.0; N],
+            g: vec![vec![0.0; N]; Q],
+            eta: vec![0.0; N],
+            w: vec![vec![false; Q]; N],
+            sigma: vec![vec![true; N]; N],
+            psi: vec![vec![true; N]; N],
+            ..Default::default()
+        };
+
+        // Assign the initial charges
+        for i in 0..N {
+            let alpha = self.data.param.alpha[i];
+            if alpha > 0.0 {
+                self.data.dec.eta[i] = alpha * self.data.param.k[self.data.param.Gam[i] as usize];
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    /// Generate information about the bus of visit `i` from the parameters.
+    ///
+    /// # Input
+    /// * `i` : Visit index of the bus
+    ///
+    /// # Output
+    /// * Bus: Information about the bus of visit `i`
+    ///
+    fn gen_bus(self: &RouteData, i: usize) -> Bus {
+        let b = self.data.param.Gam[i] as usize;
+        let bat_capacity = self.data.param.k[b];
+
+        return Bus {
+            bat_capacity,
+            initial_charge: self.data.param.alpha[i] * bat_capacity,
+            final_charge: self.data.param.beta[i] * bat_capacity,
+            discharge_rate: self.data.param.zeta.get(b).copied().unwrap_or(0.0),
+        };
+    }
+}
+
+//===============================================================================
+//
+impl Route for RouteData {
+    //---------------------------------------------------------------------------
+    /// Reset the decision variables and create the route events of the data
+    ///
+    /// # Input
+    /// * _rng: Random number generator (unused, the route is given)
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    fn run(self: &mut RouteData, _rng: &mut StdRng) -> Result<(), Error> {
+        self.buffer_decision_variables();
+        self.update_route_events();
+
+        return Ok(());
+    }
+
+    //---------------------------------------------------------------------------
+    /// Return the route data
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * `route`: Vector of route data
+    ///
+    fn get_route_events(self: &mut RouteData) -> Box<&mut Vec<RouteEvent>> {
+        return Box::new(&mut self.route);
+    }
+
+    //---------------------------------------------------------------------------
+    /// Return the data object
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * `data`: Data object
+    ///
+    fn get_data(self: &mut RouteData) -> Box<Data> {
+        return Box::new(self.data.clone());
+    }
+
+    //---------------------------------------------------------------------------
+    /// Update the route data
+    ///
+    /// # Input
+    /// * `route: Vector of route data
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    fn set_route_events(self: &mut RouteData, r: Box<&mut Vec<RouteEvent>>) {
+        self.route = r.clone();
+    }
+
+    //---------------------------------------------------------------------------
+    /// Update the data object
+    ///
+    /// # Input
+    /// * `data`: Data object
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    fn set_data(self: &mut RouteData, dat: Box<Data>) {
+        self.data = *dat;
+    }
+
+    //---------------------------------------------------------------------------
+    /// Update the route events based on the data object
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    fn update_route_events(self: &mut RouteData) {
+        self.route = (0..self.data.param.N)
+            .map(|i| {
+                let p = &self.data.param;
+                let gam = p.gam[i];
+
+                RouteEvent {
+                    visit: i,
+                    arrival_time: p.a[i],
+                    bus: self.gen_bus(i),
+                    departure_time: p.e[i],
+                    discharge: p.l[i],
+                    id: p.Gam[i],
+                    route_time: if gam >= 0 {
+                        p.e[i] - p.a[gam as usize]
+                    } else {
+                        0.0
+                    },
+                    attach_time: self.data.dec.u[i],
+                    detach_time: self.data.dec.d[i],
+                    queue: self.data.dec.v[i] as u16,
+                }
+            })
+            .collect();
+    }
+
+    //---------------------------------------------------------------------------
+    /// Update the MILP data based on the route events object. The parameters
+    /// are fixed, only the decision variables are updated.
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    fn update_milp_data(self: &mut RouteData) {
+        for (i, r) in self.route.iter().enumerate() {
+            self.data.dec.u[i] = r.attach_time;
+            self.data.dec.d[i] = r.detach_time;
+            self.data.dec.v[i] = r.queue as usize;
+        }
+    }
+}
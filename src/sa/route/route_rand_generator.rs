@@ -10,15 +10,16 @@ extern crate yaml_rust;
 //===============================================================================
 // Import Crates
 use crate::sa::data::Data;
+use rand::rngs::StdRng;
 pub use std::boxed::Box;
-use yaml_rust::Yaml;
 
 //===============================================================================
 // Import modules
+use crate::error::Error;
+use crate::sa::config::schedule::ScheduleConfig;
 use crate::sa::route::bus::Bus;
 pub use crate::sa::route::route_event::RouteEvent; // Keep public for testing
 use crate::sa::route::Route;
-use crate::util::fileio::yaml_loader;
 use crate::util::rand_utils;
 
 //===============================================================================
@@ -32,7 +33,7 @@ pub struct RouteRandGenerator {
     pub buses: Vec<Bus>,
 
     // PRIVATE
-    config: Yaml,
+    config: ScheduleConfig,
     load_from_file: bool,
 }
 
@@ -50,16 +51,32 @@ impl RouteRandGenerator {
     /// * `config_path`   : Path to YAML schedule config
     ///
     /// # Output
+    /// * `ScheduleGenerator`, or an error if the configuration file is invalid
+    ///
+    pub fn new(load_from_file: bool, config_path: &str) -> Result<RouteRandGenerator, Error> {
+        let config = ScheduleConfig::load(config_path)?;
+
+        return Ok(RouteRandGenerator::from_config(load_from_file, &config));
+    }
+
+    //---------------------------------------------------------------------------
+    /// Returns a schedule generator from a loaded schedule configuration
+    ///
+    /// # Input
+    /// * `load_from_file`: Boolean that indicates to load previous schedule from file
+    /// * `config`        : Schedule configuration
+    ///
+    /// # Output
     /// * `ScheduleGenerator`
     ///
-    pub fn new(load_from_file: bool, config_path: &str) -> RouteRandGenerator {
+    pub fn from_config(load_from_file: bool, config: &ScheduleConfig) -> RouteRandGenerator {
         // Create new RouteGenerator
         let rg = RouteRandGenerator {
             route: Box::new(Vec::new()),
             data: Box::new(Default::default()),
             buses: Vec::new(),
 
-            config: yaml_loader::load_yaml(config_path),
+            config: config.clone(),
             load_from_file,
         };
 
@@ -99,8 +116,8 @@ impl RouteRandGenerator {
     ///
     fn create_buffers(self: &mut RouteRandGenerator) {
         // Variables
-        let num_bus: usize = self.config["buses"]["num_bus"].as_i64().unwrap() as usize;
-        let visits: usize = self.config["buses"]["num_visit"].as_i64().unwrap() as usize;
+        let num_bus: usize = self.config.buses.num_bus;
+        let visits: usize = self.config.buses.num_visit;
 
         // Reserve memory for all buses
         self.buses.resize(num_bus, Bus::default());
@@ -113,24 +130,24 @@ impl RouteRandGenerator {
     /// Generates the `Route` structure data and populates it
     ///
     /// # Input
-    /// * `config`: Parsed route YAML config file
+    /// * `rng`: Random number generator
     ///
     /// # Output
     /// * `route_schedule`: The routes that the buses must adhere to
     ///
-    fn generate_routes(self: &mut RouteRandGenerator) {
+    fn generate_routes(self: &mut RouteRandGenerator, rng: &mut StdRng) {
         // Variables
-        let num_bus: u16 = self.config["buses"]["num_bus"].as_i64().unwrap() as u16;
-        let num_visit: u16 = self.config["buses"]["num_visit"].as_i64().unwrap() as u16;
+        let num_bus: u16 = self.config.buses.num_bus as u16;
+        let num_visit: u16 = self.config.buses.num_visit as u16;
         let mut route_idx: u16 = 0;
 
         // Generate number of routes (events) for each bus
-        let route_count: Vec<u16> = rand_utils::rand_route_count(num_bus, num_visit);
+        let route_count: Vec<u16> = rand_utils::rand_route_count(num_bus, num_visit, rng);
 
         // Loop through each bus
         for id in 0..num_bus {
             // Create event
-            self.create_events(id, route_count[id as usize], route_idx);
+            self.create_events(id, route_count[id as usize], route_idx, rng);
 
             // Update route index. Minus one to make zero indexed
             route_idx += route_count[id as usize];
@@ -147,11 +164,18 @@ impl RouteRandGenerator {
     /// * `id`        : ID of bus being attended to
     /// * `event_cnt` : Number of events by bus `id`
     /// * `route_idx` : Index to start appending to in route vector
+    /// * `rng`       : Random number generator
     ///
     /// # Output
     /// * NONE
     ///
-    fn create_events(self: &mut RouteRandGenerator, id: u16, event_cnt: u16, route_idx: u16) {
+    fn create_events(
+        self: &mut RouteRandGenerator,
+        id: u16,
+        event_cnt: u16,
+        route_idx: u16,
+        rng: &mut StdRng,
+    ) {
         // Variables
         let mut arrival_new: f32 = 0.0; /* Arrival time of next visit [hr]     */
         let mut arrival_old: f32; /* Arrival time of previous visit [hr] */
@@ -170,7 +194,7 @@ impl RouteRandGenerator {
             let final_visit: bool = if j == event_cnt { true } else { false };
 
             // Select departure time (based off old arrival)
-            depart = self.next_depart(arrival_old, final_visit);
+            depart = self.next_depart(arrival_old, final_visit, rng);
 
             // Select new arrival time
             arrival_new = self.next_arrival(j, event_cnt);
@@ -191,23 +215,29 @@ impl RouteRandGenerator {
     /// # Input
     /// * `arrival`     : Arrival time for bus
     /// * `final_visit` : Flag to indicate bus's last visit
+    /// * `rng`         : Random number generator
     ///
     /// # Output
     /// * `depart` : Departure time
     ///
-    fn next_depart(self: &mut RouteRandGenerator, arrival: f32, final_visit: bool) -> f32 {
+    fn next_depart(
+        self: &mut RouteRandGenerator,
+        arrival: f32,
+        final_visit: bool,
+        rng: &mut StdRng,
+    ) -> f32 {
         // Variables
         let depart: f32;
 
         if final_visit {
             // Set the final departure time as the time horizon
-            depart = self.config["time"]["EOD"].as_f64().unwrap() as f32;
+            depart = self.config.time.eod;
         } else {
-            let min_rest: f32 = self.config["buses"]["min_rest"].as_f64().unwrap() as f32;
-            let max_rest: f32 = self.config["buses"]["max_rest"].as_f64().unwrap() as f32;
+            let min_rest: f32 = self.config.buses.min_rest;
+            let max_rest: f32 = self.config.buses.max_rest;
 
             // Randomly select a value between min_rest and max_rest
-            depart = arrival + rand_utils::rand_range(min_rest, max_rest);
+            depart = arrival + rand_utils::rand_range(min_rest, max_rest, rng);
         }
 
         return depart;
@@ -225,7 +255,7 @@ impl RouteRandGenerator {
     ///
     fn next_arrival(self: &mut RouteRandGenerator, current_visit: u16, event_cnt: u16) -> f32 {
         // Variables
-        let time_horizon: f32 = self.config["time"]["EOD"].as_f64().unwrap() as f32;
+        let time_horizon: f32 = self.config.time.eod;
         let chunk: f32 = time_horizon / (event_cnt as f32);
         let next_arr: f32 = (current_visit as f32) * chunk;
 
@@ -292,25 +322,25 @@ impl RouteRandGenerator {
     /// Create the fleet of buses and assign some of their properties
     ///
     /// # Input
-    /// * NONE
+    /// * `rng`: Random number generator
     ///
     /// # Ouptut
     /// * NONE
     ///
-    fn create_buses(self: &mut RouteRandGenerator) {
+    fn create_buses(self: &mut RouteRandGenerator, rng: &mut StdRng) {
         // Variables
-        let bat_capacity: f32 = self.config["buses"]["bat_capacity"].as_f64().unwrap() as f32;
-        let dis_rat: f32 = self.config["buses"]["dis_rate"].as_f64().unwrap() as f32;
-        let fc: f32 = self.config["final_charge"].as_f64().unwrap() as f32;
-        let ic_ub: f32 = self.config["initial_charge"]["max"].as_f64().unwrap() as f32;
-        let ic_lb: f32 = self.config["initial_charge"]["min"].as_f64().unwrap() as f32;
-        let num_bus: u16 = self.config["buses"]["num_bus"].as_i64().unwrap() as u16;
+        let bat_capacity: f32 = self.config.buses.bat_capacity;
+        let dis_rat: f32 = self.config.buses.dis_rate;
+        let fc: f32 = self.config.final_charge;
+        let ic_ub: f32 = self.config.initial_charge.max;
+        let ic_lb: f32 = self.config.initial_charge.min;
+        let num_bus: u16 = self.config.buses.num_bus as u16;
 
         for b in 0..num_bus as usize {
             self.buses[b].bat_capacity = bat_capacity;
             self.buses[b].discharge_rate = dis_rat;
             self.buses[b].final_charge = fc;
-            self.buses[b].initial_charge = rand_utils::rand_range(ic_lb, ic_ub);
+            self.buses[b].initial_charge = rand_utils::rand_range(ic_lb, ic_ub, rng);
         }
     }
 }
@@ -322,12 +352,12 @@ impl Route for RouteRandGenerator {
     /// Generate or load route
     ///
     /// # Input
-    /// * NONE
+    /// * `rng`: Random number generator
     ///
     /// # Output
     /// * `route_schedule`: The routes that the buses must adhere to
     ///
-    fn run(self: &mut RouteRandGenerator) {
+    fn run(self: &mut RouteRandGenerator, rng: &mut StdRng) -> Result<(), Error> {
         // If load from file
         if self.load_from_file {
         }
@@ -337,11 +367,13 @@ impl Route for RouteRandGenerator {
             self.create_buffers();
 
             // Create buses
-            self.create_buses();
+            self.create_buses(rng);
 
             // Generate
-            self.generate_routes();
+            self.generate_routes(rng);
         }
+
+        return Ok(());
     }
 
     //---------------------------------------------------------------------------
@@ -424,11 +456,12 @@ impl Route for RouteRandGenerator {
 #[cfg(test)]
 mod priv_test_route_gen {
     use super::{Route, RouteRandGenerator};
+    use crate::util::rand_utils;
 
     //---------------------------------------------------------------------------
     //
     fn create_object() -> RouteRandGenerator {
-        return RouteRandGenerator::new(false, "./src/config/schedule-test.yaml");
+        return RouteRandGenerator::new(false, "./src/config/schedule-test.yaml").unwrap();
     }
 
     //---------------------------------------------------------------------------
@@ -437,31 +470,32 @@ mod priv_test_route_gen {
     fn test_next_depart() {
         // Variables
         let mut rg: RouteRandGenerator = create_object();
+        let mut rng = rand_utils::seeded_rng(0);
         let mut arrival: f32 = 1.0;
-        let time_horizon: f32 = rg.config["time"]["EOD"].as_f64().unwrap() as f32;
+        let time_horizon: f32 = rg.config.time.eod;
 
         // Test 1
-        let mut depart: f32 = rg.next_depart(arrival, false);
+        let mut depart: f32 = rg.next_depart(arrival, false, &mut rng);
         assert_eq!(depart, arrival + 0.1);
 
         // Test 2
         arrival = 2.0;
-        depart = rg.next_depart(arrival, false);
+        depart = rg.next_depart(arrival, false, &mut rng);
         assert_eq!(depart, arrival + 0.1);
 
         // Test 3
         arrival = 5.0;
-        depart = rg.next_depart(arrival, false);
+        depart = rg.next_depart(arrival, false, &mut rng);
         assert_eq!(depart, arrival + 0.1);
 
         // Test 4
         arrival = 1.0;
-        depart = rg.next_depart(arrival, true);
+        depart = rg.next_depart(arrival, true, &mut rng);
         assert_eq!(depart, time_horizon);
 
         // Test 5
         arrival = 5.0;
-        depart = rg.next_depart(arrival, true);
+        depart = rg.next_depart(arrival, true, &mut rng);
         assert_eq!(depart, time_horizon);
     }
 
@@ -497,9 +531,9 @@ mod priv_test_route_gen {
     #[test]
     fn test_calc_discharge() {
         let mut rg: RouteRandGenerator = create_object();
-        let dis_rat: f32 = rg.config["buses"]["dis_rate"].as_f64().unwrap() as f32;
+        let dis_rat: f32 = rg.config.buses.dis_rate;
 
-        rg.run();
+        rg.run(&mut rand_utils::seeded_rng(0)).unwrap();
 
         // Test 1
         let mut discharge: f32 = rg.calc_discharge(0, 0.0, 1.0);
@@ -523,10 +557,10 @@ mod priv_test_route_gen {
     #[test]
     fn test_add_bus_data() {
         let mut rg: RouteRandGenerator = create_object();
-        let num_event: usize = rg.config["buses"]["num_visit"].as_i64().unwrap() as usize;
-        let bat_capacity: f32 = rg.config["buses"]["bat_capacity"].as_f64().unwrap() as f32;
+        let num_event: usize = rg.config.buses.num_visit;
+        let bat_capacity: f32 = rg.config.buses.bat_capacity;
 
-        rg.run();
+        rg.run(&mut rand_utils::seeded_rng(0)).unwrap();
 
         // Test 1
         rg.add_bus_data(0, 0, 0.0, 10.0, 15.0);
@@ -558,12 +592,12 @@ mod priv_test_route_gen {
     #[test]
     fn test_create_buses() {
         let mut rg: RouteRandGenerator = create_object();
-        let bat_capacity: f32 = rg.config["buses"]["bat_capacity"].as_f64().unwrap() as f32;
-        let dis_rat: f32 = rg.config["buses"]["dis_rate"].as_f64().unwrap() as f32;
-        let fc: f32 = rg.config["final_charge"].as_f64().unwrap() as f32;
+        let bat_capacity: f32 = rg.config.buses.bat_capacity;
+        let dis_rat: f32 = rg.config.buses.dis_rate;
+        let fc: f32 = rg.config.final_charge;
 
         rg.create_buffers();
-        rg.create_buses();
+        rg.create_buses(&mut rand_utils::seeded_rng(0));
 
         // Test 1
         assert_eq!(rg.buses[0].bat_capacity, bat_capacity);
@@ -589,8 +623,8 @@ mod priv_test_route_gen {
 
         rg.create_buffers();
 
-        let visit_len: usize = rg.config["buses"]["num_visit"].as_i64().unwrap() as usize;
-        let bus_len: usize = rg.config["buses"]["num_bus"].as_i64().unwrap() as usize;
+        let visit_len: usize = rg.config.buses.num_visit;
+        let bus_len: usize = rg.config.buses.num_bus;
 
         assert_eq!(rg.route.len(), visit_len);
         assert_eq!(rg.buses.len(), bus_len);
@@ -603,9 +637,9 @@ mod priv_test_route_gen {
         let mut rg: RouteRandGenerator = create_object();
 
         rg.create_buffers();
-        rg.generate_routes();
+        rg.generate_routes(&mut rand_utils::seeded_rng(0));
 
-        let visit_len: usize = rg.config["buses"]["num_visit"].as_i64().unwrap() as usize;
+        let visit_len: usize = rg.config.buses.num_visit;
 
         assert_eq!(rg.route.len(), visit_len);
 
@@ -632,20 +666,21 @@ mod priv_test_route_gen {
         let mut rg: RouteRandGenerator = create_object();
 
         rg.create_buffers();
-        rg.create_buses();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.create_buses(&mut rng);
 
         // Test 1
-        rg.create_events(0, 1, 0);
+        rg.create_events(0, 1, 0, &mut rng);
         assert_eq!(rg.route[0].id, 0);
         assert!(rg.route[0].departure_time > 0.0);
 
         // Test 2
-        rg.create_events(1, 2, 1);
+        rg.create_events(1, 2, 1, &mut rng);
         assert_eq!(rg.route[1].id, 1);
         assert!(rg.route[0].departure_time > 0.0);
 
         // Test 2
-        rg.create_events(7, 1, 56);
+        rg.create_events(7, 1, 56, &mut rng);
         assert_eq!(rg.route[56].id, 7);
         assert!(rg.route[0].departure_time > 0.0);
     }
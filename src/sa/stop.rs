@@ -0,0 +1,95 @@
+//==============================================================================
+// Import standard library
+use std::time::Duration;
+use yaml_rust::Yaml;
+
+//==============================================================================
+/// Reason a run stopped
+//
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopReason {
+    Completed,      // The temperature schedule was completed
+    WallClock,      // The wall clock budget was used up
+    MaxEvaluations, // The maximum number of objective evaluations was reached
+    TargetScore,    // The best score reached the target score
+    NoImprovement,  // The best score did not improve for the configured number of steps
+}
+
+//==============================================================================
+/// Criteria to stop a run before the temperature schedule is completed. A
+/// criterion that is `None` is disabled.
+//
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct StopCriteria {
+    pub time: Option<Duration>,     // Wall clock budget of the run
+    pub evaluations: Option<usize>, // Maximum number of objective evaluations
+    pub target: Option<f64>,        // Target best score
+    pub stall: Option<usize>,       // Temperature steps without improving the best score
+}
+
+//==============================================================================
+/// Implementation of StopCriteria
+//
+impl StopCriteria {
+    //--------------------------------------------------------------------------
+    /// Read the stop criteria from the `stop` section of the schedule
+    /// configuration. Missing keys and values of 0 disable a criterion.
+    ///
+    /// # Input
+    /// * `stop` : `stop` section of the schedule configuration
+    ///
+    /// # Output
+    /// * `StopCriteria`: Stop criteria of the run
+    ///
+    pub fn from_yaml(stop: &Yaml) -> StopCriteria {
+        let number = |y: &Yaml| y.as_f64().or(y.as_i64().map(|x| x as f64));
+        let count = |y: &Yaml| y.as_i64().filter(|x| *x > 0).map(|x| x as usize);
+
+        return StopCriteria {
+            time: number(&stop["time"])
+                .filter(|t| *t > 0.0)
+                .map(Duration::from_secs_f64),
+            evaluations: count(&stop["evaluations"]),
+            target: number(&stop["target"]),
+            stall: count(&stop["stall"]),
+        };
+    }
+
+    //--------------------------------------------------------------------------
+    /// Check whether the run should stop.
+    ///
+    /// # Input
+    /// * `elapsed`     : Wall clock time of the run
+    /// * `evaluations` : Number of objective evaluations of the run
+    /// * `jb`          : Best score
+    /// * `stall`       : Temperature steps without improving the best score
+    ///
+    /// # Output
+    /// * `Some(StopReason)` if a criterion is met
+    ///
+    pub fn check(
+        self: &StopCriteria,
+        elapsed: Duration,
+        evaluations: usize,
+        jb: f64,
+        stall: usize,
+    ) -> Option<StopReason> {
+        if self.target.is_some_and(|t| jb <= t) {
+            return Some(StopReason::TargetScore);
+        }
+
+        if self.time.is_some_and(|t| elapsed >= t) {
+            return Some(StopReason::WallClock);
+        }
+
+        if self.evaluations.is_some_and(|e| evaluations >= e) {
+            return Some(StopReason::MaxEvaluations);
+        }
+
+        if self.stall.is_some_and(|s| stall >= s) {
+            return Some(StopReason::NoImprovement);
+        }
+
+        return None;
+    }
+}
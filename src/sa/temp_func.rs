@@ -1,9 +1,50 @@
+//===============================================================================
+// Import modules
+use crate::error::Error;
+
 //===============================================================================
 /// Types of cooling schedules
+#[derive(Clone, Debug, PartialEq)]
 pub enum CoolSchedule {
     Linear,
     Geometric,
     Exponential,
+    Logarithmic,                  // T0 / (1 + d ln(1 + k))
+    LundyMees,                    // T / (1 + d T)
+    Adaptive(f32),                // d^(ratio / target) T with the target acceptance ratio
+    Piecewise(Vec<(usize, f32)>), // Interpolated (step, temperature) points
+}
+
+//===============================================================================
+/// Implementation of `CoolSchedule`
+impl CoolSchedule {
+    //---------------------------------------------------------------------------
+    /// Create the cooling schedule of the given name from the checked `temp`
+    /// section of the schedule configuration.
+    ///
+    /// # Input
+    /// * `name`   : Name of the cooling schedule
+    /// * `target` : Target acceptance ratio of the `Adaptive` schedule
+    /// * `points` : `(step, temperature)` points of the `Piecewise` schedule
+    ///
+    /// # Output
+    /// * `CoolSchedule`, or an error if the name is unknown
+    ///
+    pub fn new(name: &str, target: f32, points: Vec<(usize, f32)>) -> Result<CoolSchedule, Error> {
+        return match name {
+            "Linear" => Ok(CoolSchedule::Linear),
+            "Geometric" => Ok(CoolSchedule::Geometric),
+            "Exponential" => Ok(CoolSchedule::Exponential),
+            "Logarithmic" => Ok(CoolSchedule::Logarithmic),
+            "LundyMees" => Ok(CoolSchedule::LundyMees),
+            "Adaptive" => Ok(CoolSchedule::Adaptive(target)),
+            "Piecewise" => Ok(CoolSchedule::Piecewise(points)),
+            _ => Err(Error::Invariant(format!(
+                "temp_func.rs: Unknown temperature schedule `{}`.",
+                name
+            ))),
+        };
+    }
 }
 
 //===============================================================================
@@ -13,6 +54,9 @@ pub struct TempFunc {
     schedule: CoolSchedule, // Schedule type
     c_step: usize,          // Current step
     d: f32,                 // Step parameter
+    origin: usize,          // Step the schedule was last (re)started at
+    ratio: f32,             // Acceptance ratio used by the adaptive schedule
+    steps: usize,           // Number of steps the schedule is fit to, 0 if `d` is given
     t: Vec<f32>,            // Current temperature
 }
 
@@ -22,6 +66,8 @@ impl TempFunc {
     // Constants
     const EXP_MAX: f32 = 0.9999;
     const MIN_TEMP: f32 = 1.0;
+    const MAX_STEPS: usize = 1000000; // Upper bound on the number of steps of a schedule
+    const ADAPT_MIN: f32 = 0.1; // Smallest rate exponent of the adaptive schedule
 
     //---------------------------------------------------------------------------
     /// Initialize the linear temperature function
@@ -39,7 +85,7 @@ impl TempFunc {
         // Check delta based on the `CoolSchedule`
         let d: f32 = match schedule {
             CoolSchedule::Linear => delta.abs(), // Linear should be positive
-            CoolSchedule::Geometric =>
+            CoolSchedule::Geometric | CoolSchedule::Adaptive(_) =>
             // Geometric should be less than 1
             {
                 if delta.abs() > TempFunc::EXP_MAX {
@@ -49,6 +95,22 @@ impl TempFunc {
                 }
             }
             CoolSchedule::Exponential => -delta.abs(), // Exponential should be negative
+            CoolSchedule::Logarithmic | CoolSchedule::LundyMees => delta.abs(), // Should be positive
+            CoolSchedule::Piecewise(_) => 0.0, // The points define the schedule
+        };
+
+        // The adaptive schedule assumes the target acceptance ratio until a ratio is observed
+        let ratio: f32 = match schedule {
+            CoolSchedule::Adaptive(target) => target,
+            _ => 0.0,
+        };
+
+        // A piecewise schedule starts at its first point if it is at step 0
+        let init_temp: f32 = match &schedule {
+            CoolSchedule::Piecewise(points) if !points.is_empty() && points[0].0 == 0 => {
+                points[0].1
+            }
+            _ => init_temp,
         };
 
         // Create `TempFunc`
@@ -57,6 +119,9 @@ impl TempFunc {
             schedule,
             c_step: 0,
             d,
+            origin: 0,
+            ratio,
+            steps: 0,
             t: vec![init_temp],
         };
 
@@ -68,6 +133,72 @@ impl TempFunc {
         return tf;
     }
 
+    //---------------------------------------------------------------------------
+    /// Initialize a temperature function of `steps` temperatures. The step size
+    /// of the Logarithmic and LundyMees schedules is fit so that they cool from
+    /// `init_temp` to the minimum temperature at step `steps`. The other
+    /// schedules use `delta`.
+    ///
+    /// # Input
+    /// * `schedule`  : `CoolSchedule` type
+    /// * `init_temp` : Initial temperature
+    /// * `delta`     : Step size of the schedules that are not fit
+    /// * `steps`     : Number of temperatures of the fit schedules
+    /// * `autorun`   : Decide whether to auto generate array of temps
+    ///
+    /// # Output
+    /// * `TempFunc`
+    ///
+    pub fn fit(
+        schedule: CoolSchedule,
+        init_temp: f32,
+        delta: f32,
+        steps: usize,
+        autorun: bool,
+    ) -> TempFunc {
+        let t0: f64 = init_temp as f64;
+        let t_min: f64 = TempFunc::MIN_TEMP as f64;
+        let n: f64 = steps as f64;
+
+        // Solve the schedule for the step size that reaches the minimum temperature at step `steps`
+        let d: f64 = match schedule {
+            CoolSchedule::Logarithmic if steps > 0 => (t0 / t_min - 1.0) / (1.0 + n).ln(),
+            CoolSchedule::LundyMees if steps > 0 => (1.0 / t_min - 1.0 / t0) / n,
+            _ => return TempFunc::new(schedule, init_temp, delta, autorun),
+        };
+
+        // Generate the temperatures once the length is bounded
+        let mut tf: TempFunc = TempFunc::new(schedule, init_temp, d.max(0.0) as f32, false);
+        tf.autorun = autorun;
+        tf.steps = steps;
+        if autorun {
+            TempFunc::autorun(&mut tf);
+        }
+
+        return tf;
+    }
+
+    //---------------------------------------------------------------------------
+    /// Create a new temperature function with the same cooling schedule and
+    /// step size, but a different initial temperature. A schedule fit to a
+    /// number of steps is fit again to the new initial temperature.
+    ///
+    /// # Input
+    /// * `init_temp` : Initial temperature
+    ///
+    /// # Output
+    /// * `TempFunc`: Temperature function starting at `init_temp`
+    ///
+    pub fn with_init_temp(self: &TempFunc, init_temp: f32) -> TempFunc {
+        return TempFunc::fit(
+            self.schedule.clone(),
+            init_temp,
+            self.d,
+            self.steps,
+            self.autorun,
+        );
+    }
+
     //---------------------------------------------------------------------------
     /// Increment the temperature schedule.
     ///
@@ -87,6 +218,97 @@ impl TempFunc {
         return None;
     }
 
+    //---------------------------------------------------------------------------
+    /// Update the adaptive schedule with the acceptance ratio observed at
+    /// temperature step `step`. The temperature of the next step is computed
+    /// from the observed ratio. If autorun is enabled, the remaining steps are
+    /// regenerated assuming the target ratio. Other schedules are not affected.
+    ///
+    /// # Input
+    /// * `step`  : Temperature step the ratio was observed at
+    /// * `ratio` : Fraction of the candidate solutions that were accepted
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    pub fn update(self: &mut TempFunc, step: usize, ratio: f32) {
+        if let CoolSchedule::Adaptive(target) = self.schedule {
+            // Discard the projected temperatures
+            self.t.truncate(step + 1);
+            self.c_step = self.t.len() - 1;
+
+            // Cool with the observed ratio
+            self.ratio = ratio;
+            let next = self.exec_cool_schedule();
+            self.ratio = target;
+
+            // Project the remaining temperatures
+            if self.autorun && next.is_some() {
+                TempFunc::autorun(self);
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    /// Reheat the schedule. The temperature of the step after `step` is set to
+    /// `temp` and the schedule cools from there as if it started at `temp`. If
+    /// autorun is enabled, the remaining steps are regenerated.
+    ///
+    /// # Input
+    /// * `step` : Last temperature step before the reheat
+    /// * `temp` : Temperature to reheat to
+    ///
+    /// # Output
+    /// * `reheated` : False if `temp` is below the minimum temperature
+    ///
+    pub fn reheat(self: &mut TempFunc, step: usize, temp: f32) -> bool {
+        if temp <= TempFunc::MIN_TEMP {
+            return false;
+        }
+
+        // Discard the remaining temperatures and restart the schedule
+        self.t.truncate(step + 1);
+        self.t.push(temp);
+        self.c_step = self.t.len() - 1;
+        self.origin = self.c_step;
+
+        // Project the remaining temperatures
+        if self.autorun {
+            TempFunc::autorun(self);
+        }
+
+        return true;
+    }
+
+    //---------------------------------------------------------------------------
+    /// Replace the temperatures, i.e. to restore a previous run.
+    ///
+    /// # Input
+    /// * `t`      : Temperature vector
+    /// * `origin` : Step the schedule was last (re)started at
+    ///
+    /// # Output
+    /// * NONE
+    ///
+    pub fn set_temp_vec(self: &mut TempFunc, t: Vec<f32>, origin: usize) {
+        self.t = t;
+        self.c_step = self.t.len() - 1;
+        self.origin = origin;
+    }
+
+    //---------------------------------------------------------------------------
+    /// Get the step the schedule was last (re)started at.
+    ///
+    /// # Input
+    /// * NONE
+    ///
+    /// # Output
+    /// * `origin` : Step of the last reheat, 0 if the schedule was not reheated
+    ///
+    pub fn get_origin(self: &TempFunc) -> usize {
+        return self.origin;
+    }
+
     //---------------------------------------------------------------------------
     /// Get the temperature from any step.
     ///
@@ -155,11 +377,31 @@ impl TempFunc {
     /// * NONE
     ///
     fn exec_cool_schedule(self: &mut TempFunc) -> Option<f32> {
+        // Index of the next step since the schedule was last (re)started
+        let k: usize = self.c_step + 1 - self.origin;
+        let t0: f32 = self.t[self.origin];
+
+        // Bound the length of slowly cooling schedules and of the schedules fit to a number of steps
+        if k >= TempFunc::MAX_STEPS || (self.steps > 0 && k >= self.steps) {
+            return None;
+        }
+
         // Execute cooling schedule
-        let t: f32 = match self.schedule {
+        let t: f32 = match &self.schedule {
             CoolSchedule::Exponential => self.d.exp() * self.t[self.c_step],
             CoolSchedule::Geometric => self.d * self.t[self.c_step],
             CoolSchedule::Linear => self.t[self.c_step] - self.d,
+            CoolSchedule::Logarithmic => t0 / (1.0 + self.d * (1.0 + k as f32).ln()),
+            CoolSchedule::LundyMees => self.t[self.c_step] / (1.0 + self.d * self.t[self.c_step]),
+            CoolSchedule::Adaptive(target) => {
+                // Accepting more than the target cools faster, accepting less cools slower
+                let rate = (self.ratio / target).max(TempFunc::ADAPT_MIN);
+                self.d.powf(rate) * self.t[self.c_step]
+            }
+            // A reheated piecewise schedule repeats the points scaled to the reheated temperature
+            CoolSchedule::Piecewise(points) => {
+                TempFunc::interpolate(points, self.t[0], k)? * t0 / self.t[0]
+            }
         };
 
         // Check if the temperature is valid
@@ -176,4 +418,38 @@ impl TempFunc {
         // Return temp
         return Some(t);
     }
+
+    //---------------------------------------------------------------------------
+    /// Linearly interpolate the temperature of step `k` from the
+    /// (step, temperature) points of a piecewise schedule. The schedule starts
+    /// at `init_temp` if the first point is after step 0.
+    ///
+    /// # Input
+    /// * `points`    : (step, temperature) points
+    /// * `init_temp` : Initial temperature
+    /// * `k`         : Step
+    ///
+    /// # Output
+    /// * `temp` : Temperature of step `k`, `None` after the last point
+    ///
+    fn interpolate(points: &[(usize, f32)], init_temp: f32, k: usize) -> Option<f32> {
+        // Start at the initial temperature if the points do not
+        let mut p0: (usize, f32) = (0, init_temp);
+
+        for p1 in points.iter() {
+            if k <= p1.0 {
+                // Handle repeated steps
+                if p1.0 == p0.0 {
+                    return Some(p1.1);
+                }
+
+                let frac = (k - p0.0) as f32 / (p1.0 - p0.0) as f32;
+                return Some(p0.1 + frac * (p1.1 - p0.1));
+            }
+
+            p0 = *p1;
+        }
+
+        return None;
+    }
 }
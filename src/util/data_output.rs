@@ -11,8 +11,12 @@ pub mod DataOutput {
 
     //==========================================================================
     // Import modules
+    use crate::error::Error;
+    use crate::lp::constraints::report::ConstraintReport;
+    use crate::lp::objectives::terms::ConsumptionCost;
     use crate::sa::charger::Charger;
     use crate::sa::data::Data;
+    use crate::sa::mosa::ParetoResults;
     use crate::sa::Results;
 
     //==========================================================================
@@ -34,27 +38,19 @@ pub mod DataOutput {
     /// # Output:
     /// * Data files
     ///
-    pub fn output_data(file_name: String, r: Results, path: Option<String>) {
+    pub fn output_data(file_name: String, r: Results, path: Option<String>) -> Result<(), Error> {
         // Extract path string
-        let fp: String;
-        if let Some(p) = path {
-            fp = p;
-        } else {
-            // Get the month and time strings
-            let current_local: DateTime<Local> = Local::now();
-            let directory = current_local.format("%m/%d/%H-%M-%S/").to_string();
-            let directory = "data/".to_string() + directory.as_str();
+        let fp = directory(path, &r.score.to_string())?;
 
-            // Create Directories
-            fs::create_dir_all(directory.clone()).unwrap();
+        // Save the settings of the run
+        run_out(&file_name, &r, &fp)?;
 
-            // Create file with score
-            let fields: Vec<String> = Vec::new();
-            let data: Vec<Vec<f32>> = Vec::new();
-            save_to_file(&directory.clone(), &r.score.to_string(), &fields, data);
+        // Save the terms of the objective function
+        objective_out(&file_name, &r, &fp)?;
 
-            // Store handle to directory
-            fp = String::from(directory);
+        // Save the replica statistics of parallel tempering
+        if !r.replicas.is_empty() {
+            replica_out(&file_name, &r, &fp)?;
         }
 
         // Extract data
@@ -62,17 +58,258 @@ pub mod DataOutput {
         let c = r.charger;
 
         // Create Plots
-        charge_out(&file_name, &d, &c, &fp);
-        charger_count_out(&file_name, &d, &c, &fp);
-        power_out(&file_name, &d, &c, &fp);
-        acc_energy_out(&file_name, &d, &c, &fp);
-        schedule_out(&file_name, &d, &c, &fp);
-        score_out(&file_name, &d, &c, &fp);
+        charge_out(&file_name, &d, &c, &fp)?;
+        charger_count_out(&file_name, &d, &c, &fp)?;
+        power_out(&file_name, &d, &c, &fp)?;
+        acc_energy_out(&file_name, &d, &c, &fp)?;
+        energy_cost_out(&file_name, &d, &c, &fp)?;
+        schedule_out(&file_name, &d, &c, &fp)?;
+        score_out(&file_name, &d, &c, &fp)?;
+        return constraint_out(&file_name, &d, &c, &fp);
+    }
+
+    //---------------------------------------------------------------------------
+    /// Output the Pareto front of a multi-objective run. The objectives of the
+    /// front are written to one file and the schedule of every solution of the
+    /// front to its own file, `<file_name>-pareto-<solution>-schedule.csv`.
+    ///
+    /// # Input:
+    /// * fn: Base name of the file
+    /// * r: Pareto front of the run
+    /// * path: Path to output directory
+    ///
+    /// # Output:
+    /// * Data files
+    ///
+    pub fn output_front(
+        file_name: String,
+        r: ParetoResults,
+        path: Option<String>,
+    ) -> Result<(), Error> {
+        // Extract path string
+        let size = format!("pareto-{}", r.archive.solutions.len());
+        let fp = directory(path, &size)?;
+
+        // Save the objectives of the front
+        front_out(&file_name, &r, &fp)?;
+
+        // Save the schedule of every solution
+        for (i, s) in r.archive.solutions.iter().enumerate() {
+            let name = format!("{}-pareto-{}", file_name, i);
+            schedule_out(&name, &s.sol, &s.charger, &fp)?;
+        }
+
+        return Ok(());
     }
 
     //===========================================================================
     // PRIVATE
 
+    //---------------------------------------------------------------------------
+    /// Return the output directory. Without a path a directory named after the
+    /// date and time is created, containing an empty file named `tag`.
+    ///
+    /// # Input:
+    /// * path: Path to output directory
+    /// * tag: Name of the empty file that labels the directory
+    ///
+    /// # Output:
+    /// * fp: Path to output directory, or an error if it could not be created
+    ///
+    fn directory(path: Option<String>, tag: &str) -> Result<String, Error> {
+        if let Some(p) = path {
+            return Ok(p);
+        }
+
+        // Get the month and time strings
+        let current_local: DateTime<Local> = Local::now();
+        let directory = current_local.format("%m/%d/%H-%M-%S/").to_string();
+        let directory = "data/".to_string() + directory.as_str();
+
+        // Create Directories
+        fs::create_dir_all(directory.clone()).map_err(|e| io_error(&directory, e))?;
+
+        // Create file with the tag
+        let fields: Vec<String> = Vec::new();
+        let data: Vec<Vec<f32>> = Vec::new();
+        save_to_file(&directory.clone(), tag, &fields, data)?;
+
+        return Ok(directory);
+    }
+
+    //---------------------------------------------------------------------------
+    /// Output the objectives of the solutions of the Pareto front, one row per
+    /// solution. The values are written as text so that the seed is not
+    /// truncated.
+    ///
+    /// # Input:
+    /// * file_name : Base name of the file
+    /// * r: Pareto front of the run
+    /// * path: Path to output directory
+    ///
+    /// # Output:
+    /// * Data files
+    ///
+    fn front_out(file_name: &str, r: &ParetoResults, path: &str) -> Result<(), Error> {
+        let name = path.to_owned() + file_name + "-pareto.csv";
+        let fields = ["Solution", "Cost", "Peak", "Margin", "Score", "Seed"];
+
+        let mut wtr = Writer::from_path(name.clone()).map_err(|e| io_error(&name, e))?;
+        wtr.write_record(fields).map_err(|e| io_error(&name, e))?;
+        for (i, s) in r.archive.solutions.iter().enumerate() {
+            wtr.write_record([
+                i.to_string(),
+                s.criteria.cost.to_string(),
+                s.criteria.peak.to_string(),
+                s.criteria.margin.to_string(),
+                s.j.to_string(),
+                r.seed.to_string(),
+            ])
+            .map_err(|e| io_error(&name, e))?;
+        }
+
+        return wtr.flush().map_err(|e| io_error(&name, e));
+    }
+
+    //---------------------------------------------------------------------------
+    /// Output the settings that were chosen for the run and the reason the run
+    /// stopped. The values are written as text so that the seed is not
+    /// truncated.
+    ///
+    /// # Input:
+    /// * file_name : Base name of the file
+    /// * r: Results structure
+    /// * path: Path to output directory
+    ///
+    /// # Output:
+    /// * Data files
+    ///
+    fn run_out(file_name: &str, r: &Results, path: &str) -> Result<(), Error> {
+        let name = path.to_owned() + file_name + "-run.csv";
+        let fields = ["Seed", "Scale", "T0", "Stop"];
+        let data = [
+            r.seed.to_string(),
+            r.scale.to_string(),
+            r.T0.to_string(),
+            format!("{:?}", r.stop),
+        ];
+
+        let mut wtr = Writer::from_path(name.clone()).map_err(|e| io_error(&name, e))?;
+        wtr.write_record(fields).map_err(|e| io_error(&name, e))?;
+        wtr.write_record(data).map_err(|e| io_error(&name, e))?;
+
+        return wtr.flush().map_err(|e| io_error(&name, e));
+    }
+
+    //---------------------------------------------------------------------------
+    /// Output the terms of the objective function of the best solution, one
+    /// column per term. The weighted terms sum to the score.
+    ///
+    /// # Input:
+    /// * file_name : Base name of the file
+    /// * r: Results structure
+    /// * path: Path to output directory
+    ///
+    /// # Output:
+    /// * Data files
+    ///
+    fn objective_out(file_name: &str, r: &Results, path: &str) -> Result<(), Error> {
+        let name = path.to_owned() + file_name + "-objective.csv";
+        let mut fields: Vec<String> = vec![String::from("Score")];
+        let mut data: Vec<String> = vec![r.score.to_string()];
+        for (term, x) in r.terms.terms.iter() {
+            fields.push(term.clone());
+            data.push(x.to_string());
+        }
+
+        let mut wtr = Writer::from_path(name.clone()).map_err(|e| io_error(&name, e))?;
+        wtr.write_record(fields).map_err(|e| io_error(&name, e))?;
+        wtr.write_record(data).map_err(|e| io_error(&name, e))?;
+
+        return wtr.flush().map_err(|e| io_error(&name, e));
+    }
+
+    //---------------------------------------------------------------------------
+    /// Output the constraints that the schedule violates, one row per
+    /// violation. The other visit is left empty for the constraints of a single
+    /// visit.
+    ///
+    /// # Input:
+    /// * file_name : Base name of the file
+    /// * dat: Data object
+    /// * char: Charger object
+    /// * path: Path to output directory
+    ///
+    /// # Output:
+    /// * Data files
+    ///
+    fn constraint_out(
+        file_name: &str,
+        dat: &Data,
+        char: &Charger,
+        path: &str,
+    ) -> Result<(), Error> {
+        let name = path.to_owned() + file_name + "-constraints.csv";
+        let fields = ["Constraint", "Visit", "Other", "Queue", "Amount"];
+        let report = ConstraintReport::new(dat, char);
+
+        let mut wtr = Writer::from_path(name.clone()).map_err(|e| io_error(&name, e))?;
+        wtr.write_record(fields).map_err(|e| io_error(&name, e))?;
+        for v in report.violations.iter() {
+            wtr.write_record([
+                format!("{:?}", v.kind),
+                v.i.to_string(),
+                v.j.map_or(String::from(E_CELL), |j| j.to_string()),
+                v.q.map_or(String::from(E_CELL), |q| q.to_string()),
+                v.amount.to_string(),
+            ])
+            .map_err(|e| io_error(&name, e))?;
+        }
+
+        return wtr.flush().map_err(|e| io_error(&name, e));
+    }
+
+    //---------------------------------------------------------------------------
+    /// Output the acceptance and exchange statistics of each replica
+    ///
+    /// # Input:
+    /// * file_name : Base name of the file
+    /// * r: Results structure
+    /// * path: Path to output directory
+    ///
+    /// # Output:
+    /// * Data files
+    ///
+    fn replica_out(file_name: &str, r: &Results, path: &str) -> Result<(), Error> {
+        let name = file_name.to_owned() + "-replicas";
+        let fields: Vec<String> = vec![
+            String::from("Temperature"),
+            String::from("Candidates"),
+            String::from("Accepted"),
+            String::from("Acceptance"),
+            String::from("SwapAttempts"),
+            String::from("Swaps"),
+            String::from("SwapAcceptance"),
+        ];
+        let data: Vec<Vec<f32>> = r
+            .replicas
+            .iter()
+            .map(|s| {
+                vec![
+                    s.temp,
+                    s.candidates as f32,
+                    s.accepted as f32,
+                    s.accepted as f32 / s.candidates.max(1) as f32,
+                    s.swap_attempts as f32,
+                    s.swaps as f32,
+                    s.swaps as f32 / s.swap_attempts.max(1) as f32,
+                ]
+            })
+            .collect();
+
+        return save_to_file(path, &name, &fields, data);
+    }
+
     //---------------------------------------------------------------------------
     /// Output charge data for each BEB over time
     ///
@@ -85,11 +322,11 @@ pub mod DataOutput {
     /// # Output:
     /// * Data files
     ///
-    fn charge_out(file_name: &String, dat: &Data, char: &Charger, path: &String) {
+    fn charge_out(file_name: &str, dat: &Data, char: &Charger, path: &str) -> Result<(), Error> {
         if dat.param.conv.len() > 0 {
-            nonlinear_soc(file_name, dat, char, path);
+            return nonlinear_soc(file_name, dat, char, path);
         } else {
-            linear_soc(file_name, dat, char, path);
+            return linear_soc(file_name, dat, char, path);
         }
     }
 
@@ -105,7 +342,12 @@ pub mod DataOutput {
     /// # Output:
     /// * Data files
     ///
-    fn charger_count_out(file_name: &String, dat: &Data, char: &Charger, path: &String) {
+    fn charger_count_out(
+        file_name: &str,
+        dat: &Data,
+        char: &Charger,
+        path: &str,
+    ) -> Result<(), Error> {
         // Variables
         let K: usize = STEP_CNT;
         let N: usize = dat.param.N;
@@ -117,7 +359,7 @@ pub mod DataOutput {
         let d: &Vec<f32> = &dat.dec.d;
 
         // Table variables
-        let name = file_name.to_owned() + &"-charge-cnt";
+        let name = file_name.to_owned() + "-charge-cnt";
         let wait: usize = char.charger_count.0;
         let slow: usize = char.charger_count.1;
         let fields: Vec<String> = vec![
@@ -159,7 +401,7 @@ pub mod DataOutput {
         }
 
         // Write data to disk
-        save_to_file(path, &name, &fields, data);
+        return save_to_file(path, &name, &fields, data);
     }
 
     //---------------------------------------------------------------------------
@@ -174,7 +416,7 @@ pub mod DataOutput {
     /// # Output:
     /// * Data files
     ///
-    fn power_out(file_name: &String, dat: &Data, _char: &Charger, path: &String) {
+    fn power_out(file_name: &str, dat: &Data, _char: &Charger, path: &str) -> Result<(), Error> {
         // Variables
         let K: usize = STEP_CNT;
         let N: usize = dat.param.N;
@@ -187,7 +429,7 @@ pub mod DataOutput {
         let w: &Vec<Vec<bool>> = &dat.dec.w;
 
         // Table variables
-        let name = file_name.to_owned() + &"-power-usage";
+        let name = file_name.to_owned() + "-power-usage";
         let mut data: Vec<Vec<f32>> = vec![vec![0.0; 2]; K];
         let fields: Vec<String> = vec![String::from("time"), String::from("power")];
 
@@ -214,7 +456,7 @@ pub mod DataOutput {
         }
 
         // Write data to disk
-        save_to_file(path, &name, &fields, data);
+        return save_to_file(path, &name, &fields, data);
     }
 
     //---------------------------------------------------------------------------
@@ -229,7 +471,12 @@ pub mod DataOutput {
     /// # Output:
     /// * Data files
     ///
-    fn acc_energy_out(file_name: &String, dat: &Data, _char: &Charger, path: &String) {
+    fn acc_energy_out(
+        file_name: &str,
+        dat: &Data,
+        _char: &Charger,
+        path: &str,
+    ) -> Result<(), Error> {
         // Variables
         let K: usize = STEP_CNT;
         let N: usize = dat.param.N;
@@ -242,7 +489,7 @@ pub mod DataOutput {
         let w: &Vec<Vec<bool>> = &dat.dec.w;
 
         // Table variables
-        let name = file_name.to_owned() + &"-acc-energy-usage";
+        let name = file_name.to_owned() + "-acc-energy-usage";
         let mut data: Vec<Vec<f32>> = vec![vec![0.0; 2]; K];
         let fields: Vec<String> = vec![String::from("time"), String::from("power")];
 
@@ -270,7 +517,64 @@ pub mod DataOutput {
         }
 
         // Write data to disk
-        save_to_file(path, &name, &fields, data);
+        return save_to_file(path, &name, &fields, data);
+    }
+
+    //---------------------------------------------------------------------------
+    /// Output the energy and time-of-use cost of each charge. The last row is
+    /// the total of the schedule.
+    ///
+    /// # Input:
+    /// * file_name : Base name of the file
+    /// * d : Data manager
+    /// * char: Charger object
+    /// * path: Path to output directory
+    ///
+    /// # Output:
+    /// * Data files
+    ///
+    fn energy_cost_out(
+        file_name: &str,
+        dat: &Data,
+        char: &Charger,
+        path: &str,
+    ) -> Result<(), Error> {
+        // Variables
+        let N: usize = dat.param.N;
+        let r: &Vec<f32> = &dat.param.r;
+        let u: &Vec<f32> = &dat.dec.u;
+        let v: &Vec<usize> = &dat.dec.v;
+        let s: &Vec<f32> = &dat.dec.s;
+        let w: &Vec<Vec<bool>> = &dat.dec.w;
+        let wait: usize = char.charger_count.0;
+
+        // Table variables
+        let name = file_name.to_owned() + "-energy-cost";
+        let mut data: Vec<Vec<f32>> = Vec::new();
+        let mut total: Vec<f32> = vec![-1.0, -1.0, -1.0, 0.0, 0.0];
+        let fields: Vec<String> = vec![
+            String::from("visit"),
+            String::from("u"),
+            String::from("s"),
+            String::from("energy"),
+            String::from("cost"),
+        ];
+
+        // For each visit that charges
+        for i in 0..N {
+            if w[i][v[i]] && v[i] >= wait {
+                let energy = r[v[i]] * char.effective_time(v[i], u[i], s[i]);
+                let cost = ConsumptionCost::cost(dat, char, i) as f32;
+                data.push(vec![i as f32, u[i], s[i], energy, cost]);
+
+                total[3] += energy;
+                total[4] += cost;
+            }
+        }
+        data.push(total);
+
+        // Write data to disk
+        return save_to_file(path, &name, &fields, data);
     }
 
     //---------------------------------------------------------------------------
@@ -285,7 +589,7 @@ pub mod DataOutput {
     /// # Output:
     /// * Data files
     ///
-    fn schedule_out(file_name: &String, dat: &Data, char: &Charger, path: &String) {
+    fn schedule_out(file_name: &str, dat: &Data, char: &Charger, path: &str) -> Result<(), Error> {
         // Variables
         let A: usize = dat.param.A;
         let N: usize = dat.param.N;
@@ -297,7 +601,7 @@ pub mod DataOutput {
         let wait: usize = char.charger_count.0;
 
         // Table variables
-        let name = file_name.to_owned() + &"-schedule";
+        let name = file_name.to_owned() + "-schedule";
         let mut data: Vec<Vec<f32>> = vec![vec![-1.0; 3 * A]; N];
         let fields: Vec<Vec<String>> = (0..A)
             .map(|b| {
@@ -323,7 +627,7 @@ pub mod DataOutput {
         }
 
         // Write data to disk
-        save_to_file(path, &name, &fields, data);
+        return save_to_file(path, &name, &fields, data);
     }
 
     //---------------------------------------------------------------------------
@@ -337,9 +641,14 @@ pub mod DataOutput {
     ///
     /// # Output:
     /// * CSV file located at 'PATH/NAME' with DATA as content
-    fn save_to_file(path: &String, name: &String, fields: &Vec<String>, data: Vec<Vec<f32>>) {
+    fn save_to_file(
+        path: &str,
+        name: &str,
+        fields: &Vec<String>,
+        data: Vec<Vec<f32>>,
+    ) -> Result<(), Error> {
         // Variables
-        let file_name = path.to_owned() + name + &".csv";
+        let file_name = path.to_owned() + name + ".csv";
 
         // Convert data to strings
         let mut data_s: Vec<Vec<String>> = Vec::new();
@@ -378,13 +687,30 @@ pub mod DataOutput {
         // }
 
         //  Save data to disk
-        if let Ok(mut wtr) = Writer::from_path(file_name.clone()) {
-            // Write each row to disk
-            wtr.write_record(fields).unwrap();
-            data_s.iter().for_each(|row| wtr.write_record(row).unwrap());
-        } else {
-            panic!("Could not write to the file: {}", file_name);
+        let mut wtr = Writer::from_path(file_name.clone()).map_err(|e| io_error(&file_name, e))?;
+
+        // Write each row to disk
+        wtr.write_record(fields)
+            .map_err(|e| io_error(&file_name, e))?;
+        for row in data_s.iter() {
+            wtr.write_record(row).map_err(|e| io_error(&file_name, e))?;
         }
+
+        return wtr.flush().map_err(|e| io_error(&file_name, e));
+    }
+
+    //---------------------------------------------------------------------------
+    /// Describe a failed write to a file
+    ///
+    /// # Input:
+    /// * name : Path of the file
+    /// * e    : Error of the write
+    ///
+    /// # Output:
+    /// * Error
+    ///
+    fn io_error(name: &str, e: impl std::fmt::Display) -> Error {
+        return Error::Io(format!("Could not write to the file {}: {}", name, e));
     }
 
     //---------------------------------------------------------------------------
@@ -399,9 +725,9 @@ pub mod DataOutput {
     /// # Output:
     /// * NONE
     ///
-    fn linear_soc(file_name: &String, dat: &Data, _char: &Charger, path: &String) {
+    fn linear_soc(file_name: &str, dat: &Data, _char: &Charger, path: &str) -> Result<(), Error> {
         // Variables
-        let name = file_name.to_owned() + &"-charge";
+        let name = file_name.to_owned() + "-charge";
         let N = dat.param.N;
         let A = dat.param.A;
         let G = &dat.param.Gam;
@@ -447,7 +773,7 @@ pub mod DataOutput {
         }
 
         // Write data to disk
-        save_to_file(path, &name, &fields, data);
+        return save_to_file(path, &name, &fields, data);
     }
 
     //---------------------------------------------------------------------------
@@ -462,9 +788,14 @@ pub mod DataOutput {
     /// # Output:
     /// * NONE
     ///
-    fn nonlinear_soc(file_name: &String, dat: &Data, _char: &Charger, path: &String) {
+    fn nonlinear_soc(
+        file_name: &str,
+        dat: &Data,
+        _char: &Charger,
+        path: &str,
+    ) -> Result<(), Error> {
         // Variables
-        let name = file_name.to_owned() + &"-charge";
+        let name = file_name.to_owned() + "-charge";
         let N = dat.param.N;
         let A = dat.param.A;
         let G = &dat.param.Gam;
@@ -520,11 +851,12 @@ pub mod DataOutput {
         }
 
         // Write data to disk
-        save_to_file(path, &name, &fields, data);
+        return save_to_file(path, &name, &fields, data);
     }
 
     //---------------------------------------------------------------------------
-    /// Output score data over the time horizon
+    /// Output score data over the time horizon. The terms of the objective
+    /// function are those of the best solution.
     ///
     /// * file_name : Base name of the file
     /// * d : Data manager
@@ -533,26 +865,37 @@ pub mod DataOutput {
     ///
     /// # Output:
     /// * Data files
-    fn score_out(file_name: &String, dat: &Data, _char: &Charger, path: &String) {
+    fn score_out(file_name: &str, dat: &Data, _char: &Charger, path: &str) -> Result<(), Error> {
         // Variables
         let jb = &dat.dec.Jb;
         let jc = &dat.dec.Jc;
         let jn = &dat.dec.Jn;
+        let reheat = &dat.dec.reheat;
+        let k = &dat.dec.K;
+        let terms = &dat.dec.terms;
 
-        let name = file_name.to_owned() + &"-score";
-        let fields: Vec<String> = vec![
+        let name = file_name.to_owned() + "-score";
+        let mut fields: Vec<String> = vec![
             String::from("Best"),
             String::from("Active"),
             String::from("Candidate"),
+            String::from("Reheats"),
+            String::from("K"),
         ];
-        let mut data: Vec<Vec<f32>> = vec![vec![0.0; 3]; jb.len()];
+        fields.extend(terms.iter().map(|t| t.0.clone()));
+        let mut data: Vec<Vec<f32>> = vec![vec![0.0; fields.len()]; jb.len()];
 
         for (idx, d) in data.iter_mut().enumerate() {
             d[0] = jb[idx] as f32;
             d[1] = jc[idx] as f32;
             d[2] = jn[idx] as f32;
+            d[3] = reheat[idx] as f32;
+            d[4] = k[idx] as f32;
+            for (t, term) in terms.iter().enumerate() {
+                d[5 + t] = term.1[idx] as f32;
+            }
         }
 
-        save_to_file(path, &name, &fields, data);
+        return save_to_file(path, &name, &fields, data);
     }
 }
@@ -1,6 +1,57 @@
 // Import standard library
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::{thread_rng, Rng};
+use rand::{Rng, SeedableRng};
+
+//===============================================================================
+/// Create a random number generator from a seed. Two generators created from
+/// the same seed produce the same sequence of values.
+///
+/// # Input
+/// * `seed`: Seed of the random number generator
+///
+/// # Output
+/// * `rng`: Seeded random number generator
+///
+pub fn seeded_rng(seed: u64) -> StdRng {
+    return StdRng::seed_from_u64(seed);
+}
+
+//===============================================================================
+/// Draw a new seed from the operating system. This is used when no seed has
+/// been provided.
+///
+/// # Input
+/// * NONE
+///
+/// # Output
+/// * `seed`: Random seed
+///
+pub fn entropy_seed() -> u64 {
+    return StdRng::from_entropy().gen();
+}
+
+//===============================================================================
+/// Derive the seed of a sub-run (i.e. a thread or a loop iteration) from the
+/// base seed. The seeds are mixed with the SplitMix64 finalizer so that
+/// neighboring ids produce uncorrelated generators.
+///
+/// # Input
+/// * `seed`: Base seed
+/// * `id`  : Id of the sub-run
+///
+/// # Output
+/// * `seed`: Derived seed
+///
+/// # References:
+/// <https://prng.di.unimi.it/splitmix64.c>
+///
+pub fn derive_seed(seed: u64, id: u64) -> u64 {
+    let mut z = seed.wrapping_add((id + 1).wrapping_mul(0x9E3779B97F4A7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    return z ^ (z >> 31);
+}
 
 //===============================================================================
 /// Creates a list of `vec_size` values that sum up `sum_val`
@@ -8,19 +59,19 @@ use rand::{thread_rng, Rng};
 /// # Input
 /// * `vec_size` : Size of vec
 /// * `sum_val`  : Value that all numbers in vec add up to
+/// * `rng`      : Random number generator
 ///
 /// # Output
 /// * `rand_vec`: vec of random values that add up to `sum_val`
 ///
-pub fn rand_route_count(vec_size: u16, sum_val: u16) -> Vec<u16> {
+pub fn rand_route_count(vec_size: u16, sum_val: u16, rng: &mut StdRng) -> Vec<u16> {
     // Variables
-    let mut rand = thread_rng();
     let mut rand_vec: Vec<u16> = vec![1; vec_size as usize];
     let mut rand_id: u16;
 
     // Randomly apply a visits to each bus
     for _ in 0..sum_val - vec_size {
-        rand_id = rand.gen_range(0..vec_size);
+        rand_id = rng.gen_range(0..vec_size);
         rand_vec[rand_id as usize] += 1;
     }
 
@@ -33,6 +84,7 @@ pub fn rand_route_count(vec_size: u16, sum_val: u16) -> Vec<u16> {
 /// # Input
 /// * `lower_bound`: Smallest value allowed
 /// * `upper_bound`: Largest value allowed
+/// * `rng`        : Random number generator
 ///
 /// # Output
 /// * `rand`: A random value within the range [lower_bound, upper_bound]
@@ -40,8 +92,9 @@ pub fn rand_route_count(vec_size: u16, sum_val: u16) -> Vec<u16> {
 pub fn rand_range<T: rand::distributions::uniform::SampleUniform + std::cmp::PartialOrd>(
     lower_bound: T,
     upper_bound: T,
+    rng: &mut StdRng,
 ) -> T {
-    return rand::thread_rng().gen_range(lower_bound..=upper_bound);
+    return rng.gen_range(lower_bound..=upper_bound);
 }
 
 //===============================================================================
@@ -49,6 +102,7 @@ pub fn rand_range<T: rand::distributions::uniform::SampleUniform + std::cmp::Par
 ///
 /// # Input
 /// * `vec` : Vector
+/// * `rng` : Random number generator
 ///
 /// # Output
 /// * `rand_vec`: randomized `vec`
@@ -56,12 +110,12 @@ pub fn rand_range<T: rand::distributions::uniform::SampleUniform + std::cmp::Par
 /// # References:
 /// <https://stackoverflow.com/questions/26033976/how-do-i-create-a-vec-from-a-range-and-shuffle-it>
 ///
-pub fn shuffle_vec<T: Clone>(vec: &Vec<T>) -> Vec<T> {
+pub fn shuffle_vec<T: Clone>(vec: &Vec<T>, rng: &mut StdRng) -> Vec<T> {
     // Copy the vector
     let mut vec = (*vec).clone();
 
     // Shuffle the vector
-    vec.shuffle(&mut thread_rng());
+    vec.shuffle(rng);
 
     return vec;
 }
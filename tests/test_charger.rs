@@ -6,10 +6,13 @@ extern crate sa_pap;
 mod test_charger {
     //---------------------------------------------------------------------------
     // Import modules
+    use super::sa_pap::error::Error;
     use super::sa_pap::sa::charger::Assignment;
     use super::sa_pap::sa::charger::Charger;
+    use super::sa_pap::sa::config::{CabinetConfig, ChargerConfig, TimeConfig};
     use super::sa_pap::sa::route::route_csv_generator::RouteCSVGenerator;
     use super::sa_pap::sa::route::Route;
+    use super::sa_pap::util::rand_utils;
 
     //---------------------------------------------------------------------------
     //
@@ -45,7 +48,7 @@ mod test_charger {
     #[test]
     fn test_charger_initilazation() {
         // Load charger parameters from YAML file
-        let charger: Charger = Charger::new(schedule_path(), true, None, None);
+        let charger: Charger = Charger::new(schedule_path(), true, None, None).unwrap();
 
         // Test 0 - Ensure the correct amount of chargers have been created
         let cc = charger.charger_count.1 + charger.charger_count.2;
@@ -60,12 +63,32 @@ mod test_charger {
         assert_eq!(cc, (0.0, 100.0, 400.0));
     }
 
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_charger_from_config() {
+        // Create the charger of the test schedule from in-memory settings
+        let config = ChargerConfig {
+            count: (11, 7, 4),
+            speed: (0.0, 100.0, 400.0),
+            ..Default::default()
+        };
+        let charger = Charger::from_config(&config, &TimeConfig::default());
+
+        // Test 1 - The charger matches the charger loaded from the YAML file
+        assert!(charger == Charger::new(schedule_path(), true, None, None).unwrap());
+
+        // Test 2 - Every charger is free from BOD to EOD
+        assert_eq!(charger.free_time.len(), 22);
+        assert!(charger.free_time.iter().all(|f| *f == vec![(0.0, 24.0)]));
+    }
+
     //---------------------------------------------------------------------------
     //
     #[test]
     fn test_charger_addition() {
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, None);
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
 
         // Make sure we have an empty charger queue
         assert_eq!(charger.schedule.is_empty(), false);
@@ -91,7 +114,7 @@ mod test_charger {
     #[test]
     fn test_charger_assignment() {
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, None);
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
 
         // Add queues (four three chargers)
         charger.add_chargers(2);
@@ -105,7 +128,7 @@ mod test_charger {
         assert!(charger.avail(&q, &c));
 
         // Assign the charger
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         assert_eq!(charger.schedule[0][0], Assignment { t: c, b: id });
 
@@ -115,7 +138,7 @@ mod test_charger {
         let id: usize = 3;
 
         // Assign the charger
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         assert_eq!(charger.schedule[q][0], Assignment { t: c, b: id });
 
@@ -125,7 +148,7 @@ mod test_charger {
         let id: usize = 2;
 
         // Assign the charger
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         assert_eq!(charger.schedule[q][0], Assignment { t: c, b: id });
 
@@ -135,7 +158,7 @@ mod test_charger {
         let id: usize = 1;
 
         // Assign the charger
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         assert_eq!(charger.schedule[q][0], Assignment { t: c, b: id });
 
@@ -145,7 +168,7 @@ mod test_charger {
         let id: usize = 1;
 
         // Assign the charger
-        assert_eq!(charger.assign(q, c, id), true);
+        assert_eq!(charger.assign(q, c, id).unwrap(), true);
 
         // Make sure that the time slice was not assigned
         assert_eq!(time_slice_exists(&charger, &q, &c), true);
@@ -156,7 +179,7 @@ mod test_charger {
         let id: usize = 2;
 
         // Assign the charger
-        assert_eq!(charger.assign(q, c, id), true);
+        assert_eq!(charger.assign(q, c, id).unwrap(), true);
 
         // Make sure that the time slice was not assigned
         assert_eq!(time_slice_exists(&charger, &q, &c), true);
@@ -167,7 +190,7 @@ mod test_charger {
         let id: usize = 1;
 
         // Assign the charger
-        assert_eq!(charger.assign(q, c, id), true);
+        assert_eq!(charger.assign(q, c, id).unwrap(), true);
 
         // Make sure that the time slice was not assigned
         assert_eq!(time_slice_exists(&charger, &q, &c), true);
@@ -178,7 +201,7 @@ mod test_charger {
         let id: usize = 0;
 
         // Assign the charger
-        assert_eq!(charger.assign(q, c, id), true);
+        assert_eq!(charger.assign(q, c, id).unwrap(), true);
 
         // Make sure that the time slice was not assigned
         assert_eq!(time_slice_exists(&charger, &q, &c), true);
@@ -189,7 +212,7 @@ mod test_charger {
         let id: usize = 0;
 
         // Assign the charger
-        assert_eq!(charger.assign(q, c, id), true);
+        assert_eq!(charger.assign(q, c, id).unwrap(), true);
 
         // Make sure that the time slice was not assigned
         assert_eq!(time_slice_exists(&charger, &q, &c), true);
@@ -200,7 +223,7 @@ mod test_charger {
         let id: usize = 0;
 
         // Assign the charger
-        assert_eq!(charger.assign(q, c, id), true);
+        assert_eq!(charger.assign(q, c, id).unwrap(), true);
 
         // Make sure that the time slice was not assigned
         assert_eq!(time_slice_exists(&charger, &q, &c), true);
@@ -211,7 +234,7 @@ mod test_charger {
         let id: usize = 0;
 
         // Assign the charger
-        assert_eq!(charger.assign(q, c, id), true);
+        assert_eq!(charger.assign(q, c, id).unwrap(), true);
 
         // Make sure that the time slice was not assigned
         assert_eq!(time_slice_exists(&charger, &q, &c), true);
@@ -222,7 +245,7 @@ mod test_charger {
         let id: usize = 0;
 
         // Assign the charger
-        assert_eq!(charger.assign(q, c, id), true);
+        assert_eq!(charger.assign(q, c, id).unwrap(), true);
 
         // Make sure that the time slice was not assigned
         assert_eq!(time_slice_exists(&charger, &q, &c), true);
@@ -233,13 +256,13 @@ mod test_charger {
         let id: usize = 0;
 
         // Assign the charger
-        assert_eq!(charger.assign(q, c, id), true);
+        assert_eq!(charger.assign(q, c, id).unwrap(), true);
 
         // Make sure that the time slice was not assigned
         assert_eq!(time_slice_exists(&charger, &q, &c), true);
 
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, None);
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
 
         // Add queues (four three chargers)
         charger.add_chargers(2);
@@ -253,7 +276,7 @@ mod test_charger {
         assert!(charger.avail(&q, &c));
 
         // Assign the charger
-        assert!(charger.assign(q, c, id));
+        assert!(charger.assign(q, c, id).unwrap());
 
         assert_eq!(charger.schedule[q][0], Assignment { t: c, b: id });
 
@@ -263,17 +286,50 @@ mod test_charger {
         let id: usize = 4;
 
         // Assign the charger
-        assert_eq!(charger.assign(q, c, id), true);
+        assert_eq!(charger.assign(q, c, id).unwrap(), true);
 
         assert!(charger.exists(&q, &c));
     }
 
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_charger_invalid_queue() {
+        // Create charger
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
+        let q: usize = charger.schedule.len();
+
+        // Test 1 - Assigning to a queue that does not exist is an internal error
+        assert!(matches!(
+            charger.assign(q, (0.1, 0.2), 0),
+            Err(Error::Invariant(_))
+        ));
+
+        // Test 2 - Removing from a queue that does not exist is an internal error
+        assert!(matches!(
+            charger.remove(q, (0.1, 0.2)),
+            Err(Error::Invariant(_))
+        ));
+
+        // Test 3 - A schedule must have a queue for every charger
+        assert!(matches!(
+            charger.set_schedule(vec![Vec::new(); q + 1]),
+            Err(Error::Invariant(_))
+        ));
+
+        // Test 4 - An invalid configuration is a configuration error
+        assert!(matches!(
+            Charger::new("bad_file.yaml", true, None, None),
+            Err(Error::Config(_))
+        ));
+    }
+
     //---------------------------------------------------------------------------
     //
     #[test]
     fn test_charger_deletion() {
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, None);
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
 
         // Create a simple schedule
         let q: usize = 0;
@@ -281,22 +337,22 @@ mod test_charger {
         let id: usize = 3;
 
         // Assign the charger
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.0, 0.02);
 
         // Assign the charger
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.3, 0.5);
 
         // Assign the charger
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.6, 0.6001);
 
         // Assign the charger
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         // Make sure they are all there
         assert!(time_slice_exists(&charger, &q, &(0.1, 0.2)));
@@ -306,28 +362,28 @@ mod test_charger {
         assert_eq!(charger.schedule[q].len(), 4);
 
         // Test 1
-        assert!(charger.remove(q, (0.1, 0.2)));
+        assert!(charger.remove(q, (0.1, 0.2)).unwrap());
         assert_eq!(time_slice_exists(&charger, &q, &(0.1, 0.2)), false);
         assert_eq!(charger.schedule[q].len(), 3);
 
         // Test 2
         println!("{:?}", charger.schedule[0]);
-        assert_eq!(charger.remove(q, (0.1, 0.2)), false);
+        assert_eq!(charger.remove(q, (0.1, 0.2)).unwrap(), false);
         assert_eq!(time_slice_exists(&charger, &q, &(0.1, 0.2)), false);
         assert_eq!(charger.schedule[q].len(), 3);
 
         // Test 3
-        assert!(charger.remove(q, (0.0, 0.02)));
+        assert!(charger.remove(q, (0.0, 0.02)).unwrap());
         assert_eq!(time_slice_exists(&charger, &q, &(0.0, 0.02)), false);
         assert_eq!(charger.schedule[q].len(), 2);
 
         // Test 4
-        assert!(charger.remove(q, (0.3, 0.5)));
+        assert!(charger.remove(q, (0.3, 0.5)).unwrap());
         assert_eq!(time_slice_exists(&charger, &q, &(0.3, 0.5)), false);
         assert_eq!(charger.schedule[q].len(), 1);
 
         // Test 5
-        assert!(charger.remove(q, (0.6, 0.6001)));
+        assert!(charger.remove(q, (0.6, 0.6001)).unwrap());
         assert_eq!(time_slice_exists(&charger, &q, &(0.6, 0.6001)), false);
         assert_eq!(charger.schedule[q].len(), 0);
     }
@@ -337,22 +393,22 @@ mod test_charger {
     #[test]
     fn test_charger_ordering() {
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, None);
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
 
         // Create a simple schedule
         let q: usize = 0;
         let c: (f32, f32) = (0.1, 0.2);
         let id: usize = 3;
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.6, 0.7);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.0, 0.02);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.3, 0.5);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         // Get the first item in the schedule
         let mut s_prev = charger.schedule[q][0].clone();
@@ -374,7 +430,7 @@ mod test_charger {
     #[test]
     fn test_charger_avail() {
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, None);
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
 
         // Add queues (four total chargers)
         charger.add_chargers(3);
@@ -388,7 +444,7 @@ mod test_charger {
         assert!(charger.avail(&q, &c));
 
         // Assign the charger
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         // Test 2 - Check if previous assigned charger is still available
         assert_eq!(charger.avail(&q, &c), false);
@@ -398,19 +454,19 @@ mod test_charger {
         let c: (f32, f32) = (0.21, 0.23);
         let id: usize = 3;
         assert!(charger.avail(&q, &c));
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let q: usize = 0;
         let c: (f32, f32) = (0.01, 0.09);
         let id: usize = 3;
         assert!(charger.avail(&q, &c));
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let q: usize = 0;
         let c: (f32, f32) = (0.4, 0.51);
         let id: usize = 3;
         assert!(charger.avail(&q, &c));
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         // Test 4 - Assign a huge time
         let q: usize = 0;
@@ -438,7 +494,7 @@ mod test_charger {
     #[test]
     fn test_charger_get_ts() {
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, None);
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
 
         // Add queues (four total chargers)
         charger.add_chargers(3);
@@ -452,7 +508,7 @@ mod test_charger {
         assert_ne!(charger.get_ts(&q, &c), (0.0, 0.0));
 
         // Assign the charger
-        assert!(charger.assign(q, c, id));
+        assert!(charger.assign(q, c, id).unwrap());
 
         // Test 2 - Check if previous assigned charger is still available
         assert_eq!(charger.get_ts(&q, &c), (0.0, 0.0));
@@ -462,19 +518,19 @@ mod test_charger {
         let c: (f32, f32) = (0.21, 0.23);
         let id: usize = 3;
         assert_ne!(charger.get_ts(&q, &c), (0.0, 0.0));
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let q: usize = 0;
         let c: (f32, f32) = (0.01, 0.09);
         let id: usize = 3;
         assert_ne!(charger.get_ts(&q, &c), (0.0, 0.0));
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let q: usize = 0;
         let c: (f32, f32) = (0.4, 0.51);
         let id: usize = 3;
         assert_ne!(charger.get_ts(&q, &c), (0.0, 0.0));
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         // Test 4 - Assign a huge time
         let q: usize = 0;
@@ -503,7 +559,7 @@ mod test_charger {
     fn test_free_time() {
         // Create charger
         let q: usize = 0;
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, None);
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
 
         // Test 0
         assert_eq!(charger.free_time[q].is_empty(), false);
@@ -512,13 +568,13 @@ mod test_charger {
         // Create a simple schedule
         let c: (f32, f32) = (0.1, 0.2);
         let id: usize = 3;
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.4, 0.5);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.7, 0.8);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         // Test 1
         assert_eq!(charger.free_time[q].is_empty(), false);
@@ -531,18 +587,18 @@ mod test_charger {
 
         // Test 3
         let c: (f32, f32) = (0.1, 0.2);
-        charger.remove(q, c);
+        charger.remove(q, c).unwrap();
         assert_eq!(charger.free_time[q][0], (0.0, 0.4));
         assert_eq!(charger.free_time[q][1], (0.5, 0.7));
         assert_eq!(charger.free_time[q][2], (0.8, 24.0));
 
         let c: (f32, f32) = (0.4, 0.5);
-        charger.remove(q, c);
+        charger.remove(q, c).unwrap();
         assert_eq!(charger.free_time[q][0], (0.0, 0.7));
         assert_eq!(charger.free_time[q][1], (0.8, 24.0));
 
         let c: (f32, f32) = (0.7, 0.8);
-        charger.remove(q, c);
+        charger.remove(q, c).unwrap();
         assert_eq!(charger.free_time[q][0], (0.0, 24.0));
     }
 
@@ -552,22 +608,23 @@ mod test_charger {
     fn test_find_free_time() {
         // Create charger
         let q: usize = 0;
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, None);
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
 
         // Create a simple schedule
         let c: (f32, f32) = (0.1, 0.2);
         let id: usize = 3;
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.4, 0.5);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.7, 0.8);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         // Test 1 - fully valid request
         let ts = charger.free_time[q][0];
-        let (fits, ud) = charger.find_free_time(&(0.0, 0.1), &ts);
+        let mut rng = rand_utils::seeded_rng(0);
+        let (fits, ud) = charger.find_free_time(&(0.0, 0.1), &ts, &mut rng);
         if fits {
             assert!(fits);
             assert!(ud.0 >= 0.0);
@@ -578,7 +635,7 @@ mod test_charger {
 
         // Test 2 - lower bound overlap
         let ts = charger.free_time[q][1];
-        let (fits, ud) = charger.find_free_time(&(0.19, 0.3), &ts);
+        let (fits, ud) = charger.find_free_time(&(0.19, 0.3), &ts, &mut rng);
         if fits {
             assert!(fits);
             assert!(ud.0 >= 0.2);
@@ -589,7 +646,7 @@ mod test_charger {
 
         // Test 3 - upper bound overlap
         let ts = charger.free_time[q][1];
-        let (fits, ud) = charger.find_free_time(&(0.2, 0.51), &ts);
+        let (fits, ud) = charger.find_free_time(&(0.2, 0.51), &ts, &mut rng);
         if fits {
             assert!(fits);
             assert!(ud.0 >= 0.2);
@@ -600,7 +657,7 @@ mod test_charger {
 
         // Test 4 - lower/upper bound overlap
         let ts = charger.free_time[q][1];
-        let (fits, ud) = charger.find_free_time(&(0.0, 0.51), &ts);
+        let (fits, ud) = charger.find_free_time(&(0.0, 0.51), &ts, &mut rng);
         if fits {
             assert!(fits);
             assert!(ud.0 >= 0.2);
@@ -611,7 +668,7 @@ mod test_charger {
 
         // Test 5 - times do not match up
         let ts = charger.free_time[q][1];
-        let (fits, ud) = charger.find_free_time(&(0.11, 0.19), &ts);
+        let (fits, ud) = charger.find_free_time(&(0.11, 0.19), &ts, &mut rng);
         if fits {
             assert!(!fits);
             assert!(ud.0 == 0.11);
@@ -622,7 +679,7 @@ mod test_charger {
 
         // Test 6 - invalid request
         let ts = charger.free_time[q][0];
-        let (fits, ud) = charger.find_free_time(&(0.1, 0.2), &ts);
+        let (fits, ud) = charger.find_free_time(&(0.1, 0.2), &ts, &mut rng);
         if fits {
             assert!(!fits);
             assert!(ud.0 == 0.1);
@@ -632,29 +689,29 @@ mod test_charger {
         }
 
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, Some(2));
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, Some(2)).unwrap();
 
         // Create a simple schedule
         let c: (f32, f32) = (0.1, 0.2);
         let id: usize = 3;
-        assert!(charger.assign(0, c, id));
+        assert!(charger.assign(0, c, id).unwrap());
 
         let c: (f32, f32) = (0.1, 0.2);
         let id: usize = 2;
-        assert_eq!(charger.assign(0, c, id), true);
+        assert_eq!(charger.assign(0, c, id).unwrap(), true);
 
         let c: (f32, f32) = (0.4, 0.5);
-        assert!(charger.assign(1, c, id));
+        assert!(charger.assign(1, c, id).unwrap());
 
         let c: (f32, f32) = (0.7, 0.8);
-        assert!(charger.assign(0, c, id));
+        assert!(charger.assign(0, c, id).unwrap());
     }
     //---------------------------------------------------------------------------
     //
     #[test]
     fn test_get_ts() {
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, None);
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
 
         // Add queues (four three chargers)
         charger.add_chargers(2);
@@ -671,7 +728,7 @@ mod test_charger {
         assert_eq!(charger.get_ts(&0, &(0.12, 0.19)), (0.0, 24.0));
 
         // Assign the charger
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         // One assignment has been made
         assert_eq!(charger.get_ts(&0, &(0.12, 0.19)), (0.0, 0.0));
@@ -684,7 +741,7 @@ mod test_charger {
         let id: usize = 3;
 
         // Assign the charger
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         // One assignment has been made
         assert_eq!(charger.get_ts(&0, &(0.12, 0.19)), (0.0, 0.0));
@@ -697,7 +754,7 @@ mod test_charger {
         let id: usize = 2;
 
         // Assign the charger
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         assert_eq!(charger.schedule[q][0], Assignment { t: c, b: id });
     }
@@ -708,14 +765,15 @@ mod test_charger {
     fn test_milp_to_schedule() {
         // Create route generator
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path());
+            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path()).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Create charger object
         let mut charger: Charger =
-            Charger::new(schedule_path(), false, None, Some(rg.data.param.Q));
+            Charger::new(schedule_path(), false, None, Some(rg.data.param.Q)).unwrap();
 
         // Assign some BEBs to queues
         for b in 0..rg.data.param.A {
@@ -742,4 +800,75 @@ mod test_charger {
         assert_eq!(charger.schedule[4][0].t.1, 2.0);
         assert!(charger.schedule[0][0].b <= rg.data.param.A);
     }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_grid_power() {
+        // Create charger (queues 11-17 are slow, 18-21 are fast)
+        let mut charger: Charger = Charger::new(schedule_path(), true, None, None).unwrap();
+        charger.assign(0, (0.0, 4.0), 0).unwrap();
+        charger.assign(11, (1.0, 3.0), 1).unwrap();
+        charger.assign(18, (2.0, 4.0), 2).unwrap();
+
+        // Test 1 - Only the chargers that are attached draw power
+        assert_eq!(charger.power_at(0.5), 0.0);
+        assert_eq!(charger.power_at(1.0), 100.0);
+        assert_eq!(charger.power_at(2.5), 500.0);
+        assert_eq!(charger.power_at(3.0), 400.0);
+
+        // Test 2 - The peak includes the attach times inside the time slice
+        assert_eq!(charger.peak_power(&(0.0, 1.5)), 100.0);
+        assert_eq!(charger.peak_power(&(0.0, 2.5)), 500.0);
+        assert_eq!(charger.peak_power(&(3.0, 5.0)), 400.0);
+
+        // Test 3 - A charge fits if the peak and its rate stay within the limit
+        assert!(charger.fits_grid(12, &(0.0, 1.5), 200.0));
+        assert!(!charger.fits_grid(12, &(0.0, 2.5), 500.0));
+        assert!(charger.fits_grid(12, &(0.0, 2.5), 600.0));
+
+        // Test 4 - Wait queues and a zero limit are never restricted
+        assert!(charger.fits_grid(1, &(0.0, 4.0), 1.0));
+        assert!(charger.fits_grid(19, &(0.0, 4.0), 0.0));
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_cabinet() {
+        // Create two wait queues, a slow charger and two fast chargers that share a cabinet
+        let config = ChargerConfig {
+            count: (2, 1, 2),
+            speed: (0.0, 100.0, 400.0),
+            cabinets: vec![CabinetConfig {
+                chargers: vec![1, 2],
+                power: 600.0,
+            }],
+            ..Default::default()
+        };
+        let mut charger = Charger::from_config(&config, &TimeConfig::default());
+        charger.assign(2, (0.0, 4.0), 0).unwrap();
+        charger.assign(3, (1.0, 3.0), 1).unwrap();
+        charger.assign(4, (2.0, 4.0), 2).unwrap();
+
+        // Test 1 - The chargers of the cabinet are counted from the first slow charger
+        assert_eq!(charger.cabinets[0].queues, vec![3, 4]);
+        assert!(charger.cabinet(2).is_none());
+        assert_eq!(charger.cabinet(4).unwrap().power, 600.0);
+
+        // Test 2 - The power is split once the cabinet is asked for more than it supplies
+        assert_eq!(charger.rate_at(3, 1.5), 400.0);
+        assert_eq!(charger.rate_at(3, 2.5), 300.0);
+        assert_eq!(charger.rate_at(4, 2.5), 300.0);
+        assert_eq!(charger.rate_at(4, 3.5), 400.0);
+        assert_eq!(charger.rate_at(2, 2.5), 100.0);
+        assert_eq!(charger.power_at(2.5), 700.0);
+
+        // Test 3 - The charge time at the full rate follows the shared rate
+        assert_eq!(charger.effective_time(3, 1.0, 2.0), 1.75);
+        assert_eq!(charger.service_time(3, 1.0, 1.75), 2.0);
+        assert_eq!(charger.service_time(4, 2.0, 1.0), 1.25);
+        assert_eq!(charger.effective_time(2, 1.0, 2.0), 2.0);
+        assert_eq!(charger.service_time(2, 1.0, 2.0), 2.0);
+    }
 }
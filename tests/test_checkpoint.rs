@@ -0,0 +1,161 @@
+#![allow(non_snake_case)]
+
+extern crate sa_pap;
+
+//===============================================================================
+//
+#[cfg(test)]
+mod test_checkpoint {
+    //---------------------------------------------------------------------------
+    // Import modules
+    use super::sa_pap::error::Error;
+    use super::sa_pap::lp::objectives::std_obj::StdObj;
+    use super::sa_pap::lp::objectives::Objective;
+    use super::sa_pap::sa::charger::Charger;
+    use super::sa_pap::sa::checkpoint::Checkpoint;
+    use super::sa_pap::sa::generators::gen_new_visits::GenNewVisits;
+    use super::sa_pap::sa::generators::Generator;
+    use super::sa_pap::sa::route::route_csv_generator::RouteCSVGenerator;
+    use super::sa_pap::sa::route::Route;
+    use super::sa_pap::util::rand_utils;
+
+    //---------------------------------------------------------------------------
+    //
+    fn yaml_path() -> &'static str {
+        return "./src/config/schedule-test.yaml";
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    fn csv_path() -> &'static str {
+        return "./src/config/routes.csv";
+    }
+
+    //------------------------------------------------------------------------------
+    //
+    fn general_path() -> &'static str {
+        return "./src/config/general.yaml";
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_save_load_checkpoint() {
+        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        // Generate a solution
+        let mut gsys: Box<dyn Route> =
+            Box::new(RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap());
+        let mut rng = rand_utils::seeded_rng(0);
+        gsys.run(&mut rng).unwrap();
+
+        let A = Some(gsys.get_data().param.A);
+        let mut charger: Charger = Charger::new(yaml_path(), true, A, None).unwrap();
+        let mut gsol = GenNewVisits::new();
+        gsol.run(&mut gsys, &mut charger, &mut rng).unwrap();
+
+        let mut sol = *gsys.get_data();
+        let J = StdObj::default()
+            .run(&mut sol, &mut charger, false)
+            .unwrap();
+        let terms = vec![
+            (String::from("AC"), vec![0.0, 0.0]),
+            (String::from("UC"), vec![J * 0.25, J * 0.125]),
+            (String::from("phi"), vec![1.5, 0.0]),
+            (String::from("demand"), vec![J * 0.75, J * 0.875]),
+            (String::from("DC"), vec![12.5, 10.25]),
+        ];
+        sol.dec.terms = terms.clone();
+
+        //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+        // Save and load the checkpoint
+        let c = Checkpoint {
+            step: 41,
+            seed: u64::MAX - 41,
+            J0: J,
+            J1: J + 0.1,
+            JB: J,
+            JORIG: J + 1.0,
+            scale: 1234.5,
+            temps: vec![42.25, 21.125, 10.5625],
+            origin: 1,
+            stall: 3,
+            evaluations: 4100,
+            since_best: 5,
+            sol_best: sol.clone(),
+            sol_current: sol.clone(),
+            schedule: charger.schedule.clone(),
+            Jb: vec![J + 1.0, J],
+            Jc: vec![J + 1.0, J + 0.5],
+            Jn: vec![J + 1.0, J + 0.1],
+            terms: terms.clone(),
+            reheat: vec![0, 1],
+            K: vec![0, 100],
+        };
+
+        let path = std::env::temp_dir()
+            .join("sa-pap-test-checkpoint.yaml")
+            .to_string_lossy()
+            .to_string();
+        c.save(&path).unwrap();
+        let loaded = Checkpoint::load(&path).unwrap().unwrap();
+        Checkpoint::remove(&path).unwrap();
+
+        // Test 0 - The loaded checkpoint matches the saved checkpoint
+        assert_eq!(loaded.sol_best, sol);
+        assert_eq!(loaded.sol_current, sol);
+        assert_eq!(loaded, c);
+
+        // Test 1 - A restored charger matches the original charger
+        let mut restored: Charger = Charger::new(yaml_path(), true, A, None).unwrap();
+        restored.set_schedule(loaded.schedule).unwrap();
+        assert!(restored == charger, "The restored charger does not match");
+
+        // Test 2 - Missing checkpoints are not loaded
+        assert!(Checkpoint::load(&path).unwrap().is_none());
+        assert!(Checkpoint::remove(&path).is_ok());
+
+        // Test 3 - Empty and malformed checkpoints are reported as errors
+        for text in ["", "step: [", "step: 41\n"] {
+            std::fs::write(&path, text).unwrap();
+            let res = Checkpoint::load(&path);
+            Checkpoint::remove(&path).unwrap();
+            assert!(
+                matches!(res, Err(Error::Config(ref e)) if e.file == path),
+                "The checkpoint {:?} was not rejected",
+                text
+            );
+        }
+
+        // Test 4 - Missing and mistyped keys are reported by their key
+        c.save(&path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        Checkpoint::remove(&path).unwrap();
+        let edits = [
+            ("reheat", text.replace("\nreheat:", "\nreheats:")),
+            ("temps", text.replace("\ntemps:", "\ntemps: 1\nold_temps:")),
+            (
+                "sol_best.param.A",
+                text.replace(
+                    "\nsol_best:\n  param:\n    A:",
+                    "\nsol_best:\n  param:\n    B:",
+                ),
+            ),
+            (
+                "reheat",
+                text.replace("\nreheat:", "\nreheat: []\nreheats:"),
+            ),
+        ];
+        for (key, edit) in edits {
+            assert_ne!(edit, text);
+            std::fs::write(&path, edit).unwrap();
+            let res = Checkpoint::load(&path);
+            Checkpoint::remove(&path).unwrap();
+            assert!(
+                matches!(res, Err(Error::Config(ref e)) if e.file == path && e.key == key),
+                "The key {} was not reported: {:?}",
+                key,
+                res.err()
+            );
+        }
+    }
+}
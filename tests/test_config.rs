@@ -0,0 +1,395 @@
+extern crate sa_pap;
+
+//===============================================================================
+//
+#[cfg(test)]
+mod test_config {
+    //---------------------------------------------------------------------------
+    // Import modules
+    use super::sa_pap::lp::objectives::Weights;
+    use super::sa_pap::sa::config::general::GeneralConfig;
+    use super::sa_pap::sa::config::schedule::ScheduleConfig;
+    use super::sa_pap::sa::config::{CabinetConfig, ConfigError, Scale};
+    use super::sa_pap::sa::data::{Degradation, Demand};
+    use super::sa_pap::sa::temp_func::CoolSchedule;
+    use yaml_rust::YamlLoader;
+
+    //---------------------------------------------------------------------------
+    //
+    fn yaml_path() -> &'static str {
+        return "./src/config/schedule-test.yaml";
+    }
+
+    //---------------------------------------------------------------------------
+    // Required sections of a schedule configuration
+    //
+    fn base() -> &'static str {
+        return "
+buses: {num_bus: 2, num_visit: 10, bat_capacity: 388.0, min_charge: 0.25, dis_rate: 30.0,
+        min_rest: 0.1, max_rest: 0.2}
+initial_charge: {min: 0.8, max: 0.9}
+final_charge: 0.7
+chargers: {slow: {num: 1, rate: 30.0}, fast: {num: 2, rate: 400.0}}
+";
+    }
+
+    //---------------------------------------------------------------------------
+    // Check a schedule configuration given as a YAML string
+    //
+    fn schedule(s: &str) -> Result<ScheduleConfig, ConfigError> {
+        let y = YamlLoader::load_from_str(s).unwrap();
+        return ScheduleConfig::from_yaml(&y[0], "schedule.yaml");
+    }
+
+    //---------------------------------------------------------------------------
+    // Check a general configuration given as a YAML string
+    //
+    fn general(s: &str) -> Result<GeneralConfig, ConfigError> {
+        let y = YamlLoader::load_from_str(s).unwrap();
+        return GeneralConfig::from_yaml(&y[0], "general.yaml");
+    }
+
+    //---------------------------------------------------------------------------
+    // Key named by the error of an invalid configuration
+    //
+    fn error_key(r: Result<ScheduleConfig, ConfigError>) -> String {
+        let e = r.unwrap_err();
+        assert_eq!(e.file, "schedule.yaml");
+        return e.key;
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_shipped_configs() {
+        // Test 1 - The configuration files of the repository are valid
+        assert!(GeneralConfig::load("./src/config/general.yaml").is_ok());
+        assert!(ScheduleConfig::load("./src/config/schedule.yaml").is_ok());
+
+        // Test 2 - The test schedule is read into typed settings
+        let s = ScheduleConfig::load(yaml_path()).unwrap();
+        assert_eq!(s.time.k, 100);
+        assert_eq!(s.buses.num_bus, 11);
+        assert_eq!(s.chargers.count, (11, 7, 4));
+        assert_eq!(s.chargers.speed, (0.0, 100.0, 400.0));
+        assert_eq!(s.final_charge, 0.95);
+        assert_eq!(s.ignore.len(), 24);
+
+        // Test 3 - A missing file names the file
+        let e = ScheduleConfig::load("bad_file.yaml").unwrap_err();
+        assert_eq!(e.file, "bad_file.yaml");
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_defaults() {
+        // Test 1 - Missing run sections take their defaults
+        let s = schedule(base()).unwrap();
+        assert_eq!((s.time.bod, s.time.eod, s.time.k), (0.0, 24.0, 500));
+        assert_eq!(s.temp.schedule, CoolSchedule::Geometric);
+        assert_eq!(s.temp.scale, Scale::Fixed(9000000.0));
+        assert_eq!((s.search.min, s.search.max), (500, 500));
+        assert_eq!(s.reheat.steps, 0);
+        assert_eq!((s.pt.replicas, s.pt.t_max), (8, 90000.0));
+        assert_eq!(s.buses.max_charge, 1.0);
+        assert!(s.ignore.is_empty());
+
+        // Test 2 - An empty general configuration takes its defaults
+        let g = general("{}").unwrap();
+        assert_eq!(g, GeneralConfig::default());
+        assert_eq!((g.cores, g.seed, g.solver.as_str()), (1, None, "sa"));
+
+        // Test 3 - Flags are booleans or 0/1 and a negative seed draws a random seed
+        let g = general("{plot: 1, run_all_constr: true, seed: -1}").unwrap();
+        assert!(g.plot && g.run_all_constr);
+        assert_eq!(g.seed, None);
+        assert_eq!(general("seed: 7").unwrap().seed, Some(7));
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_range_checks() {
+        // Test 1 - The minimum charge may not exceed the maximum charge
+        let s = base().replace("min_charge: 0.25", "min_charge: 0.5, max_charge: 0.4");
+        assert_eq!(error_key(schedule(&s)), "buses.min_charge");
+
+        // Test 2 - The final charge may not exceed the maximum initial charge
+        let s = base().replace("final_charge: 0.7", "final_charge: 0.95");
+        assert_eq!(error_key(schedule(&s)), "final_charge");
+
+        // Test 3 - The Geometric step size must be below 1
+        let s = format!("{}temp: {{type: Geometric, delta: 1.0}}", base());
+        assert_eq!(error_key(schedule(&s)), "temp.delta");
+        let s = format!("{}temp: {{type: Linear, delta: 1.0}}", base());
+        assert!(schedule(&s).is_ok());
+
+        // Test 4 - Charges are fractions of the battery capacity
+        let s = base().replace("min: 0.8", "min: 1.2");
+        assert_eq!(error_key(schedule(&s)), "initial_charge.min");
+
+        // Test 5 - The day ends after it begins
+        let s = format!("{}time: {{BOD: 10.0, EOD: 5.0}}", base());
+        assert_eq!(error_key(schedule(&s)), "time.EOD");
+
+        // Test 6 - Piecewise points must have increasing steps
+        let s = format!(
+            "{}temp: {{type: Piecewise, points: [[5, 10.0], [2, 1.0]]}}",
+            base()
+        );
+        assert_eq!(error_key(schedule(&s)), "temp.points");
+
+        // Test 7 - The Logarithmic schedule has a length instead of a step size
+        let s = format!("{}temp: {{type: Logarithmic, delta: 0.0}}", base());
+        assert_eq!(schedule(&s).unwrap().temp.steps, 4000);
+        let s = format!("{}temp: {{type: Logarithmic, steps: 1}}", base());
+        assert_eq!(error_key(schedule(&s)), "temp.steps");
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_errors_name_key() {
+        // Test 1 - A missing required key is named
+        let s = base().replace("bat_capacity: 388.0, ", "");
+        let e = schedule(&s).unwrap_err();
+        assert_eq!(e.key, "buses.bat_capacity");
+        assert_eq!(
+            e.to_string(),
+            "schedule.yaml: buses.bat_capacity: is missing"
+        );
+
+        // Test 2 - A value of the wrong type is named
+        let s = base().replace("num: 1,", "num: many,");
+        assert_eq!(error_key(schedule(&s)), "chargers.slow.num");
+
+        // Test 3 - An unknown option of the general configuration is named
+        let e = general("solver: \"annealing\"").unwrap_err();
+        assert_eq!(
+            (e.file.as_str(), e.key.as_str()),
+            ("general.yaml", "solver")
+        );
+        assert_eq!(general("cores: 0").unwrap_err().key, "cores");
+
+        // Test 4 - The island model requires the CSV schedule
+        let e = general("{island_interval: 10, schedule: rand}").unwrap_err();
+        assert_eq!(e.key, "island_interval");
+        assert!(general("{island_interval: 10, schedule: csv}").is_ok());
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_tariff() {
+        // Test 1 - Without a tariff section energy is priced at 1
+        let s = schedule(base()).unwrap();
+        assert_eq!(s.tariff.off_peak, 1.0);
+        assert!(s.tariff.tariff(0.0, 24.0).bands.is_empty());
+
+        // Test 2 - Bands repeat on their weekdays over the time horizon
+        let s = format!(
+            "{}tariff: {{day: fri, off_peak: 0.1, bands: [
+                {{start: 16.0, end: 21.0, price: 0.3, days: [mon, fri]}},
+                {{start: 8.0, end: 16.0, price: 0.2}}]}}",
+            base()
+        );
+        let t = schedule(&s).unwrap().tariff;
+        assert_eq!(t.day, 4);
+        assert_eq!(t.bands[1].days.len(), 7);
+        let tariff = t.tariff(10.0, 80.0);
+        assert_eq!(tariff.base, 0.1);
+        assert_eq!(
+            tariff.bands,
+            vec![
+                (10.0, 16.0, 0.2),
+                (16.0, 21.0, 0.3),
+                (32.0, 40.0, 0.2),
+                (56.0, 64.0, 0.2)
+            ]
+        );
+
+        // Test 3 - Bands of the same weekday may not overlap
+        let s = format!(
+            "{}tariff: {{bands: [{{start: 8.0, end: 16.0, price: 0.2}},
+                {{start: 12.0, end: 20.0, price: 0.3, days: [sun]}}]}}",
+            base()
+        );
+        assert_eq!(error_key(schedule(&s)), "tariff.bands[1]");
+
+        // Test 4 - Bands lie in a day and weekdays are named
+        let s = format!(
+            "{}tariff: {{bands: [{{start: 20.0, end: 25.0, price: 0.2}}]}}",
+            base()
+        );
+        assert_eq!(error_key(schedule(&s)), "tariff.bands[0].end");
+        let s = format!(
+            "{}tariff: {{bands: [{{start: 8.0, end: 9.0, price: 0.2, days: [monday]}}]}}",
+            base()
+        );
+        assert_eq!(error_key(schedule(&s)), "tariff.bands[0].days[0]");
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_demand() {
+        // Test 1 - The default window is 15 minutes without a billed peak
+        let s = schedule(base()).unwrap();
+        assert_eq!(s.demand, Demand::default());
+        assert_eq!((s.demand.window, s.demand.peak), (15, 0.0));
+
+        // Test 2 - The window, price and billed peak are read
+        let s = format!("{}demand: {{window: 30, price: 12.5, peak: 800}}", base());
+        let d = schedule(&s).unwrap().demand;
+        assert_eq!((d.window, d.price, d.peak), (30, 12.5, 800.0));
+
+        // Test 3 - The window must be positive and the peak not negative
+        let s = format!("{}demand: {{window: 0}}", base());
+        assert_eq!(error_key(schedule(&s)), "demand.window");
+        let s = format!("{}demand: {{peak: -1.0}}", base());
+        assert_eq!(error_key(schedule(&s)), "demand.peak");
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_degradation() {
+        // Test 1 - Without a degradation section the batteries do not wear
+        let s = schedule(base()).unwrap();
+        assert_eq!(s.degradation, Degradation::default());
+        assert!(s.degradation.cost.is_empty());
+
+        // Test 2 - The battery price is a single price or one price per bus
+        let s = format!("{}degradation: {{cost: 5000, soc: 0.9, rate: 0.5}}", base());
+        let d = schedule(&s).unwrap().degradation;
+        assert_eq!((d.cost, d.soc, d.rate), (vec![5000.0], 0.9, 0.5));
+        let s = format!("{}degradation: {{cost: [5000, 6000.0]}}", base());
+        let d = schedule(&s).unwrap().degradation;
+        assert_eq!(d.cost, vec![5000.0, 6000.0]);
+
+        // Test 3 - A list of prices must cover every bus
+        let s = format!("{}degradation: {{cost: [5000, 6000, 7000]}}", base());
+        assert_eq!(error_key(schedule(&s)), "degradation.cost");
+        let s = format!("{}degradation: {{cost: [5000, -1]}}", base());
+        assert_eq!(error_key(schedule(&s)), "degradation.cost[1]");
+        let s = format!("{}degradation: {{cycles: 0}}", base());
+        assert_eq!(error_key(schedule(&s)), "degradation.cycles");
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_weights() {
+        // Test 1 - Without an objective section every term has a unit weight
+        let s = schedule(base()).unwrap();
+        assert_eq!(s.weights, Weights::default());
+        let names: Vec<&str> = s.weights.terms.iter().map(|t| t.0.as_str()).collect();
+        assert_eq!(names, vec!["AC", "UC", "phi", "demand", "DC"]);
+        assert_eq!((s.weights.get("AC"), s.weights.get("demand")), (1.0, 1.0));
+
+        // Test 2 - The weights that are set replace the unit weights
+        let s = format!("{}objective: {{UC: 2.5, demand: 0, DC: 0.5}}", base());
+        let w = schedule(&s).unwrap().weights;
+        assert_eq!(
+            (
+                w.get("AC"),
+                w.get("UC"),
+                w.get("phi"),
+                w.get("demand"),
+                w.get("DC")
+            ),
+            (1.0, 2.5, 1.0, 0.0, 0.5)
+        );
+
+        // Test 3 - The weights may not be negative
+        let s = format!("{}objective: {{phi: -1}}", base());
+        assert_eq!(error_key(schedule(&s)), "objective.phi");
+
+        // Test 4 - Every weight belongs to a term of the objective function
+        let s = format!("{}objective: {{XY: 1}}", base());
+        assert_eq!(error_key(schedule(&s)), "objective.XY");
+        let s = format!("{}objective: 1", base());
+        assert_eq!(error_key(schedule(&s)), "objective");
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_mosa() {
+        // Test 1 - The soft limit defaults to twice the archive size
+        let m = schedule(base()).unwrap().mosa;
+        assert_eq!((m.archive, m.soft), (20, 40));
+        assert_eq!((m.t_max, m.t_min, m.alpha), (100.0, 0.01, 0.9));
+        let s = format!("{}mosa: {{archive: 5}}", base());
+        assert_eq!(schedule(&s).unwrap().mosa.soft, 10);
+
+        // Test 2 - The soft limit may not be below the archive size
+        let s = format!("{}mosa: {{archive: 5, soft: 4}}", base());
+        assert_eq!(error_key(schedule(&s)), "mosa.soft");
+
+        // Test 3 - The temperature falls and the cooling factor is below 1
+        let s = format!("{}mosa: {{t_max: 1.0, t_min: 2.0}}", base());
+        assert_eq!(error_key(schedule(&s)), "mosa.t_min");
+        let s = format!("{}mosa: {{alpha: 1.0}}", base());
+        assert_eq!(error_key(schedule(&s)), "mosa.alpha");
+
+        // Test 4 - The multi-objective solver can be chosen
+        assert_eq!(general("solver: mosa").unwrap().solver, "mosa");
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_grid() {
+        // Test 1 - Without a grid section the connection is unlimited
+        assert_eq!(schedule(base()).unwrap().grid, 0.0);
+
+        // Test 2 - The power limit is read from the grid section
+        let s = format!("{}grid: {{limit: 750.0}}", base());
+        assert_eq!(schedule(&s).unwrap().grid, 750.0);
+
+        // Test 3 - The power limit may not be negative
+        let s = format!("{}grid: {{limit: -1.0}}", base());
+        assert_eq!(error_key(schedule(&s)), "grid.limit");
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_cabinets() {
+        let cabinets = |c: &str| -> String {
+            return base().replace(
+                "rate: 400.0}}",
+                &format!("rate: 400.0}}, cabinets: {}}}", c),
+            );
+        };
+
+        // Test 1 - Without cabinets every charger has its own power
+        assert!(schedule(base()).unwrap().chargers.cabinets.is_empty());
+
+        // Test 2 - The chargers of a cabinet are counted from the first slow charger
+        let s = cabinets("[{chargers: [1, 2], power: 600.0}]");
+        assert_eq!(
+            schedule(&s).unwrap().chargers.cabinets,
+            vec![CabinetConfig {
+                chargers: vec![1, 2],
+                power: 600.0
+            }]
+        );
+
+        // Test 3 - A cabinet names existing chargers that are in no other cabinet
+        let s = cabinets("[{chargers: [0, 3], power: 600.0}]");
+        assert_eq!(error_key(schedule(&s)), "chargers.cabinets[0].chargers[1]");
+        let s = cabinets("[{chargers: [0], power: 60.0}, {chargers: [0], power: 60.0}]");
+        assert_eq!(error_key(schedule(&s)), "chargers.cabinets[1].chargers[0]");
+        let s = cabinets("[{chargers: [], power: 60.0}]");
+        assert_eq!(error_key(schedule(&s)), "chargers.cabinets[0].chargers");
+
+        // Test 4 - The power of a cabinet is required and positive
+        let s = cabinets("[{chargers: [0]}]");
+        assert_eq!(error_key(schedule(&s)), "chargers.cabinets[0].power");
+        let s = cabinets("[{chargers: [0], power: 0}]");
+        assert_eq!(error_key(schedule(&s)), "chargers.cabinets[0].power");
+    }
+}
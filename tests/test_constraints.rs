@@ -11,6 +11,7 @@ mod test_packing_constraints {
     use super::sa_pap::sa::charger::Charger;
     use super::sa_pap::sa::route::route_csv_generator::RouteCSVGenerator;
     use super::sa_pap::sa::route::Route;
+    use super::sa_pap::util::rand_utils;
     use sa_pap::lp::constraints::packing::psi_sigma::PsiSigma;
     use sa_pap::lp::constraints::packing::service_time::ServiceTime;
     use sa_pap::lp::constraints::packing::space_time_big_o::SpaceTimeBigO;
@@ -40,11 +41,12 @@ mod test_packing_constraints {
     #[test]
     fn test_space_time_big_o_psi() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path());
-        let mut charger: Charger = Charger::new(yaml_path(), true, None, None);
+            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap();
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Set some visit queues
         {
@@ -85,11 +87,12 @@ mod test_packing_constraints {
     #[test]
     fn test_space_time_big_o_sigma() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path());
-        let mut charger: Charger = Charger::new(yaml_path(), true, None, None);
+            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap();
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Set some visit queues
         {
@@ -135,11 +138,12 @@ mod test_packing_constraints {
     #[test]
     fn test_service_time() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path());
-        let mut charger: Charger = Charger::new(yaml_path(), true, None, None);
+            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap();
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Extract variables
         let n = rg.data.param.N.clone();
@@ -178,11 +182,12 @@ mod test_packing_constraints {
     fn test_psi_sigma() {
         // Test 0 - Obvious case
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path());
-        let mut charger: Charger = Charger::new(yaml_path(), true, None, None);
+            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap();
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Set some visit queues
         {
@@ -210,10 +215,10 @@ mod test_packing_constraints {
 
         // Test 1 - All time
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path());
+            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        rg.run(&mut rng).unwrap();
 
         // Set some visit queues
         {
@@ -238,10 +243,10 @@ mod test_packing_constraints {
 
         // Test 2 - All queue
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path());
+            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        rg.run(&mut rng).unwrap();
 
         // Set some visit queues
         {
@@ -277,11 +282,12 @@ mod test_packing_constraints {
     #[test]
     fn test_valid_init_dep_end_time() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path());
-        let mut charger: Charger = Charger::new(yaml_path(), true, None, None);
+            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap();
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Extract variables
         let n = rg.data.param.N.clone();
@@ -321,9 +327,10 @@ mod test_packing_constraints {
 mod test_dynamic_constraints {
     //---------------------------------------------------------------------------
     // Import modules
-    use super::sa_pap::sa::charger::Charger;
+    use super::sa_pap::sa::charger::{Cabinet, Charger};
     use super::sa_pap::sa::route::route_csv_generator::RouteCSVGenerator;
     use super::sa_pap::sa::route::Route;
+    use super::sa_pap::util::rand_utils;
     use sa_pap::lp::constraints::dynamic::charge_propagation::ChargePropagate;
     use sa_pap::lp::constraints::dynamic::init_final_charge::InitFinalCharge;
     use sa_pap::lp::constraints::dynamic::scalar_to_vector_queue::ScalarToVectorQueue;
@@ -353,11 +360,12 @@ mod test_dynamic_constraints {
     #[test]
     fn test_charge_propagation() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path());
-        let mut charger: Charger = Charger::new(yaml_path(), true, None, None);
+            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap();
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Update initial and final charge times
         {
@@ -386,16 +394,62 @@ mod test_dynamic_constraints {
         }
     }
 
+    //---------------------------------------------------------------------------
+    // Test charge propagation on a charger that shares a cabinet
+    #[test]
+    fn test_shared_charge_propagation() {
+        let mut rg: RouteCSVGenerator =
+            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap();
+
+        // Load the CSV schedule
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
+        let mut charger: Charger =
+            Charger::new(yaml_path(), true, Some(rg.data.param.A), None).unwrap();
+
+        // Share the power of a fast charger with a fast charger that is busy all day
+        let q = charger.schedule.len() - 1;
+        let rate = charger.get_charge_rate(q);
+        charger.cabinets.push(Cabinet {
+            queues: vec![q - 1, q],
+            power: rate,
+        });
+        charger.assign(q - 1, (0.0, 24.0), 0).unwrap();
+
+        // Charge a visit that is 100 KWh short of a full battery for an hour
+        let i = (0..rg.data.param.N)
+            .find(|i| rg.data.param.gam[*i] >= 0)
+            .unwrap();
+        let kappa = rg.data.param.k[rg.data.param.Gam[i] as usize];
+        rg.data.dec.v[i] = q;
+        rg.data.dec.u[i] = rg.data.param.a[i];
+        rg.data.dec.s[i] = 1.0;
+        rg.data.dec.d[i] = rg.data.param.a[i] + 1.0;
+        rg.data.dec.eta[i] = kappa - 100.0;
+        assert_eq!(rg.data.param.r[q], rate);
+
+        // Test 1 - At half of its rate the charger needs twice the time to fill the battery
+        assert!(ChargePropagate::run(&mut rg.data, &mut charger, i, 0));
+        assert!((rg.data.dec.s[i] - 200.0 / rate).abs() < 1e-4);
+
+        // Test 2 - The next visit starts with a full battery less the route discharge
+        let next = rg.data.param.gam[i] as usize;
+        let eta = kappa - rg.data.param.l[i];
+        assert!((rg.data.dec.eta[next] - eta).abs() < 1e-2);
+        assert!(ChargePropagate::shared_violation(&rg.data, &charger, i) < 1e-2);
+    }
+
     //---------------------------------------------------------------------------
     // Test initial/final charge constraint
     #[test]
     fn test_init_final_charge_propagation() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path());
-        let mut charger: Charger = Charger::new(yaml_path(), true, None, None);
+            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap();
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Run constraint
 
@@ -448,11 +502,12 @@ mod test_dynamic_constraints {
     #[test]
     fn test_init_charge_propagation() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path());
-        let mut charger: Charger = Charger::new(yaml_path(), true, None, None);
+            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap();
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Run constraint
 
@@ -483,11 +538,12 @@ mod test_dynamic_constraints {
     #[should_panic]
     fn test_final_charge_insufficient_charge() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path());
-        let mut charger: Charger = Charger::new(yaml_path(), true, None, None);
+            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap();
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Run constraint
 
@@ -521,11 +577,12 @@ mod test_dynamic_constraints {
     #[test]
     fn test_scalar_to_vector_queue_propagation() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path());
-        let mut charger: Charger = Charger::new(yaml_path(), true, None, None);
+            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap();
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Run constraint
 
@@ -552,3 +609,171 @@ mod test_dynamic_constraints {
         }
     }
 }
+
+//===============================================================================
+//
+#[cfg(test)]
+mod test_constraint_report {
+    //---------------------------------------------------------------------------
+    // Import modules
+    use super::sa_pap::error::Error;
+    use super::sa_pap::lp::constraints::packing::grid_capacity::GridCapacity;
+    use super::sa_pap::lp::constraints::report::{ConstraintKind, ConstraintReport};
+    use super::sa_pap::lp::constraints::Constraint;
+    use super::sa_pap::lp::objectives::std_obj::StdObj;
+    use super::sa_pap::lp::objectives::Objective;
+    use super::sa_pap::sa::charger::{Cabinet, Charger};
+    use super::sa_pap::sa::generators::gen_wait_queue::GenWaitQueue;
+    use super::sa_pap::sa::generators::Generator;
+    use super::sa_pap::sa::route::route_csv_generator::RouteCSVGenerator;
+    use super::sa_pap::sa::route::Route;
+    use super::sa_pap::util::rand_utils;
+
+    //---------------------------------------------------------------------------
+    // Evaluate the schedule that keeps every bus on its wait queue
+    //
+    fn wait_schedule() -> (Box<dyn Route>, Charger) {
+        let mut rg: Box<dyn Route> = Box::new(
+            RouteCSVGenerator::new(
+                "./src/config/schedule-test.yaml",
+                "./src/config/general.yaml",
+                "./src/config/routes.csv",
+            )
+            .unwrap(),
+        );
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
+
+        let mut charger: Charger = Charger::new(
+            "./src/config/schedule-test.yaml",
+            true,
+            Some(rg.get_data().param.A),
+            None,
+        )
+        .unwrap();
+        GenWaitQueue::new()
+            .run(&mut rg, &mut charger, &mut rng)
+            .unwrap();
+
+        let mut data = *rg.get_data();
+        StdObj::default()
+            .run(&mut data, &mut charger, true)
+            .unwrap();
+        rg.set_data(Box::new(data));
+
+        return (rg, charger);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_constraint_report() {
+        let (mut rg, charger) = wait_schedule();
+        let data = *rg.get_data();
+
+        // Test 1 - Without charging, only the final charges are violated
+        let report = ConstraintReport::new(&data, &charger);
+        assert!(!report.is_feasible());
+        let finals = report.of(ConstraintKind::InitFinalCharge);
+        assert_eq!(finals.len(), report.violations.len());
+        for v in finals {
+            let k = data.param.k[data.param.Gam[v.i] as usize];
+            assert!(data.param.beta[v.i] > 0.0);
+            assert_eq!(v.j, None);
+            assert!((v.amount - (data.param.beta[v.i] * k - data.dec.eta[v.i])).abs() < 1e-3);
+        }
+
+        // Test 2 - Two visits that share a queue at the same time overlap
+        let (i, j) = (data.param.A, data.param.A + 1);
+        let mut d = data.clone();
+        d.dec.v[j] = d.dec.v[i];
+        d.dec.u[j] = d.dec.u[i];
+        d.dec.d[j] = d.dec.d[i];
+        d.dec.s[j] = d.dec.s[i];
+        let report = ConstraintReport::new(&d, &charger);
+        let overlap = report.of(ConstraintKind::PsiSigma);
+        assert_eq!(overlap.len(), 1);
+        assert_eq!((overlap[0].i, overlap[0].j), (i, Some(j)));
+        assert_eq!(overlap[0].amount, 1.0);
+
+        // Test 3 - A visit on the wait queue that is moved before it arrives
+        let mut d = data.clone();
+        assert_eq!(d.dec.u[i], d.param.a[i]);
+        d.dec.u[i] -= 0.5;
+        d.dec.d[i] -= 0.5;
+        let report = ConstraintReport::new(&d, &charger);
+        let early = report.of(ConstraintKind::ValidInitDepEndTimes);
+        assert_eq!(early.len(), 1);
+        assert_eq!(early[0].i, i);
+        assert!((early[0].amount - 0.5).abs() < 1e-3);
+
+        // Test 4 - A service time that does not match the attach and detach times
+        let mut d = data.clone();
+        d.dec.s[i] += 0.25;
+        let report = ConstraintReport::new(&d, &charger);
+        let service = report.of(ConstraintKind::ServiceTime);
+        assert_eq!(service.len(), 1);
+        assert!((service[0].amount - 0.25).abs() < 1e-3);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_grid_capacity() {
+        let (mut rg, charger) = wait_schedule();
+        let data = *rg.get_data();
+
+        // Move two visits onto fast chargers at the same time (400 KW each)
+        let (A, i, j) = (data.param.A, data.param.A, data.param.A + 1);
+        let (q0, q1) = (A + 7, A + 8);
+        let ud = (data.dec.u[i], data.dec.u[i] + 0.1);
+        let mut d = data.clone();
+        let mut ch = charger.clone();
+        for (k, q) in [(i, q0), (j, q1)] {
+            d.dec.v[k] = q;
+            d.dec.w[k] = vec![false; d.param.Q];
+            d.dec.w[k][q] = true;
+            d.dec.u[k] = ud.0;
+            d.dec.d[k] = ud.1;
+            d.dec.s[k] = ud.1 - ud.0;
+            ch.assign(q, ud, d.param.Gam[k] as usize).unwrap();
+        }
+
+        // Test 1 - Without a limit the grid connection is never exceeded
+        assert!(GridCapacity::run(&mut d, &mut ch, i, 0));
+        assert_eq!(GridCapacity::violation(&d, i, 0), 0.0);
+
+        // Test 2 - The summed charge rate may reach the limit
+        d.param.grid = 800.0;
+        assert!(GridCapacity::run(&mut d, &mut ch, i, 0));
+        assert_eq!(GridCapacity::violation(&d, j, 0), 0.0);
+
+        // Test 3 - Both visits report the power above the limit
+        d.param.grid = 500.0;
+        assert!(!GridCapacity::run(&mut d, &mut ch, i, 0));
+        assert_eq!(GridCapacity::violation(&d, i, 0), 300.0);
+        assert_eq!(GridCapacity::violation(&d, j, 0), 300.0);
+        let report = ConstraintReport::new(&d, &ch);
+        let grid = report.of(ConstraintKind::GridCapacity);
+        assert_eq!(grid.len(), 2);
+        assert_eq!((grid[0].i, grid[1].i), (i, j));
+
+        // Test 4 - The evaluation rejects the schedule as infeasible
+        match StdObj::default().evaluate(&mut d, &mut ch, false) {
+            Err(Error::Infeasible(_)) => {}
+            _ => panic!("The grid capacity was not enforced."),
+        }
+
+        // Test 5 - The chargers of a cabinet draw at most the power of the cabinet
+        ch.cabinets.push(Cabinet {
+            queues: vec![q0, q1],
+            power: 500.0,
+        });
+        assert!(GridCapacity::run(&mut d, &mut ch, i, 0));
+        assert_eq!(GridCapacity::violation(&d, i, 0), 300.0);
+        assert_eq!(GridCapacity::shared_violation(&d, &ch, i), 0.0);
+        assert_eq!(GridCapacity::shared_violation(&d, &ch, j), 0.0);
+        let report = ConstraintReport::new(&d, &ch);
+        assert!(report.of(ConstraintKind::GridCapacity).is_empty());
+    }
+}
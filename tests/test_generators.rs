@@ -12,6 +12,7 @@ mod test_generators {
     use super::sa_pap::sa::generators::Generator;
     use super::sa_pap::sa::route::route_csv_generator::RouteCSVGenerator;
     use super::sa_pap::sa::route::Route;
+    use super::sa_pap::util::rand_utils;
 
     //---------------------------------------------------------------------------
     //
@@ -36,9 +37,11 @@ mod test_generators {
     #[test]
     fn test_schedule_generator() {
         // Create CSV generator
-        let rg: RouteCSVGenerator = RouteCSVGenerator::new(yaml_path(), general_path(), csv_path());
+        let rg: RouteCSVGenerator =
+            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap();
         let mut rg: Box<dyn Route> = Box::new(rg);
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Create charger
         let mut charger: Charger = Charger::new(
@@ -46,7 +49,8 @@ mod test_generators {
             true,
             Some(rg.get_data().param.A as usize),
             None,
-        );
+        )
+        .unwrap();
 
         // Create the generator
         let mut sg = GenWaitQueue::new();
@@ -55,7 +59,7 @@ mod test_generators {
         let ep = rg.get_data().param.ts;
 
         // Run the generator
-        sg.run(&mut rg, &mut charger);
+        sg.run(&mut rg, &mut charger, &mut rng).unwrap();
 
         // Test 0 - Check first index of a few chargers
         assert_eq!(charger.schedule[0][0].t, (0.0, 0.0 + ep));
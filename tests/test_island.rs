@@ -0,0 +1,181 @@
+#![allow(non_snake_case)]
+
+extern crate sa_pap;
+
+//===============================================================================
+//
+#[cfg(test)]
+mod test_island {
+    //---------------------------------------------------------------------------
+    // Import modules
+    use super::sa_pap::lp::objectives::std_obj::StdObj;
+    use super::sa_pap::lp::objectives::Objective;
+    use super::sa_pap::sa::charger::{Assignment, Charger};
+    use super::sa_pap::sa::data::Data;
+    use super::sa_pap::sa::generators::gen_new_visits::GenNewVisits;
+    use super::sa_pap::sa::generators::tweak_schedule::TweakSchedule;
+    use super::sa_pap::sa::generators::Generator;
+    use super::sa_pap::sa::island::Island;
+    use super::sa_pap::sa::route::route_csv_generator::RouteCSVGenerator;
+    use super::sa_pap::sa::route::Route;
+    use super::sa_pap::sa::temp_func::{CoolSchedule::Geometric, TempFunc};
+    use super::sa_pap::sa::{Results, SA};
+    use super::sa_pap::util::rand_utils;
+
+    //---------------------------------------------------------------------------
+    //
+    fn yaml_path() -> &'static str {
+        return "./src/config/schedule-test.yaml";
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    fn csv_path() -> &'static str {
+        return "./src/config/routes.csv";
+    }
+
+    //------------------------------------------------------------------------------
+    //
+    fn general_path() -> &'static str {
+        return "./src/config/general.yaml";
+    }
+
+    //---------------------------------------------------------------------------
+    // Generate a solution with the given seed
+    //
+    fn solution(seed: u64) -> (Data, Charger) {
+        let mut gsys: Box<dyn Route> =
+            Box::new(RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap());
+        let mut rng = rand_utils::seeded_rng(seed);
+        gsys.run(&mut rng).unwrap();
+
+        let A = Some(gsys.get_data().param.A);
+        let mut charger: Charger = Charger::new(yaml_path(), true, A, None).unwrap();
+        GenNewVisits::new()
+            .run(&mut gsys, &mut charger, &mut rng)
+            .unwrap();
+
+        return (*gsys.get_data(), charger);
+    }
+
+    //---------------------------------------------------------------------------
+    // Run a short simulated annealing run on the given island
+    //
+    fn run_sa(seed: u64, name: &str, island: Island) -> Results {
+        let mut tf = Box::new(TempFunc::new(Geometric, 8.0, 0.5, true));
+        let gsys =
+            Box::new(RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap());
+
+        let mut sa: SA = SA::new(
+            general_path(),
+            yaml_path(),
+            Box::new(GenNewVisits::new()),
+            gsys,
+            Box::new(TweakSchedule::new()),
+            &mut tf,
+            seed,
+        )
+        .unwrap();
+
+        // Keep the checkpoints of the test out of the data directory
+        let ckpt = std::env::temp_dir().join(format!("sa-pap-test-{}.yaml", name));
+        sa.set_checkpoint_path(&ckpt.to_string_lossy());
+        sa.set_island(island);
+
+        return sa.run(false).unwrap();
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_migrate() {
+        let (a, ca) = solution(0);
+        let (b, cb) = solution(1);
+        let island = Island::new(2, false);
+        let other = island.clone();
+
+        // Test 1 - Migration happens every `interval` temperature steps
+        assert!(!island.is_migration_step(0));
+        assert!(island.is_migration_step(1));
+        assert!(!Island::new(0, false).is_migration_step(1));
+
+        // Test 2 - The first island shares its best solution
+        assert!(island.migrate(10.0, &a, &ca).is_none());
+        assert_eq!(other.best().unwrap().score, 10.0);
+
+        // Test 3 - A worse island adopts the best solution of the islands
+        let m = other
+            .migrate(20.0, &b, &cb)
+            .expect("The global best was not adopted");
+        assert_eq!(m.score, 10.0);
+        assert!(*m.data == a);
+        assert!(*m.charger == ca);
+
+        // Test 4 - A better island replaces the best solution of the islands
+        assert!(other.migrate(5.0, &b, &cb).is_none());
+        assert!(*island.best().unwrap().data == b);
+        assert!(island.migrate(5.0, &a, &ca).is_none());
+        assert!(*island.best().unwrap().data == b);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_crossover() {
+        let (a, mut ca) = solution(0);
+        let (b, _) = solution(1);
+        let mut rng = rand_utils::seeded_rng(0);
+        let child = Island::crossover(&a, &b, &mut rng);
+
+        // Test 1 - Every bus keeps the visits of one of the parents
+        for bus in 0..a.param.A {
+            let visits: Vec<usize> = (0..a.param.N)
+                .filter(|i| a.param.Gam[*i] as usize == bus)
+                .collect();
+            let from_a = visits
+                .iter()
+                .all(|i| child.dec.v[*i] == a.dec.v[*i] && child.dec.u[*i] == a.dec.u[*i]);
+            let from_b = visits
+                .iter()
+                .all(|i| child.dec.v[*i] == b.dec.v[*i] && child.dec.u[*i] == b.dec.u[*i]);
+            assert!(from_a || from_b, "Bus {} mixes the parents", bus);
+        }
+
+        // Test 2 - A generated schedule is valid
+        StdObj::default()
+            .run(&mut a.clone(), &mut ca, false)
+            .unwrap();
+        assert!(Island::is_valid(&ca));
+
+        // Test 3 - Overlapping visits are not valid
+        let q = a.param.A;
+        ca.schedule[q] = vec![
+            Assignment {
+                b: 0,
+                t: (1.0, 2.0),
+            },
+            Assignment {
+                b: 1,
+                t: (1.5, 2.5),
+            },
+        ];
+        assert!(!Island::is_valid(&ca));
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_islands_share_best() {
+        for crossover in [false, true] {
+            let island = Island::new(1, crossover);
+            let a = run_sa(3, "island-a", island.clone());
+            let b = run_sa(4, "island-b", island.clone());
+
+            // Test 1 - The second island is at least as good as the first
+            assert!(b.score <= a.score, "{} is worse than {}", b.score, a.score);
+
+            // Test 2 - The best solution of the islands is the best of both runs
+            assert_eq!(island.best().unwrap().score, b.score);
+        }
+    }
+}
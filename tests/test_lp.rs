@@ -7,12 +7,18 @@ mod test_constraints {
 
     //---------------------------------------------------------------------------
     // Import modules
+    use super::sa_pap::lp::constraints::dynamic::charge_propagation::ChargePropagate;
     use super::sa_pap::lp::objectives::std_obj::StdObj;
-    use super::sa_pap::lp::objectives::Objective;
-    use super::sa_pap::sa::charger::Charger;
-    use super::sa_pap::sa::data::Data;
+    use super::sa_pap::lp::objectives::terms::{ConsumptionCost, DegradationCost};
+    use super::sa_pap::lp::objectives::{Evaluation, Objective, Term, Weights};
+    use super::sa_pap::sa::charger::{Cabinet, Charger};
+    use super::sa_pap::sa::data::{Data, Degradation, Tariff};
+    use super::sa_pap::sa::generators::gen_new_visits::GenNewVisits;
+    use super::sa_pap::sa::generators::tweak_schedule::TweakSchedule;
+    use super::sa_pap::sa::generators::Generator;
     use super::sa_pap::sa::route::route_csv_generator::RouteCSVGenerator;
     use super::sa_pap::sa::route::Route;
+    use super::sa_pap::util::rand_utils;
 
     //---------------------------------------------------------------------------
     //
@@ -36,10 +42,11 @@ mod test_constraints {
     //
     fn get_data() -> Data {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path());
+            RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         return rg.data;
     }
@@ -50,7 +57,7 @@ mod test_constraints {
     fn test_std_obj() {
         // Create objective and data object
         let mut d: Data = get_data();
-        let mut charger: Charger = Charger::new(yaml_path(), true, None, None);
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
 
         // Set some dummy values
 
@@ -67,13 +74,13 @@ mod test_constraints {
         d.dec.w[1][0] = true;
         d.dec.v[1] = 0;
         d.dec.v[0] = 0;
-        let (_, j0) = StdObj::run(&mut d, &mut charger, false);
+        let j0 = StdObj::default().run(&mut d, &mut charger, false).unwrap();
         assert!(j0 >= 0.0);
 
         // Test 1
         d.dec.w[0][35] = true;
         d.dec.v[0] = 35;
-        let (_, j1) = StdObj::run(&mut d, &mut charger, false);
+        let j1 = StdObj::default().run(&mut d, &mut charger, false).unwrap();
         assert!(j1 >= j0);
 
         // Test 2
@@ -82,7 +89,7 @@ mod test_constraints {
         d.dec.w[3][38] = true;
         d.dec.v[0] = 35;
         d.dec.v[3] = 38;
-        let (_, j2) = StdObj::run(&mut d, &mut charger, false);
+        let j2 = StdObj::default().run(&mut d, &mut charger, false).unwrap();
         assert!(j2 >= j1);
 
         // Test 3
@@ -92,7 +99,7 @@ mod test_constraints {
         d.dec.v[2] = 35;
         d.dec.v[1] = 42;
         d.dec.v[5] = 37;
-        let (_, j3) = StdObj::run(&mut d, &mut charger, false);
+        let j3 = StdObj::default().run(&mut d, &mut charger, false).unwrap();
         assert!(j3 >= j2);
 
         // Reset w terms
@@ -105,14 +112,14 @@ mod test_constraints {
         d.dec.s[0] = 1.0;
         d.dec.w[0][35] = true;
         d.dec.v[0] = 35;
-        let (_, j0) = StdObj::run(&mut d, &mut charger, false);
+        let j0 = StdObj::default().run(&mut d, &mut charger, false).unwrap();
         assert!(j0 > 0.0);
 
         // Test 5
         d.dec.w[3][36] = true;
         d.dec.v[3] = 36;
         d.dec.s[3] = 3.0;
-        let (_, j1) = StdObj::run(&mut d, &mut charger, false);
+        let j1 = StdObj::default().run(&mut d, &mut charger, false).unwrap();
         println!("{} > {}", j1, j0);
         assert!(j1 > j0);
 
@@ -123,7 +130,472 @@ mod test_constraints {
         d.dec.w[1][35] = true;
         d.dec.v[1] = 35;
         d.dec.s[1] = 1.0;
-        let (_, j2) = StdObj::run(&mut d, &mut charger, false);
+        let j2 = StdObj::default().run(&mut d, &mut charger, false).unwrap();
         assert!(j2 > j1);
     }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_delta_obj() {
+        // Generate a schedule
+        let mut rng = rand_utils::seeded_rng(3);
+        let mut gsys: Box<dyn Route> =
+            Box::new(RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap());
+        gsys.run(&mut rng).unwrap();
+        let a = gsys.get_data().param.A;
+        let mut charger: Charger = Charger::new(yaml_path(), true, Some(a), None).unwrap();
+        GenNewVisits::new()
+            .run(&mut gsys, &mut charger, &mut rng)
+            .unwrap();
+
+        // Evaluate the schedule on its own charge schedule
+        let mut sol: Data = *gsys.get_data();
+        StdObj::default()
+            .run(&mut sol, &mut charger, false)
+            .unwrap();
+        charger.milp_to_schedule(&sol);
+        gsys.set_data(Box::new(sol.clone()));
+        let mut base = StdObj::default()
+            .evaluate(&mut sol.clone(), &mut charger, false)
+            .unwrap();
+
+        let mut gtweak = TweakSchedule::new();
+        let mut tweaks = 0;
+        while tweaks < 200 {
+            charger.clear_delta();
+            if !gtweak.run(&mut gsys, &mut charger, &mut rng).unwrap() {
+                continue;
+            }
+            tweaks += 1;
+
+            // Test 7 - The tweak reports the visit and charge intervals it changed
+            assert_eq!(charger.delta.visits.len(), 1);
+            assert!(!charger.delta.added.is_empty());
+            assert!(!charger.delta.removed.is_empty());
+
+            // Test 8 - The delta evaluation matches the full evaluation
+            let mut sol_delta: Data = *gsys.get_data();
+            let mut sol_full: Data = *gsys.get_data();
+            let e = StdObj::default()
+                .run_delta(&mut sol_delta, &mut charger.clone(), &base)
+                .unwrap();
+            let j = StdObj::default()
+                .run(&mut sol_full, &mut charger.clone(), false)
+                .unwrap();
+            assert_eq!(e.J, j);
+            assert_eq!(sol_delta.dec.eta, sol_full.dec.eta);
+            assert_eq!(sol_delta.dec.d, sol_full.dec.d);
+
+            // Keep every other candidate
+            if tweaks % 2 == 0 {
+                gsys.set_data(Box::new(sol.clone()));
+                charger.milp_to_schedule(&sol);
+            } else {
+                sol = sol_delta;
+                base = e;
+                gsys.set_data(Box::new(sol.clone()));
+                charger.milp_to_schedule(&sol);
+
+                // Test 9 - The evaluation follows the charge schedule of the kept candidate
+                let f = StdObj::default()
+                    .evaluate(&mut sol.clone(), &mut charger.clone(), false)
+                    .unwrap();
+                assert_eq!(base, f);
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_tariff() {
+        // Create a schedule with a single charge
+        let mut d: Data = get_data();
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
+        d.dec.w = vec![vec![false; d.param.Q]; d.param.N];
+        d.dec.s = vec![0.0; d.param.N];
+        d.dec.w[0][35] = true;
+        d.dec.v[0] = 35;
+        d.dec.u[0] = 7.0;
+        d.dec.s[0] = 2.0;
+        let r = d.param.r[35];
+
+        // Test 10 - The flat tariff prices the energy of the charge at 1
+        assert_eq!(d.param.tariff, Tariff::default());
+        let uc = ConsumptionCost::cost(&d, &charger, 0);
+        assert_eq!(uc, (r * 2.0) as f64);
+        let mut d0 = d.clone();
+        let j0 = StdObj::default().run(&mut d0, &mut charger, false).unwrap();
+
+        // Test 11 - The price is integrated over the part of the charge in each band
+        d.param.tariff = Tariff {
+            base: 0.5,
+            bands: vec![(6.0, 7.5, 2.0), (8.5, 12.0, 4.0)],
+        };
+        assert_eq!(
+            d.param.tariff.cost(7.0, 2.0),
+            0.5 * 2.0 + 1.5 * 0.5 + 3.5 * 0.5
+        );
+        assert_eq!(ConsumptionCost::cost(&d, &charger, 0), (r * 3.5) as f64);
+
+        // Test 12 - The objective function includes the tariff of the evaluated charge
+        let mut d1 = d.clone();
+        let j1 = StdObj::default().run(&mut d1, &mut charger, false).unwrap();
+        let uc = ConsumptionCost::cost(&d1, &charger, 0) - ConsumptionCost::cost(&d0, &charger, 0);
+        assert!(uc != 0.0);
+        assert!((j1 - j0 - uc).abs() < 1e-6 * j1);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_demand() {
+        // Create a schedule with a single one hour charge on a fast charger
+        let mut d: Data = get_data();
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
+        d.dec.w = vec![vec![false; d.param.Q]; d.param.N];
+        d.dec.s = vec![0.0; d.param.N];
+        let q = charger.schedule.len() - 1;
+        let rate = charger.get_charge_rate(q) as f64;
+        charger.assign(q, (1.0, 2.0), 0).unwrap();
+
+        // Test 13 - The peak is the rolling maximum of the power over the window
+        let e = StdObj::default()
+            .evaluate(&mut d.clone(), &mut charger, false)
+            .unwrap();
+        assert_eq!(e.peak, rate);
+        assert_eq!(e.terms.get("demand"), d.param.demand.price as f64 * rate);
+
+        // Test 14 - A longer window averages the power over the window
+        d.param.demand.window = 120;
+        let e = StdObj::default()
+            .evaluate(&mut d.clone(), &mut charger, false)
+            .unwrap();
+        assert!(e.peak < 0.6 * rate && e.peak > 0.4 * rate);
+        d.param.demand.window = 15;
+
+        // Test 15 - Only the increase over the billed peak is charged
+        d.param.demand.peak = (rate / 4.0) as f32;
+        let e1 = StdObj::default()
+            .evaluate(&mut d.clone(), &mut charger, false)
+            .unwrap();
+        assert_eq!(e1.peak, rate);
+        assert_eq!(
+            e1.terms.get("demand"),
+            d.param.demand.price as f64 * 0.75 * rate
+        );
+        assert!((e.J - e1.J - (e.terms.get("demand") - e1.terms.get("demand"))).abs() < 1e-6 * e.J);
+
+        d.param.demand.peak = (2.0 * rate) as f32;
+        let e2 = StdObj::default()
+            .evaluate(&mut d.clone(), &mut charger, false)
+            .unwrap();
+        assert_eq!(e2.terms.get("demand"), 0.0);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_degradation() {
+        // Create a visit that charges from half of the battery capacity over
+        // half of the longest visit
+        let mut d: Data = get_data();
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
+        let i = (0..d.param.N)
+            .max_by(|x, y| {
+                let sx = d.param.e[*x] - d.param.a[*x];
+                let sy = d.param.e[*y] - d.param.a[*y];
+                sx.total_cmp(&sy)
+            })
+            .unwrap();
+        let kappa = d.param.k[d.param.Gam[i] as usize];
+        d.dec.v[i] = 35;
+        d.dec.u[i] = d.param.a[i];
+        d.dec.s[i] = 0.5 * (d.param.e[i] - d.param.a[i]);
+        d.dec.eta[i] = 0.5 * kappa;
+        let e1 = (0.5 * kappa + d.param.r[35] * d.dec.s[i]).min(kappa);
+        let dod = (e1 - 0.5 * kappa) / kappa;
+
+        // Test 16 - The batteries do not wear without a degradation section
+        assert_eq!(d.param.deg, Degradation::default());
+        assert_eq!(DegradationCost::cost(&d, &charger, i), 0.0);
+
+        // Test 17 - The cycle wears the battery by DOD^exponent / cycles of its price
+        d.param.deg = Degradation {
+            cost: vec![6000.0; d.param.A],
+            cycles: 3000.0,
+            exponent: 2.0,
+            soc: 1.0,
+            rate: 2.0,
+        };
+        let cycle = 2.0 * dod * dod;
+        assert!((DegradationCost::cost(&d, &charger, i) - cycle as f64).abs() < 1e-4);
+
+        // Test 18 - The time at the depot after the SOC passes the high SOC is charged
+        d.param.deg.soc = 0.6;
+        let t_high = d.param.e[i] - (d.dec.u[i] + d.dec.s[i] * 0.1 * kappa / (e1 - 0.5 * kappa));
+        assert!(t_high > 0.0);
+        let dc = DegradationCost::cost(&d, &charger, i);
+        assert!((dc - (cycle + 2.0 * t_high) as f64).abs() < 1e-4);
+
+        // Test 19 - A BEB that arrives above the high SOC is charged for the whole visit
+        d.dec.eta[i] = 0.7 * kappa;
+        let dc = DegradationCost::cost(&d, &charger, i);
+        assert!(dc >= (2.0 * (d.param.e[i] - d.param.a[i])) as f64);
+
+        // Test 20 - The degradation cost is a term of the objective function
+        let e = StdObj::default()
+            .evaluate(&mut d.clone(), &mut charger, false)
+            .unwrap();
+        let b = e.breakdown();
+        assert!(b.get("DC") > 0.0);
+        assert!((b.total() - e.J).abs() < 1e-9 * e.J);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_weights() {
+        // Create a schedule with a single one hour charge on a fast charger
+        let mut d: Data = get_data();
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
+        d.dec.w = vec![vec![false; d.param.Q]; d.param.N];
+        d.dec.s = vec![0.0; d.param.N];
+        let q = charger.schedule.len() - 1;
+        charger.assign(q, (1.0, 2.0), 0).unwrap();
+        let e = StdObj::default()
+            .evaluate(&mut d.clone(), &mut charger, false)
+            .unwrap();
+        let b = e.breakdown();
+
+        // Test 21 - Unit weights give the sum of the terms
+        assert_eq!(b.weighted(&Weights::default()), b.total());
+
+        // Test 22 - The objective function is the weighted sum of the terms
+        let w = Weights::default()
+            .with("AC", 2.0)
+            .with("UC", 0.5)
+            .with("demand", 0.0)
+            .with("DC", 3.0);
+        let ew = StdObj::new(&w)
+            .unwrap()
+            .evaluate(&mut d.clone(), &mut charger, false)
+            .unwrap();
+        let expected = 2.0 * b.get("AC") + 0.5 * b.get("UC") + b.get("phi") + 3.0 * b.get("DC");
+        assert!((ew.J - expected).abs() < 1e-9 * expected.abs().max(1.0));
+
+        // Test 23 - The terms do not depend on the weights
+        assert_eq!(ew.breakdown(), b);
+
+        // Test 24 - A weight of zero drops the demand charge
+        let w = Weights::default().with("demand", 0.0);
+        let e0 = StdObj::new(&w)
+            .unwrap()
+            .evaluate(&mut d.clone(), &mut charger, false)
+            .unwrap();
+        assert!(b.get("demand") > 0.0);
+        assert!((e.J - b.get("demand") - e0.J).abs() < 1e-9 * e.J);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_cabinet() {
+        // Create a schedule with two one hour charges on the last two fast chargers
+        let mut d: Data = get_data();
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
+        d.dec.w = vec![vec![false; d.param.Q]; d.param.N];
+        d.dec.s = vec![0.0; d.param.N];
+        let q = charger.schedule.len() - 1;
+        let rate = charger.get_charge_rate(q) as f64;
+        charger.assign(q - 1, (1.0, 2.0), 0).unwrap();
+        charger.assign(q, (1.0, 2.0), 1).unwrap();
+        let e = StdObj::default()
+            .evaluate(&mut d.clone(), &mut charger, false)
+            .unwrap();
+        assert_eq!(e.peak, 2.0 * rate);
+
+        // Test 25 - The chargers of a cabinet draw at most the power of the cabinet
+        charger.cabinets.push(Cabinet {
+            queues: vec![q - 1, q],
+            power: (1.5 * rate) as f32,
+        });
+        let e = StdObj::default()
+            .evaluate(&mut d.clone(), &mut charger, false)
+            .unwrap();
+        assert_eq!(e.peak, 1.5 * rate);
+
+        // Test 26 - A shared charger delivers the charge of its shared rate
+        let i = d.param.A;
+        d.dec.v[i] = q;
+        d.dec.u[i] = 1.0;
+        d.dec.s[i] = 1.0;
+        d.dec.d[i] = 2.0;
+        let mut full = d.clone();
+        full.dec.s[i] = 0.75;
+        assert_eq!(
+            ChargePropagate::shared_charge(&d, &charger, i),
+            ChargePropagate::charge(&full, i)
+        );
+
+        // Test 27 - A charge that overlaps less of the other charge receives more power
+        d.dec.u[i] = 1.5;
+        d.dec.d[i] = 2.5;
+        full.dec.s[i] = 0.875;
+        assert_eq!(
+            ChargePropagate::shared_charge(&d, &charger, i),
+            ChargePropagate::charge(&full, i)
+        );
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_delta_cabinet() {
+        // Generate a schedule whose fast chargers share a cabinet
+        let mut rng = rand_utils::seeded_rng(5);
+        let mut gsys: Box<dyn Route> =
+            Box::new(RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap());
+        gsys.run(&mut rng).unwrap();
+        let a = gsys.get_data().param.A;
+        let mut charger: Charger = Charger::new(yaml_path(), true, Some(a), None).unwrap();
+        let q = charger.schedule.len();
+        charger.cabinets.push(Cabinet {
+            queues: (q - 4..q).collect(),
+            power: 2.0 * charger.get_charge_rate(q - 1),
+        });
+        GenNewVisits::new()
+            .run(&mut gsys, &mut charger, &mut rng)
+            .unwrap();
+
+        let mut sol: Data = *gsys.get_data();
+        charger.milp_to_schedule(&sol);
+        let base = StdObj::default()
+            .evaluate(&mut sol, &mut charger.clone(), false)
+            .unwrap();
+        gsys.set_data(Box::new(sol.clone()));
+
+        // Test 28 - The delta evaluation matches the full evaluation of a shared charger
+        let mut gtweak = TweakSchedule::new();
+        let mut tweaks = 0;
+        while tweaks < 50 {
+            charger.clear_delta();
+            if !gtweak.run(&mut gsys, &mut charger, &mut rng).unwrap() {
+                continue;
+            }
+            tweaks += 1;
+
+            let mut sol_delta: Data = *gsys.get_data();
+            let mut sol_full: Data = *gsys.get_data();
+            let e = StdObj::default()
+                .run_delta(&mut sol_delta, &mut charger.clone(), &base)
+                .unwrap();
+            let j = StdObj::default()
+                .run(&mut sol_full, &mut charger.clone(), false)
+                .unwrap();
+            assert_eq!(e.J, j);
+            assert_eq!(sol_delta.dec.eta, sol_full.dec.eta);
+
+            gsys.set_data(Box::new(sol.clone()));
+            charger.milp_to_schedule(&sol);
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_terms() {
+        // Create a schedule with a single one hour charge on a fast charger
+        let mut d: Data = get_data();
+        let mut charger: Charger = Charger::new(yaml_path(), true, None, None).unwrap();
+        d.dec.w = vec![vec![false; d.param.Q]; d.param.N];
+        d.dec.s = vec![0.0; d.param.N];
+        let q = charger.schedule.len() - 1;
+        charger.assign(q, (1.0, 2.0), 0).unwrap();
+        let b = StdObj::default()
+            .evaluate(&mut d.clone(), &mut charger, false)
+            .unwrap()
+            .breakdown();
+
+        // Test 29 - Every weight belongs to a term of the objective function
+        assert!(StdObj::new(&Weights::default().with("XY", 1.0)).is_err());
+
+        // Test 30 - An objective function composed of other terms
+        let obj = StdObj::with_terms(vec![
+            (1.0, Box::new(ConsumptionCost {})),
+            (2.0, Box::new(Peak {})),
+        ]);
+        let ec = obj.evaluate(&mut d.clone(), &mut charger, false).unwrap();
+        let names: Vec<&str> = ec.terms.terms.iter().map(|t| t.0.as_str()).collect();
+        assert_eq!(names, vec!["UC", "peak"]);
+        assert_eq!(ec.terms.get("peak"), ec.peak);
+        assert!((ec.J - b.get("UC") - 2.0 * ec.peak).abs() < 1e-9 * ec.J);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_cabinet_cost() {
+        // Create a charge on the last fast charger while the charger next to it
+        // charges another BEB
+        let mut d: Data = get_data();
+        let mut charger: Charger = Charger::new(yaml_path(), true, Some(d.param.A), None).unwrap();
+        d.dec.w = vec![vec![false; d.param.Q]; d.param.N];
+        d.dec.s = vec![0.0; d.param.N];
+        let q = charger.schedule.len() - 1;
+        let rate = charger.get_charge_rate(q);
+        charger.assign(q - 1, (1.0, 2.0), 0).unwrap();
+        charger.assign(q, (1.0, 1.1), 1).unwrap();
+        let i = d.param.A;
+        let kappa = d.param.k[d.param.Gam[i] as usize];
+        d.dec.v[i] = q;
+        d.dec.u[i] = 1.0;
+        d.dec.s[i] = 0.1;
+        d.dec.d[i] = 1.1;
+        d.dec.eta[i] = 0.5 * kappa;
+        d.param.deg = Degradation {
+            cost: vec![6000.0; d.param.A],
+            cycles: 3000.0,
+            exponent: 2.0,
+            soc: 1.0,
+            rate: 2.0,
+        };
+        let uc = ConsumptionCost::cost(&d, &charger, i);
+        let dc = DegradationCost::cost(&d, &charger, i);
+
+        // Share the power of the two chargers
+        let mut shared = charger.clone();
+        shared.cabinets.push(Cabinet {
+            queues: vec![q - 1, q],
+            power: 1.5 * rate,
+        });
+
+        // Test 31 - A shared charger pays for the energy it is delivered
+        let uc_shared = ConsumptionCost::cost(&d, &shared, i);
+        assert!(uc_shared < uc);
+        assert!((uc_shared - 0.75 * uc).abs() < 1e-6 * uc);
+
+        // Test 32 - The battery of a shared charger wears by the charge it is delivered
+        let mut full = d.clone();
+        full.dec.s[i] = 0.075;
+        let dc_shared = DegradationCost::cost(&d, &shared, i);
+        assert!(dc_shared < dc);
+        assert!((dc_shared - DegradationCost::cost(&full, &charger, i)).abs() < 1e-6);
+    }
+
+    //---------------------------------------------------------------------------
+    // Global term of the peak power
+    //
+    struct Peak {}
+
+    impl Term for Peak {
+        fn name(&self) -> &str {
+            return "peak";
+        }
+
+        fn global(&self, _dat: &Data, e: &Evaluation) -> f64 {
+            return e.peak;
+        }
+    }
 }
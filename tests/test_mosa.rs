@@ -0,0 +1,181 @@
+extern crate sa_pap;
+
+//===============================================================================
+//
+#[cfg(test)]
+mod test_mosa {
+    //---------------------------------------------------------------------------
+    // Import modules
+    use super::sa_pap::lp::objectives::std_obj::StdObj;
+    use super::sa_pap::lp::objectives::Objective;
+    use super::sa_pap::sa::charger::Charger;
+    use super::sa_pap::sa::data::Data;
+    use super::sa_pap::sa::generators::gen_new_visits::GenNewVisits;
+    use super::sa_pap::sa::generators::tweak_schedule::TweakSchedule;
+    use super::sa_pap::sa::mosa::{Archive, Criteria, ParetoResults, ParetoSolution, MOSA};
+    use super::sa_pap::sa::route::route_csv_generator::RouteCSVGenerator;
+    use super::sa_pap::util::data_output::DataOutput;
+
+    //---------------------------------------------------------------------------
+    //
+    fn yaml_path() -> &'static str {
+        return "./src/config/schedule-test.yaml";
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    fn csv_path() -> &'static str {
+        return "./src/config/routes.csv";
+    }
+
+    //------------------------------------------------------------------------------
+    //
+    fn general_path() -> &'static str {
+        return "./src/config/general.yaml";
+    }
+
+    //---------------------------------------------------------------------------
+    // Write a copy of the test schedule with a short annealing section
+    //
+    fn schedule_path() -> String {
+        let path = std::env::temp_dir().join("sa-pap-test-mosa-schedule.yaml");
+        let mosa = "mosa:\n  archive: 4\n  soft: 6\n  t_max: 10.0\n  t_min: 1.0\n  alpha: 0.5\n";
+        let sched = std::fs::read_to_string(yaml_path()).unwrap() + mosa;
+        std::fs::write(&path, sched).unwrap();
+
+        return path.to_string_lossy().to_string();
+    }
+
+    //---------------------------------------------------------------------------
+    // Run a short multi-objective annealing run with the given seed
+    //
+    fn run_mosa(seed: u64, schedule_path: &str) -> ParetoResults {
+        let gsys =
+            Box::new(RouteCSVGenerator::new(schedule_path, general_path(), csv_path()).unwrap());
+
+        let mut mosa: MOSA = MOSA::new(
+            general_path(),
+            schedule_path,
+            Box::new(GenNewVisits::new()),
+            gsys,
+            Box::new(TweakSchedule::new()),
+            seed,
+        )
+        .unwrap();
+
+        return mosa.run().unwrap();
+    }
+
+    //---------------------------------------------------------------------------
+    // Solution of an archive with the given objectives
+    //
+    fn solution(cost: f64, peak: f64, margin: f64) -> ParetoSolution {
+        return ParetoSolution {
+            sol: Data::default(),
+            charger: Charger::new(yaml_path(), true, None, None).unwrap(),
+            criteria: Criteria { cost, peak, margin },
+            j: 0.0,
+        };
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_archive() {
+        let mut a = Archive::default();
+
+        // Test 1 - A larger SOC margin is better, a larger cost or peak is worse
+        let c = Criteria {
+            cost: 1.0,
+            peak: 1.0,
+            margin: 0.5,
+        };
+        assert!(c.dominates(&Criteria { margin: 0.4, ..c }));
+        assert!(c.dominates(&Criteria { cost: 2.0, ..c }));
+        assert!(!c.dominates(&c));
+        assert!(!c.dominates(&Criteria {
+            cost: 2.0,
+            margin: 0.6,
+            ..c
+        }));
+
+        // Test 2 - Dominated solutions and duplicates are not added
+        assert!(a.add(solution(1.0, 4.0, 0.1)));
+        assert!(a.add(solution(4.0, 1.0, 0.1)));
+        assert!(!a.add(solution(4.0, 4.0, 0.1)));
+        assert!(!a.add(solution(1.0, 4.0, 0.1)));
+        assert_eq!(a.solutions.len(), 2);
+        assert_eq!(
+            a.dominating(&Criteria {
+                cost: 5.0,
+                peak: 5.0,
+                margin: 0.0
+            }),
+            vec![0, 1]
+        );
+
+        // Test 3 - A solution that dominates archived solutions replaces them
+        assert!(a.add(solution(0.5, 3.0, 0.2)));
+        assert_eq!(a.solutions.len(), 2);
+        assert_eq!(a.solutions[1].criteria.cost, 0.5);
+
+        // Test 4 - Thinning removes the most crowded solution
+        assert!(a.add(solution(3.9, 1.1, 0.1)));
+        a.reduce(2);
+        let costs: Vec<f64> = a.solutions.iter().map(|s| s.criteria.cost).collect();
+        assert_eq!(a.solutions.len(), 2);
+        assert!(costs.contains(&0.5));
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_pareto_front() {
+        let path = schedule_path();
+        let a = run_mosa(13, &path);
+        let b = run_mosa(13, &path);
+        let front = &a.archive.solutions;
+
+        // Test 1 - The front is non-empty, within the archive size and ordered by cost
+        assert!(!front.is_empty() && front.len() <= 4);
+        assert!(front
+            .windows(2)
+            .all(|w| w[0].criteria.cost <= w[1].criteria.cost));
+        assert_eq!((a.seed, a.T0), (13, 10.0));
+
+        // Test 2 - No solution of the front dominates another
+        for x in front.iter() {
+            for y in front.iter() {
+                assert!(!x.criteria.dominates(&y.criteria));
+            }
+        }
+
+        // Test 3 - The objectives match an evaluation of the schedule
+        for s in front.iter() {
+            let mut d = s.sol.clone();
+            let mut c = s.charger.clone();
+            let e = StdObj::default().evaluate(&mut d, &mut c, false).unwrap();
+            let f = Criteria::new(&e, &d);
+            assert!((f.cost - s.criteria.cost).abs() <= 1e-9 * f.cost.abs().max(1.0));
+            assert_eq!(f.peak, s.criteria.peak);
+            assert_eq!(f.margin, s.criteria.margin);
+        }
+
+        // Test 4 - Two runs with the same seed produce identical fronts
+        let fb: Vec<Criteria> = b.archive.solutions.iter().map(|s| s.criteria).collect();
+        let fa: Vec<Criteria> = front.iter().map(|s| s.criteria).collect();
+        assert_eq!(fa, fb);
+
+        // Test 5 - The front and a schedule per solution are written out
+        let dir = std::env::temp_dir().join("sa-pap-test-mosa/");
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir = dir.to_string_lossy().to_string();
+        DataOutput::output_front(String::from("mosa"), a.clone(), Some(dir.clone())).unwrap();
+        let csv = std::fs::read_to_string(dir.clone() + "mosa-pareto.csv").unwrap();
+        assert_eq!(csv.lines().count(), front.len() + 1);
+        for i in 0..front.len() {
+            let name = format!("{}mosa-pareto-{}-schedule.csv", dir, i);
+            assert!(std::path::Path::new(&name).exists());
+        }
+    }
+}
@@ -0,0 +1,172 @@
+extern crate sa_pap;
+
+//===============================================================================
+//
+#[cfg(test)]
+mod test_observers {
+    //---------------------------------------------------------------------------
+    // Import standard library
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    //---------------------------------------------------------------------------
+    // Import modules
+    use super::sa_pap::sa::data::Data;
+    use super::sa_pap::sa::generators::gen_new_visits::GenNewVisits;
+    use super::sa_pap::sa::generators::tweak_schedule::TweakSchedule;
+    use super::sa_pap::sa::observers::json_lines_observer::JsonLinesObserver;
+    use super::sa_pap::sa::observers::{SaObserver, TempStep};
+    use super::sa_pap::sa::route::route_csv_generator::RouteCSVGenerator;
+    use super::sa_pap::sa::temp_func::{CoolSchedule::Geometric, TempFunc};
+    use super::sa_pap::sa::{Results, SA};
+
+    //---------------------------------------------------------------------------
+    //
+    fn yaml_path() -> &'static str {
+        return "./src/config/schedule-test.yaml";
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    fn csv_path() -> &'static str {
+        return "./src/config/routes.csv";
+    }
+
+    //------------------------------------------------------------------------------
+    //
+    fn general_path() -> &'static str {
+        return "./src/config/general.yaml";
+    }
+
+    //---------------------------------------------------------------------------
+    // Events recorded by `Recorder`
+    //
+    #[derive(Default)]
+    struct Events {
+        start: usize,
+        iterations: usize,
+        accepted: usize,
+        rejected: usize,
+        best: Vec<f64>,
+        temp_steps: usize,
+        candidates: usize,
+    }
+
+    //---------------------------------------------------------------------------
+    // Observer that records the events of a run
+    //
+    struct Recorder {
+        events: Rc<RefCell<Events>>,
+    }
+
+    impl SaObserver for Recorder {
+        fn on_start(&mut self, _step: usize, _steps: usize, _t0: f32) {
+            self.events.borrow_mut().start += 1;
+        }
+
+        fn on_iteration(&mut self, _step: usize, _iteration: usize, _t: f32, _j: f64) {
+            self.events.borrow_mut().iterations += 1;
+        }
+
+        fn on_accept(&mut self, _step: usize, _j: f64) {
+            self.events.borrow_mut().accepted += 1;
+        }
+
+        fn on_reject(&mut self, _step: usize, _j: f64) {
+            self.events.borrow_mut().rejected += 1;
+        }
+
+        fn on_new_best(&mut self, _step: usize, jb: f64, _sol: &Data) {
+            self.events.borrow_mut().best.push(jb);
+        }
+
+        fn on_temp_step(&mut self, s: &TempStep) {
+            let mut e = self.events.borrow_mut();
+            e.temp_steps += 1;
+            e.candidates += s.candidates;
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    // Run a short simulated annealing run with the given observer
+    //
+    fn run_sa(seed: u64, name: &str, observer: Box<dyn SaObserver>) -> Results {
+        let mut tf = Box::new(TempFunc::new(Geometric, 8.0, 0.5, true));
+        let gsys =
+            Box::new(RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap());
+
+        let mut sa: SA = SA::new(
+            general_path(),
+            yaml_path(),
+            Box::new(GenNewVisits::new()),
+            gsys,
+            Box::new(TweakSchedule::new()),
+            &mut tf,
+            seed,
+        )
+        .unwrap();
+
+        // Keep the checkpoints of the test out of the data directory
+        let ckpt = std::env::temp_dir().join(format!("sa-pap-test-{}.yaml", name));
+        sa.set_checkpoint_path(&ckpt.to_string_lossy());
+        sa.add_observer(observer);
+
+        return sa.run(false).unwrap();
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_observer_events() {
+        let events = Rc::new(RefCell::new(Events::default()));
+        let res = run_sa(
+            3,
+            "observer-events",
+            Box::new(Recorder {
+                events: events.clone(),
+            }),
+        );
+        let e = events.borrow();
+
+        // The start is reported once and every temperature step is reported
+        assert_eq!(e.start, 1);
+        assert_eq!(e.temp_steps, res.data.dec.Jb.len() - 1);
+
+        // Every candidate is either accepted or rejected
+        assert!(e.iterations > 0);
+        assert_eq!(e.accepted + e.rejected, e.iterations);
+        assert_eq!(e.candidates, e.iterations);
+
+        // The best score only improves
+        assert!(e.best.windows(2).all(|w| w[1] < w[0]));
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_json_lines_observer() {
+        let path = std::env::temp_dir().join("sa-pap-test-observer.jsonl");
+        let path = path.to_string_lossy().to_string();
+        let log = JsonLinesObserver::new(&path, true).unwrap();
+        let res = run_sa(4, "observer-json", Box::new(log));
+
+        let lines: Vec<String> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|l| l.to_string())
+            .collect();
+
+        // Every line is a JSON object naming its event
+        assert!(lines
+            .iter()
+            .all(|l| l.starts_with("{\"event\":\"") && l.ends_with('}')));
+        assert!(lines[0].starts_with("{\"event\":\"start\""));
+
+        // Every temperature step is logged
+        let steps = lines.iter().filter(|l| l.contains("\"temp_step\"")).count();
+        assert_eq!(steps, res.data.dec.Jb.len() - 1);
+        assert!(lines
+            .iter()
+            .any(|l| l.contains("\"accept\"") || l.contains("\"reject\"")));
+    }
+}
@@ -26,6 +26,7 @@ mod test_primitive_generators {
     use super::sa_pap::sa::generators::primitives::wait::*;
     use super::sa_pap::sa::route::route_csv_generator::RouteCSVGenerator;
     use super::sa_pap::sa::route::Route;
+    use super::sa_pap::util::rand_utils;
 
     //---------------------------------------------------------------------------
     //
@@ -50,10 +51,10 @@ mod test_primitive_generators {
     fn get_data() -> Box<Data> {
         // Create empty `RouteEvent` vector
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path());
+            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path()).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        rg.run(&mut rand_utils::seeded_rng(0)).unwrap();
 
         return rg.get_data();
     }
@@ -75,9 +76,10 @@ mod test_primitive_generators {
     fn test_new_visit() {
         // Get route data
         let mut rd = get_data();
+        let mut rng = rand_utils::seeded_rng(0);
 
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, None);
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
 
         // Queue index
         let q: usize = 0;
@@ -100,8 +102,9 @@ mod test_primitive_generators {
                 id,
                 id,
                 &(0.01, 0.09),
-                &(0.01, 0.09)
-            ),
+                &(0.01, 0.09),
+                &mut rng
+            ).unwrap(),
             "Could not create new visit."
         );
         assert_eq!(rd.dec.v[0], 0);
@@ -111,17 +114,17 @@ mod test_primitive_generators {
         assert_eq!(charger.free_time[q].len(), 2);
 
         // Create a new charger
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, None);
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
 
         // Assign some visits
         let c: (f32, f32) = (0.1, 0.2);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.2, 0.5);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.6, 0.7);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         // Test 4 - Check the set free time
         assert_eq!(charger.free_time[q][0], (0.0, 0.1));
@@ -138,22 +141,41 @@ mod test_primitive_generators {
             id,
             id,
             &(0.7, 1.0),
-            &(0.7, 1.0)
-        ));
+            &(0.7, 1.0),
+            &mut rng
+        ).unwrap());
         assert_eq!(charger.free_time[q].len(), 5);
         assert_eq!(rd.dec.v[0], id);
         assert_eq!(rd.dec.w[0][0], true);
 
         // Test 6 - Assign a bus to be charged with an invalid time
         assert_eq!(
-            new_visit::run(&mut rd, 0, &mut charger, id, id, &(0.2, 0.5), &(2.0, 2.5)),
+            new_visit::run(
+                &mut rd,
+                0,
+                &mut charger,
+                id,
+                id,
+                &(0.2, 0.5),
+                &(2.0, 2.5),
+                &mut rng
+            ).unwrap(),
             false
         );
         assert_eq!(charger.free_time[q].len(), 6);
 
         // Test 7 - Assign a new bus to be charged in a busy schedule
         for idx in 0..10 {
-            if new_visit::run(&mut rd, 0, &mut charger, id, id, &(0.0, 0.1), &(0.0, 0.1)) {
+            if new_visit::run(
+                &mut rd,
+                0,
+                &mut charger,
+                id,
+                id,
+                &(0.0, 0.1),
+                &(0.0, 0.1),
+                &mut rng,
+            ).unwrap() {
                 assert!(true);
                 break;
             } else if idx + 1 == 10 {
@@ -170,8 +192,9 @@ mod test_primitive_generators {
             id,
             id,
             &(0.5, 1.0),
-            &(0.5, 1.0)
-        ));
+            &(0.5, 1.0),
+            &mut rng
+        ).unwrap());
         assert!(new_visit::run(
             &mut rd,
             0,
@@ -179,8 +202,9 @@ mod test_primitive_generators {
             id,
             id,
             &(1.0, 1.5),
-            &(1.0, 1.5)
-        ));
+            &(1.0, 1.5),
+            &mut rng
+        ).unwrap());
         assert_eq!(charger.free_time[q].len(), 9);
     }
 
@@ -192,7 +216,7 @@ mod test_primitive_generators {
         let mut rd = get_data();
 
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), true, Some(rd.param.A), None);
+        let mut charger: Charger = Charger::new(schedule_path(), true, Some(rd.param.A), None).unwrap();
 
         // Create a simple schedule
         let q: usize = 0;
@@ -209,17 +233,17 @@ mod test_primitive_generators {
         }
 
         // Assign the charger
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.0, 0.02);
 
         // Assign the charger
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.3, 0.5);
 
         // Assign the charger
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         // Make sure they are all there
         assert!(time_slice_exists(&charger, &q, &(0.1, 0.2)));
@@ -236,7 +260,7 @@ mod test_primitive_generators {
             id,
             &(0.1, 0.2),
             &(0.1, 0.2)
-        ));
+        ).unwrap());
         assert_eq!(rd.dec.v[i], id);
         assert_eq!(rd.dec.w[i][rd.dec.v[i]], true);
         assert_eq!(time_slice_exists(&charger, &id, &(0.1, 0.2)), true);
@@ -244,7 +268,7 @@ mod test_primitive_generators {
         assert_eq!(charger.schedule[q].len(), 2);
 
         // Test 2
-        assert!(purge::run(&mut rd, i, &mut charger, id, &(0.1, 0.2)));
+        assert!(purge::run(&mut rd, i, &mut charger, id, &(0.1, 0.2)).unwrap());
         assert_eq!(rd.dec.v[i], id);
         assert_eq!(rd.dec.w[i][rd.dec.v[i]], true);
         assert_eq!(time_slice_exists(&charger, &id, &(0.1, 0.2)), false);
@@ -254,7 +278,7 @@ mod test_primitive_generators {
         // Test 2
         println!("{:?}", charger.schedule[0]);
         assert_eq!(
-            wait::run(&mut rd, i, &mut charger, q, id, &(0.1, 0.2), &(0.1, 0.2)),
+            wait::run(&mut rd, i, &mut charger, q, id, &(0.1, 0.2), &(0.1, 0.2)).unwrap(),
             false
         );
         assert_eq!(time_slice_exists(&charger, &q, &(0.1, 0.2)), false);
@@ -269,13 +293,13 @@ mod test_primitive_generators {
             id,
             &(0.0, 0.02),
             &(0.0, 0.02)
-        ));
+        ).unwrap());
         assert_eq!(time_slice_exists(&charger, &id, &(0.0, 0.02)), true);
         assert_eq!(charger.schedule[id].len(), 1);
         assert_eq!(charger.schedule[q].len(), 1);
 
         // Test 4
-        assert!(purge::run(&mut rd, 0, &mut charger, q, &(0.3, 0.5)));
+        assert!(purge::run(&mut rd, 0, &mut charger, q, &(0.3, 0.5)).unwrap());
         assert_eq!(time_slice_exists(&charger, &q, &(0.3, 0.5)), false);
         assert_eq!(charger.schedule[q].len(), 0);
     }
@@ -285,30 +309,40 @@ mod test_primitive_generators {
     #[test]
     fn test_new_window() {
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, None);
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
 
         // Get route data
         let mut rd = get_data();
+        let mut rng = rand_utils::seeded_rng(0);
 
         // Create a simple schedule
         let q: usize = 0;
         let id: usize = 3;
 
         let c: (f32, f32) = (0.1, 0.2);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.0, 0.02);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.3, 0.5);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         // Test 1 - Check the number of assignments
         assert_eq!(charger.schedule[q].len(), 3);
 
         // Un-assign and reassign bus
         assert_eq!(
-            new_window::run(&mut rd, 0, &mut charger, q, 0, &(0.1, 0.2), &(0.1, 0.2)),
+            new_window::run(
+                &mut rd,
+                0,
+                &mut charger,
+                q,
+                0,
+                &(0.1, 0.2),
+                &(0.1, 0.2),
+                &mut rng
+            ).unwrap(),
             true
         );
         assert_eq!(charger.schedule[q].len(), 3);
@@ -338,7 +372,16 @@ mod test_primitive_generators {
 
         // Un-assign and reassign bus
         assert_eq!(
-            new_window::run(&mut rd, 0, &mut charger, q, 0, &(0.3, 0.5), &(0.3, 0.5)),
+            new_window::run(
+                &mut rd,
+                0,
+                &mut charger,
+                q,
+                0,
+                &(0.3, 0.5),
+                &(0.3, 0.5),
+                &mut rng
+            ).unwrap(),
             true
         );
         assert_eq!(charger.schedule[q].len(), 3);
@@ -373,30 +416,40 @@ mod test_primitive_generators {
     #[test]
     fn test_slide() {
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, None);
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
 
         // Get route data
         let mut rd = get_data();
+        let mut rng = rand_utils::seeded_rng(0);
 
         // Create a simple schedule
         let q: usize = 0;
         let id: usize = 3;
 
         let c: (f32, f32) = (0.1, 0.2);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.0, 0.02);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let c: (f32, f32) = (0.3, 0.5);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         // Test 1 - Check the number of assignments
         assert_eq!(charger.schedule[q].len(), 3);
 
         // Test 2 - Un-assign and reassign bus
         assert!(
-            new_window::run(&mut rd, 0, &mut charger, q, 0, &(0.1, 0.2), &(0.1, 0.2)),
+            new_window::run(
+                &mut rd,
+                0,
+                &mut charger,
+                q,
+                0,
+                &(0.1, 0.2),
+                &(0.1, 0.2),
+                &mut rng
+            ).unwrap(),
             "Failed to find new window."
         );
         assert_eq!(charger.schedule[q].len(), 3);
@@ -405,7 +458,16 @@ mod test_primitive_generators {
 
         // Un-assign and reassign bus
         assert!(
-            slide_visit::run(&mut rd, 0, &mut charger, id, q, &(0.3, 0.5), &(0.3, 0.5),),
+            slide_visit::run(
+                &mut rd,
+                0,
+                &mut charger,
+                id,
+                q,
+                &(0.3, 0.5),
+                &(0.3, 0.5),
+                &mut rng
+            ).unwrap(),
             "Failed to find new window."
         );
         assert_eq!(charger.schedule[q].len(), 3);
@@ -423,25 +485,26 @@ mod test_primitive_generators {
     fn test_new_charger() {
         // Get route data
         let mut rd = get_data();
+        let mut rng = rand_utils::seeded_rng(0);
 
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), true, Some(rd.param.A), None);
+        let mut charger: Charger = Charger::new(schedule_path(), true, Some(rd.param.A), None).unwrap();
 
         // Create a simple schedule
         let q: usize = 1;
         let c: (f32, f32) = (0.1, 0.2);
         let id: usize = 3;
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let q: usize = 0;
         let id: usize = 1;
         let c: (f32, f32) = (0.0, 0.02);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let q: usize = 1;
         let id: usize = 2;
         let c: (f32, f32) = (0.3174, 0.5);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         // Test 1 - Check the number of assignments
         assert_eq!(charger.schedule[q].len(), 2);
@@ -453,9 +516,24 @@ mod test_primitive_generators {
             &mut charger,
             1,
             3,
-            &(0.1, 0.2)
-        ));
+            &(0.1, 0.2),
+            &mut rng
+        ).unwrap());
         assert_ne!(rd.dec.v[0], 1);
+
+        // Test 3 - No charger is chosen if every charger exceeds the grid connection
+        rd.param.grid = 50.0;
+        charger.assign(1, (0.1, 0.2), 3).unwrap();
+        assert!(!new_charger::run(
+            &mut rd,
+            0,
+            &mut charger,
+            1,
+            3,
+            &(0.1, 0.2),
+            &mut rng
+        ).unwrap());
+        assert!(charger.schedule[1].iter().any(|a| a.t == (0.1, 0.2)));
     }
 
     //---------------------------------------------------------------------------
@@ -464,25 +542,26 @@ mod test_primitive_generators {
     fn test_new_charger_quick() {
         // Get route data
         let mut rd = get_data();
+        let mut rng = rand_utils::seeded_rng(0);
 
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), true, Some(rd.param.A), None);
+        let mut charger: Charger = Charger::new(schedule_path(), true, Some(rd.param.A), None).unwrap();
 
         // Create a simple schedule
         let q: usize = 1;
         let c: (f32, f32) = (0.1, 0.2);
         let id: usize = 3;
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let q: usize = 0;
         let id: usize = 1;
         let c: (f32, f32) = (0.0, 0.02);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         let q: usize = 1;
         let id: usize = 2;
         let c: (f32, f32) = (0.3174, 0.5);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         // Test 1 - Check the number of assignments
         assert_eq!(charger.schedule[q].len(), 2);
@@ -494,8 +573,9 @@ mod test_primitive_generators {
             &mut charger,
             1,
             3,
-            &(0.1, 0.2)
-        ));
+            &(0.1, 0.2),
+            &mut rng
+        ).unwrap());
         assert_ne!(rd.dec.v[0], 1);
     }
 
@@ -505,9 +585,10 @@ mod test_primitive_generators {
     fn test_new_visit_quick() {
         // Get route data
         let mut rd = get_data();
+        let mut rng = rand_utils::seeded_rng(0);
 
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, None);
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
 
         // Queue index
         let q: usize = 0;
@@ -516,7 +597,7 @@ mod test_primitive_generators {
         let id: usize = 0;
 
         // Create random assignment
-        charger.assign(0, (0.01, 0.09), 0);
+        charger.assign(0, (0.01, 0.09), 0).unwrap();
 
         // Test 0 - Ensure that the free time is (BOD, EOD)
         assert_eq!(charger.free_time[q][0], (0.0, 0.01));
@@ -534,8 +615,9 @@ mod test_primitive_generators {
                 q,
                 id,
                 &(0.01, 0.09),
-                &(0.01, 0.09)
-            ),
+                &(0.01, 0.09),
+                &mut rng
+            ).unwrap(),
             "Could not create new visit."
         );
         assert_eq!(rd.dec.v[0], 0);
@@ -550,24 +632,34 @@ mod test_primitive_generators {
     #[test]
     fn test_slide_visit_quick() {
         // Create charger
-        let mut charger: Charger = Charger::new(schedule_path(), false, None, None);
+        let mut charger: Charger = Charger::new(schedule_path(), false, None, None).unwrap();
 
         // Get route data
         let mut rd = get_data();
+        let mut rng = rand_utils::seeded_rng(0);
 
         // Create a simple schedule
         let q: usize = 0;
         let id: usize = 0;
 
         let c: (f32, f32) = (0.1, 0.2);
-        charger.assign(q, c, id);
+        charger.assign(q, c, id).unwrap();
 
         // Test 1 - Check the number of assignments
         assert_eq!(charger.schedule[q].len(), 1);
 
         // Test 2 - Un-assign and reassign bus
         assert!(
-            new_window::run(&mut rd, 0, &mut charger, q, 0, &(0.1, 0.2), &(0.1, 0.2)),
+            new_window::run(
+                &mut rd,
+                0,
+                &mut charger,
+                q,
+                0,
+                &(0.1, 0.2),
+                &(0.1, 0.2),
+                &mut rng
+            ).unwrap(),
             "Failed to find new window."
         );
         assert_eq!(charger.schedule[q].len(), 1);
@@ -0,0 +1,174 @@
+extern crate sa_pap;
+
+//===============================================================================
+//
+#[cfg(test)]
+mod test_pt {
+    //---------------------------------------------------------------------------
+    // Import standard library
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    //---------------------------------------------------------------------------
+    // Import modules
+    use super::sa_pap::sa::generators::gen_new_visits::GenNewVisits;
+    use super::sa_pap::sa::generators::tweak_schedule::TweakSchedule;
+    use super::sa_pap::sa::observers::{Fallback, SaObserver};
+    use super::sa_pap::sa::pt::PT;
+    use super::sa_pap::sa::route::route_csv_generator::RouteCSVGenerator;
+    use super::sa_pap::sa::Results;
+
+    //---------------------------------------------------------------------------
+    //
+    fn yaml_path() -> &'static str {
+        return "./src/config/schedule-test.yaml";
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    fn csv_path() -> &'static str {
+        return "./src/config/routes.csv";
+    }
+
+    //------------------------------------------------------------------------------
+    //
+    fn general_path() -> &'static str {
+        return "./src/config/general.yaml";
+    }
+
+    //---------------------------------------------------------------------------
+    // Write a copy of the test schedule with a short replica exchange section
+    //
+    fn schedule_path() -> String {
+        return schedule_with("pt", "");
+    }
+
+    //---------------------------------------------------------------------------
+    // Write a copy of the test schedule with a short replica exchange section
+    // and the given `extra` sections
+    //
+    fn schedule_with(name: &str, extra: &str) -> String {
+        let path = std::env::temp_dir().join(format!("sa-pap-test-{}-schedule.yaml", name));
+        let pt = "pt:\n  replicas: 3\n  t_min: 1.0\n  t_max: 100.0\n  rounds: 4\n  interval: 1\n";
+        let sched = std::fs::read_to_string(yaml_path()).unwrap() + pt + extra;
+        std::fs::write(&path, sched).unwrap();
+
+        return path.to_string_lossy().to_string();
+    }
+
+    //---------------------------------------------------------------------------
+    // Observer that records the fallbacks of a run
+    //
+    struct FallbackRecorder {
+        fallbacks: Rc<RefCell<Vec<Fallback>>>,
+    }
+
+    impl SaObserver for FallbackRecorder {
+        fn on_fallback(&mut self, f: &Fallback) {
+            self.fallbacks.borrow_mut().push(f.clone());
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    // Run a short parallel tempering run with the given seed
+    //
+    fn run_pt(seed: u64, schedule_path: &str) -> Results {
+        return run_pt_observed(seed, schedule_path, Vec::new());
+    }
+
+    //---------------------------------------------------------------------------
+    // Run a short parallel tempering run with the given seed and observers
+    //
+    fn run_pt_observed(
+        seed: u64,
+        schedule_path: &str,
+        observers: Vec<Box<dyn SaObserver>>,
+    ) -> Results {
+        let gsys =
+            Box::new(RouteCSVGenerator::new(schedule_path, general_path(), csv_path()).unwrap());
+
+        let mut pt: PT = PT::new(
+            general_path(),
+            schedule_path,
+            Box::new(GenNewVisits::new()),
+            gsys,
+            Box::new(TweakSchedule::new()),
+            seed,
+        )
+        .unwrap();
+        for o in observers {
+            pt.add_observer(o);
+        }
+
+        return pt.run().unwrap();
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_replica_exchange() {
+        let path = schedule_path();
+        let a = run_pt(13, &path);
+        let b = run_pt(13, &path);
+
+        // Test 1 - The replicas follow the temperature ladder
+        let temps: Vec<f32> = a.replicas.iter().map(|r| r.temp).collect();
+        assert_eq!(temps, vec![1.0, 10.0, 100.0]);
+        assert_eq!(a.T0, 100.0);
+
+        // Test 2 - The statistics are consistent
+        for r in a.replicas.iter() {
+            assert!(r.candidates > 0);
+            assert!(r.accepted <= r.candidates);
+            assert!(r.swaps <= r.swap_attempts);
+        }
+
+        // Test 3 - Even and odd pairs take turns, the hottest replica has no hotter neighbor
+        assert_eq!(a.replicas[0].swap_attempts, 2);
+        assert_eq!(a.replicas[1].swap_attempts, 2);
+        assert_eq!(a.replicas[2].swap_attempts, 0);
+
+        // Test 4 - The score history covers every round and the best score never increases
+        let jb = &a.data.dec.Jb;
+        assert_eq!(jb.len(), 5);
+        assert_eq!(a.data.dec.reheat.len(), jb.len());
+        assert!(jb.windows(2).all(|w| w[1] <= w[0]));
+        assert_eq!(*jb.last().unwrap(), a.score);
+        assert_eq!(a.data.dec.term("demand").len(), jb.len());
+        assert!((a.terms.total() - a.score).abs() <= 1e-9 * a.score.abs());
+
+        // Test 5 - Two runs with the same seed produce identical results
+        assert_eq!(a.score.to_bits(), b.score.to_bits());
+        assert_eq!(a.replicas, b.replicas);
+        assert!(a.data == b.data, "The solutions do not match");
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_acceptance_scale() {
+        // Test 1 - The default scale is used when none is configured
+        let res = run_pt(5, &schedule_path());
+        assert_eq!(res.scale, 9000000.0);
+
+        // Test 2 - The automatic scale is the mean uphill delta of the warmup
+        let path = schedule_with("pt-scale-auto", "temp:\n  scale: auto\n  warmup: 30\n");
+        let a = run_pt(5, &path);
+        let b = run_pt(5, &path);
+        assert!(a.scale > 0.0 && a.scale != 9000000.0);
+        assert_eq!(a.scale.to_bits(), b.scale.to_bits());
+
+        // Test 3 - Without uphill moves during the warmup the default scale is reported as a fallback
+        let path = schedule_with("pt-scale-none", "temp:\n  scale: auto\n  warmup: 0\n");
+        let fallbacks = Rc::new(RefCell::new(Vec::new()));
+        let res = run_pt_observed(
+            5,
+            &path,
+            vec![Box::new(FallbackRecorder {
+                fallbacks: fallbacks.clone(),
+            })],
+        );
+        assert_eq!(res.scale, 9000000.0);
+        assert_eq!(*fallbacks.borrow(), vec![Fallback::Scale(9000000.0)]);
+    }
+}
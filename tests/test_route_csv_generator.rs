@@ -9,8 +9,10 @@ extern crate sa_pap;
 mod test_route_csv_generator {
     //---------------------------------------------------------------------------
     // Import modules
+    use super::sa_pap::error::Error;
     use super::sa_pap::sa::route::route_csv_generator::{parse_routes, RouteCSVGenerator};
     use super::sa_pap::sa::route::Route;
+    use super::sa_pap::util::rand_utils;
 
     //---------------------------------------------------------------------------
     //
@@ -35,10 +37,11 @@ mod test_route_csv_generator {
     #[test]
     fn test_csv_load() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path());
+            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path()).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Test bus IDs
         assert_eq!(rg.csv_schedule.0[0], 0);
@@ -70,9 +73,8 @@ mod test_route_csv_generator {
     //---------------------------------------------------------------------------
     //
     #[test]
-    #[should_panic]
     fn test_csv_bad_path() {
-        parse_routes::read_csv("./routes.csv");
+        assert!(parse_routes::read_csv("./routes.csv").is_err());
     }
 
     //---------------------------------------------------------------------------
@@ -80,10 +82,11 @@ mod test_route_csv_generator {
     #[test]
     fn test_visit_count() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path());
+            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path()).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Test the route counts
         assert_eq!(
@@ -98,10 +101,11 @@ mod test_route_csv_generator {
     #[test]
     fn test_route_data() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path());
+            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path()).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Test the route counts
 
@@ -140,10 +144,11 @@ mod test_route_csv_generator {
     #[test]
     fn test_route_sort() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path());
+            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path()).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Ensure the arrival times are increasing
         for i in 0..rg.route.len() - 1 {
@@ -159,10 +164,11 @@ mod test_route_csv_generator {
     #[test]
     fn test_charge_rate_vector() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path());
+            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path()).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Ensure the arrival times are increasing
         for i in 0..rg.data.param.r.len() {
@@ -183,10 +189,11 @@ mod test_route_csv_generator {
     #[test]
     fn test_charge_assignment_vector() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path());
+            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path()).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Ensure the arrival times are increasing
         for i in 1..rg.data.param.r.len() {
@@ -199,10 +206,11 @@ mod test_route_csv_generator {
     #[test]
     fn test_assignment_cost() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path());
+            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path()).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Ensure the arrival times are increasing
         for i in 0..rg.data.param.ep.len() {
@@ -222,10 +230,11 @@ mod test_route_csv_generator {
     #[test]
     fn test_route_visit_index() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path());
+            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path()).unwrap();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Check the index of the routes increases
         for i in 0..rg.route.len() {
@@ -238,13 +247,14 @@ mod test_route_csv_generator {
     #[test]
     fn test_milp_data_update() {
         let mut rg: RouteCSVGenerator =
-            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path());
+            RouteCSVGenerator::new(schedule_path(), general_path(), csv_path()).unwrap();
 
         // Get a copy of the MILP data
         let data_cpy = rg.get_data().clone();
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Change some things in the route data
         rg.get_route_events()[0].arrival_time = 10.0;
@@ -306,14 +316,12 @@ mod test_route_csv_generator {
     //
     #[test]
     fn test_route_data_update() {
-        let mut rg: Box<dyn Route> = Box::new(RouteCSVGenerator::new(
-            schedule_path(),
-            general_path(),
-            csv_path(),
-        ));
+        let mut rg: Box<dyn Route> =
+            Box::new(RouteCSVGenerator::new(schedule_path(), general_path(), csv_path()).unwrap());
 
         // Load the CSV schedule
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         // Change some things in MILP data. Note `get_data` returns a copy of the MILP data, not a reference.
         rg.get_data().param.a[0] = 10.0;
@@ -346,4 +354,39 @@ mod test_route_csv_generator {
             assert_ne!(milp.param.e[8], 70.0);
         }
     }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_csv_errors() {
+        let mut rng = rand_utils::seeded_rng(0);
+
+        // Test 1 - A malformed route file is a configuration error that names the file
+        let path = std::env::temp_dir().join("sa-pap-test-bad-routes.csv");
+        std::fs::write(&path, "ID, BEG, END\n0, 0.0, 19200.0\n1, 0.0, noon\n").unwrap();
+        let path = path.to_string_lossy().to_string();
+        let mut rg = RouteCSVGenerator::new(schedule_path(), general_path(), &path).unwrap();
+        match rg.run(&mut rng) {
+            Err(Error::Config(e)) => {
+                assert_eq!(e.file, path);
+                assert!(e.message.contains("noon"), "{}", e.message);
+            }
+            r => panic!("Expected a configuration error, found {:?}", r),
+        }
+
+        // Test 2 - A route file without routes is infeasible
+        let path = std::env::temp_dir().join("sa-pap-test-empty-routes.csv");
+        std::fs::write(&path, "ID, BEG, END\n").unwrap();
+        let mut rg =
+            RouteCSVGenerator::new(schedule_path(), general_path(), &path.to_string_lossy())
+                .unwrap();
+        assert!(matches!(rg.run(&mut rng), Err(Error::Infeasible(_))));
+
+        // Test 3 - A missing route file is a configuration error of the constructor
+        match RouteCSVGenerator::new(schedule_path(), general_path(), "./missing-routes.csv") {
+            Err(Error::Config(e)) => assert_eq!(e.file, "./missing-routes.csv"),
+            Err(e) => panic!("Expected a configuration error, found {:?}", e),
+            Ok(_) => panic!("Expected a configuration error"),
+        }
+    }
 }
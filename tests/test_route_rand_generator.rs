@@ -11,6 +11,7 @@ mod test_route_rand_generator {
     // Import modules
     use super::sa_pap::sa::route::route_rand_generator::RouteRandGenerator;
     use super::sa_pap::sa::route::Route;
+    use super::sa_pap::util::rand_utils;
     use sa_pap::util::fileio::yaml_loader;
 
     //---------------------------------------------------------------------------
@@ -39,9 +40,10 @@ mod test_route_rand_generator {
     //
     #[test]
     fn test_valid_load_yaml() {
-        let mut rg: RouteRandGenerator = RouteRandGenerator::new(false, yaml_path());
+        let mut rg: RouteRandGenerator = RouteRandGenerator::new(false, yaml_path()).unwrap();
 
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         assert_eq!(rg.route.capacity(), get_route_size());
         assert_eq!(rg.buses.capacity(), get_bus_size());
@@ -51,9 +53,10 @@ mod test_route_rand_generator {
     //
     #[test]
     fn test_valid_bus_data() {
-        let mut rg: RouteRandGenerator = RouteRandGenerator::new(false, yaml_path());
+        let mut rg: RouteRandGenerator = RouteRandGenerator::new(false, yaml_path()).unwrap();
 
-        rg.run();
+        let mut rng = rand_utils::seeded_rng(0);
+        rg.run(&mut rng).unwrap();
 
         let e = rg.route;
 
@@ -0,0 +1,504 @@
+extern crate sa_pap;
+
+//===============================================================================
+//
+#[cfg(test)]
+mod test_sa {
+    //---------------------------------------------------------------------------
+    // Import standard library
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    //---------------------------------------------------------------------------
+    // Import modules
+    use super::sa_pap::error::Error;
+    use super::sa_pap::lp::objectives::std_obj::StdObj;
+    use super::sa_pap::lp::objectives::{Evaluation, Objective, Weights};
+    use super::sa_pap::sa::charger::Charger;
+    use super::sa_pap::sa::config::{ChargerConfig, SaConfig, TimeConfig};
+    use super::sa_pap::sa::data::Data;
+    use super::sa_pap::sa::generators::gen_new_visits::GenNewVisits;
+    use super::sa_pap::sa::generators::tweak_schedule::TweakSchedule;
+    use super::sa_pap::sa::observers::{Fallback, SaObserver};
+    use super::sa_pap::sa::route::route_csv_generator::RouteCSVGenerator;
+    use super::sa_pap::sa::route::route_data::RouteData;
+    use super::sa_pap::sa::route::Route;
+    use super::sa_pap::sa::stop::StopReason;
+    use super::sa_pap::sa::temp_func::{CoolSchedule::Geometric, TempFunc};
+    use super::sa_pap::sa::{Results, SA};
+    use super::sa_pap::util::rand_utils;
+
+    //---------------------------------------------------------------------------
+    //
+    fn yaml_path() -> &'static str {
+        return "./src/config/schedule-test.yaml";
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    fn csv_path() -> &'static str {
+        return "./src/config/routes.csv";
+    }
+
+    //------------------------------------------------------------------------------
+    //
+    fn general_path() -> &'static str {
+        return "./src/config/general.yaml";
+    }
+
+    //---------------------------------------------------------------------------
+    // Write a copy of the test schedule with the given `section`
+    //
+    fn schedule_with(section: &str, body: &str, name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("sa-pap-test-{}-schedule.yaml", name));
+        let sched = std::fs::read_to_string(yaml_path()).unwrap() + section + ":\n" + body;
+        std::fs::write(&path, sched).unwrap();
+
+        return path.to_string_lossy().to_string();
+    }
+
+    //---------------------------------------------------------------------------
+    // Objective function that only minimizes the consumption cost
+    //
+    struct EnergyObj {}
+
+    impl Objective for EnergyObj {
+        fn evaluate(
+            &self,
+            dat: &mut Data,
+            ch: &mut Charger,
+            run_constr: bool,
+        ) -> Result<Evaluation, Error> {
+            let mut e = StdObj::default().evaluate(dat, ch, run_constr)?;
+            e.J = e.terms.get("UC");
+            return Ok(e);
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    // Observer that copies the checkpoint file once the run reaches `step`, as
+    // if the run had been interrupted there
+    //
+    struct CopyCheckpoint {
+        from: String,
+        to: String,
+        step: usize,
+    }
+
+    impl SaObserver for CopyCheckpoint {
+        fn on_iteration(&mut self, step: usize, _iteration: usize, _t: f32, _j: f64) {
+            if step == self.step {
+                std::fs::copy(&self.from, &self.to).unwrap();
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    // Run a short simulated annealing run with the given seed
+    //
+    fn run_sa(seed: u64, name: &str) -> Results {
+        return run_sa_with(seed, name, yaml_path());
+    }
+
+    //---------------------------------------------------------------------------
+    // Observer that records the fallbacks of a run
+    //
+    struct FallbackRecorder {
+        fallbacks: Rc<RefCell<Vec<Fallback>>>,
+    }
+
+    impl SaObserver for FallbackRecorder {
+        fn on_fallback(&mut self, f: &Fallback) {
+            self.fallbacks.borrow_mut().push(f.clone());
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    // Run a short simulated annealing run with the given seed and schedule
+    //
+    fn run_sa_with(seed: u64, name: &str, schedule_path: &str) -> Results {
+        return run_sa_observed(seed, name, schedule_path, Vec::new());
+    }
+
+    //---------------------------------------------------------------------------
+    // Run a short simulated annealing run with the given seed, schedule and
+    // observers
+    //
+    fn run_sa_observed(
+        seed: u64,
+        name: &str,
+        schedule_path: &str,
+        observers: Vec<Box<dyn SaObserver>>,
+    ) -> Results {
+        let mut tf = Box::new(TempFunc::new(Geometric, 8.0, 0.5, true));
+        let gsys =
+            Box::new(RouteCSVGenerator::new(schedule_path, general_path(), csv_path()).unwrap());
+
+        let mut sa: SA = SA::new(
+            general_path(),
+            schedule_path,
+            Box::new(GenNewVisits::new()),
+            gsys,
+            Box::new(TweakSchedule::new()),
+            &mut tf,
+            seed,
+        )
+        .unwrap();
+
+        // Keep the checkpoints of the test out of the data directory
+        let ckpt = std::env::temp_dir().join(format!("sa-pap-test-{}.yaml", name));
+        sa.set_checkpoint_path(&ckpt.to_string_lossy());
+        for o in observers {
+            sa.add_observer(o);
+        }
+
+        return sa.run(false).unwrap();
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_seeded_runs_match() {
+        let a = run_sa(7, "seed-a");
+        let b = run_sa(7, "seed-b");
+
+        // Test 1 - Two runs with the same seed produce identical results
+        assert_eq!(a.seed, b.seed);
+        assert_eq!(a.score.to_bits(), b.score.to_bits());
+        assert!(a.data == b.data, "The solutions do not match");
+        assert!(a.charger == b.charger, "The charge schedules do not match");
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_acceptance_scale() {
+        // Test 1 - The default scale is used when none is configured
+        let res = run_sa(3, "scale-default");
+        assert_eq!(res.scale, 9000000.0);
+
+        // Test 2 - A fixed scale is used as is
+        let path = schedule_with("temp", "  scale: 250.0\n", "scale-fixed");
+        let res = run_sa_with(3, "scale-fixed", &path);
+        assert_eq!(res.scale, 250.0);
+
+        // Test 3 - The automatic scale is the mean uphill delta of the warmup
+        let path = schedule_with("temp", "  scale: auto\n  warmup: 30\n", "scale-auto");
+        let a = run_sa_with(3, "scale-auto", &path);
+        let b = run_sa_with(3, "scale-auto", &path);
+        assert!(a.scale > 0.0 && a.scale != 9000000.0);
+        assert_eq!(a.scale.to_bits(), b.scale.to_bits());
+
+        // Test 4 - Without uphill moves during the warmup the default scale is reported as a fallback
+        let path = schedule_with("temp", "  scale: auto\n  warmup: 0\n", "scale-none");
+        let fallbacks = Rc::new(RefCell::new(Vec::new()));
+        let res = run_sa_observed(
+            3,
+            "scale-none",
+            &path,
+            vec![Box::new(FallbackRecorder {
+                fallbacks: fallbacks.clone(),
+            })],
+        );
+        assert_eq!(res.scale, 9000000.0);
+        assert_eq!(*fallbacks.borrow(), vec![Fallback::Scale(9000000.0)]);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_initial_temp() {
+        // Test 1 - A configured initial temperature is used as is
+        let res = run_sa(5, "temp-fixed");
+        assert_eq!(res.T0, 8.0);
+
+        // Test 2 - The estimated temperature grows with the target acceptance ratio
+        let temp = "  init: auto\n  scale: auto\n  warmup: 30\n  samples: 30\n";
+        let low = schedule_with("temp", &(temp.to_owned() + "  accept: 0.5\n"), "temp-low");
+        let high = schedule_with("temp", &(temp.to_owned() + "  accept: 0.95\n"), "temp-high");
+        let low = run_sa_with(5, "temp-low", &low);
+        let high = run_sa_with(5, "temp-high", &high);
+        assert!(low.T0 > 0.0);
+        assert!(low.T0 < high.T0, "{} is not less than {}", low.T0, high.T0);
+
+        // Test 3 - A target above the ratio of the largest temperature is reported as a fallback
+        let temp = "  init: auto\n  scale: 1.0\n  samples: 30\n  accept: 0.999999999999\n";
+        let path = schedule_with("temp", temp, "temp-none");
+        let fallbacks = Rc::new(RefCell::new(Vec::new()));
+        let res = run_sa_observed(
+            5,
+            "temp-none",
+            &path,
+            vec![Box::new(FallbackRecorder {
+                fallbacks: fallbacks.clone(),
+            })],
+        );
+        assert_eq!(res.T0, 1.0e12);
+        assert_eq!(*fallbacks.borrow(), vec![Fallback::InitTemp(1.0e12)]);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_reheat() {
+        // Test 1 - Without a reheat policy the run is not reheated
+        let res = run_sa(11, "reheat-none");
+        assert!(res.data.dec.reheat.iter().all(|r| *r == 0));
+        assert_eq!(res.data.dec.reheat.len(), res.data.dec.Jb.len());
+
+        // Test 2 - A run that never improves enough is reheated up to the maximum
+        let reheat = "  steps: 1\n  fraction: 0.5\n  max: 2\n  improve: 1.0\n";
+        let path = schedule_with("reheat", reheat, "reheat-best");
+        let res = run_sa_with(11, "reheat-best", &path);
+        let r = &res.data.dec.reheat;
+        assert_eq!(r.len(), res.data.dec.Jb.len());
+        assert_eq!(*r.last().unwrap(), 2);
+        assert!(r.windows(2).all(|w| w[1] == w[0] || w[1] == w[0] + 1));
+
+        // Test 3 - Restarting from a perturbed copy is deterministic
+        let path = schedule_with(
+            "reheat",
+            &(reheat.to_owned() + "  perturb: 5\n"),
+            "reheat-perturb",
+        );
+        let a = run_sa_with(11, "reheat-perturb-a", &path);
+        let b = run_sa_with(11, "reheat-perturb-b", &path);
+        assert_eq!(*a.data.dec.reheat.last().unwrap(), 2);
+        assert_eq!(a.score.to_bits(), b.score.to_bits());
+        assert!(a.data == b.data, "The solutions do not match");
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_stop() {
+        // Test 1 - Without stop criteria the temperature schedule is completed
+        let res = run_sa(2, "stop-none");
+        assert_eq!(res.stop, StopReason::Completed);
+        let steps = res.data.dec.Jb.len();
+
+        // Test 2 - The run stops after the maximum number of evaluations
+        let path = schedule_with("stop", "  evaluations: 120\n", "stop-evals");
+        let res = run_sa_with(2, "stop-evals", &path);
+        assert_eq!(res.stop, StopReason::MaxEvaluations);
+        assert!(res.data.dec.Jb.len() < steps);
+
+        // Test 3 - The run stops once the target score is reached
+        let path = schedule_with("stop", &format!("  target: {}\n", res.score), "stop-target");
+        let res = run_sa_with(2, "stop-target", &path);
+        assert_eq!(res.stop, StopReason::TargetScore);
+        assert!(res.data.dec.Jb.len() < steps);
+
+        // Test 4 - The stop reason is reported when the best solution did not improve
+        let path = schedule_with("stop", "  evaluations: 1\n", "stop-unimproved");
+        let res = run_sa_with(0, "stop-unimproved", &path);
+        assert!(!res.improved);
+        assert_eq!(res.stop, StopReason::MaxEvaluations);
+        assert_eq!(res.score, res.data.dec.Jb[0]);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_adaptive_k() {
+        // Test 1 - Without an acceptance target every step runs `time.K` tweaks
+        let res = run_sa(6, "k-fixed");
+        let k = &res.data.dec.K;
+        assert_eq!(k.len(), res.data.dec.Jb.len());
+        assert_eq!(k[0], 0);
+        assert!(k.iter().skip(1).all(|k| *k == 100));
+
+        // Test 2 - With an acceptance target the length stays within its bounds
+        let search = "  accept: 5\n  min: 10\n  max: 40\n";
+        let path = schedule_with("search", search, "k-adaptive");
+        let res = run_sa_with(4, "k-adaptive", &path);
+        let k = &res.data.dec.K;
+        assert_eq!(k.len(), res.data.dec.Jb.len());
+        assert!(k.iter().skip(1).all(|k| (10..=40).contains(k)), "{:?}", k);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_in_memory() {
+        // Load the route of the test schedule into memory
+        let mut rg = RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap();
+        rg.run(&mut rand_utils::seeded_rng(0)).unwrap();
+        let data = *rg.get_data();
+
+        // Settings of the test schedule and the general configuration
+        let config = SaConfig {
+            time: TimeConfig {
+                k: 100,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let charger = ChargerConfig {
+            count: (0, 7, 4),
+            speed: (0.0, 100.0, 400.0),
+            ..Default::default()
+        };
+
+        // Test 1 - The in-memory data is solved without the configuration files
+        let mut tf = Box::new(TempFunc::new(Geometric, 8.0, 0.5, true));
+        let mut sa: SA = SA::with_config(
+            config,
+            charger,
+            Box::new(GenNewVisits::new()),
+            Box::new(RouteData::new(data.clone())),
+            Box::new(TweakSchedule::new()),
+            &mut tf,
+            9,
+        )
+        .unwrap();
+        let res = sa.run(false).unwrap();
+        assert_eq!(res.data.param, data.param);
+        assert_eq!(res.charger.charger_count, (data.param.A, 7, 4));
+
+        // Test 2 - The result matches a run from the configuration files
+        let file = run_sa(9, "in-memory");
+        assert_eq!(res.score.to_bits(), file.score.to_bits());
+        assert!(res.data == file.data, "The solutions do not match");
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_objective_breakdown() {
+        let res = run_sa(5, "breakdown");
+        let dec = &res.data.dec;
+
+        // Test 1 - The terms of the best solution are recorded every temperature step
+        let steps = dec.Jb.len();
+        let names: Vec<&str> = dec.terms.iter().map(|t| t.0.as_str()).collect();
+        assert_eq!(names, vec!["AC", "UC", "phi", "demand", "DC"]);
+        for (name, h) in dec.terms.iter() {
+            assert_eq!(h.len(), steps, "The history of {} is incomplete", name);
+        }
+
+        // Test 2 - The terms sum to the score
+        let t = res.terms;
+        assert!((t.total() - res.score).abs() <= 1e-9 * res.score.abs());
+        for i in 0..steps {
+            let sum: f64 = dec.terms.iter().map(|t| t.1[i]).sum();
+            assert!((sum - dec.Jb[i]).abs() <= 1e-9 * dec.Jb[i].abs());
+        }
+
+        // Test 3 - The final terms are the last recorded terms
+        for (name, x) in t.terms.iter() {
+            assert_eq!(*x, dec.term(name)[steps - 1]);
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_objective() {
+        // Test 1 - The weights of the schedule configuration weigh the terms
+        let body = "  UC: 2.0\n  demand: 0.0\n";
+        let path = schedule_with("objective", body, "objective");
+        let res = run_sa_with(5, "objective", &path);
+        let w = Weights::default().with("UC", 2.0).with("demand", 0.0);
+        assert!((res.terms.weighted(&w) - res.score).abs() <= 1e-9 * res.score.abs());
+
+        // Test 2 - The run minimizes the objective function that is set
+        let mut tf = Box::new(TempFunc::new(Geometric, 8.0, 0.5, true));
+        let mut sa: SA = SA::new(
+            general_path(),
+            yaml_path(),
+            Box::new(GenNewVisits::new()),
+            Box::new(RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap()),
+            Box::new(TweakSchedule::new()),
+            &mut tf,
+            5,
+        )
+        .unwrap();
+        let ckpt = std::env::temp_dir().join("sa-pap-test-custom-objective.yaml");
+        sa.set_checkpoint_path(&ckpt.to_string_lossy());
+        sa.set_objective(Box::new(EnergyObj {}));
+        let res = sa.run(false).unwrap();
+        assert_eq!(res.score, res.terms.get("UC"));
+        for i in 0..res.data.dec.Jb.len() {
+            assert_eq!(res.data.dec.Jb[i], res.data.dec.term("UC")[i]);
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_resume() {
+        let tmp = std::env::temp_dir();
+        let ckpt = tmp
+            .join("sa-pap-test-resume.yaml")
+            .to_string_lossy()
+            .to_string();
+        let copy = tmp
+            .join("sa-pap-test-resume-copy.yaml")
+            .to_string_lossy()
+            .to_string();
+
+        let new_sa = |tf, seed| {
+            let mut sa: SA = SA::new(
+                general_path(),
+                yaml_path(),
+                Box::new(GenNewVisits::new()),
+                Box::new(RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap()),
+                Box::new(TweakSchedule::new()),
+                tf,
+                seed,
+            )
+            .unwrap();
+            sa.set_checkpoint_path(&ckpt);
+            return sa;
+        };
+
+        // Run without interruption and keep the checkpoint of the tenth temperature step
+        let mut tf = Box::new(TempFunc::new(Geometric, 8.0, 0.9, true));
+        let mut sa = new_sa(&mut tf, 13);
+        sa.add_observer(Box::new(CopyCheckpoint {
+            from: ckpt.clone(),
+            to: copy.clone(),
+            step: 10,
+        }));
+        let a = sa.run(false).unwrap();
+        drop(sa);
+        std::fs::rename(&copy, &ckpt).unwrap();
+
+        // Resume the run from the checkpoint, the seed of the new solver is replaced by the seed of the run
+        let mut tf = Box::new(TempFunc::new(Geometric, 8.0, 0.9, true));
+        let mut sa = new_sa(&mut tf, 14);
+        let b = sa.run(true).unwrap();
+
+        // Test 1 - The resumed run finishes as the uninterrupted run
+        assert_eq!(a.score.to_bits(), b.score.to_bits());
+        assert!(a.data == b.data, "The solutions do not match");
+        assert!(a.charger == b.charger, "The charge schedules do not match");
+
+        // Test 2 - The resumed run continues with the seed of the interrupted run
+        assert_eq!(b.seed, 13);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_route_error() {
+        // Test 1 - A route that could not be generated fails the construction of the solver
+        let path = std::env::temp_dir().join("sa-pap-test-sa-empty-routes.csv");
+        std::fs::write(&path, "ID, BEG, END\n").unwrap();
+        let mut tf = Box::new(TempFunc::new(Geometric, 8.0, 0.5, true));
+        let sa = SA::new(
+            general_path(),
+            yaml_path(),
+            Box::new(GenNewVisits::new()),
+            Box::new(
+                RouteCSVGenerator::new(yaml_path(), general_path(), &path.to_string_lossy())
+                    .unwrap(),
+            ),
+            Box::new(TweakSchedule::new()),
+            &mut tf,
+            1,
+        );
+        assert!(matches!(sa, Err(Error::Infeasible(_))));
+    }
+}
@@ -0,0 +1,66 @@
+extern crate sa_pap;
+
+//===============================================================================
+//
+#[cfg(test)]
+mod test_stop {
+    //---------------------------------------------------------------------------
+    // Import modules
+    use super::sa_pap::sa::stop::{StopCriteria, StopReason};
+    use std::time::Duration;
+    use yaml_rust::YamlLoader;
+
+    //---------------------------------------------------------------------------
+    // Load the stop criteria from a YAML string
+    //
+    fn criteria(s: &str) -> StopCriteria {
+        let y = YamlLoader::load_from_str(s).unwrap();
+        return StopCriteria::from_yaml(&y[0]["stop"]);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_from_yaml() {
+        // Test 1 - Missing sections and zeros disable the criteria
+        assert_eq!(criteria("time: {K: 1}"), StopCriteria::default());
+        assert_eq!(
+            criteria("stop: {time: 0, evaluations: 0, stall: 0}"),
+            StopCriteria::default()
+        );
+
+        // Test 2 - Integers and reals are accepted
+        let c = criteria("stop: {time: 1.5, evaluations: 10, target: 3, stall: 4}");
+        assert_eq!(c.time, Some(Duration::from_millis(1500)));
+        assert_eq!(c.evaluations, Some(10));
+        assert_eq!(c.target, Some(3.0));
+        assert_eq!(c.stall, Some(4));
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_check() {
+        let c = criteria("stop: {time: 1, evaluations: 10, target: 3.0, stall: 4}");
+        let s = Duration::from_millis(10);
+
+        // Test 1 - No criterion is met
+        assert_eq!(c.check(s, 9, 3.5, 3), None);
+
+        // Test 2 - Each criterion stops the run
+        assert_eq!(c.check(s, 9, 3.0, 3), Some(StopReason::TargetScore));
+        assert_eq!(
+            c.check(Duration::from_secs(1), 9, 3.5, 3),
+            Some(StopReason::WallClock)
+        );
+        assert_eq!(c.check(s, 10, 3.5, 3), Some(StopReason::MaxEvaluations));
+        assert_eq!(c.check(s, 9, 3.5, 4), Some(StopReason::NoImprovement));
+
+        // Test 3 - Disabled criteria never stop the run
+        let d = StopCriteria::default();
+        assert_eq!(
+            d.check(Duration::from_secs(1000), 1000000, -1e30, 1000),
+            None
+        );
+    }
+}
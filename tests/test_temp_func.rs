@@ -7,7 +7,7 @@ mod test_temp_func
 {
     //---------------------------------------------------------------------------
     // Import modules
-    use sa_pap::sa::temp_func::{TempFunc, CoolSchedule::*};
+    use sa_pap::sa::temp_func::{TempFunc, CoolSchedule, CoolSchedule::*};
 
     //---------------------------------------------------------------------------
     //
@@ -67,4 +67,172 @@ mod test_temp_func
         for _ in 1..10 { e.step(); }
         assert_eq!(e.get_temp_vec().unwrap().len(), 10);
     }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_logarithmic()
+    {
+        // Auto run, the schedule ends at the configured length
+        let l: TempFunc = TempFunc::fit(Logarithmic, 500.0, 1.0, 1000, true);
+        let t = l.get_temp_vec().unwrap();
+        assert_eq!(t[0], 500.0);
+        assert_eq!(t.len(), 1000);
+        assert!(*t.last().unwrap() > 1.0 && *t.last().unwrap() < 1.01);
+
+        // The default length cools from the default initial temperature
+        let l: TempFunc = TempFunc::fit(Logarithmic, 90000.0, 0.997, 4000, true);
+        assert_eq!(l.get_temp_vec().unwrap().len(), 4000);
+
+        // A new initial temperature is fit to the same length
+        let l: TempFunc = l.with_init_temp(200.0);
+        assert_eq!(l.get_temp(Some(0)).unwrap(), 200.0);
+        assert_eq!(l.get_temp_vec().unwrap().len(), 4000);
+
+        // Stepping
+        let mut l: TempFunc = TempFunc::new(Logarithmic, 500.0, 1.0, false);
+        for _ in 1..10 { l.step(); }
+        assert_eq!(l.get_temp_vec().unwrap().len(), 10);
+
+        // Check temperature
+        let t = l.get_temp_vec().unwrap();
+        assert_eq!(t[1], 500.0 / (1.0 + 2.0_f32.ln()));
+        assert_eq!(t[9], 500.0 / (1.0 + 10.0_f32.ln()));
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_lundy_mees()
+    {
+        // Auto run
+        let l: TempFunc = TempFunc::new(LundyMees, 500.0, 0.001, true);
+        let t = l.get_temp_vec().unwrap();
+
+        // Check temperature
+        assert_eq!(t[0], 500.0);
+        assert_eq!(t[1], 500.0 / (1.0 + 0.001 * 500.0));
+        assert!(t.windows(2).all(|w| w[1] < w[0]));
+        assert!(*t.last().unwrap() > 0.0);
+
+        // The schedule ends at the configured length
+        let l: TempFunc = TempFunc::fit(LundyMees, 90000.0, 0.997, 4000, true);
+        let t = l.get_temp_vec().unwrap();
+        assert_eq!(t.len(), 4000);
+        assert!(t.windows(2).all(|w| w[1] < w[0]));
+        assert!(*t.last().unwrap() > 1.0 && *t.last().unwrap() < 1.01);
+
+        // Stepping
+        let mut l: TempFunc = TempFunc::new(LundyMees, 500.0, 0.001, false);
+        for _ in 1..10 { l.step(); }
+        assert_eq!(l.get_temp_vec().unwrap().len(), 10);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_adaptive()
+    {
+        // Without observed ratios the schedule is geometric
+        let a: TempFunc = TempFunc::new(Adaptive(0.4), 500.0, 0.995, true);
+        let g: TempFunc = TempFunc::new(Geometric, 500.0, 0.995, true);
+        assert_eq!(a.get_temp_vec(), g.get_temp_vec());
+
+        // Accepting more than the target cools faster
+        let mut fast: TempFunc = TempFunc::new(Adaptive(0.4), 500.0, 0.995, true);
+        fast.update(0, 0.8);
+        assert_eq!(fast.get_temp(Some(1)).unwrap(), 0.995_f32.powf(2.0) * 500.0);
+        assert!(fast.get_temp_vec().unwrap().len() < g.get_temp_vec().unwrap().len());
+
+        // Accepting less than the target cools slower
+        let mut slow: TempFunc = TempFunc::new(Adaptive(0.4), 500.0, 0.995, true);
+        slow.update(0, 0.2);
+        assert!(slow.get_temp(Some(1)).unwrap() > g.get_temp(Some(1)).unwrap());
+        assert!(slow.get_temp_vec().unwrap().len() > g.get_temp_vec().unwrap().len());
+
+        // Other schedules are not affected
+        let mut g2: TempFunc = TempFunc::new(Geometric, 500.0, 0.995, true);
+        g2.update(0, 0.8);
+        assert_eq!(g2.get_temp_vec(), g.get_temp_vec());
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_piecewise()
+    {
+        // Auto run
+        let p: TempFunc = TempFunc::new(Piecewise(vec![(0, 100.0), (10, 50.0), (20, 10.0)]), 1.0, 0.0, true);
+        assert_eq!(p.get_temp_vec().unwrap().len(), 21);
+
+        // Check temperature
+        assert_eq!(p.get_temp(Some(0)).unwrap(), 100.0);
+        assert_eq!(p.get_temp(Some(5)).unwrap(), 75.0);
+        assert_eq!(p.get_temp(Some(10)).unwrap(), 50.0);
+        assert_eq!(p.get_temp(Some(15)).unwrap(), 30.0);
+        assert_eq!(p.get_temp(None).unwrap(), 10.0);
+
+        // Points after step 0 start at the initial temperature
+        let p: TempFunc = TempFunc::new(Piecewise(vec![(4, 100.0)]), 500.0, 0.0, true);
+        assert_eq!(p.get_temp(Some(0)).unwrap(), 500.0);
+        assert_eq!(p.get_temp(Some(2)).unwrap(), 300.0);
+        assert_eq!(p.get_temp(None).unwrap(), 100.0);
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_new()
+    {
+        let schedule = |s: &str| -> CoolSchedule
+        {
+            return CoolSchedule::new(s, 0.6, vec![(0, 100.0), (10, 1.5)]).unwrap();
+        };
+
+        assert_eq!(schedule("Linear"), Linear);
+        assert_eq!(schedule("Geometric"), Geometric);
+        assert_eq!(schedule("Exponential"), Exponential);
+        assert_eq!(schedule("Logarithmic"), Logarithmic);
+        assert_eq!(schedule("LundyMees"), LundyMees);
+        assert_eq!(schedule("Adaptive"), Adaptive(0.6));
+        assert_eq!(schedule("Piecewise"), Piecewise(vec![(0, 100.0), (10, 1.5)]));
+
+        // An unknown schedule is an error
+        assert!(CoolSchedule::new("Cubic", 0.6, vec![]).is_err());
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_reheat()
+    {
+        // Reheat a geometric schedule
+        let mut g: TempFunc = TempFunc::new(Geometric, 500.0, 0.5, true);
+        let len = g.get_temp_vec().unwrap().len();
+        assert!(g.reheat(3, 250.0));
+        assert_eq!(g.get_origin(), 4);
+
+        // Check temperature
+        let t = g.get_temp_vec().unwrap();
+        assert_eq!(t[3], 62.5);
+        assert_eq!(t[4], 250.0);
+        assert_eq!(t[5], 125.0);
+        assert_eq!(t.len(), 4 + len - 1);
+
+        // A logarithmic schedule cools from the reheated temperature
+        let mut l: TempFunc = TempFunc::new(Logarithmic, 500.0, 1.0, false);
+        for _ in 1..10 { l.step(); }
+        assert!(l.reheat(9, 100.0));
+        l.step();
+        assert_eq!(l.get_temp(None).unwrap(), 100.0 / (1.0 + 2.0_f32.ln()));
+
+        // A piecewise schedule repeats its points scaled to the reheated temperature
+        let mut p: TempFunc = TempFunc::new(Piecewise(vec![(0, 100.0), (10, 50.0)]), 1.0, 0.0, true);
+        assert!(p.reheat(10, 50.0));
+        assert_eq!(p.get_temp(Some(16)).unwrap(), 37.5);
+        assert_eq!(p.get_temp(None).unwrap(), 25.0);
+
+        // Temperatures below the minimum are not reheated
+        assert!(!g.reheat(3, 0.5));
+    }
 }
@@ -14,6 +14,7 @@ mod test_tweak_schedule {
     use super::sa_pap::sa::generators::Generator;
     use super::sa_pap::sa::route::route_csv_generator::RouteCSVGenerator;
     use super::sa_pap::sa::route::Route;
+    use super::sa_pap::util::rand_utils;
 
     //--------------------------------------------------------------------------
     //
@@ -40,22 +41,20 @@ mod test_tweak_schedule {
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Initialize
         // Load charger parameters from YAML file
-        let mut charger: Charger = Charger::new(yaml_path(), true, Some(35), None);
+        let mut charger: Charger = Charger::new(yaml_path(), true, Some(35), None).unwrap();
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Generate schedule
-        let mut gsys: Box<RouteCSVGenerator> = Box::new(RouteCSVGenerator::new(
-            yaml_path(),
-            general_path(),
-            csv_path(),
-        ));
-        gsys.run();
+        let mut gsys: Box<RouteCSVGenerator> =
+            Box::new(RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap());
+        let mut rng = rand_utils::seeded_rng(0);
+        gsys.run(&mut rng).unwrap();
         let mut gsys: Box<dyn Route> = gsys;
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Create solution generator and run it
         let mut gsol: Box<GenNewVisits> = Box::new(GenNewVisits::new());
-        gsol.run(&mut gsys, &mut charger);
+        gsol.run(&mut gsys, &mut charger, &mut rng).unwrap();
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Get a copy of the solution
@@ -69,7 +68,7 @@ mod test_tweak_schedule {
             //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
             // Tweak the original schedule and check for updates
             for _ in 0..4 {
-                while !gtweak.run(&mut gsys, &mut charger) {}
+                while !gtweak.run(&mut gsys, &mut charger, &mut rng).unwrap() {}
             }
 
             let sol_new = gsys.get_data().dec;
@@ -85,22 +84,20 @@ mod test_tweak_schedule {
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Initialize
         // Load charger parameters from YAML file
-        let mut charger: Charger = Charger::new(yaml_path(), true, Some(35), None);
+        let mut charger: Charger = Charger::new(yaml_path(), true, Some(35), None).unwrap();
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Generate schedule
-        let mut gsys: Box<RouteCSVGenerator> = Box::new(RouteCSVGenerator::new(
-            yaml_path(),
-            general_path(),
-            csv_path(),
-        ));
-        gsys.run();
+        let mut gsys: Box<RouteCSVGenerator> =
+            Box::new(RouteCSVGenerator::new(yaml_path(), general_path(), csv_path()).unwrap());
+        let mut rng = rand_utils::seeded_rng(0);
+        gsys.run(&mut rng).unwrap();
         let mut gsys: Box<dyn Route> = gsys;
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Create solution generator and run it
         let mut gsol: Box<GenNewVisits> = Box::new(GenNewVisits::new());
-        gsol.run(&mut gsys, &mut charger);
+        gsol.run(&mut gsys, &mut charger, &mut rng).unwrap();
 
         //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
         // Get a copy of the solution
@@ -114,7 +111,7 @@ mod test_tweak_schedule {
             //~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
             // Tweak the original schedule and check for updates
             for _ in 0..4 {
-                while !gtweak.run(&mut gsys, &mut charger) {}
+                while !gtweak.run(&mut gsys, &mut charger, &mut rng).unwrap() {}
             }
 
             let sol_new = gsys.get_data().dec;
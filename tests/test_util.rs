@@ -84,9 +84,10 @@ mod test_rand_utils {
     #[test]
     fn test_rand_route_count() {
         // Test objects
-        let a = rand_utils::rand_route_count(1, 10);
-        let b = rand_utils::rand_route_count(10, 100);
-        let c = rand_utils::rand_route_count(30, 400);
+        let mut rng = rand_utils::seeded_rng(0);
+        let a = rand_utils::rand_route_count(1, 10, &mut rng);
+        let b = rand_utils::rand_route_count(10, 100, &mut rng);
+        let c = rand_utils::rand_route_count(30, 400, &mut rng);
 
         // Test each object length
         assert_eq!(get_vec_size(&a), 1);
@@ -105,14 +106,15 @@ mod test_rand_utils {
     fn test_range() {
         let lower: f32 = 1.0;
         let upper: f32 = 100.0;
+        let mut rng = rand_utils::seeded_rng(0);
 
-        let mut v: f32 = rand_utils::rand_range(lower, upper);
+        let mut v: f32 = rand_utils::rand_range(lower, upper, &mut rng);
         assert!(v >= lower && v <= upper);
 
-        v = rand_utils::rand_range(lower, upper);
+        v = rand_utils::rand_range(lower, upper, &mut rng);
         assert!(v >= lower && v <= upper);
 
-        v = rand_utils::rand_range(lower, upper);
+        v = rand_utils::rand_range(lower, upper, &mut rng);
         assert!(v >= lower && v <= upper);
     }
 
@@ -121,7 +123,8 @@ mod test_rand_utils {
     #[test]
     fn test_shuffle_vec() {
         let v: Vec<u16> = (0..10).collect();
-        let v_shuffle = rand_utils::shuffle_vec(&v);
+        let mut rng = rand_utils::seeded_rng(0);
+        let v_shuffle = rand_utils::shuffle_vec(&v, &mut rng);
 
         // Test 1 - first shuffle
         let match_cnt = v
@@ -133,7 +136,7 @@ mod test_rand_utils {
 
         // Test 2 - Shuffle the shuffle
         let v: Vec<u16> = v_shuffle.clone();
-        let v_shuffle = rand_utils::shuffle_vec(&v);
+        let v_shuffle = rand_utils::shuffle_vec(&v, &mut rng);
 
         let match_cnt = v
             .iter()
@@ -142,6 +145,51 @@ mod test_rand_utils {
             .count();
         assert!(match_cnt < v.len());
     }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_seeded_rng() {
+        let v: Vec<u16> = (0..100).collect();
+
+        // Test 1 - The same seed produces the same values
+        let mut rng_a = rand_utils::seeded_rng(42);
+        let mut rng_b = rand_utils::seeded_rng(42);
+        assert_eq!(
+            rand_utils::shuffle_vec(&v, &mut rng_a),
+            rand_utils::shuffle_vec(&v, &mut rng_b)
+        );
+        assert_eq!(
+            rand_utils::rand_range(0.0_f32, 1.0, &mut rng_a),
+            rand_utils::rand_range(0.0_f32, 1.0, &mut rng_b)
+        );
+
+        // Test 2 - Different seeds produce different values
+        let mut rng_c = rand_utils::seeded_rng(43);
+        assert_ne!(
+            rand_utils::shuffle_vec(&v, &mut rng_a),
+            rand_utils::shuffle_vec(&v, &mut rng_c)
+        );
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_derive_seed() {
+        // Test 1 - Derived seeds are reproducible
+        assert_eq!(rand_utils::derive_seed(7, 3), rand_utils::derive_seed(7, 3));
+
+        // Test 2 - Each id derives a different seed
+        let seeds: Vec<u64> = (0..16).map(|id| rand_utils::derive_seed(7, id)).collect();
+        for i in 0..seeds.len() {
+            for j in i + 1..seeds.len() {
+                assert_ne!(seeds[i], seeds[j]);
+            }
+        }
+
+        // Test 3 - Each base seed derives a different seed
+        assert_ne!(rand_utils::derive_seed(7, 0), rand_utils::derive_seed(8, 0));
+    }
 }
 
 //===============================================================================
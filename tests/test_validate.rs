@@ -0,0 +1,222 @@
+extern crate sa_pap;
+
+//===============================================================================
+//
+#[cfg(test)]
+mod test_validate {
+    //---------------------------------------------------------------------------
+    // Import modules
+    use super::sa_pap::error::Error;
+    use super::sa_pap::lp::constraints::report::ConstraintKind;
+    use super::sa_pap::lp::validate::{load_schedule, validate};
+    use super::sa_pap::sa::charger::Assignment;
+    use super::sa_pap::sa::generators::gen_new_visits::GenNewVisits;
+    use super::sa_pap::sa::generators::tweak_schedule::TweakSchedule;
+    use super::sa_pap::sa::route::route_csv_generator::RouteCSVGenerator;
+    use super::sa_pap::sa::route::Route;
+    use super::sa_pap::sa::temp_func::{CoolSchedule::Geometric, TempFunc};
+    use super::sa_pap::sa::{Results, SA};
+    use super::sa_pap::util::data_output::DataOutput;
+    use super::sa_pap::util::rand_utils;
+
+    //---------------------------------------------------------------------------
+    // Solve the test schedule
+    //
+    fn solve() -> Results {
+        let mut tf = Box::new(TempFunc::new(Geometric, 8.0, 0.5, true));
+        let gsys = Box::new(
+            RouteCSVGenerator::new(
+                "./src/config/schedule-test.yaml",
+                "./src/config/general.yaml",
+                "./src/config/routes.csv",
+            )
+            .unwrap(),
+        );
+        let mut sa: SA = SA::new(
+            "./src/config/general.yaml",
+            "./src/config/schedule-test.yaml",
+            Box::new(GenNewVisits::new()),
+            gsys,
+            Box::new(TweakSchedule::new()),
+            &mut tf,
+            4,
+        )
+        .unwrap();
+        let ckpt = std::env::temp_dir().join("sa-pap-test-validate.yaml");
+        sa.set_checkpoint_path(&ckpt.to_string_lossy());
+
+        return sa.run(false).unwrap();
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_validate() {
+        let res = solve();
+        let (data, charger) = (*res.data, *res.charger);
+
+        // Test 1 - The solution is scheduled on the chargers without overlaps
+        let report = validate(&data, &charger);
+        for kind in [
+            ConstraintKind::UnscheduledVisit,
+            ConstraintKind::UnknownAssignment,
+            ConstraintKind::QueueOverlap,
+            ConstraintKind::ValidInitDepEndTimes,
+            ConstraintKind::ServiceTime,
+        ] {
+            assert!(report.of(kind).is_empty(), "{}", report);
+        }
+
+        // Test 2 - An assignment that overlaps the first charge of a queue
+        let q = (0..charger.schedule.len())
+            .find(|q| !charger.schedule[*q].is_empty())
+            .unwrap();
+        let first = charger.schedule[q]
+            .iter()
+            .min_by(|x, y| x.t.0.total_cmp(&y.t.0))
+            .unwrap()
+            .clone();
+        let mut ch = charger.clone();
+        ch.schedule[q].push(Assignment {
+            t: (first.t.1 - 0.1, first.t.1 + 0.1),
+            b: first.b,
+        });
+        let report = validate(&data, &ch);
+        let overlap = report.of(ConstraintKind::QueueOverlap);
+        assert_eq!(overlap.len(), 1);
+        assert_eq!(overlap[0].q, Some(q));
+        assert!((overlap[0].amount - 0.1).abs() < 1e-3);
+        let unknown = report.of(ConstraintKind::UnknownAssignment);
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0].i, ch.schedule[q].len() - 1);
+
+        // Test 3 - A visit that is attached before it arrives and is not on the charger schedule
+        let i = data.param.A;
+        let mut d = data.clone();
+        d.dec.u[i] = d.param.a[i] - 0.2;
+        let report = validate(&d, &charger);
+        assert_eq!(report.of(ConstraintKind::ValidInitDepEndTimes)[0].i, i);
+        assert_eq!(report.of(ConstraintKind::ServiceTime)[0].i, i);
+        assert_eq!(report.of(ConstraintKind::UnscheduledVisit)[0].i, i);
+
+        // Test 4 - An initial charge below the minimum charge
+        let mut d = data.clone();
+        d.param.alpha[0] = d.param.nu / 2.0;
+        let report = validate(&d, &charger);
+        let low = report.of(ConstraintKind::MinCharge);
+        let k = d.param.k[d.param.Gam[0] as usize];
+        assert_eq!(low[0].i, 0);
+        assert!((low[0].amount - d.param.nu * k / 2.0).abs() < 1e-3);
+
+        // Test 5 - The report lists every violation
+        let text = report.to_string();
+        assert!(text.starts_with(&format!(
+            "{} constraint violations",
+            report.violations.len()
+        )));
+        assert!(text.contains("- MinCharge: visit 0 by"));
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_load_schedule() {
+        let res = solve();
+        let (data, charger) = (*res.data.clone(), *res.charger.clone());
+
+        // Write the schedule of the solution
+        let dir = std::env::temp_dir().join("sa-pap-test-validate/");
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir = dir.to_string_lossy().to_string();
+        DataOutput::output_data(String::from("sa"), res, Some(dir.clone())).unwrap();
+        let path = dir.clone() + "sa-schedule.csv";
+
+        // Load the route
+        let mut gsys = RouteCSVGenerator::new(
+            "./src/config/schedule-test.yaml",
+            "./src/config/general.yaml",
+            "./src/config/routes.csv",
+        )
+        .unwrap();
+        gsys.run(&mut rand_utils::seeded_rng(0)).unwrap();
+        let route = *gsys.get_data();
+
+        // Test 1 - The charges of the written schedule are restored
+        let mut d = route.clone();
+        let mut ch = charger.clone();
+        load_schedule(&path, &mut d, &mut ch).unwrap();
+        let wait = charger.charger_count.0;
+        for i in 0..d.param.N {
+            if data.dec.v[i] >= wait && data.dec.s[i] > 0.001 {
+                assert_eq!(d.dec.v[i], data.dec.v[i]);
+                assert_eq!(d.dec.u[i], data.dec.u[i]);
+                assert_eq!(d.dec.s[i], data.dec.s[i]);
+            } else {
+                assert_eq!(d.dec.v[i], d.param.Gam[i] as usize);
+            }
+        }
+
+        // Test 2 - The restored schedule is scheduled on the chargers without overlaps
+        let report = validate(&d, &ch);
+        for kind in [
+            ConstraintKind::UnscheduledVisit,
+            ConstraintKind::UnknownAssignment,
+            ConstraintKind::QueueOverlap,
+            ConstraintKind::ScalarToVectorQueue,
+        ] {
+            assert!(report.of(kind).is_empty(), "{}", report);
+        }
+
+        // Test 3 - Missing, malformed and mismatched files are reported as errors
+        let text = std::fs::read_to_string(&path).unwrap();
+        let bad = dir.clone() + "sa-bad-schedule.csv";
+        let header = text.lines().next().unwrap().to_string();
+        let missing = dir.clone() + "sa-missing-schedule.csv";
+        for (file, contents) in [
+            (&missing, None),
+            (&bad, Some(String::new())),
+            (&bad, Some(text.replacen("nan", "abc", 1))),
+            (&bad, Some(header.clone() + "\n")),
+            (&bad, Some(text.replacen(&header, "charger0,u0,s0", 1))),
+        ] {
+            if let Some(c) = contents {
+                std::fs::write(file, c).unwrap();
+            }
+            let mut d = route.clone();
+            let mut ch = charger.clone();
+            let res = load_schedule(file, &mut d, &mut ch);
+            assert!(
+                matches!(res, Err(Error::Config(ref e)) if e.file == *file),
+                "The schedule was not rejected"
+            );
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    //
+    #[test]
+    fn test_validate_command() {
+        // Run the program in a directory with its own configuration
+        let dir = std::env::temp_dir().join("sa-pap-test-validate-command");
+        let config = dir.join("src/config");
+        std::fs::create_dir_all(&config).unwrap();
+        std::fs::copy("./src/config/schedule.yaml", config.join("schedule.yaml")).unwrap();
+        let run = || -> Option<i32> {
+            return std::process::Command::new(env!("CARGO_BIN_EXE_sa_pap"))
+                .args(["validate", "sa-schedule.csv"])
+                .current_dir(&dir)
+                .output()
+                .unwrap()
+                .status
+                .code();
+        };
+
+        // Test 1 - A malformed general configuration is bad input
+        std::fs::write(config.join("general.yaml"), "cores: [").unwrap();
+        assert_eq!(run(), Some(2));
+
+        // Test 2 - An invalid general configuration is bad input
+        std::fs::write(config.join("general.yaml"), "solver: annealing").unwrap();
+        assert_eq!(run(), Some(2));
+    }
+}